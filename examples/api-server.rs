@@ -4,13 +4,15 @@
 
 use axum::{
     extract::{Json, Path},
-    http::StatusCode,
     response::IntoResponse,
-    routing::{get, post},
+    routing::get,
     Router,
 };
-use hwhkit::{WebServerBuilder, Deserialize, Serialize};
-use std::collections::HashMap;
+use hwhkit::{
+    response::{self, ApiError, Created},
+    routing::RouteDoc,
+    WebServerBuilder, Deserialize, Serialize,
+};
 
 #[derive(Serialize, Deserialize)]
 struct User {
@@ -19,45 +21,8 @@ struct User {
     email: String,
 }
 
-#[derive(Serialize)]
-struct ApiResponse<T> {
-    success: bool,
-    data: T,
-    message: String,
-}
-
-impl<T> ApiResponse<T> {
-    fn success(data: T) -> Self {
-        Self {
-            success: true,
-            data,
-            message: "操作成功".to_string(),
-        }
-    }
-}
-
-#[derive(Serialize)]
-struct ErrorResponse {
-    success: bool,
-    error: String,
-    code: u16,
-}
-
-impl ErrorResponse {
-    fn new(code: u16, error: String) -> Self {
-        Self {
-            success: false,
-            error,
-            code,
-        }
-    }
-}
-
-// 模拟数据库
-type UserStore = std::sync::Arc<std::sync::Mutex<HashMap<u64, User>>>;
-
 // API 路由处理器
-async fn get_users() -> impl IntoResponse {
+async fn get_users() -> response::Ok<Vec<User>> {
     let users = vec![
         User {
             id: 1,
@@ -71,32 +36,30 @@ async fn get_users() -> impl IntoResponse {
         },
     ];
 
-    Json(ApiResponse::success(users))
+    response::Ok(users)
 }
 
-async fn get_user(Path(id): Path<u64>) -> impl IntoResponse {
+async fn get_user(Path(id): Path<u64>) -> Result<response::Ok<User>, ApiError> {
     if id == 1 {
         let user = User {
             id: 1,
             name: "张三".to_string(),
             email: "zhangsan@example.com".to_string(),
         };
-        (StatusCode::OK, Json(ApiResponse::success(user))).into_response()
+        Ok(response::Ok(user))
     } else {
-        let error = ErrorResponse::new(404, "用户不存在".to_string());
-        (StatusCode::NOT_FOUND, Json(error)).into_response()
+        Err(ApiError::not_found("用户不存在"))
     }
 }
 
-async fn create_user(Json(user): Json<User>) -> impl IntoResponse {
+async fn create_user(Json(user): Json<User>) -> Result<Created<User>, ApiError> {
     // 简单验证
     if user.name.is_empty() || user.email.is_empty() {
-        let error = ErrorResponse::new(400, "用户名和邮箱不能为空".to_string());
-        return (StatusCode::BAD_REQUEST, Json(error)).into_response();
+        return Err(ApiError::bad_request("用户名和邮箱不能为空"));
     }
 
     // 在实际应用中，这里会保存到数据库
-    (StatusCode::CREATED, Json(ApiResponse::success(user))).into_response()
+    Ok(Created(user))
 }
 
 async fn health_check() -> impl IntoResponse {
@@ -115,15 +78,25 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .route("/users/:id", get(get_user))
         .route("/health", get(health_check));
 
-    // 构建应用路由
-    let app_routes = Router::new()
-        .nest("/api/v1", api_routes)
-        .route("/", get(|| async { "HwhKit API Server is running!" }));
+    // 构建应用路由，首页由 [server.index_page] 根据路由文档生成
+    let app_routes = Router::new().nest("/api/v1", api_routes);
 
     // 创建服务器
     let server = WebServerBuilder::new()
         .config_from_file("examples/api-config.toml")
         .routes(app_routes)
+        .route_doc(RouteDoc::new("GET", "/api/v1/users", "获取用户列表").tag("用户"))
+        .route_doc(
+            RouteDoc::new("POST", "/api/v1/users", "创建用户")
+                .tag("用户")
+                .example_body(serde_json::json!({
+                    "id": 3,
+                    "name": "王五",
+                    "email": "wangwu@example.com"
+                })),
+        )
+        .route_doc(RouteDoc::new("GET", "/api/v1/users/1", "获取单个用户").tag("用户"))
+        .route_doc(RouteDoc::new("GET", "/api/v1/health", "健康检查").tag("系统"))
         .build()
         .await?;
 
@@ -3,15 +3,17 @@
 //! 这个示例展示了如何使用 HwhKit 创建一个包含模板渲染的全栈应用
 
 use axum::{
-    extract::{Json, Path, Query},
-    http::StatusCode,
-    response::{Html, IntoResponse},
-    routing::{get, post},
+    extract::{Json, Path},
+    response::IntoResponse,
+    routing::get,
     Router,
 };
-use hwhkit::{WebServerBuilder, Deserialize, Serialize};
+use hwhkit::{
+    response::{self, ApiError, Created},
+    templates::Render,
+    Config, WebServerBuilder, Deserialize, Serialize,
+};
 use serde_json::json;
-use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize, Clone)]
 struct User {
@@ -20,23 +22,6 @@ struct User {
     email: String,
 }
 
-#[derive(Serialize)]
-struct ApiResponse<T> {
-    success: bool,
-    data: T,
-    message: String,
-}
-
-impl<T> ApiResponse<T> {
-    fn success(data: T) -> Self {
-        Self {
-            success: true,
-            data,
-            message: "操作成功".to_string(),
-        }
-    }
-}
-
 // 模拟数据库
 fn get_mock_users() -> Vec<User> {
     vec![
@@ -59,36 +44,30 @@ fn get_mock_users() -> Vec<User> {
 }
 
 // 页面路由处理器
-async fn index_page() -> impl IntoResponse {
+async fn index_page() -> Render<serde_json::Value> {
     let template_data = json!({
         "title": "首页",
-        "app_name": "HwhKit 演示应用",
-        "version": "1.0.0",
         "users_count": get_mock_users().len(),
         "requests_count": 1024,
         "uptime": "7天"
     });
 
-    render_template("index.html", &template_data).await
+    Render("index.html", template_data)
 }
 
-async fn users_page() -> impl IntoResponse {
+async fn users_page() -> Render<serde_json::Value> {
     let users = get_mock_users();
     let template_data = json!({
         "title": "用户管理",
-        "app_name": "HwhKit 演示应用",
-        "version": "1.0.0",
         "users": users
     });
 
-    render_template("users.html", &template_data).await
+    Render("users.html", template_data)
 }
 
-async fn about_page() -> impl IntoResponse {
+async fn about_page() -> Render<serde_json::Value> {
     let template_data = json!({
         "title": "关于我们",
-        "app_name": "HwhKit 演示应用",
-        "version": "1.0.0",
         "description": "HwhKit 是一个强大且易用的 Rust Web 框架",
         "features": [
             "高性能的异步架构",
@@ -98,43 +77,26 @@ async fn about_page() -> impl IntoResponse {
         ]
     });
 
-    render_template("about.html", &template_data).await
+    Render("about.html", template_data)
 }
 
 // API 路由处理器
-async fn api_get_users() -> impl IntoResponse {
-    let users = get_mock_users();
-    Json(ApiResponse::success(users))
+async fn api_get_users() -> response::Ok<Vec<User>> {
+    response::Ok(get_mock_users())
 }
 
-async fn api_get_user(Path(id): Path<u64>) -> impl IntoResponse {
-    let users = get_mock_users();
-    
-    if let Some(user) = users.into_iter().find(|u| u.id == id) {
-        (StatusCode::OK, Json(ApiResponse::success(user))).into_response()
-    } else {
-        (
-            StatusCode::NOT_FOUND,
-            Json(json!({
-                "success": false,
-                "error": "用户不存在",
-                "code": 404
-            }))
-        ).into_response()
-    }
+async fn api_get_user(Path(id): Path<u64>) -> Result<response::Ok<User>, ApiError> {
+    get_mock_users()
+        .into_iter()
+        .find(|u| u.id == id)
+        .map(response::Ok)
+        .ok_or_else(|| ApiError::not_found("用户不存在"))
 }
 
-async fn api_create_user(Json(user): Json<User>) -> impl IntoResponse {
+async fn api_create_user(Json(user): Json<User>) -> Result<Created<User>, ApiError> {
     // 简单验证
     if user.name.is_empty() || user.email.is_empty() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(json!({
-                "success": false,
-                "error": "用户名和邮箱不能为空",
-                "code": 400
-            }))
-        ).into_response();
+        return Err(ApiError::bad_request("用户名和邮箱不能为空"));
     }
 
     // 模拟保存用户
@@ -144,7 +106,7 @@ async fn api_create_user(Json(user): Json<User>) -> impl IntoResponse {
         email: user.email,
     };
 
-    (StatusCode::CREATED, Json(ApiResponse::success(new_user))).into_response()
+    Ok(Created(new_user))
 }
 
 async fn api_health() -> impl IntoResponse {
@@ -162,218 +124,12 @@ async fn api_health() -> impl IntoResponse {
     }))
 }
 
-// 简化的模板渲染函数
-async fn render_template(template_name: &str, data: &serde_json::Value) -> impl IntoResponse {
-    let html_content = match template_name {
-        "index.html" => generate_index_html(data),
-        "users.html" => generate_users_html(data),
-        "about.html" => generate_about_html(data),
-        _ => format!("<h1>模板 {} 不存在</h1>", template_name),
-    };
-    
-    Html(html_content)
-}
-
-fn generate_index_html(data: &serde_json::Value) -> String {
-    format!(
-        r#"<!DOCTYPE html>
-<html lang="zh-CN">
-<head>
-    <meta charset="UTF-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>{} - HwhKit</title>
-    <link rel="stylesheet" href="/static/style.css">
-</head>
-<body>
-    <header>
-        <nav class="navbar">
-            <div class="nav-brand">
-                <h1>{}</h1>
-            </div>
-            <ul class="nav-links">
-                <li><a href="/">首页</a></li>
-                <li><a href="/users">用户</a></li>
-                <li><a href="/about">关于</a></li>
-            </ul>
-        </nav>
-    </header>
-    <main class="container">
-        <div class="hero">
-            <h1>🚀 欢迎使用 HwhKit</h1>
-            <p class="subtitle">一个强大且易用的 Rust Web 框架</p>
-            <div class="stats">
-                <div class="stat">
-                    <span class="stat-number">{}</span>
-                    <span class="stat-label">注册用户</span>
-                </div>
-                <div class="stat">
-                    <span class="stat-number">{}</span>
-                    <span class="stat-label">处理请求</span>
-                </div>
-                <div class="stat">
-                    <span class="stat-number">{}</span>
-                    <span class="stat-label">运行时间</span>
-                </div>
-            </div>
-            <div class="actions">
-                <a href="/users" class="btn btn-primary">查看用户</a>
-                <a href="/api/v1/health" class="btn btn-secondary">API 状态</a>
-            </div>
-        </div>
-    </main>
-    <footer>
-        <p>&copy; 2024 HwhKit. 版本: {}</p>
-    </footer>
-    <script src="/static/script.js"></script>
-</body>
-</html>"#,
-        data["title"].as_str().unwrap_or("首页"),
-        data["app_name"].as_str().unwrap_or("HwhKit"),
-        data["users_count"].as_u64().unwrap_or(0),
-        data["requests_count"].as_u64().unwrap_or(0),
-        data["uptime"].as_str().unwrap_or("0d"),
-        data["version"].as_str().unwrap_or("1.0.0")
-    )
-}
-
-fn generate_users_html(_data: &serde_json::Value) -> String {
-    let users = get_mock_users();
-    let users_html: String = users.iter().map(|user| {
-        format!(
-            r#"<div class="user-card">
-                <div class="user-avatar">
-                    <span>{}</span>
-                </div>
-                <div class="user-info">
-                    <h3>{}</h3>
-                    <p>{}</p>
-                    <small>ID: {}</small>
-                </div>
-                <div class="user-actions">
-                    <button class="btn btn-sm btn-edit">编辑</button>
-                    <button class="btn btn-sm btn-delete">删除</button>
-                </div>
-            </div>"#, 
-            user.name.chars().next().unwrap_or('?').to_uppercase(),
-            user.name,
-            user.email,
-            user.id
-        )
-    }).collect();
-
-    format!(
-        r#"<!DOCTYPE html>
-<html lang="zh-CN">
-<head>
-    <meta charset="UTF-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>用户管理 - HwhKit</title>
-    <link rel="stylesheet" href="/static/style.css">
-</head>
-<body>
-    <header>
-        <nav class="navbar">
-            <div class="nav-brand">
-                <h1>HwhKit 演示应用</h1>
-            </div>
-            <ul class="nav-links">
-                <li><a href="/">首页</a></li>
-                <li><a href="/users">用户</a></li>
-                <li><a href="/about">关于</a></li>
-            </ul>
-        </nav>
-    </header>
-    <main class="container">
-        <div class="page-header">
-            <h1>👥 用户管理</h1>
-            <p>管理系统中的所有用户</p>
-        </div>
-        <div class="users-section">
-            <div class="section-header">
-                <h2>用户列表</h2>
-                <button class="btn btn-primary">添加用户</button>
-            </div>
-            <div class="users-grid">
-                {}
-            </div>
-        </div>
-    </main>
-    <footer>
-        <p>&copy; 2024 HwhKit. 版本: 1.0.0</p>
-    </footer>
-    <script src="/static/script.js"></script>
-</body>
-</html>"#, 
-        users_html
-    )
-}
-
-fn generate_about_html(data: &serde_json::Value) -> String {
-    format!(
-        r#"<!DOCTYPE html>
-<html lang="zh-CN">
-<head>
-    <meta charset="UTF-8">
-    <meta name="viewport" content="width=device-width, initial-scale=1.0">
-    <title>{} - HwhKit</title>
-    <link rel="stylesheet" href="/static/style.css">
-</head>
-<body>
-    <header>
-        <nav class="navbar">
-            <div class="nav-brand">
-                <h1>{}</h1>
-            </div>
-            <ul class="nav-links">
-                <li><a href="/">首页</a></li>
-                <li><a href="/users">用户</a></li>
-                <li><a href="/about">关于</a></li>
-            </ul>
-        </nav>
-    </header>
-    <main class="container">
-        <div class="page-header">
-            <h1>📖 关于 HwhKit</h1>
-            <p>{}</p>
-        </div>
-        <div class="users-section">
-            <h2>主要特性</h2>
-            <div class="features">
-                <div class="feature-card">
-                    <h3>⚡ 高性能</h3>
-                    <p>基于 Axum 和 Tokio，提供出色的性能表现</p>
-                </div>
-                <div class="feature-card">
-                    <h3>🛠️ 灵活的中间件</h3>
-                    <p>丰富的中间件支持，满足各种开发需求</p>
-                </div>
-                <div class="feature-card">
-                    <h3>🎨 模板支持</h3>
-                    <p>内置模板引擎，轻松构建动态页面</p>
-                </div>
-                <div class="feature-card">
-                    <h3>⚙️ 简单配置</h3>
-                    <p>基于 TOML 的配置管理，简单易用</p>
-                </div>
-            </div>
-        </div>
-    </main>
-    <footer>
-        <p>&copy; 2024 HwhKit. 版本: {}</p>
-    </footer>
-    <script src="/static/script.js"></script>
-</body>
-</html>"#,
-        data["title"].as_str().unwrap_or("关于我们"),
-        data["app_name"].as_str().unwrap_or("HwhKit"),
-        data["description"].as_str().unwrap_or("HwhKit 是一个强大且易用的 Rust Web 框架"),
-        data["version"].as_str().unwrap_or("1.0.0")
-    )
-}
-
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // 构建页面路由
+    // 也可以通过 hwhkit::scaffold::full_demo("my-app") 在其他目录生成本示例的模板和配置
+    let config = Config::from_file("examples/full-config.toml")?;
+
+    // 构建页面路由，服务器按 [middleware.templates] 创建模板引擎并渲染 Render
     let page_routes = Router::new()
         .route("/", get(index_page))
         .route("/users", get(users_page))
@@ -392,7 +148,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // 创建服务器
     let server = WebServerBuilder::new()
-        .config_from_file("examples/full-config.toml")
+        .config(config)
         .routes(app_routes)
         .build()
         .await?;
@@ -412,6 +168,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use hwhkit::templates::TemplateEngine;
 
     #[test]
     fn test_mock_users() {
@@ -421,18 +178,19 @@ mod tests {
     }
 
     #[test]
-    fn test_template_generation() {
+    fn test_template_rendering() {
+        let config = Config::from_file("examples/full-config.toml").unwrap();
+        let templates = TemplateEngine::new(&config.middleware.templates).unwrap();
         let data = json!({
             "title": "测试页面",
             "app_name": "测试应用",
             "version": "1.0.0",
-            "users_count": 5,
-            "requests_count": 100,
-            "uptime": "1天"
+            "users": get_mock_users(),
         });
 
-        let html = generate_index_html(&data);
+        let html = templates.render_in_layout("users.html", &data).unwrap();
         assert!(html.contains("测试页面"));
         assert!(html.contains("测试应用"));
+        assert!(html.contains("zhangsan@example.com"));
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,446 @@
+//! 管理接口模块
+//!
+//! 在 `[admin] path`（默认 `/__admin`）下提供运维接口，所有请求都需要携带
+//! `Authorization: Bearer <token>`：
+//!
+//! - `GET/PUT /maintenance` - 查看/切换维护模式
+//! - `GET/PUT /log-level` - 查看/调整日志级别
+//! - `POST /cache/purge` - 调用已注册的缓存清除回调
+//! - `GET /feature-flags`、`PUT /feature-flags/:name` - 查看/覆盖功能开关
+//! - `GET /config` - 导出当前配置（敏感字段会被隐藏）
+
+use crate::{
+    config::{AdminConfig, Config},
+    error::{Error, Result},
+    middleware::constant_time_eq,
+    routing::RouteTable,
+};
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Path, Request, State},
+    http::request::Parts,
+    middleware::{self, Next},
+    response::Response,
+    routing::{get, post, put},
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, RwLock,
+    },
+};
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+/// 运行时调整日志级别的句柄
+pub type LogLevelHandle = reload::Handle<EnvFilter, Registry>;
+
+/// 功能开关
+///
+/// 初始值来自 `[feature_flags]`，可通过管理接口在运行时覆盖。
+/// 可以在处理器中直接作为提取器使用。
+#[derive(Debug, Clone, Default)]
+pub struct FeatureFlags(Arc<RwLock<HashMap<String, bool>>>);
+
+impl FeatureFlags {
+    /// 使用初始值创建功能开关
+    pub fn new(flags: HashMap<String, bool>) -> Self {
+        Self(Arc::new(RwLock::new(flags)))
+    }
+
+    /// 功能是否开启，未配置的功能视为关闭
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.0
+            .read()
+            .map(|flags| flags.get(name).copied().unwrap_or(false))
+            .unwrap_or(false)
+    }
+
+    /// 设置功能开关
+    pub fn set(&self, name: &str, enabled: bool) {
+        if let Ok(mut flags) = self.0.write() {
+            flags.insert(name.to_string(), enabled);
+        }
+    }
+
+    /// 获取所有功能开关的当前值
+    pub fn snapshot(&self) -> HashMap<String, bool> {
+        self.0.read().map(|flags| flags.clone()).unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for FeatureFlags {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+        parts
+            .extensions
+            .get::<FeatureFlags>()
+            .cloned()
+            .ok_or_else(|| Error::Internal("功能开关未注册".to_string()))
+    }
+}
+
+/// 维护模式开关
+#[derive(Debug, Clone, Default)]
+pub struct MaintenanceMode(Arc<AtomicBool>);
+
+impl MaintenanceMode {
+    /// 是否处于维护模式
+    pub fn is_enabled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// 切换维护模式
+    pub fn set(&self, enabled: bool) {
+        self.0.store(enabled, Ordering::Relaxed);
+    }
+}
+
+/// 缓存清除回调列表
+#[derive(Clone, Default)]
+pub struct CachePurgeHooks(Vec<Arc<dyn Fn() + Send + Sync>>);
+
+impl CachePurgeHooks {
+    /// 注册缓存清除回调
+    pub fn push<F: Fn() + Send + Sync + 'static>(&mut self, hook: F) {
+        self.0.push(Arc::new(hook));
+    }
+
+    /// 调用所有回调，返回调用的数量
+    pub fn purge(&self) -> usize {
+        self.0.iter().for_each(|hook| hook());
+        self.0.len()
+    }
+}
+
+impl std::fmt::Debug for CachePurgeHooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CachePurgeHooks").field("len", &self.0.len()).finish()
+    }
+}
+
+/// 管理接口状态
+#[derive(Debug, Clone)]
+pub struct AdminState {
+    config: Arc<Config>,
+    maintenance: MaintenanceMode,
+    flags: FeatureFlags,
+    log_level: Option<LogLevelHandle>,
+    cache_purge: CachePurgeHooks,
+}
+
+impl AdminState {
+    /// 创建管理接口状态
+    pub fn new(
+        config: Config,
+        flags: FeatureFlags,
+        log_level: Option<LogLevelHandle>,
+        cache_purge: CachePurgeHooks,
+    ) -> Self {
+        Self {
+            config: Arc::new(config),
+            maintenance: MaintenanceMode::default(),
+            flags,
+            log_level,
+            cache_purge,
+        }
+    }
+
+    /// 维护模式开关
+    pub fn maintenance(&self) -> &MaintenanceMode {
+        &self.maintenance
+    }
+}
+
+/// 将管理接口挂载到应用上
+///
+/// 启用维护模式功能时，应用路由（不含管理接口）会被维护模式中间件包裹。
+pub fn mount(app: Router, state: AdminState) -> Router {
+    let admin = &state.config.admin;
+    let app = if admin.maintenance {
+        app.layer(middleware::from_fn_with_state(
+            state.maintenance.clone(),
+            maintenance_middleware,
+        ))
+    } else {
+        app
+    };
+
+    tracing::info!("启用管理接口: {}", admin.path);
+    app.nest(&admin.path.clone(), admin_router(state))
+}
+
+/// 记录管理接口的路由，与 [`admin_router`] 保持一致
+pub(crate) fn record_routes(admin: &AdminConfig, routes: &mut RouteTable) {
+    let base = admin.path.trim_end_matches('/');
+    let enabled = [
+        (admin.maintenance, &[("GET", "/maintenance"), ("PUT", "/maintenance")][..]),
+        (admin.log_level, &[("GET", "/log-level"), ("PUT", "/log-level")]),
+        (admin.cache_purge, &[("POST", "/cache/purge")]),
+        (admin.feature_flags, &[("GET", "/feature-flags"), ("PUT", "/feature-flags/:name")]),
+        (admin.config_dump, &[("GET", "/config")]),
+    ];
+    for (_, entries) in enabled.iter().filter(|(enabled, _)| *enabled) {
+        for (method, path) in *entries {
+            routes.add(method, &format!("{}{}", base, path));
+        }
+    }
+}
+
+/// 创建管理接口路由（不含路径前缀）
+pub fn admin_router(state: AdminState) -> Router {
+    let admin = &state.config.admin;
+    let mut router = Router::new();
+
+    if admin.maintenance {
+        router = router.route("/maintenance", get(get_maintenance).put(put_maintenance));
+    }
+    if admin.log_level {
+        router = router.route("/log-level", get(get_log_level).put(put_log_level));
+    }
+    if admin.cache_purge {
+        router = router.route("/cache/purge", post(purge_cache));
+    }
+    if admin.feature_flags {
+        router = router
+            .route("/feature-flags", get(get_feature_flags))
+            .route("/feature-flags/:name", put(put_feature_flag));
+    }
+    if admin.config_dump {
+        router = router.route("/config", get(dump_config));
+    }
+
+    let token = Arc::new(admin.effective_token().to_string());
+    router
+        .route_layer(middleware::from_fn_with_state(token, admin_auth))
+        .layer(Extension(state))
+}
+
+/// 管理接口认证中间件
+async fn admin_auth(
+    State(token): State<Arc<String>>,
+    request: Request,
+    next: Next,
+) -> Result<Response> {
+    let provided = request
+        .headers()
+        .get("authorization")
+        .and_then(|header| header.to_str().ok())
+        .and_then(|header| header.strip_prefix("Bearer "))
+        .unwrap_or_default();
+
+    if token.is_empty() || !constant_time_eq(provided.as_bytes(), token.as_bytes()) {
+        return Err(Error::Unauthorized("无效的管理接口令牌".to_string()));
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// 维护模式中间件
+pub async fn maintenance_middleware(
+    State(maintenance): State<MaintenanceMode>,
+    request: Request,
+    next: Next,
+) -> Result<Response> {
+    if maintenance.is_enabled() {
+        return Err(Error::ServiceUnavailable("服务维护中，请稍后再试".to_string()));
+    }
+
+    Ok(next.run(request).await)
+}
+
+#[derive(Deserialize)]
+struct ToggleRequest {
+    enabled: bool,
+}
+
+#[derive(Deserialize)]
+struct LogLevelRequest {
+    level: String,
+}
+
+async fn get_maintenance(Extension(state): Extension<AdminState>) -> Json<Value> {
+    Json(json!({ "enabled": state.maintenance.is_enabled() }))
+}
+
+async fn put_maintenance(
+    Extension(state): Extension<AdminState>,
+    Json(body): Json<ToggleRequest>,
+) -> Json<Value> {
+    state.maintenance.set(body.enabled);
+    tracing::warn!("维护模式已{}", if body.enabled { "开启" } else { "关闭" });
+    Json(json!({ "enabled": body.enabled }))
+}
+
+async fn get_log_level(Extension(state): Extension<AdminState>) -> Result<Json<Value>> {
+    let handle = log_level_handle(&state)?;
+    let level = handle
+        .with_current(|filter| filter.to_string())
+        .map_err(|e| Error::Internal(format!("无法读取日志级别: {}", e)))?;
+    Ok(Json(json!({ "level": level })))
+}
+
+async fn put_log_level(
+    Extension(state): Extension<AdminState>,
+    Json(body): Json<LogLevelRequest>,
+) -> Result<Json<Value>> {
+    let handle = log_level_handle(&state)?;
+    let filter = EnvFilter::try_new(&body.level)
+        .map_err(|e| Error::BadRequest(format!("无效的日志级别 '{}': {}", body.level, e)))?;
+    handle
+        .reload(filter)
+        .map_err(|e| Error::Internal(format!("无法更新日志级别: {}", e)))?;
+
+    tracing::warn!("日志级别已调整为: {}", body.level);
+    Ok(Json(json!({ "level": body.level })))
+}
+
+fn log_level_handle(state: &AdminState) -> Result<&LogLevelHandle> {
+    state
+        .log_level
+        .as_ref()
+        .ok_or_else(|| Error::Internal("日志系统不支持运行时调整级别".to_string()))
+}
+
+async fn purge_cache(Extension(state): Extension<AdminState>) -> Json<Value> {
+    let purged = state.cache_purge.purge();
+    tracing::info!("已清除缓存: {} 个回调", purged);
+    Json(json!({ "purged": purged }))
+}
+
+async fn get_feature_flags(Extension(state): Extension<AdminState>) -> Json<HashMap<String, bool>> {
+    Json(state.flags.snapshot())
+}
+
+async fn put_feature_flag(
+    Extension(state): Extension<AdminState>,
+    Path(name): Path<String>,
+    Json(body): Json<ToggleRequest>,
+) -> Json<Value> {
+    state.flags.set(&name, body.enabled);
+    tracing::info!("功能开关 {} 已设置为 {}", name, body.enabled);
+    Json(json!({ "name": name, "enabled": body.enabled }))
+}
+
+async fn dump_config(Extension(state): Extension<AdminState>) -> Result<Json<Value>> {
+    let mut value = serde_json::to_value(&*state.config)?;
+    redact(&mut value, &["middleware", "jwt", "secret"]);
+    redact(&mut value, &["middleware", "session", "secret"]);
+    redact(&mut value, &["admin", "token"]);
+    redact(&mut value, &["cdn", "api_token"]);
+    redact(&mut value, &["docs", "password"]);
+    Ok(Json(value))
+}
+
+/// 隐藏配置中的敏感字段
+fn redact(value: &mut Value, path: &[&str]) {
+    let pointer = format!("/{}", path.join("/"));
+    if let Some(field) = value.pointer_mut(&pointer) {
+        if field.as_str().is_some_and(|s| !s.is_empty()) {
+            *field = Value::String("******".to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::StatusCode};
+    use tower::util::ServiceExt;
+
+    fn test_app() -> (Router, AdminState) {
+        let mut config = Config::default();
+        config.admin.enabled = true;
+        config.admin.token = "admin-token".to_string();
+        config.middleware.jwt.secret = "jwt-secret".to_string();
+
+        let flags = FeatureFlags::new(HashMap::from([("new_ui".to_string(), false)]));
+        let state = AdminState::new(config, flags, None, CachePurgeHooks::default());
+        let app = Router::new().route("/", get(|| async { "ok" }));
+        (mount(app, state.clone()), state)
+    }
+
+    fn admin_request(method: &str, uri: &str, body: Option<Value>) -> axum::http::Request<Body> {
+        let builder = axum::http::Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("authorization", "Bearer admin-token");
+        match body {
+            Some(body) => builder
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+            None => builder.body(Body::empty()).unwrap(),
+        }
+    }
+
+    async fn body_json(response: Response) -> Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_admin_requires_token() {
+        let (app, _) = test_app();
+        let request = axum::http::Request::builder()
+            .uri("/__admin/config")
+            .header("authorization", "Bearer wrong")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_maintenance_toggle() {
+        let (app, state) = test_app();
+
+        let response = app
+            .clone()
+            .oneshot(admin_request("PUT", "/__admin/maintenance", Some(json!({ "enabled": true }))))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(state.maintenance().is_enabled());
+
+        let request = axum::http::Request::builder().uri("/").body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn test_feature_flag_override_and_config_dump() {
+        let (app, state) = test_app();
+
+        let response = app
+            .clone()
+            .oneshot(admin_request("PUT", "/__admin/feature-flags/new_ui", Some(json!({ "enabled": true }))))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(state.flags.is_enabled("new_ui"));
+
+        let response = app.oneshot(admin_request("GET", "/__admin/config", None)).await.unwrap();
+        let config = body_json(response).await;
+        assert_eq!(config["middleware"]["jwt"]["secret"], "******");
+        assert_eq!(config["admin"]["token"], "******");
+    }
+
+    #[tokio::test]
+    async fn test_disabled_endpoint_not_mounted() {
+        let mut config = Config::default();
+        config.admin.token = "admin-token".to_string();
+        config.admin.config_dump = false;
+        let state = AdminState::new(config, FeatureFlags::default(), None, CachePurgeHooks::default());
+        let app = mount(Router::new(), state);
+
+        let response = app.oneshot(admin_request("GET", "/__admin/config", None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
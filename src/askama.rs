@@ -0,0 +1,94 @@
+//! Askama 编译期模板模块
+//!
+//! 模板在编译时检查并生成渲染代码，运行时不需要模板目录。处理函数返回 [`Askama`]，
+//! 渲染失败时转换为 [`Error::Askama`](crate::Error::Askama) 并返回 500：
+//!
+//! ```rust
+//! use hwhkit::askama::{Askama, Template};
+//!
+//! // 未直接依赖 askama 时需要通过 askama 参数指向重新导出的路径
+//! #[derive(Template)]
+//! #[template(source = "<h1>你好，{{ name }}</h1>", ext = "html", askama = hwhkit::askama::askama)]
+//! struct Hello {
+//!     name: String,
+//! }
+//!
+//! async fn hello() -> Askama<Hello> {
+//!     Askama(Hello { name: "世界".to_string() })
+//! }
+//! ```
+//!
+//! 在 Full 架构下使用 [`WebServerBuilder::askama_templates`](crate::WebServerBuilder::askama_templates)
+//! 或 `[middleware.templates] engine = "askama"` 声明使用编译期模板，构建时不再检查模板目录。
+
+use crate::error::Result;
+use axum::response::{Html, IntoResponse, Response};
+
+pub use ::askama::{self, Template};
+
+/// 渲染 Askama 模板的响应
+#[derive(Debug, Clone)]
+pub struct Askama<T>(pub T);
+
+impl<T: Template> Askama<T> {
+    /// 渲染为 HTML 字符串
+    pub fn render(&self) -> Result<String> {
+        Ok(self.0.render()?)
+    }
+}
+
+impl<T: Template> IntoResponse for Askama<T> {
+    fn into_response(self) -> Response {
+        match self.render() {
+            Ok(html) => Html(html).into_response(),
+            Err(e) => {
+                tracing::error!("模板渲染失败: {}", e);
+                e.into_response()
+            }
+        }
+    }
+}
+
+impl<T: Template> From<T> for Askama<T> {
+    fn from(template: T) -> Self {
+        Self(template)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use axum::http::{header, StatusCode};
+
+    #[derive(Template)]
+    #[template(source = "<p>{{ name }}</p>", ext = "html")]
+    struct Greeting<'a> {
+        name: &'a str,
+    }
+
+    #[derive(Template)]
+    #[template(source = "{{ self.total()? }}", ext = "txt")]
+    struct Failing;
+
+    impl Failing {
+        fn total(&self) -> ::askama::Result<u32> {
+            Err(::askama::Error::Fmt)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_render() {
+        let response = Askama(Greeting { name: "<b>alice</b>" }).into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "text/html; charset=utf-8");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"<p>&#60;b&#62;alice&#60;/b&#62;</p>");
+    }
+
+    #[test]
+    fn test_render_error() {
+        assert!(matches!(Askama(Failing).render(), Err(Error::Askama(_))));
+        assert_eq!(Askama(Failing).into_response().status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -0,0 +1,216 @@
+//! 静态资源处理模块
+//!
+//! 在提供静态文件之前，将源文件目录中的 SCSS、TypeScript 等文件交给外部命令
+//! （如 `sass`、`esbuild`）转换或压缩，输出到静态文件目录。启动时处理一次，
+//! 开启 `watch` 后定期检查源文件变化并重新处理。
+//!
+//! ```toml
+//! [middleware.static_files.pipeline]
+//! enabled = true
+//! source_dir = "assets"
+//! watch = true
+//!
+//! [[middleware.static_files.pipeline.rules]]
+//! extension = "scss"
+//! output_extension = "css"
+//! command = ["sass", "--no-source-map", "{input}", "{output}"]
+//! ```
+
+use crate::{
+    config::{AssetPipelineConfig, AssetRule, StaticConfig},
+    error::{Error, Result},
+};
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tokio::{process::Command, task::JoinHandle};
+
+/// 静态资源处理流水线
+#[derive(Debug, Clone)]
+pub struct AssetPipeline {
+    config: AssetPipelineConfig,
+    output_dir: PathBuf,
+}
+
+impl AssetPipeline {
+    /// 根据静态文件配置创建流水线，输出到静态文件目录
+    pub fn new(config: &StaticConfig) -> Self {
+        Self {
+            config: config.pipeline.clone(),
+            output_dir: PathBuf::from(&config.dir),
+        }
+    }
+
+    /// 处理所有输出缺失或已过期的源文件，返回处理的文件数
+    ///
+    /// # Errors
+    ///
+    /// 源文件目录不存在或命令执行失败时返回错误
+    pub async fn run(&self) -> Result<usize> {
+        let source_dir = Path::new(&self.config.source_dir);
+        if !source_dir.is_dir() {
+            return Err(Error::Config(format!(
+                "资源源文件目录不存在: {}",
+                self.config.source_dir
+            )));
+        }
+
+        let mut processed = 0;
+        for input in collect_files(source_dir)? {
+            let Some(rule) = self.rule_for(&input) else {
+                continue;
+            };
+
+            let relative = input.strip_prefix(source_dir).unwrap_or(&input);
+            let output = self
+                .output_dir
+                .join(relative)
+                .with_extension(&rule.output_extension);
+            if !is_stale(&input, &output) {
+                continue;
+            }
+
+            process(rule, &input, &output).await?;
+            tracing::debug!("资源处理完成: {} -> {}", input.display(), output.display());
+            processed += 1;
+        }
+
+        Ok(processed)
+    }
+
+    /// 在后台定期检查源文件变化并重新处理
+    pub fn watch(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(Duration::from_millis(self.config.poll_interval_ms.max(100)));
+
+            loop {
+                interval.tick().await;
+                match self.run().await {
+                    Ok(0) => {}
+                    Ok(count) => tracing::info!("🎨 重新处理了 {} 个资源文件", count),
+                    Err(e) => tracing::warn!("资源处理失败: {}", e),
+                }
+            }
+        })
+    }
+
+    fn rule_for(&self, input: &Path) -> Option<&AssetRule> {
+        let extension = input.extension()?.to_str()?;
+        self.config
+            .rules
+            .iter()
+            .find(|rule| rule.extension.eq_ignore_ascii_case(extension))
+    }
+}
+
+/// 递归列出目录中的文件
+fn collect_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(collect_files(&path)?);
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+/// 输出文件不存在或早于源文件时需要重新处理
+fn is_stale(input: &Path, output: &Path) -> bool {
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    match (modified(input), modified(output)) {
+        (Some(input), Some(output)) => output < input,
+        _ => true,
+    }
+}
+
+/// 执行处理命令
+async fn process(rule: &AssetRule, input: &Path, output: &Path) -> Result<()> {
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let input = input.to_string_lossy();
+    let output = output.to_string_lossy();
+    let args: Vec<String> = rule
+        .command
+        .iter()
+        .map(|arg| arg.replace("{input}", &input).replace("{output}", &output))
+        .collect();
+    let (program, args) = args
+        .split_first()
+        .ok_or_else(|| Error::Config(format!("资源处理规则 '{}' 缺少命令", rule.extension)))?;
+
+    let result = Command::new(program)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| Error::Internal(format!("无法执行资源处理命令 '{}': {}", program, e)))?;
+
+    if !result.status.success() {
+        return Err(Error::Internal(format!(
+            "资源处理命令失败 ({}): {} {}",
+            result.status,
+            input,
+            String::from_utf8_lossy(&result.stderr).trim()
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn pipeline(root: &Path, command: &[&str]) -> AssetPipeline {
+        std::fs::create_dir_all(root.join("assets/css")).unwrap();
+        AssetPipeline::new(&StaticConfig {
+            enabled: true,
+            dir: root.join("static").to_string_lossy().to_string(),
+            pipeline: AssetPipelineConfig {
+                enabled: true,
+                source_dir: root.join("assets").to_string_lossy().to_string(),
+                rules: vec![AssetRule {
+                    extension: "scss".to_string(),
+                    output_extension: "css".to_string(),
+                    command: command.iter().map(|s| s.to_string()).collect(),
+                }],
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_processes_stale_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let pipeline = pipeline(root, &["cp", "{input}", "{output}"]);
+        std::fs::write(root.join("assets/css/site.scss"), "body {}").unwrap();
+        std::fs::write(root.join("assets/readme.txt"), "ignored").unwrap();
+
+        assert_eq!(pipeline.run().await.unwrap(), 1);
+        let output = root.join("static/css/site.css");
+        assert_eq!(std::fs::read_to_string(&output).unwrap(), "body {}");
+        assert!(!root.join("static/readme.txt").exists());
+
+        // 输出已是最新，不会重复处理
+        assert_eq!(pipeline.run().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_command_failure() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let pipeline = pipeline(root, &["false"]);
+        std::fs::write(root.join("assets/css/site.scss"), "body {}").unwrap();
+
+        assert!(pipeline.run().await.is_err());
+    }
+}
@@ -0,0 +1,5 @@
+//! 认证模块
+
+#[cfg(feature = "oauth")]
+pub mod oauth;
+pub mod rbac;
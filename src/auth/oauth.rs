@@ -0,0 +1,519 @@
+//! OAuth2 / OpenID Connect 登录模块
+//!
+//! 通过 `WebServerBuilder::oauth_provider` 注册登录方式后，自动挂载
+//! `/auth/{provider}/login` 和 `/auth/{provider}/callback`。登录成功后用户身份保存在
+//! 会话中，处理器通过 `OAuthIdentity` 提取器读取。需要启用 `oauth` feature 和会话。
+//!
+//! ```rust,no_run
+//! use hwhkit::{auth::oauth::{OAuthIdentity, OAuthProvider}, get, Router, WebServerBuilder};
+//!
+//! async fn me(identity: OAuthIdentity) -> String {
+//!     identity.name.unwrap_or(identity.subject)
+//! }
+//!
+//! let builder = WebServerBuilder::new()
+//!     .routes(Router::new().route("/me", get(me)))
+//!     .oauth_provider(OAuthProvider::github(
+//!         "client-id",
+//!         "client-secret",
+//!         "https://example.com/auth/github/callback",
+//!     ));
+//! ```
+
+use crate::{
+    config::OAuthConfig,
+    error::{Error, Result},
+    middleware::{constant_time_eq, session::Session, trace_context::TraceContext},
+};
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Path, Query, State},
+    http::{header, request::Parts},
+    response::Redirect,
+    routing::get,
+    Router,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt, sync::Arc};
+
+/// 会话中保存登录身份的键
+const IDENTITY_KEY: &str = "oauth_identity";
+/// 会话中保存进行中登录的键
+const PENDING_KEY: &str = "oauth_pending";
+
+/// OAuth2 / OIDC 登录方式
+#[derive(Clone)]
+pub struct OAuthProvider {
+    name: String,
+    client_id: String,
+    client_secret: String,
+    redirect_url: String,
+    auth_url: String,
+    token_url: String,
+    userinfo_url: String,
+    scopes: Vec<String>,
+    issuer: Option<String>,
+}
+
+impl OAuthProvider {
+    /// 创建登录方式，需要再通过 `endpoints` 设置授权、令牌和用户信息地址
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - 名称，用于路由路径
+    /// * `redirect_url` - 回调地址，即 `https://<域名>/auth/<name>/callback`
+    pub fn new(name: &str, client_id: &str, client_secret: &str, redirect_url: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            client_id: client_id.to_string(),
+            client_secret: client_secret.to_string(),
+            redirect_url: redirect_url.to_string(),
+            auth_url: String::new(),
+            token_url: String::new(),
+            userinfo_url: String::new(),
+            scopes: Vec::new(),
+            issuer: None,
+        }
+    }
+
+    /// Google 登录
+    pub fn google(client_id: &str, client_secret: &str, redirect_url: &str) -> Self {
+        Self::new("google", client_id, client_secret, redirect_url)
+            .endpoints(
+                "https://accounts.google.com/o/oauth2/v2/auth",
+                "https://oauth2.googleapis.com/token",
+                "https://openidconnect.googleapis.com/v1/userinfo",
+            )
+            .scopes(&["openid", "email", "profile"])
+    }
+
+    /// GitHub 登录
+    pub fn github(client_id: &str, client_secret: &str, redirect_url: &str) -> Self {
+        Self::new("github", client_id, client_secret, redirect_url)
+            .endpoints(
+                "https://github.com/login/oauth/authorize",
+                "https://github.com/login/oauth/access_token",
+                "https://api.github.com/user",
+            )
+            .scopes(&["read:user", "user:email"])
+    }
+
+    /// 通用 OpenID Connect 登录，启动时从 `{issuer}/.well-known/openid-configuration` 读取端点
+    pub fn oidc(name: &str, issuer: &str, client_id: &str, client_secret: &str, redirect_url: &str) -> Self {
+        let mut provider = Self::new(name, client_id, client_secret, redirect_url)
+            .scopes(&["openid", "email", "profile"]);
+        provider.issuer = Some(issuer.trim_end_matches('/').to_string());
+        provider
+    }
+
+    /// 设置授权、令牌和用户信息地址
+    pub fn endpoints(mut self, auth_url: &str, token_url: &str, userinfo_url: &str) -> Self {
+        self.auth_url = auth_url.to_string();
+        self.token_url = token_url.to_string();
+        self.userinfo_url = userinfo_url.to_string();
+        self
+    }
+
+    /// 设置申请的权限范围
+    pub fn scopes(mut self, scopes: &[&str]) -> Self {
+        self.scopes = scopes.iter().map(|s| s.to_string()).collect();
+        self
+    }
+
+    /// 名称
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 从 OIDC 发现文档读取端点
+    async fn discover(&mut self, client: &reqwest::Client) -> Result<()> {
+        #[derive(Deserialize)]
+        struct Discovery {
+            authorization_endpoint: String,
+            token_endpoint: String,
+            userinfo_endpoint: String,
+        }
+
+        let Some(issuer) = &self.issuer else {
+            return Ok(());
+        };
+        if !self.auth_url.is_empty() {
+            return Ok(());
+        }
+
+        let url = format!("{}/.well-known/openid-configuration", issuer);
+        let discovery: Discovery = client
+            .get(&url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| Error::Config(format!("无法读取 OIDC 发现文档 {}: {}", url, e)))?
+            .json()
+            .await
+            .map_err(|e| Error::Config(format!("无效的 OIDC 发现文档 {}: {}", url, e)))?;
+
+        self.auth_url = discovery.authorization_endpoint;
+        self.token_url = discovery.token_endpoint;
+        self.userinfo_url = discovery.userinfo_endpoint;
+        Ok(())
+    }
+
+    fn authorize_url(&self, state: &str) -> Result<String> {
+        let query = serde_urlencoded::to_string([
+            ("response_type", "code"),
+            ("client_id", self.client_id.as_str()),
+            ("redirect_uri", self.redirect_url.as_str()),
+            ("scope", self.scopes.join(" ").as_str()),
+            ("state", state),
+        ])
+        .map_err(|e| Error::Internal(format!("无法生成授权地址: {}", e)))?;
+
+        let separator = if self.auth_url.contains('?') { '&' } else { '?' };
+        Ok(format!("{}{}{}", self.auth_url, separator, query))
+    }
+}
+
+impl fmt::Debug for OAuthProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OAuthProvider")
+            .field("name", &self.name)
+            .field("client_id", &self.client_id)
+            .field("auth_url", &self.auth_url)
+            .field("issuer", &self.issuer)
+            .finish_non_exhaustive()
+    }
+}
+
+/// 登录用户身份
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OAuthIdentity {
+    /// 登录方式名称
+    pub provider: String,
+    /// 用户在登录方式中的唯一标识（OIDC `sub`，GitHub `id`）
+    pub subject: String,
+    /// 邮箱
+    pub email: Option<String>,
+    /// 显示名称
+    pub name: Option<String>,
+    /// 用户信息接口返回的原始数据
+    pub claims: serde_json::Value,
+}
+
+impl OAuthIdentity {
+    fn from_claims(provider: &str, claims: serde_json::Value) -> Result<Self> {
+        let text = |key: &str| claims.get(key).and_then(|v| v.as_str()).map(str::to_string);
+
+        let subject = match claims.get("sub").or_else(|| claims.get("id")) {
+            Some(serde_json::Value::String(s)) => s.clone(),
+            Some(serde_json::Value::Number(n)) => n.to_string(),
+            _ => return Err(Error::Unauthorized("用户信息中缺少用户标识".to_string())),
+        };
+
+        Ok(Self {
+            provider: provider.to_string(),
+            subject,
+            email: text("email"),
+            name: text("name").or_else(|| text("login")),
+            claims,
+        })
+    }
+
+    /// 退出登录
+    pub fn logout(session: &Session) {
+        session.remove(IDENTITY_KEY);
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for OAuthIdentity {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self> {
+        let session = Session::from_request_parts(parts, state).await?;
+        session
+            .get(IDENTITY_KEY)
+            .ok_or_else(|| Error::Unauthorized("未登录".to_string()))
+    }
+}
+
+/// 进行中的登录
+#[derive(Serialize, Deserialize)]
+struct PendingLogin {
+    provider: String,
+    state: String,
+}
+
+struct OAuthState {
+    providers: HashMap<String, OAuthProvider>,
+    client: reqwest::Client,
+    after_login: String,
+}
+
+impl OAuthState {
+    fn provider(&self, name: &str) -> Result<&OAuthProvider> {
+        self.providers
+            .get(name)
+            .ok_or_else(|| Error::BadRequest(format!("未知的登录方式: {}", name)))
+    }
+}
+
+/// 挂载登录路由
+///
+/// OIDC 登录方式会在此时读取发现文档
+pub async fn mount(app: Router, config: &OAuthConfig, providers: Vec<OAuthProvider>) -> Result<Router> {
+    let client = reqwest::Client::builder()
+        .user_agent(concat!("hwhkit/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| Error::Config(format!("无法创建 HTTP 客户端: {}", e)))?;
+
+    let mut registered = HashMap::new();
+    for mut provider in providers {
+        provider.discover(&client).await?;
+        if provider.auth_url.is_empty() || provider.token_url.is_empty() {
+            return Err(Error::Config(format!("登录方式 '{}' 缺少端点配置", provider.name)));
+        }
+        tracing::info!("启用登录方式: {}", provider.name);
+        registered.insert(provider.name.clone(), provider);
+    }
+
+    let state = Arc::new(OAuthState {
+        providers: registered,
+        client,
+        after_login: config.after_login.clone(),
+    });
+    let base = config.path.trim_end_matches('/');
+    let router = Router::new()
+        .route(&format!("{}/:provider/login", base), get(login))
+        .route(&format!("{}/:provider/callback", base), get(callback))
+        .with_state(state);
+
+    Ok(app.merge(router))
+}
+
+async fn login(
+    State(state): State<Arc<OAuthState>>,
+    Path(provider): Path<String>,
+    session: Session,
+) -> Result<Redirect> {
+    let provider = state.provider(&provider)?;
+    let csrf_state: String = (0..32).map(|_| format!("{:02x}", fastrand::u8(..))).collect();
+    session.insert(
+        PENDING_KEY,
+        PendingLogin {
+            provider: provider.name.clone(),
+            state: csrf_state.clone(),
+        },
+    )?;
+
+    Ok(Redirect::to(&provider.authorize_url(&csrf_state)?))
+}
+
+#[derive(Deserialize)]
+struct CallbackQuery {
+    code: Option<String>,
+    state: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+async fn callback(
+    State(state): State<Arc<OAuthState>>,
+    Path(provider): Path<String>,
+    Query(query): Query<CallbackQuery>,
+    session: Session,
+) -> Result<Redirect> {
+    let provider = state.provider(&provider)?;
+    if let Some(error) = query.error {
+        return Err(Error::Unauthorized(format!("授权失败: {}", error)));
+    }
+
+    // 校验 state，防止登录 CSRF
+    let pending: Option<PendingLogin> = session.get(PENDING_KEY);
+    session.remove(PENDING_KEY);
+    let valid = match (&pending, &query.state) {
+        (Some(pending), Some(received)) => {
+            pending.provider == provider.name
+                && constant_time_eq(pending.state.as_bytes(), received.as_bytes())
+        }
+        _ => false,
+    };
+    if !valid {
+        return Err(Error::Forbidden("OAuth state 校验失败".to_string()));
+    }
+    let code = query
+        .code
+        .ok_or_else(|| Error::BadRequest("缺少授权码".to_string()))?;
+
+    let token: TokenResponse = state
+        .client
+        .post(&provider.token_url)
+        .header(header::ACCEPT, "application/json")
+        .form(&[
+            ("grant_type", "authorization_code"),
+            ("code", code.as_str()),
+            ("redirect_uri", provider.redirect_url.as_str()),
+            ("client_id", provider.client_id.as_str()),
+            ("client_secret", provider.client_secret.as_str()),
+        ])
+        .headers(TraceContext::outgoing_headers())
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| Error::Unauthorized(format!("无法获取访问令牌: {}", e)))?
+        .json()
+        .await
+        .map_err(|e| Error::Unauthorized(format!("无效的令牌响应: {}", e)))?;
+
+    let claims: serde_json::Value = state
+        .client
+        .get(&provider.userinfo_url)
+        .bearer_auth(&token.access_token)
+        .header(header::ACCEPT, "application/json")
+        .headers(TraceContext::outgoing_headers())
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| Error::Unauthorized(format!("无法获取用户信息: {}", e)))?
+        .json()
+        .await
+        .map_err(|e| Error::Unauthorized(format!("无效的用户信息: {}", e)))?;
+
+    let identity = OAuthIdentity::from_claims(&provider.name, claims)?;
+    tracing::info!("用户通过 {} 登录: {}", identity.provider, identity.subject);
+    session.insert(IDENTITY_KEY, identity)?;
+
+    Ok(Redirect::to(&state.after_login))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::SessionConfig, middleware::session::apply_session};
+    use axum::{
+        body::Body,
+        http::{HeaderMap, Request, StatusCode},
+        routing::post,
+        Json,
+    };
+    use tower::util::ServiceExt;
+
+    /// 启动模拟的 OIDC 服务，返回 issuer 地址
+    async fn mock_issuer() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+
+        let discovery = serde_json::json!({
+            "authorization_endpoint": format!("{}/authorize", issuer),
+            "token_endpoint": format!("{}/token", issuer),
+            "userinfo_endpoint": format!("{}/userinfo", issuer),
+        });
+        let mock = Router::new()
+            .route(
+                "/.well-known/openid-configuration",
+                get(move || async move { Json(discovery) }),
+            )
+            .route(
+                "/token",
+                post(|body: String| async move {
+                    assert!(body.contains("code=good-code"));
+                    Json(serde_json::json!({ "access_token": "access-123" }))
+                }),
+            )
+            .route(
+                "/userinfo",
+                get(|headers: HeaderMap| async move {
+                    assert_eq!(headers["authorization"], "Bearer access-123");
+                    Json(serde_json::json!({ "sub": "u-1", "email": "alice@example.com", "name": "Alice" }))
+                }),
+            );
+        tokio::spawn(async move { axum::serve(listener, mock).await.unwrap() });
+        issuer
+    }
+
+    async fn app() -> Router {
+        let issuer = mock_issuer().await;
+        let provider = OAuthProvider::oidc("mock", &issuer, "id", "secret", "http://app/auth/mock/callback");
+        let router = Router::new().route(
+            "/me",
+            get(|identity: OAuthIdentity| async move { Json(identity) }),
+        );
+        let router = mount(router, &OAuthConfig::default(), vec![provider]).await.unwrap();
+        let session = SessionConfig {
+            enabled: true,
+            secret: "0123456789abcdef0123456789abcdef".to_string(),
+            ..Default::default()
+        };
+        apply_session(router, &session, &Default::default(), None).unwrap()
+    }
+
+    async fn get_page(app: &Router, uri: &str, cookie: Option<&str>) -> (StatusCode, HeaderMap, String) {
+        let mut request = Request::builder().uri(uri);
+        if let Some(cookie) = cookie {
+            request = request.header(header::COOKIE, cookie);
+        }
+        let response = app.clone().oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+        let (parts, body) = response.into_parts();
+        let body = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+        (parts.status, parts.headers, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    fn session_cookie(headers: &HeaderMap) -> String {
+        headers[header::SET_COOKIE].to_str().unwrap().split(';').next().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_oidc_login_flow() {
+        let app = app().await;
+
+        let (status, _, _) = get_page(&app, "/me", None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, headers, _) = get_page(&app, "/auth/mock/login", None).await;
+        assert_eq!(status, StatusCode::SEE_OTHER);
+        let location = headers[header::LOCATION].to_str().unwrap();
+        assert!(location.contains("/authorize?response_type=code&client_id=id"));
+        let state = location.split("state=").nth(1).unwrap().to_string();
+        let cookie = session_cookie(&headers);
+
+        let callback = format!("/auth/mock/callback?code=good-code&state={}", state);
+        let (status, headers, _) = get_page(&app, &callback, Some(&cookie)).await;
+        assert_eq!(status, StatusCode::SEE_OTHER);
+        assert_eq!(headers[header::LOCATION], "/");
+        let cookie = session_cookie(&headers);
+
+        let (status, _, body) = get_page(&app, "/me", Some(&cookie)).await;
+        assert_eq!(status, StatusCode::OK);
+        let identity: OAuthIdentity = serde_json::from_str(&body).unwrap();
+        assert_eq!(identity.provider, "mock");
+        assert_eq!(identity.subject, "u-1");
+        assert_eq!(identity.email.as_deref(), Some("alice@example.com"));
+    }
+
+    #[tokio::test]
+    async fn test_callback_rejects_wrong_state() {
+        let app = app().await;
+
+        let (_, headers, _) = get_page(&app, "/auth/mock/login", None).await;
+        let cookie = session_cookie(&headers);
+
+        let (status, _, _) =
+            get_page(&app, "/auth/mock/callback?code=good-code&state=forged", Some(&cookie)).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    fn test_identity_from_github_claims() {
+        let identity = OAuthIdentity::from_claims(
+            "github",
+            serde_json::json!({ "id": 42, "login": "octocat", "email": null }),
+        )
+        .unwrap();
+        assert_eq!(identity.subject, "42");
+        assert_eq!(identity.name.as_deref(), Some("octocat"));
+        assert_eq!(identity.email, None);
+    }
+}
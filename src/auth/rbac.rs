@@ -0,0 +1,291 @@
+//! 基于角色的访问控制模块
+//!
+//! 角色和权限在 `[middleware.rbac]` 中声明，或通过 `WebServerBuilder::role` 在代码中添加。
+//! 当前用户的角色从 JWT 声明（`claim`，默认 `roles`）读取，没有 JWT 时从会话
+//! （`session_key`，默认 `roles`）读取。
+//!
+//! 使用 [`require_role`] 或 [`require_permission`] 保护路由：
+//!
+//! ```rust
+//! use hwhkit::auth::rbac::{require_permission, require_role};
+//! use axum::{routing::{delete, get}, Router};
+//!
+//! let admin: Router = Router::new()
+//!     .route("/admin/users", get(|| async { "users" }))
+//!     .route_layer(require_role("admin"));
+//! let articles: Router = Router::new()
+//!     .route("/articles/:id", delete(|| async { "deleted" }))
+//!     .route_layer(require_permission("articles:write"));
+//! ```
+//!
+//! 未认证的请求返回 401，缺少角色或权限时返回 403，响应体为标准 JSON 错误格式。
+
+use crate::{
+    config::RbacConfig,
+    error::{Error, Result},
+    middleware::jwt::Claims,
+};
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request},
+    http::{request::Parts, Extensions},
+    response::{IntoResponse, Response},
+};
+use futures_util::future::{self, Either, Ready};
+use std::{
+    collections::HashSet,
+    convert::Infallible,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tower::{Layer, Service};
+
+/// 表示全部权限的通配符
+pub const ALL_PERMISSIONS: &str = "*";
+
+/// 角色和权限定义
+///
+/// 构建服务器时作为请求扩展提供，未提供时使用默认配置（只能按角色检查）
+#[derive(Debug, Clone, Default)]
+pub struct Rbac {
+    config: RbacConfig,
+}
+
+impl Rbac {
+    /// 根据配置创建
+    pub fn new(config: &RbacConfig) -> Self {
+        Self {
+            config: config.clone(),
+        }
+    }
+
+    /// 角色集合是否拥有指定权限
+    pub fn has_permission<R: AsRef<str>>(&self, roles: &[R], permission: &str) -> bool {
+        roles
+            .iter()
+            .filter_map(|role| self.config.roles.get(role.as_ref()))
+            .flatten()
+            .any(|granted| granted == ALL_PERMISSIONS || granted == permission)
+    }
+
+    /// 从请求扩展中读取当前用户的角色
+    ///
+    /// 优先使用 JWT 声明，其次使用会话。两者都不存在时返回 `None`（未认证）
+    pub fn roles(&self, extensions: &Extensions) -> Option<Vec<String>> {
+        if let Some(claims) = extensions.get::<Claims>() {
+            let roles = claims
+                .claim::<Vec<String>>(&self.config.claim)
+                .or_else(|| claims.claim::<String>(&self.config.claim).map(|role| vec![role]))
+                .unwrap_or_default();
+            return Some(roles);
+        }
+
+        #[cfg(feature = "sessions")]
+        if let Some(session) = extensions.get::<crate::middleware::session::Session>() {
+            return session.get::<Vec<String>>(&self.config.session_key);
+        }
+
+        None
+    }
+}
+
+/// 当前用户的角色
+///
+/// 处理函数可以直接提取，未认证时返回 401
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Roles(pub Vec<String>);
+
+impl Roles {
+    /// 是否拥有指定角色
+    pub fn contains(&self, role: &str) -> bool {
+        self.0.iter().any(|r| r == role)
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Roles {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+        rbac(&parts.extensions)
+            .roles(&parts.extensions)
+            .map(Roles)
+            .ok_or_else(|| Error::Unauthorized("请求未经过认证".to_string()))
+    }
+}
+
+fn rbac(extensions: &Extensions) -> Arc<Rbac> {
+    extensions.get::<Arc<Rbac>>().cloned().unwrap_or_default()
+}
+
+/// 访问要求
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Requirement {
+    /// 拥有任一角色
+    AnyRole(HashSet<String>),
+    /// 拥有权限
+    Permission(String),
+}
+
+impl Requirement {
+    fn check(&self, extensions: &Extensions) -> Result<()> {
+        let rbac = rbac(extensions);
+        let roles = rbac
+            .roles(extensions)
+            .ok_or_else(|| Error::Unauthorized("请求未经过认证".to_string()))?;
+
+        let allowed = match self {
+            Requirement::AnyRole(required) => roles.iter().any(|role| required.contains(role)),
+            Requirement::Permission(permission) => rbac.has_permission(&roles, permission),
+        };
+        if allowed {
+            Ok(())
+        } else {
+            Err(Error::Forbidden("没有访问权限".to_string()))
+        }
+    }
+}
+
+/// 要求指定角色的路由层
+pub fn require_role(role: &str) -> RequireLayer {
+    require_any_role([role])
+}
+
+/// 要求任一角色的路由层
+pub fn require_any_role<I, R>(roles: I) -> RequireLayer
+where
+    I: IntoIterator<Item = R>,
+    R: Into<String>,
+{
+    RequireLayer {
+        requirement: Arc::new(Requirement::AnyRole(roles.into_iter().map(Into::into).collect())),
+    }
+}
+
+/// 要求指定权限的路由层，权限由 `[middleware.rbac.roles]` 中的角色授予
+pub fn require_permission(permission: &str) -> RequireLayer {
+    RequireLayer {
+        requirement: Arc::new(Requirement::Permission(permission.to_string())),
+    }
+}
+
+/// 访问控制路由层，通过 [`require_role`]、[`require_any_role`] 或 [`require_permission`] 创建
+#[derive(Debug, Clone)]
+pub struct RequireLayer {
+    requirement: Arc<Requirement>,
+}
+
+impl<S> Layer<S> for RequireLayer {
+    type Service = RequireService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequireService {
+            inner,
+            requirement: self.requirement.clone(),
+        }
+    }
+}
+
+/// 访问控制服务
+#[derive(Debug, Clone)]
+pub struct RequireService<S> {
+    inner: S,
+    requirement: Arc<Requirement>,
+}
+
+impl<S> Service<Request> for RequireService<S>
+where
+    S: Service<Request, Response = Response, Error = Infallible>,
+{
+    type Response = Response;
+    type Error = Infallible;
+    type Future = Either<Ready<std::result::Result<Response, Infallible>>, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        match self.requirement.check(request.extensions()) {
+            Ok(()) => Either::Right(self.inner.call(request)),
+            Err(e) => {
+                tracing::debug!("拒绝访问 {}: {}", request.uri().path(), e);
+                Either::Left(future::ready(Ok(e.into_response())))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::StatusCode, routing::get, Extension, Router};
+    use serde_json::{json, Map};
+    use std::collections::HashMap;
+    use tower::util::ServiceExt;
+
+    fn claims(roles: serde_json::Value) -> Claims {
+        let mut extra = Map::new();
+        extra.insert("roles".to_string(), roles);
+        Claims {
+            sub: "user123".to_string(),
+            exp: 0,
+            iat: 0,
+            aud: "hwhkit".to_string(),
+            iss: "hwhkit".to_string(),
+            extra,
+        }
+    }
+
+    fn app() -> Router {
+        let rbac = Rbac::new(&RbacConfig {
+            roles: HashMap::from([
+                ("admin".to_string(), vec![ALL_PERMISSIONS.to_string()]),
+                ("editor".to_string(), vec!["articles:write".to_string()]),
+            ]),
+            ..Default::default()
+        });
+        Router::new()
+            .route("/admin", get(|| async { "admin" }).route_layer(require_role("admin")))
+            .route(
+                "/articles",
+                get(|roles: Roles| async move { roles.0.join(",") })
+                    .route_layer(require_permission("articles:write")),
+            )
+            .layer(Extension(Arc::new(rbac)))
+    }
+
+    async fn status(uri: &str, claims: Option<Claims>) -> StatusCode {
+        let mut request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        if let Some(claims) = claims {
+            request.extensions_mut().insert(claims);
+        }
+        app().oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_require_role() {
+        assert_eq!(status("/admin", None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(status("/admin", Some(claims(json!(["editor"])))).await, StatusCode::FORBIDDEN);
+        assert_eq!(status("/admin", Some(claims(json!(["admin"])))).await, StatusCode::OK);
+        // 单个角色也可以是字符串
+        assert_eq!(status("/admin", Some(claims(json!("admin")))).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_require_permission() {
+        assert_eq!(status("/articles", Some(claims(json!(["viewer"])))).await, StatusCode::FORBIDDEN);
+        assert_eq!(status("/articles", Some(claims(json!(["editor"])))).await, StatusCode::OK);
+        assert_eq!(status("/articles", Some(claims(json!(["admin"])))).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_forbidden_body() {
+        let mut request = Request::builder().uri("/admin").body(Body::empty()).unwrap();
+        request.extensions_mut().insert(claims(json!([])));
+        let response = app().oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["status"], 403);
+    }
+}
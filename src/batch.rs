@@ -0,0 +1,266 @@
+//! 批量请求模块
+//!
+//! 客户端可以在一次 `POST /batch` 中提交多个子请求，服务端通过内部路由并发执行
+//! （经过完整的中间件链），按顺序返回每个子请求的结果，减少高延迟网络下的往返次数。
+//!
+//! ```json
+//! [
+//!   { "method": "GET", "path": "/api/v1/users/1" },
+//!   { "method": "POST", "path": "/api/v1/users", "body": { "name": "张三" } }
+//! ]
+//! ```
+//!
+//! 外层请求的请求头（如 `Authorization`、`Cookie`）会传递给每个子请求，子请求中的
+//! 同名请求头优先。
+
+use crate::{
+    config::BatchConfig,
+    error::{Error, Result},
+};
+use axum::{
+    body::Body,
+    extract::{Json, Request, State},
+    http::{header, HeaderMap, HeaderName, HeaderValue, Method},
+    routing::post,
+    Router,
+};
+use futures_util::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Arc};
+use tower::util::ServiceExt;
+
+/// 子请求
+#[derive(Debug, Clone, Deserialize)]
+pub struct BatchRequest {
+    /// HTTP 方法，默认为 GET
+    #[serde(default = "default_method")]
+    pub method: String,
+    /// 请求路径（可包含查询字符串）
+    pub path: String,
+    /// 请求头
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// JSON 请求体
+    #[serde(default)]
+    pub body: Option<serde_json::Value>,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+/// 子请求结果
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BatchResponse {
+    /// 状态码
+    pub status: u16,
+    /// 响应头
+    pub headers: HashMap<String, String>,
+    /// 响应体，JSON 响应会被解析，其余为字符串
+    pub body: serde_json::Value,
+}
+
+struct BatchState {
+    router: Router,
+    config: BatchConfig,
+}
+
+/// 为应用添加批量请求接口
+///
+/// 子请求通过传入的 `app`（包含全部中间件）执行
+pub fn batch_router(app: Router, config: &BatchConfig) -> Router {
+    let state = Arc::new(BatchState {
+        router: app.clone(),
+        config: config.clone(),
+    });
+    app.route(&config.path, post(batch_handler).with_state(state))
+}
+
+async fn batch_handler(
+    State(state): State<Arc<BatchState>>,
+    headers: HeaderMap,
+    Json(requests): Json<Vec<BatchRequest>>,
+) -> Result<Json<Vec<BatchResponse>>> {
+    if requests.len() > state.config.max_requests {
+        return Err(Error::BadRequest(format!(
+            "批量请求最多包含 {} 个子请求",
+            state.config.max_requests
+        )));
+    }
+
+    let headers = &headers;
+    let state = &state;
+    let responses = stream::iter(requests)
+        .map(|request| async move {
+            execute(state, headers, request)
+                .await
+                .unwrap_or_else(|e| error_response(&e))
+        })
+        .buffered(state.config.concurrency.max(1))
+        .collect()
+        .await;
+
+    Ok(Json(responses))
+}
+
+/// 通过内部路由执行子请求
+async fn execute(state: &BatchState, outer: &HeaderMap, item: BatchRequest) -> Result<BatchResponse> {
+    let method = Method::from_bytes(item.method.to_uppercase().as_bytes())
+        .map_err(|_| Error::BadRequest(format!("无效的 HTTP 方法: {}", item.method)))?;
+    if !item.path.starts_with('/') {
+        return Err(Error::BadRequest(format!("子请求路径必须以 / 开头: {}", item.path)));
+    }
+    if item.path.split('?').next() == Some(state.config.path.as_str()) {
+        return Err(Error::BadRequest("子请求不能嵌套批量请求".to_string()));
+    }
+
+    let mut builder = Request::builder().method(method).uri(&item.path);
+    let headers = builder.headers_mut().expect("请求构建器状态有效");
+    for (name, value) in outer {
+        if *name != header::CONTENT_LENGTH && *name != header::CONTENT_TYPE {
+            headers.append(name, value.clone());
+        }
+    }
+    for (name, value) in &item.headers {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| Error::BadRequest(format!("无效的请求头: {}", name)))?;
+        let value = HeaderValue::from_str(value)
+            .map_err(|_| Error::BadRequest(format!("无效的请求头值: {}", name)))?;
+        headers.insert(name, value);
+    }
+
+    let body = match &item.body {
+        Some(body) => {
+            headers
+                .entry(header::CONTENT_TYPE)
+                .or_insert(HeaderValue::from_static("application/json"));
+            Body::from(serde_json::to_vec(body)?)
+        }
+        None => Body::empty(),
+    };
+    let request = builder
+        .body(body)
+        .map_err(|e| Error::BadRequest(format!("无效的子请求: {}", e)))?;
+
+    let response = state
+        .router
+        .clone()
+        .oneshot(request)
+        .await
+        .map_err(|e| Error::Internal(format!("子请求执行失败: {}", e)))?;
+
+    let (parts, body) = response.into_parts();
+    let bytes = axum::body::to_bytes(body, usize::MAX)
+        .await
+        .map_err(|e| Error::Internal(format!("无法读取子请求响应: {}", e)))?;
+
+    let is_json = parts
+        .headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("application/json"));
+    let body = if is_json {
+        serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null)
+    } else {
+        serde_json::Value::String(String::from_utf8_lossy(&bytes).into_owned())
+    };
+
+    Ok(BatchResponse {
+        status: parts.status.as_u16(),
+        headers: parts
+            .headers
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect(),
+        body,
+    })
+}
+
+/// 无法执行的子请求使用与 `Error` 响应一致的格式
+fn error_response(error: &Error) -> BatchResponse {
+    let status = match error {
+        Error::BadRequest(_) | Error::Serialization(_) => 400,
+        _ => 500,
+    };
+    BatchResponse {
+        status,
+        headers: HashMap::new(),
+        body: serde_json::json!({ "error": error.to_string(), "status": status }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::Path, http::StatusCode, routing::get};
+
+    fn app(config: &BatchConfig) -> Router {
+        let app = Router::new()
+            .route(
+                "/users/:id",
+                get(|Path(id): Path<u32>| async move { Json(serde_json::json!({ "id": id })) }),
+            )
+            .route(
+                "/echo",
+                post(|headers: HeaderMap, body: String| async move {
+                    let auth = headers
+                        .get("authorization")
+                        .and_then(|v| v.to_str().ok())
+                        .unwrap_or_default()
+                        .to_string();
+                    format!("{} {}", auth, body)
+                }),
+            );
+        batch_router(app, config)
+    }
+
+    async fn send(app: Router, body: serde_json::Value) -> (StatusCode, serde_json::Value) {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/batch")
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(header::AUTHORIZATION, "Bearer outer")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_batch_requests() {
+        let (status, body) = send(
+            app(&BatchConfig::default()),
+            serde_json::json!([
+                { "path": "/users/1" },
+                { "method": "post", "path": "/echo", "body": { "a": 1 } },
+                { "path": "/missing" },
+                { "method": "BAD METHOD", "path": "/users/1" },
+                { "method": "POST", "path": "/batch" },
+            ]),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let results: Vec<BatchResponse> = serde_json::from_value(body).unwrap();
+        let statuses: Vec<u16> = results.iter().map(|r| r.status).collect();
+        assert_eq!(statuses, [200, 200, 404, 400, 400]);
+        assert_eq!(results[0].body, serde_json::json!({ "id": 1 }));
+        assert_eq!(results[1].body, serde_json::json!(r#"Bearer outer {"a":1}"#));
+    }
+
+    #[tokio::test]
+    async fn test_batch_limit() {
+        let config = BatchConfig {
+            max_requests: 1,
+            ..Default::default()
+        };
+        let (status, _) = send(
+            app(&config),
+            serde_json::json!([{ "path": "/users/1" }, { "path": "/users/2" }]),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
@@ -1,13 +1,30 @@
 //! Web 服务构建器模块
 
 use crate::{
-    config::{ArchitectureType, Config},
-    error::{Error, Result},
-    middleware::MiddlewareManager,
-    server::WebServer,
+    admin::{self, AdminState, CachePurgeHooks, FeatureFlags, LogLevelHandle},
+    assets::AssetPipeline,
+    auth::rbac::Rbac,
+    config::{ArchitectureType, CdnProvider, Config, DeprecatedRouteConfig, DocsUi},
+    database::Databases,
+    error::{Error, ErrorHandler, Result},
+    inject::{AppConfig, Services},
+    module::{AppModule, ModuleContext, PendingModule},
+    middleware::{
+        catch_panic::{PanicHooks, PanicReport},
+        interceptor::{Interceptors, RequestInterceptor, ResponseInterceptor},
+        logging::LogGuards,
+        ConditionalLayer, RequestMatcher,
+        session::SessionStore,
+        MiddlewareManager,
+    },
+    readiness::{readiness_router, ReadinessChecks},
+    routing::{RouteDoc, RouteGroup, RouteInfo, RouteTable},
+    server::{LifecycleHooks, WebServer},
+    shutdown::ShutdownSignal,
 };
-use axum::Router;
-use std::path::Path;
+use axum::{routing::MethodRouter, Extension, Router};
+use futures_util::future::BoxFuture;
+use std::{any::Any, collections::HashSet, fmt, future::Future, path::Path, sync::Arc};
 
 /// Web 服务构建器
 /// 
@@ -15,7 +32,7 @@ use std::path::Path;
 /// 
 /// # Examples
 /// 
-/// ```rust
+/// ```rust,no_run
 /// use hwhkit::WebServerBuilder;
 /// 
 /// #[tokio::main]
@@ -33,7 +50,104 @@ use std::path::Path;
 pub struct WebServerBuilder {
     config: Config,
     router: Option<Router>,
+    fallback: Option<MethodRouter>,
     custom_middleware: Vec<Box<dyn MiddlewareFactory>>,
+    named_routes: Vec<(String, String, MethodRouter)>,
+    groups: Vec<RouteGroup>,
+    modules: Vec<PendingModule>,
+    route_docs: Vec<RouteDoc>,
+    interceptors: Interceptors,
+    error_handler: Option<ErrorHandler>,
+    panic_hooks: PanicHooks,
+    session_store: Option<Arc<dyn SessionStore>>,
+    conditional_layers: Vec<ConditionalLayer>,
+    readiness: ReadinessChecks,
+    state: Option<Box<AppState>>,
+    services: axum::http::Extensions,
+    config_sections: Vec<ConfigSection>,
+    lifecycle: LifecycleHooks,
+    stateful_routes: Vec<StatefulRoutes>,
+    #[cfg(feature = "oauth")]
+    oauth_providers: Vec<crate::auth::oauth::OAuthProvider>,
+    #[cfg(feature = "diesel")]
+    diesel: Option<crate::database::diesel::DieselConnector>,
+    #[cfg(feature = "ws")]
+    websockets: Vec<WebSocketRoute>,
+    #[cfg(feature = "proxy")]
+    proxies: Vec<(String, crate::proxy::ReverseProxy)>,
+    #[cfg(feature = "openapi")]
+    openapi: Option<utoipa::openapi::OpenApi>,
+    #[cfg(feature = "embed")]
+    embedded_static: Vec<EmbeddedStatic>,
+    #[cfg(feature = "templates")]
+    template_customizers: Vec<TemplateCustomizer>,
+    #[cfg(feature = "templates")]
+    template_globals: Vec<(String, serde_json::Value)>,
+    cache_purge: CachePurgeHooks,
+}
+
+type ConfigSectionFn = dyn FnOnce(&Config, &mut axum::http::Extensions) -> Result<()> + Send;
+
+/// 构建时读取的应用配置节
+struct ConfigSection(String, Box<ConfigSectionFn>);
+
+impl fmt::Debug for ConfigSection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ConfigSection").field(&self.0).finish_non_exhaustive()
+    }
+}
+
+/// 类型擦除后的应用状态
+type AppState = dyn Any + Send + Sync;
+
+type StatefulRoutesFn = dyn FnOnce(Option<&AppState>) -> Result<Router> + Send;
+
+/// 使用应用状态的路由，构建时取得状态后调用 `with_state`
+struct StatefulRoutes(Box<StatefulRoutesFn>);
+
+impl fmt::Debug for StatefulRoutes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StatefulRoutes").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "ws")]
+type WebSocketRouteFn = dyn FnOnce(&crate::config::WebSocketConfig) -> MethodRouter + Send;
+
+/// WebSocket 路由，构建时按 `[middleware.websocket]` 配置创建
+#[cfg(feature = "ws")]
+struct WebSocketRoute(String, Box<WebSocketRouteFn>);
+
+#[cfg(feature = "ws")]
+impl fmt::Debug for WebSocketRoute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("WebSocketRoute").field(&self.0).finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "embed")]
+type EmbeddedStaticFn = dyn FnOnce(&crate::config::StaticCacheConfig) -> Result<Router> + Send;
+
+/// 内嵌静态资源，构建时按 `[middleware.static_files.cache]` 创建路由
+#[cfg(feature = "embed")]
+struct EmbeddedStatic(String, Box<EmbeddedStaticFn>);
+
+#[cfg(feature = "embed")]
+impl fmt::Debug for EmbeddedStatic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("EmbeddedStatic").field(&self.0).finish_non_exhaustive()
+    }
+}
+
+/// 构建模板引擎时执行的自定义函数
+#[cfg(feature = "templates")]
+struct TemplateCustomizer(Box<dyn FnOnce(&mut tera::Tera) + Send>);
+
+#[cfg(feature = "templates")]
+impl fmt::Debug for TemplateCustomizer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TemplateCustomizer").finish_non_exhaustive()
+    }
 }
 
 /// 中间件工厂特征
@@ -59,7 +173,40 @@ impl WebServerBuilder {
         Self {
             config: Config::default(),
             router: None,
+            fallback: None,
             custom_middleware: Vec::new(),
+            named_routes: Vec::new(),
+            groups: Vec::new(),
+            modules: Vec::new(),
+            route_docs: Vec::new(),
+            interceptors: Interceptors::default(),
+            error_handler: None,
+            panic_hooks: PanicHooks::default(),
+            session_store: None,
+            conditional_layers: Vec::new(),
+            readiness: ReadinessChecks::default(),
+            state: None,
+            services: axum::http::Extensions::new(),
+            config_sections: Vec::new(),
+            lifecycle: LifecycleHooks::default(),
+            stateful_routes: Vec::new(),
+            #[cfg(feature = "oauth")]
+            oauth_providers: Vec::new(),
+            #[cfg(feature = "diesel")]
+            diesel: None,
+            #[cfg(feature = "ws")]
+            websockets: Vec::new(),
+            #[cfg(feature = "proxy")]
+            proxies: Vec::new(),
+            #[cfg(feature = "openapi")]
+            openapi: None,
+            #[cfg(feature = "embed")]
+            embedded_static: Vec::new(),
+            #[cfg(feature = "templates")]
+            template_customizers: Vec::new(),
+            #[cfg(feature = "templates")]
+            template_globals: Vec::new(),
+            cache_purge: CachePurgeHooks::default(),
         }
     }
 
@@ -89,6 +236,67 @@ impl WebServerBuilder {
         self
     }
 
+    /// 按顺序加载并深度合并多个配置文件，后面的文件按键覆盖前面的文件
+    ///
+    /// 任一文件加载失败时与 [`config_from_file`](Self::config_from_file) 一样使用默认配置
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use hwhkit::WebServerBuilder;
+    ///
+    /// let builder = WebServerBuilder::new()
+    ///     .config_from_files(["base.toml", "overrides.toml"]);
+    /// ```
+    pub fn config_from_files<I, P>(mut self, paths: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        match Config::from_files(paths) {
+            Ok(config) => {
+                self.config = config;
+            }
+            Err(e) => {
+                eprintln!("警告: 无法加载配置文件: {}，使用默认配置", e);
+            }
+        }
+        self
+    }
+
+    /// 使用命令行参数覆盖配置
+    ///
+    /// 解析进程的命令行参数（`--config`、`--host`、`--port`、`--log-level`）。
+    /// 指定了 `--config` 时会重新加载该文件，其余参数覆盖当前配置中的值。
+    ///
+    /// # Errors
+    ///
+    /// `--config` 指定的文件无法读取或解析时返回错误
+    #[cfg(feature = "cli")]
+    pub fn with_cli_args(self) -> Result<Self> {
+        use clap::Parser;
+
+        self.cli_args(crate::cli::CliArgs::parse())
+    }
+
+    /// 使用已解析的命令行参数覆盖配置
+    ///
+    /// # Arguments
+    ///
+    /// * `args` - 命令行参数，通常嵌入在应用自己的 clap 定义中
+    ///
+    /// # Errors
+    ///
+    /// `--config` 指定的文件无法读取或解析时返回错误
+    #[cfg(feature = "cli")]
+    pub fn cli_args(mut self, args: crate::cli::CliArgs) -> Result<Self> {
+        if let Some(path) = &args.config {
+            self.config = Config::from_file(path)?;
+        }
+        args.apply(&mut self.config);
+        Ok(self)
+    }
+
     /// 设置配置
     /// 
     /// # Arguments
@@ -160,6 +368,60 @@ impl WebServerBuilder {
         self
     }
 
+    /// 使用 Askama 编译期模板（仅在 Full 架构下有效）
+    ///
+    /// 模板在编译时生成，不需要运行时的模板目录，处理函数返回
+    /// [`Askama`](crate::askama::Askama) 即可渲染
+    #[cfg(feature = "askama")]
+    pub fn askama_templates(mut self) -> Self {
+        if self.config.server.architecture == ArchitectureType::Full {
+            self.config.middleware.templates.enabled = true;
+            self.config.middleware.templates.engine = crate::config::TemplateEngineKind::Askama;
+        }
+        self
+    }
+
+    /// 自定义模板引擎，如注册过滤器、函数和测试器
+    ///
+    /// 启用 `[middleware.templates]` 时，构建服务器会创建 [`TemplateEngine`](crate::templates::TemplateEngine)
+    /// 并依次执行这些函数，之后以 `Extension<Arc<TemplateEngine>>` 提供给处理函数
+    ///
+    /// ```rust
+    /// use hwhkit::{templates::tera, WebServerBuilder};
+    /// use std::collections::HashMap;
+    ///
+    /// let builder = WebServerBuilder::new().templates_customize(|tera| {
+    ///     tera.register_filter("currency", |value: &tera::Value, _: &HashMap<String, tera::Value>| {
+    ///         Ok(format!("¥{:.2}", value.as_f64().unwrap_or_default()).into())
+    ///     });
+    /// });
+    /// ```
+    #[cfg(feature = "templates")]
+    pub fn templates_customize<F>(mut self, customize: F) -> Self
+    where
+        F: FnOnce(&mut tera::Tera) + Send + 'static,
+    {
+        self.template_customizers.push(TemplateCustomizer(Box::new(customize)));
+        self
+    }
+
+    /// 添加模板全局变量，合并到每次渲染的上下文中
+    ///
+    /// 与 `[middleware.templates.globals]` 中的同名变量相比，这里添加的优先
+    ///
+    /// ```rust
+    /// use hwhkit::WebServerBuilder;
+    ///
+    /// let builder = WebServerBuilder::new()
+    ///     .template_global("app_name", "HwhKit")
+    ///     .template_global("version", env!("CARGO_PKG_VERSION"));
+    /// ```
+    #[cfg(feature = "templates")]
+    pub fn template_global(mut self, key: &str, value: impl Into<serde_json::Value>) -> Self {
+        self.template_globals.push((key.to_string(), value.into()));
+        self
+    }
+
     /// 启用 JWT 认证
     /// 
     /// # Arguments
@@ -193,6 +455,238 @@ impl WebServerBuilder {
         self
     }
 
+    /// 设置未匹配任何路由时的处理函数
+    ///
+    /// 替换 `[server.fallback]` 默认返回的 JSON 404，也会替换 `routes` 中路由器自带的 fallback
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - 处理函数
+    pub fn fallback<H, T>(mut self, handler: H) -> Self
+    where
+        H: axum::handler::Handler<T, ()>,
+        T: 'static,
+    {
+        self.fallback = Some(axum::routing::any(handler));
+        self
+    }
+
+    /// 设置应用状态
+    ///
+    /// 通过 [`routes_with_state`](Self::routes_with_state) 添加的路由使用该状态，处理器通过
+    /// `State<T>` 提取。状态由多个部分组成时，可以为各部分实现 `FromRef` 以便单独提取
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - 应用状态，重复设置时替换
+    pub fn state<T: Clone + Send + Sync + 'static>(mut self, state: T) -> Self {
+        self.state = Some(Box::new(state));
+        self
+    }
+
+    /// 登记服务，处理器通过 `Inject<T>` 提取器获取
+    ///
+    /// # Arguments
+    ///
+    /// * `service` - 服务实例，同一类型重复登记时替换
+    pub fn provide<T: Clone + Send + Sync + 'static>(mut self, service: T) -> Self {
+        self.services.insert(service);
+        self
+    }
+
+    /// 登记应用配置节
+    ///
+    /// 构建时将 `[extensions.<name>]` 反序列化为 `T`，配置无效时构建失败；
+    /// 处理器通过 [`AppConfig<T>`](crate::inject::AppConfig) 提取器获取
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - 配置节名称
+    pub fn config_section<T>(mut self, name: &str) -> Self
+    where
+        T: serde::de::DeserializeOwned + Send + Sync + 'static,
+    {
+        let section = name.to_string();
+        self.config_sections.push(ConfigSection(
+            name.to_string(),
+            Box::new(move |config, services| {
+                let value: T = config.extension(&section)?;
+                services.insert(AppConfig(Arc::new(value)));
+                Ok(())
+            }),
+        ));
+        self
+    }
+
+    /// 添加使用应用状态的路由
+    ///
+    /// 构建时以 [`state`](Self::state) 设置的状态调用 `routes`，再由 HwhKit 调用
+    /// `Router::with_state`，状态未设置或类型不一致时构建失败
+    ///
+    /// ```rust
+    /// use hwhkit::{get, Router, State, WebServerBuilder};
+    ///
+    /// #[derive(Clone)]
+    /// struct AppState {
+    ///     greeting: String,
+    /// }
+    ///
+    /// let builder = WebServerBuilder::new()
+    ///     .state(AppState { greeting: "hello".to_string() })
+    ///     .routes_with_state(|_state: &AppState| {
+    ///         Router::new().route("/", get(|State(state): State<AppState>| async move { state.greeting }))
+    ///     });
+    /// ```
+    pub fn routes_with_state<T, F>(mut self, routes: F) -> Self
+    where
+        T: Clone + Send + Sync + 'static,
+        F: FnOnce(&T) -> Router<T> + Send + 'static,
+    {
+        self.stateful_routes.push(StatefulRoutes(Box::new(move |state| {
+            let state = state.and_then(|state| state.downcast_ref::<T>()).ok_or_else(|| {
+                Error::Config(format!(
+                    "routes_with_state 需要先通过 state() 设置 {} 类型的应用状态",
+                    std::any::type_name::<T>()
+                ))
+            })?;
+            Ok(routes(state).with_state(state.clone()))
+        })));
+        self
+    }
+
+    /// 添加 WebSocket 路由
+    ///
+    /// 升级后以 [`Socket`](crate::websocket::Socket) 调用 `handler`，帧大小限制和
+    /// Ping 间隔取自 `[middleware.websocket]`，详见 [`websocket`](crate::websocket) 模块
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - 路由路径
+    /// * `handler` - 连接处理函数
+    #[cfg(feature = "ws")]
+    pub fn websocket<F, Fut>(mut self, path: &str, handler: F) -> Self
+    where
+        F: Fn(crate::websocket::Socket) -> Fut + Clone + Send + Sync + 'static,
+        Fut: std::future::Future<Output = ()> + Send + 'static,
+    {
+        self.websockets.push(WebSocketRoute(
+            path.to_string(),
+            Box::new(move |config| crate::websocket::route(config, handler)),
+        ));
+        self
+    }
+
+    /// 把匹配 `path` 的所有请求转发到 `upstream`
+    ///
+    /// 超时、重试等设置取自 `[reverse_proxy]`，详见 [`proxy`](crate::proxy) 模块
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - 路由路径，如 `/legacy/*path`
+    /// * `upstream` - 上游地址，如 `http://old-service:8080`
+    #[cfg(feature = "proxy")]
+    pub fn proxy(self, path: &str, upstream: &str) -> Self {
+        self.proxy_with(path, crate::proxy::ReverseProxy::new(upstream))
+    }
+
+    /// 添加自定义选项的代理路由
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - 路由路径
+    /// * `proxy` - 代理设置
+    #[cfg(feature = "proxy")]
+    pub fn proxy_with(mut self, path: &str, proxy: crate::proxy::ReverseProxy) -> Self {
+        self.proxies.push((path.to_string(), proxy));
+        self
+    }
+
+    /// 提供编译进二进制的静态资源
+    ///
+    /// 不需要磁盘上的静态文件目录，适合单文件部署。详见 [`embed`](crate::embed) 模块
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - URL 前缀
+    #[cfg(feature = "embed")]
+    pub fn embedded_static<E>(mut self, prefix: &str) -> Self
+    where
+        E: crate::embed::RustEmbed + Send + Sync + 'static,
+    {
+        let path = prefix.to_string();
+        self.embedded_static.push(EmbeddedStatic(
+            prefix.to_string(),
+            Box::new(move |cache| crate::embed::router::<E>(&path, cache)),
+        ));
+        self
+    }
+
+    /// 登记 OpenAPI 文档
+    ///
+    /// 构建时挂载到 `[openapi] path`，多次调用时合并文档。详见 [`openapi`](crate::openapi) 模块
+    #[cfg(feature = "openapi")]
+    pub fn openapi(mut self, doc: utoipa::openapi::OpenApi) -> Self {
+        self.openapi = Some(match self.openapi.take() {
+            Some(existing) => existing.merge_from(doc),
+            None => doc,
+        });
+        self
+    }
+
+    /// 添加命名路由
+    ///
+    /// 构建后可以通过 `url_for!` 或模板函数 `url_for` 按名称生成 URL
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - 路由名称
+    /// * `path` - 路由路径，如 `/users/:id`
+    /// * `method_router` - 处理器
+    pub fn route_named(mut self, name: &str, path: &str, method_router: MethodRouter) -> Self {
+        self.named_routes
+            .push((name.to_string(), path.to_string(), method_router));
+        self
+    }
+
+    /// 添加路由组
+    ///
+    /// 组内路由共享路径前缀，组内添加的访问控制和中间件层只作用于组内路由，详见 [`RouteGroup`]
+    ///
+    /// ```rust
+    /// use hwhkit::{auth::rbac::require_role, get, WebServerBuilder};
+    ///
+    /// let builder = WebServerBuilder::new().group("/admin", |group| {
+    ///     group
+    ///         .guard(require_role("admin"))
+    ///         .route("/stats", get(|| async { "stats" }))
+    /// });
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - 路径前缀
+    /// * `group` - 声明组内路由的函数
+    pub fn group<F>(mut self, prefix: &str, group: F) -> Self
+    where
+        F: FnOnce(RouteGroup) -> RouteGroup,
+    {
+        self.groups.push(group(RouteGroup::new(prefix)));
+        self
+    }
+
+    /// 挂载功能模块
+    ///
+    /// 构建时读取模块的 `[modules.<name>]` 配置节，创建状态并执行初始化后合并模块路由，
+    /// 详见 [`module`](crate::module) 模块
+    ///
+    /// # Arguments
+    ///
+    /// * `module` - 实现了 AppModule 的模块
+    pub fn mount<M: AppModule>(mut self, module: M) -> Self {
+        self.modules.push(PendingModule::new(module));
+        self
+    }
+
     /// 添加自定义中间件
     /// 
     /// # Arguments
@@ -203,6 +697,232 @@ impl WebServerBuilder {
         self
     }
 
+    /// 启用管理接口
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - 访问令牌
+    pub fn admin(mut self, token: &str) -> Self {
+        self.config.admin.enabled = true;
+        self.config.admin.token = token.to_string();
+        self
+    }
+
+    /// 注册缓存清除回调，由管理接口 `POST /cache/purge` 触发
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - 清除缓存的回调
+    pub fn on_cache_purge<F: Fn() + Send + Sync + 'static>(mut self, hook: F) -> Self {
+        self.cache_purge.push(hook);
+        self
+    }
+
+    /// 注册启动回调
+    ///
+    /// 在绑定监听地址之后、开始接受连接之前按注册顺序执行，适合预热缓存等准备工作。
+    /// 返回错误时服务器不会启动
+    pub fn on_startup<F, Fut>(mut self, hook: F) -> Self
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        self.lifecycle.startup.push(Box::new(move || Box::pin(hook())));
+        self
+    }
+
+    /// 注册关闭回调
+    ///
+    /// 在连接关闭（或等待超时）之后按注册顺序执行，适合刷新缓冲区、关闭外部连接等清理工作。
+    /// 返回错误时记录日志并继续执行后续回调
+    pub fn on_shutdown<F, Fut>(mut self, hook: F) -> Self
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        self.lifecycle.shutdown.push(Box::new(move || Box::pin(hook())));
+        self
+    }
+
+    /// 登记就绪检查，启用 `[server.readiness]` 时由就绪探针执行
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - 检查名称，显示在探针响应中
+    /// * `check` - 返回错误时探针报告未就绪
+    pub fn readiness_check<F>(mut self, name: &str, check: F) -> Self
+    where
+        F: Fn() -> BoxFuture<'static, Result<()>> + Send + Sync + 'static,
+    {
+        self.readiness.add(name, check);
+        self
+    }
+
+    /// 设置会话存储
+    ///
+    /// 用于接入数据库等自定义存储，覆盖配置中的 `store`
+    ///
+    /// # Arguments
+    ///
+    /// * `store` - 会话存储
+    pub fn session_store<S: SessionStore>(mut self, store: S) -> Self {
+        self.session_store = Some(Arc::new(store));
+        self
+    }
+
+    /// 注册 OAuth2 / OIDC 登录方式
+    ///
+    /// 自动挂载 `/auth/{provider}/login` 和 `/auth/{provider}/callback`，需要启用会话
+    ///
+    /// # Arguments
+    ///
+    /// * `provider` - 登录方式
+    #[cfg(feature = "oauth")]
+    pub fn oauth_provider(mut self, provider: crate::auth::oauth::OAuthProvider) -> Self {
+        self.oauth_providers.push(provider);
+        self
+    }
+
+    /// 使用 Diesel 连接池
+    ///
+    /// 构建服务器时根据 `[database]` 配置创建 `C` 类型连接的 r2d2 连接池，处理器通过
+    /// `Extension<DieselPool<C>>` 获取，配合 `run_blocking_db` 执行查询
+    #[cfg(feature = "diesel")]
+    pub fn diesel<C: diesel::r2d2::R2D2Connection + 'static>(mut self) -> Self {
+        self.diesel = Some(crate::database::diesel::connector::<C>());
+        self
+    }
+
+    /// 登记路由文档
+    ///
+    /// 启用 `[server.index_page]` 时用于生成接口列表首页
+    ///
+    /// # Arguments
+    ///
+    /// * `doc` - 路由文档
+    pub fn route_doc(mut self, doc: RouteDoc) -> Self {
+        self.route_docs.push(doc);
+        self
+    }
+
+    /// 将接口标记为已弃用
+    ///
+    /// 与配置文件中的 `[[middleware.deprecations]]` 合并，响应会带上
+    /// `Deprecation`、`Sunset` 和 `Link` 头
+    pub fn deprecate(mut self, route: DeprecatedRouteConfig) -> Self {
+        self.config.middleware.deprecations.push(route);
+        self
+    }
+
+    /// 声明角色及其权限
+    ///
+    /// 与配置文件中的 `[middleware.rbac.roles]` 合并，同名角色的权限会追加
+    ///
+    /// # Arguments
+    ///
+    /// * `role` - 角色名称
+    /// * `permissions` - 角色拥有的权限，`*` 表示全部权限
+    pub fn role<I, P>(mut self, role: &str, permissions: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<String>,
+    {
+        self.config
+            .middleware
+            .rbac
+            .roles
+            .entry(role.to_string())
+            .or_default()
+            .extend(permissions.into_iter().map(Into::into));
+        self
+    }
+
+    /// 添加只对匹配请求生效的中间件层
+    ///
+    /// 例如只压缩 `/api/*` 的 GET 响应：
+    /// `.layer_when(RequestMatcher::new().path("/api/*").method(Method::GET), CompressionLayer::new())`
+    ///
+    /// # Arguments
+    ///
+    /// * `matcher` - 请求匹配条件
+    /// * `layer` - 中间件层
+    pub fn layer_when<L>(mut self, matcher: RequestMatcher, layer: L) -> Self
+    where
+        L: tower::Layer<axum::routing::Route> + Clone + Send + Sync + 'static,
+        L::Service: tower::Service<axum::extract::Request> + Clone + Send + 'static,
+        <L::Service as tower::Service<axum::extract::Request>>::Response:
+            axum::response::IntoResponse + 'static,
+        <L::Service as tower::Service<axum::extract::Request>>::Error:
+            Into<std::convert::Infallible> + 'static,
+        <L::Service as tower::Service<axum::extract::Request>>::Future: Send + 'static,
+    {
+        self.conditional_layers.push(ConditionalLayer::new(matcher, layer));
+        self
+    }
+
+    /// 添加请求拦截器
+    ///
+    /// 执行顺序和启用状态可以通过 `[middleware.interceptors]` 配置
+    ///
+    /// # Arguments
+    ///
+    /// * `interceptor` - 实现了 RequestInterceptor 的拦截器
+    pub fn request_interceptor<I: RequestInterceptor>(mut self, interceptor: I) -> Self {
+        self.interceptors.add_request(interceptor);
+        self
+    }
+
+    /// 添加响应拦截器
+    ///
+    /// 执行顺序和启用状态可以通过 `[middleware.interceptors]` 配置
+    ///
+    /// # Arguments
+    ///
+    /// * `interceptor` - 实现了 ResponseInterceptor 的拦截器
+    pub fn response_interceptor<I: ResponseInterceptor>(mut self, interceptor: I) -> Self {
+        self.interceptors.add_response(interceptor);
+        self
+    }
+
+    /// 设置错误处理函数
+    ///
+    /// 处理函数和中间件返回的 [`Error`] 不再使用默认的 JSON 格式，而是交给 `handler` 生成响应，
+    /// 可以统一响应格式、记录日志或调整状态码：
+    ///
+    /// ```rust
+    /// use hwhkit::{Error, WebServerBuilder};
+    /// use axum::{http::StatusCode, response::IntoResponse, Json};
+    /// use serde_json::json;
+    ///
+    /// let builder = WebServerBuilder::new().on_error(|err, parts| {
+    ///     tracing::warn!("{} {} 失败: {}", parts.method, parts.uri, err);
+    ///     let status = match err {
+    ///         Error::NotFound(_) => StatusCode::NOT_FOUND,
+    ///         _ => StatusCode::INTERNAL_SERVER_ERROR,
+    ///     };
+    ///     (status, Json(json!({ "code": status.as_u16(), "msg": err.to_string() }))).into_response()
+    /// });
+    /// ```
+    pub fn on_error<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&Error, &axum::http::request::Parts) -> axum::response::Response + Send + Sync + 'static,
+    {
+        self.error_handler = Some(ErrorHandler::new(handler));
+        self
+    }
+
+    /// 注册处理函数 panic 时调用的回调，用于上报到错误收集服务
+    ///
+    /// 需要启用 `[middleware.catch_panic]`（默认启用）
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - 接收 panic 信息、请求方法、路径和请求 ID 的回调
+    pub fn on_panic<F: Fn(&PanicReport) + Send + Sync + 'static>(mut self, hook: F) -> Self {
+        self.panic_hooks.push(hook);
+        self
+    }
+
     /// 添加自定义配置参数
     /// 
     /// # Arguments
@@ -223,12 +943,55 @@ impl WebServerBuilder {
     /// # Returns
     /// 
     /// 返回配置好的 WebServer 实例或错误
-    pub async fn build(self) -> Result<WebServer> {
+    pub async fn build(mut self) -> Result<WebServer> {
+        // 读取密钥文件
+        self.config.resolve_secret_files()?;
+
         // 验证配置
         self.config.validate()?;
 
+        // 读取应用配置节
+        for ConfigSection(_, load) in self.config_sections.drain(..) {
+            load(&self.config, &mut self.services)?;
+        }
+
+        // 登记共享 HTTP 客户端，已通过 provide 登记时不覆盖
+        #[cfg(feature = "http_client")]
+        if self.services.get::<crate::http_client::HttpClient>().is_none() {
+            let client = crate::http_client::HttpClient::new(&self.config.http_client)?;
+            self.services.insert(client);
+        }
+
+        // 登记 Webhook 签名校验器
+        #[cfg(feature = "webhooks")]
+        self.services
+            .insert(crate::webhooks::WebhookVerifiers::new(&self.config.webhooks)?);
+        #[cfg(not(feature = "webhooks"))]
+        if !self.config.webhooks.is_empty() {
+            return Err(Error::Config("Webhook 签名校验需要启用 `webhooks` feature".to_string()));
+        }
+
+        // 登记 Cookie 密钥，供 `CookieJar` 和 `Cookie<T>` 提取器使用
+        if !self.config.cookies.effective_keys().is_empty() {
+            #[cfg(feature = "cookies")]
+            self.services
+                .insert(crate::middleware::cookies::CookieSettings::new(&self.config.cookies)?);
+            #[cfg(not(feature = "cookies"))]
+            return Err(Error::Config("[cookies] 密钥需要启用 `cookies` feature".to_string()));
+        }
+
         // 初始化日志
-        self.init_logging()?;
+        let (log_guards, log_level) = self.init_logging()?;
+
+        // 处理静态资源
+        let static_files = &self.config.middleware.static_files;
+        if static_files.enabled && static_files.pipeline.enabled {
+            let processed = AssetPipeline::new(static_files).run().await?;
+            tracing::info!("🎨 处理了 {} 个资源文件", processed);
+        }
+
+        // 设置错误响应是否包含内部错误的详细信息
+        crate::error::set_expose_details(self.config.errors.expose_details);
 
         // 创建中间件管理器
         let mut middleware_manager = MiddlewareManager::new(self.config.clone());
@@ -237,36 +1000,427 @@ impl WebServerBuilder {
         for middleware in self.custom_middleware {
             middleware_manager.add_custom_middleware(middleware);
         }
+        middleware_manager.set_interceptors(self.interceptors);
+        if let Some(handler) = self.error_handler {
+            middleware_manager.set_error_handler(handler);
+        }
+        middleware_manager.set_panic_hooks(self.panic_hooks);
+        for layer in self.conditional_layers {
+            middleware_manager.add_conditional_layer(layer);
+        }
+        if let Some(store) = self.session_store {
+            middleware_manager.set_session_store(store);
+        }
+
+        // 连接数据库，模块初始化时可能需要使用
+        let databases = Databases::connect(&self.config.database).await?;
+        #[cfg(feature = "diesel")]
+        let databases = match self.diesel {
+            Some(connector) => databases.with_diesel(connector, &self.config.database).await?,
+            None => databases,
+        };
 
         // 构建路由器
-        let base_router = self.router.unwrap_or_default();
-        let app = middleware_manager.apply_middleware(base_router).await?;
+        let mut base_router = self.router.unwrap_or_default();
+
+        // 记录已知的路由，通过 routes() 添加的路由器只能借助路由文档得知
+        let mut routes = RouteTable::default();
+        for doc in &self.route_docs {
+            routes.add(&doc.method, &doc.path);
+        }
+
+        // 添加使用应用状态的路由
+        for routes in self.stateful_routes {
+            base_router = base_router.merge((routes.0)(self.state.as_deref())?);
+        }
+
+        // 注册命名路由
+        for (name, path, method_router) in self.named_routes {
+            crate::routing::register(&name, &path)?;
+            routes.add_route(&path, Some(&name));
+            base_router = base_router.route(&path, method_router);
+        }
+
+        // 挂载功能模块
+        let mut module_names = HashSet::new();
+        let context = ModuleContext::new(&self.config, &databases);
+        for module in self.modules {
+            if !module_names.insert(module.name().to_string()) {
+                return Err(Error::Config(format!("模块 '{}' 重复挂载", module.name())));
+            }
+            base_router = base_router.merge(module.mount(&context).await?);
+        }
+
+        // 注册路由组
+        for group in self.groups {
+            for (path, name) in group.routes() {
+                if let Some(name) = name {
+                    crate::routing::register(name, path)?;
+                }
+                routes.add_route(path, name.as_deref());
+            }
+            base_router = base_router.merge(group.into_router());
+        }
+
+        // 挂载反向代理
+        #[cfg(feature = "proxy")]
+        {
+            let config = &self.config.reverse_proxy;
+            let mut proxies = config
+                .routes
+                .iter()
+                .map(|route| (route.path.clone(), crate::proxy::ReverseProxy::new(&route.upstream)))
+                .chain(self.proxies)
+                .peekable();
+            if proxies.peek().is_some() {
+                let client = crate::proxy::client(config)?;
+                for (path, proxy) in proxies {
+                    routes.add("*", &path);
+                    base_router = base_router.merge(proxy.into_router(&path, config, &client)?);
+                }
+            }
+        }
+        #[cfg(not(feature = "proxy"))]
+        if !self.config.reverse_proxy.routes.is_empty() {
+            return Err(Error::Config("反向代理需要启用 `proxy` feature".to_string()));
+        }
+
+        // 注册 WebSocket 路由
+        #[cfg(feature = "ws")]
+        for WebSocketRoute(path, route) in self.websockets {
+            routes.add("GET", &path);
+            base_router = base_router.route(&path, route(&self.config.middleware.websocket));
+        }
+
+        // 挂载内嵌静态资源
+        #[cfg(feature = "embed")]
+        for EmbeddedStatic(prefix, router) in self.embedded_static {
+            if self.fallback.is_some() && prefix.trim_end_matches('/').is_empty() {
+                return Err(Error::Config(
+                    "挂载在根路径的内嵌静态资源与 fallback 处理函数冲突".to_string(),
+                ));
+            }
+            routes.add("GET", &format!("{}/*path", prefix.trim_end_matches('/')));
+            base_router = base_router.merge(router(&self.config.middleware.static_files.cache)?);
+        }
+
+        // 设置未匹配路由的处理函数
+        let custom_fallback = self.fallback.is_some();
+        if let Some(fallback) = self.fallback {
+            base_router = base_router.fallback_service(fallback);
+        }
+
+        // 生成接口列表首页
+        let index_page = &self.config.server.index_page;
+        if index_page.enabled {
+            let host = match self.config.server.host.as_str() {
+                "0.0.0.0" | "::" => "localhost",
+                host => host,
+            };
+            let base_url = format!("http://{}:{}", host, self.config.server.port);
+            let html = crate::routing::render_index_page(&index_page.title, &base_url, &self.route_docs);
+            routes.add("GET", &index_page.path);
+            base_router = base_router.route(
+                &index_page.path,
+                axum::routing::get(move || async move { axum::response::Html(html) }),
+            );
+        }
+
+        // 挂载 OpenAPI 文档和文档界面
+        let docs = &self.config.docs;
+        #[cfg(feature = "openapi")]
+        match self.openapi.take() {
+            Some(doc) => {
+                let doc = crate::openapi::document(doc, &self.route_docs);
+                let spec_path = &self.config.openapi.path;
+                routes.add("GET", spec_path);
+                if docs.ui != DocsUi::None {
+                    routes.add("GET", &docs.path);
+                }
+                let docs_router = crate::openapi::router(spec_path, &doc)?
+                    .merge(crate::openapi::docs_router(docs, spec_path));
+                base_router = base_router.merge(crate::openapi::protect(docs_router, docs));
+            }
+            None if docs.ui != DocsUi::None => {
+                return Err(Error::Config("API 文档界面需要先通过 openapi() 登记文档".to_string()));
+            }
+            None => {}
+        }
+        #[cfg(not(feature = "openapi"))]
+        if docs.ui != DocsUi::None {
+            return Err(Error::Config("API 文档界面需要启用 `openapi` feature".to_string()));
+        }
+
+        // 挂载登录路由
+        #[cfg(feature = "oauth")]
+        if !self.oauth_providers.is_empty() {
+            if !self.config.middleware.session.enabled {
+                return Err(Error::Config("OAuth 登录需要启用会话".to_string()));
+            }
+            let base = self.config.oauth.path.trim_end_matches('/');
+            routes.add("GET", &format!("{}/:provider/login", base));
+            routes.add("GET", &format!("{}/:provider/callback", base));
+            base_router =
+                crate::auth::oauth::mount(base_router, &self.config.oauth, self.oauth_providers).await?;
+        }
+
+        // 挂载 JWT 刷新接口
+        let jwt = &self.config.middleware.jwt;
+        if jwt.enabled && jwt.refresh.enabled {
+            routes.add("POST", &jwt.refresh.path);
+            base_router = base_router.merge(crate::middleware::jwt::refresh_router(jwt)?);
+        }
+
+        // 挂载管理接口
+        let feature_flags = FeatureFlags::new(self.config.feature_flags.clone());
+        if self.config.admin.enabled {
+            let state = AdminState::new(
+                self.config.clone(),
+                feature_flags.clone(),
+                log_level,
+                self.cache_purge,
+            );
+            admin::record_routes(&self.config.admin, &mut routes);
+            base_router = admin::mount(base_router, state);
+        }
+        // 提供文件上传配置
+        #[cfg(feature = "uploads")]
+        {
+            base_router = base_router.layer(Extension(Arc::new(self.config.middleware.uploads.clone())));
+        }
+
+        // 创建模板引擎
+        #[cfg(feature = "templates")]
+        #[cfg_attr(not(feature = "mail"), allow(unused_variables))]
+        let template_engine = {
+            let templates = &self.config.middleware.templates;
+            let engine = if templates.enabled && templates.engine.is_runtime() {
+                let mut engine = crate::templates::TemplateEngine::new(templates)?;
+                for TemplateCustomizer(customize) in self.template_customizers {
+                    engine.customize(customize)?;
+                }
+                for (key, value) in self.template_globals {
+                    engine.add_global(key, value)?;
+                }
+                engine.set_feature_flags(feature_flags.clone());
+                let engine = Arc::new(engine);
+                base_router = base_router.layer(Extension(engine.clone()));
+                middleware_manager.set_template_engine(engine.clone());
+                Some(engine)
+            } else if !self.template_customizers.is_empty() || !self.template_globals.is_empty() {
+                return Err(Error::Config(
+                    "templates_customize 和 template_global 需要启用 [middleware.templates] 且使用运行时模板引擎"
+                        .to_string(),
+                ));
+            } else {
+                None
+            };
+            // 使用模板引擎渲染处理函数返回的 Render
+            base_router = base_router.layer(axum::middleware::from_fn_with_state(
+                engine.clone(),
+                crate::templates::render_middleware,
+            ));
+            engine
+        };
+
+        // 未匹配的路由返回 JSON 404
+        let fallback = &self.config.server.fallback;
+        if fallback.enabled && !custom_fallback {
+            base_router = base_router.layer(axum::middleware::from_fn_with_state(
+                Arc::<str>::from(fallback.message.as_str()),
+                crate::routing::not_found_middleware,
+            ));
+        }
+
+        // 按 Range 头返回文件的部分内容
+        base_router = base_router.layer(axum::middleware::from_fn(crate::file::range_middleware));
+
+        // 按 Accept 头改写 Negotiate 响应
+        base_router = base_router.layer(axum::middleware::from_fn(crate::negotiate::negotiate_middleware));
+
+        // 关闭统一响应格式的包装
+        if !self.config.server.response.envelope {
+            base_router = base_router.layer(axum::middleware::from_fn(crate::response::unwrap_envelope));
+        }
+
+        let shutdown = ShutdownSignal::new(&self.config.server.shutdown);
+        let rbac = Arc::new(Rbac::new(&self.config.middleware.rbac));
+        let base_router = base_router
+            .layer(Extension(feature_flags))
+            .layer(Extension(shutdown.clone()))
+            .layer(Extension(rbac));
+
+        // 提供已登记的服务
+        let services = Services::new(self.services);
+        let base_router = base_router.layer(Extension(services.clone()));
+
+        // 提供 CDN 缓存清除客户端
+        let cdn_enabled = self.config.cdn.provider != CdnProvider::None;
+        #[cfg(feature = "cdn")]
+        let base_router = if cdn_enabled {
+            base_router.layer(Extension(crate::cache::PurgeClient::new(&self.config.cdn)?))
+        } else {
+            base_router
+        };
+        #[cfg(not(feature = "cdn"))]
+        if cdn_enabled {
+            return Err(Error::Config("CDN 缓存清除需要启用 `cdn` feature".to_string()));
+        }
+
+        // 创建邮件客户端
+        #[cfg(feature = "mail")]
+        let base_router = if self.config.mail.enabled {
+            let mailer = crate::mail::Mailer::new(&self.config.mail)?;
+            #[cfg(feature = "templates")]
+            let mailer = match template_engine {
+                Some(engine) => mailer.with_templates(engine),
+                None => mailer,
+            };
+            base_router.layer(Extension(mailer))
+        } else {
+            base_router
+        };
+        #[cfg(not(feature = "mail"))]
+        if self.config.mail.enabled {
+            return Err(Error::Config("邮件发送需要启用 `mail` feature".to_string()));
+        }
+
+        // 提供数据库连接
+        let base_router = databases.extend(base_router);
+
+        // 创建 MongoDB 客户端
+        #[cfg(feature = "mongodb")]
+        let base_router = if self.config.mongodb.enabled {
+            let mongo = crate::database::mongo::connect(&self.config.mongodb).await?;
+            let check = mongo.clone();
+            self.readiness.add("mongodb", move || {
+                let mongo = check.clone();
+                Box::pin(async move { mongo.ping().await })
+            });
+            base_router.layer(Extension(mongo))
+        } else {
+            base_router
+        };
+        #[cfg(not(feature = "mongodb"))]
+        if self.config.mongodb.enabled {
+            return Err(Error::Config("MongoDB 集成需要启用 `mongodb` feature".to_string()));
+        }
+
+        // 中间件和服务器挂载的路由
+        let static_files = &self.config.middleware.static_files;
+        if static_files.enabled {
+            routes.add("GET", &format!("{}/*path", static_files.prefix.trim_end_matches('/')));
+        }
+        let metrics = &self.config.telemetry.metrics;
+        if metrics.enabled {
+            routes.add("GET", &metrics.path);
+        }
+        if self.config.server.batch.enabled {
+            routes.add("POST", &self.config.server.batch.path);
+        }
+        if self.config.server.readiness.enabled {
+            routes.add("GET", &self.config.server.readiness.path);
+        }
+
+        // 路由列表调试接口，经过中间件以便受认证保护
+        let routes_endpoint = &self.config.server.routes_endpoint;
+        if routes_endpoint.enabled {
+            routes.add("GET", &routes_endpoint.path);
+        }
+        let routes: Arc<[RouteInfo]> = routes.into_sorted().into();
+        let base_router = if routes_endpoint.enabled {
+            base_router.merge(crate::routing::routes_router(&routes_endpoint.path, routes.clone()))
+        } else {
+            base_router
+        };
+
+        let mut app = middleware_manager.apply_middleware(base_router).await?;
+
+        // 批量请求接口，子请求经过完整的中间件链
+        if self.config.server.batch.enabled {
+            app = crate::batch::batch_router(app, &self.config.server.batch);
+        }
+
+        // 就绪探针，不经过中间件
+        let readiness = &self.config.server.readiness;
+        if readiness.enabled {
+            app = app.merge(readiness_router(&readiness.path, self.readiness, shutdown.clone()));
+        }
 
         // 创建服务器
-        Ok(WebServer::new(app, self.config))
+        Ok(WebServer::new(app, self.config)
+            .with_log_guards(log_guards)
+            .with_shutdown_signal(shutdown)
+            .with_databases(databases)
+            .with_services(services)
+            .with_routes(routes)
+            .with_lifecycle_hooks(self.lifecycle))
     }
 
     /// 初始化日志系统
-    fn init_logging(&self) -> Result<()> {
-        use tracing_subscriber::{EnvFilter, fmt, prelude::*};
+    ///
+    /// 返回后台日志写入线程的 guard（需要在服务器运行期间保持存活），
+    /// 以及用于运行时调整日志级别的句柄（全局订阅者已存在时为 `None`）
+    fn init_logging(&self) -> Result<(LogGuards, Option<LogLevelHandle>)> {
+        use crate::middleware::logging::{
+            create_access_log_layer, create_backend_layer, is_app_event,
+        };
+        use tracing_subscriber::{
+            filter::{filter_fn, FilterExt},
+            prelude::*,
+            reload, EnvFilter, Layer, Registry,
+        };
+
+        let logging = &self.config.middleware.logging;
 
         let filter = EnvFilter::try_from_default_env()
-            .or_else(|_| EnvFilter::try_new(&self.config.middleware.logging.level))
+            .or_else(|_| EnvFilter::try_new(&logging.level))
             .map_err(|e| Error::Config(format!("无效的日志级别: {}", e)))?;
 
-        let fmt_layer = fmt::layer()
-            .with_target(false)
-            .with_thread_ids(false)
-            .with_file(false)
-            .with_line_number(false);
+        let (filter, handle) = reload::Layer::new(filter);
+
+        // 应用日志不包含访问日志事件，访问日志由独立的输出层处理
+        let app_layer = create_backend_layer(logging)?
+            .with_filter(filter.and(filter_fn(is_app_event)));
+
+        let mut layers: Vec<Box<dyn Layer<Registry> + Send + Sync>> = vec![app_layer.boxed()];
+        let mut guards = LogGuards::default();
+
+        if logging.access_log.enabled {
+            let (access_layer, guard) = create_access_log_layer(logging)?;
+            layers.push(access_layer);
+            guards.workers.extend(guard);
+        }
+
+        // 导出请求追踪，不导出 HTTP 客户端和导出器自身的 span
+        let tracing = &self.config.middleware.tracing;
+        #[cfg(feature = "otel")]
+        if tracing.enabled {
+            use tracing_subscriber::filter::{LevelFilter, Targets};
+
+            let (otel_layer, guard) = crate::middleware::otel::create_otel_layer(tracing)?;
+            let targets = Targets::new()
+                .with_default(LevelFilter::INFO)
+                .with_target("hyper", LevelFilter::OFF)
+                .with_target("h2", LevelFilter::OFF)
+                .with_target("reqwest", LevelFilter::OFF)
+                .with_target("opentelemetry", LevelFilter::OFF);
+            layers.push(otel_layer.with_filter(targets).boxed());
+            guards.otel = Some(guard);
+        }
+        #[cfg(not(feature = "otel"))]
+        if tracing.enabled {
+            return Err(Error::Config("追踪导出需要启用 `otel` feature".to_string()));
+        }
 
         // 尝试设置全局默认订阅者，如果失败则忽略（可能已经设置过）
-        let _ = tracing_subscriber::registry()
-            .with(filter)
-            .with(fmt_layer)
-            .try_init();
+        let handle = tracing_subscriber::registry()
+            .with(layers)
+            .try_init()
+            .ok()
+            .map(|_| handle);
 
-        Ok(())
+        Ok((guards, handle))
     }
 }
 
@@ -296,4 +1450,34 @@ mod tests {
         assert!(builder.config.middleware.cors.enabled);
         assert_eq!(builder.config.middleware.logging.level, "debug");
     }
-}
\ No newline at end of file
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn test_builder_cli_args() {
+        use crate::cli::CliArgs;
+        use clap::Parser;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        let mut file_config = Config::default();
+        file_config.server.port = 9000;
+        file_config.middleware.logging.level = "warn".to_string();
+        file_config.save_to_file(&config_path).unwrap();
+
+        let args = CliArgs::try_parse_from([
+            "app",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--port",
+            "8080",
+        ])
+        .unwrap();
+        let builder = WebServerBuilder::new().cli_args(args).unwrap();
+        assert_eq!(builder.config.server.port, 8080);
+        assert_eq!(builder.config.middleware.logging.level, "warn");
+
+        // 显式指定的配置文件不存在时必须报错，而不是回退到默认配置
+        let args = CliArgs::try_parse_from(["app", "--config", "/nonexistent/config.toml"]).unwrap();
+        assert!(WebServerBuilder::new().cli_args(args).is_err());
+    }
+}
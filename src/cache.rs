@@ -0,0 +1,375 @@
+//! CDN 边缘缓存模块
+//!
+//! 处理函数通过 [`CachePolicy`] 设置面向 CDN 的缓存头，包括 `s-maxage`、
+//! `stale-while-revalidate` 和 surrogate key（缓存标签）：
+//!
+//! ```rust
+//! use hwhkit::{cache::CachePolicy, Json};
+//!
+//! async fn article() -> (CachePolicy, Json<serde_json::Value>) {
+//!     let policy = CachePolicy::edge(300)
+//!         .stale_while_revalidate(60)
+//!         .surrogate_key("articles")
+//!         .surrogate_key("article-42");
+//!     (policy, Json(serde_json::json!({ "id": 42 })))
+//! }
+//! ```
+//!
+//! 内容更新后，使用 `PurgeClient`（需要 `cdn` feature）按 surrogate key 清除
+//! Fastly 或 Cloudflare 上的缓存，处理函数和后台任务都可以调用。设置
+//! `[cdn.circuit_breaker] enabled = true` 后，CDN 接口持续失败时清除请求会被熔断。
+
+use axum::{
+    http::{header, HeaderName, HeaderValue},
+    response::{IntoResponseParts, ResponseParts},
+};
+use std::convert::Infallible;
+
+#[cfg(feature = "cdn")]
+pub use purge::PurgeClient;
+
+/// Fastly 使用的缓存标签响应头，多个标签以空格分隔
+pub const SURROGATE_KEY: HeaderName = HeaderName::from_static("surrogate-key");
+/// Cloudflare 使用的缓存标签响应头，多个标签以逗号分隔
+pub const CACHE_TAG: HeaderName = HeaderName::from_static("cache-tag");
+
+/// 响应缓存策略
+///
+/// 作为响应的一部分返回时写入 `Cache-Control`，设置了 surrogate key 时同时写入
+/// `Surrogate-Key` 和 `Cache-Tag`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CachePolicy {
+    no_store: bool,
+    private: bool,
+    public: bool,
+    max_age: Option<u64>,
+    s_maxage: Option<u64>,
+    stale_while_revalidate: Option<u64>,
+    stale_if_error: Option<u64>,
+    surrogate_keys: Vec<String>,
+}
+
+impl CachePolicy {
+    /// 空策略，只设置显式指定的指令
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 禁止任何缓存，适用于包含敏感数据的响应
+    pub fn no_store() -> Self {
+        Self {
+            no_store: true,
+            ..Default::default()
+        }
+    }
+
+    /// 只允许浏览器缓存 `max_age` 秒，CDN 不缓存
+    pub fn private(max_age: u64) -> Self {
+        Self {
+            private: true,
+            max_age: Some(max_age),
+            ..Default::default()
+        }
+    }
+
+    /// 由 CDN 缓存 `s_maxage` 秒，浏览器每次都回源到 CDN 校验
+    pub fn edge(s_maxage: u64) -> Self {
+        Self {
+            public: true,
+            max_age: Some(0),
+            s_maxage: Some(s_maxage),
+            ..Default::default()
+        }
+    }
+
+    /// 浏览器和 CDN 都缓存 `max_age` 秒，适用于带版本号的静态资源
+    pub fn immutable(max_age: u64) -> Self {
+        Self {
+            public: true,
+            max_age: Some(max_age),
+            ..Default::default()
+        }
+    }
+
+    /// 设置 `max-age`
+    pub fn max_age(mut self, secs: u64) -> Self {
+        self.max_age = Some(secs);
+        self
+    }
+
+    /// 设置 `s-maxage`
+    pub fn s_maxage(mut self, secs: u64) -> Self {
+        self.s_maxage = Some(secs);
+        self
+    }
+
+    /// 缓存过期后的 `secs` 秒内先返回旧内容，同时在后台重新验证
+    pub fn stale_while_revalidate(mut self, secs: u64) -> Self {
+        self.stale_while_revalidate = Some(secs);
+        self
+    }
+
+    /// 回源失败时的 `secs` 秒内继续返回旧内容
+    pub fn stale_if_error(mut self, secs: u64) -> Self {
+        self.stale_if_error = Some(secs);
+        self
+    }
+
+    /// 添加 surrogate key，用于按标签清除 CDN 缓存
+    pub fn surrogate_key(mut self, key: impl Into<String>) -> Self {
+        self.surrogate_keys.push(key.into());
+        self
+    }
+
+    /// 批量添加 surrogate key
+    pub fn surrogate_keys<I, K>(mut self, keys: I) -> Self
+    where
+        I: IntoIterator<Item = K>,
+        K: Into<String>,
+    {
+        self.surrogate_keys.extend(keys.into_iter().map(Into::into));
+        self
+    }
+
+    /// `Cache-Control` 头的值
+    pub fn cache_control(&self) -> String {
+        if self.no_store {
+            return "no-store".to_string();
+        }
+
+        let mut directives = Vec::new();
+        if self.public {
+            directives.push("public".to_string());
+        }
+        if self.private {
+            directives.push("private".to_string());
+        }
+        let mut seconds = |name: &str, value: Option<u64>| {
+            if let Some(value) = value {
+                directives.push(format!("{}={}", name, value));
+            }
+        };
+        seconds("max-age", self.max_age);
+        seconds("s-maxage", self.s_maxage);
+        seconds("stale-while-revalidate", self.stale_while_revalidate);
+        seconds("stale-if-error", self.stale_if_error);
+        directives.join(", ")
+    }
+}
+
+impl IntoResponseParts for CachePolicy {
+    type Error = Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        let headers = res.headers_mut();
+        let cache_control = self.cache_control();
+        if !cache_control.is_empty() {
+            if let Ok(value) = HeaderValue::from_str(&cache_control) {
+                headers.insert(header::CACHE_CONTROL, value);
+            }
+        }
+
+        // 私有响应不应带缓存标签
+        if !self.surrogate_keys.is_empty() && !self.private && !self.no_store {
+            if let Ok(value) = HeaderValue::from_str(&self.surrogate_keys.join(" ")) {
+                headers.insert(SURROGATE_KEY, value);
+            }
+            if let Ok(value) = HeaderValue::from_str(&self.surrogate_keys.join(",")) {
+                headers.insert(CACHE_TAG, value);
+            }
+        }
+
+        Ok(res)
+    }
+}
+
+#[cfg(feature = "cdn")]
+mod purge {
+    use crate::{
+        config::{CdnConfig, CdnProvider},
+        error::{Error, Result},
+        middleware::trace_context::TraceContext,
+        resilience::CircuitBreaker,
+    };
+    use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+
+    const FASTLY_API: &str = "https://api.fastly.com";
+    const CLOUDFLARE_API: &str = "https://api.cloudflare.com/client/v4";
+
+    /// CDN 缓存清除客户端
+    ///
+    /// 启用 `[cdn]` 配置后会作为请求扩展提供给处理函数，也可以直接创建后在后台任务中使用
+    #[derive(Debug, Clone)]
+    pub struct PurgeClient {
+        config: CdnConfig,
+        http: reqwest::Client,
+        breaker: Option<CircuitBreaker>,
+    }
+
+    impl PurgeClient {
+        /// 根据配置创建客户端
+        ///
+        /// # Errors
+        ///
+        /// 未配置 CDN 服务商时返回配置错误
+        pub fn new(config: &CdnConfig) -> Result<Self> {
+            if config.provider == CdnProvider::None {
+                return Err(Error::Config("未配置 CDN 服务商".to_string()));
+            }
+            Ok(Self {
+                config: config.clone(),
+                http: reqwest::Client::new(),
+                breaker: config
+                    .circuit_breaker
+                    .enabled
+                    .then(|| CircuitBreaker::new("cdn", &config.circuit_breaker)),
+            })
+        }
+
+        /// 按 surrogate key 清除缓存
+        pub async fn purge_keys<K: AsRef<str>>(&self, keys: &[K]) -> Result<()> {
+            if keys.is_empty() {
+                return Ok(());
+            }
+            let keys: Vec<&str> = keys.iter().map(AsRef::as_ref).collect();
+            let token = self.config.effective_token();
+
+            let request = match self.config.provider {
+                CdnProvider::Fastly => self
+                    .http
+                    .post(format!(
+                        "{}/service/{}/purge",
+                        self.api_base(FASTLY_API),
+                        self.config.service_id
+                    ))
+                    .header("Fastly-Key", token)
+                    .header("Surrogate-Key", keys.join(" ")),
+                CdnProvider::Cloudflare => self
+                    .http
+                    .post(format!(
+                        "{}/zones/{}/purge_cache",
+                        self.api_base(CLOUDFLARE_API),
+                        self.config.zone_id
+                    ))
+                    .bearer_auth(token)
+                    .json(&serde_json::json!({ "tags": keys })),
+                CdnProvider::None => return Ok(()),
+            };
+
+            let send = async {
+                let response = request
+                    .headers(TraceContext::outgoing_headers())
+                    .send()
+                    .await
+                    .map_err(|e| Error::Internal(format!("CDN 缓存清除请求失败: {}", e)))?;
+                if !response.status().is_success() {
+                    return Err(Error::Internal(format!(
+                        "CDN 缓存清除失败 ({}): {}",
+                        response.status(),
+                        response.text().await.unwrap_or_default()
+                    )));
+                }
+                Ok(())
+            };
+            match &self.breaker {
+                Some(breaker) => breaker.call(send).await?,
+                None => send.await?,
+            }
+
+            tracing::info!("已清除 CDN 缓存: {}", keys.join(", "));
+            Ok(())
+        }
+
+        fn api_base<'a>(&'a self, default: &'a str) -> &'a str {
+            self.config
+                .api_base
+                .as_deref()
+                .unwrap_or(default)
+                .trim_end_matches('/')
+        }
+    }
+
+    #[async_trait]
+    impl<S: Send + Sync> FromRequestParts<S> for PurgeClient {
+        type Rejection = Error;
+
+        async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+            parts
+                .extensions
+                .get::<PurgeClient>()
+                .cloned()
+                .ok_or_else(|| Error::Internal("CDN 缓存清除未配置".to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::response::IntoResponse;
+
+    #[test]
+    fn test_presets() {
+        assert_eq!(CachePolicy::no_store().cache_control(), "no-store");
+        assert_eq!(CachePolicy::private(60).cache_control(), "private, max-age=60");
+        assert_eq!(
+            CachePolicy::edge(300).stale_while_revalidate(60).stale_if_error(600).cache_control(),
+            "public, max-age=0, s-maxage=300, stale-while-revalidate=60, stale-if-error=600"
+        );
+        assert_eq!(CachePolicy::immutable(31536000).cache_control(), "public, max-age=31536000");
+    }
+
+    #[test]
+    fn test_surrogate_key_headers() {
+        let response = (
+            CachePolicy::edge(60).surrogate_keys(["articles", "article-42"]),
+            "ok",
+        )
+            .into_response();
+        let headers = response.headers();
+        assert_eq!(headers[SURROGATE_KEY], "articles article-42");
+        assert_eq!(headers[CACHE_TAG], "articles,article-42");
+
+        let response = (CachePolicy::private(60).surrogate_key("user-1"), "ok").into_response();
+        assert!(response.headers().get(SURROGATE_KEY).is_none());
+    }
+
+    #[cfg(feature = "cdn")]
+    #[tokio::test]
+    async fn test_purge_fastly() {
+        use crate::config::{CdnConfig, CdnProvider};
+        use axum::{extract::Path, http::HeaderMap, routing::post, Router};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let mock = Router::new().route(
+            "/service/:id/purge",
+            post(|Path(id): Path<String>, headers: HeaderMap| async move {
+                assert_eq!(id, "svc-1");
+                assert_eq!(headers["fastly-key"], "token");
+                assert_eq!(headers["surrogate-key"], "articles article-42");
+                "{}"
+            }),
+        );
+        tokio::spawn(async move { axum::serve(listener, mock).await.unwrap() });
+
+        let client = PurgeClient::new(&CdnConfig {
+            provider: CdnProvider::Fastly,
+            api_token: "token".to_string(),
+            service_id: "svc-1".to_string(),
+            api_base: Some(base.clone()),
+            ..Default::default()
+        })
+        .unwrap();
+        client.purge_keys(&["articles", "article-42"]).await.unwrap();
+
+        let client = PurgeClient::new(&CdnConfig {
+            provider: CdnProvider::Fastly,
+            service_id: "missing".to_string(),
+            api_base: Some(format!("{}/nowhere", base)),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(client.purge_keys(&["articles"]).await.is_err());
+    }
+}
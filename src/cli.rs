@@ -0,0 +1,117 @@
+//! 命令行参数模块
+//!
+//! 允许通过命令行参数或环境变量覆盖配置文件中的值
+
+use crate::{config::Config, error::Result};
+use clap::Parser;
+use std::path::PathBuf;
+
+/// 命令行参数
+///
+/// 推荐通过 `#[command(flatten)]` 嵌入到应用自己的命令行定义中，
+/// 由应用提供程序名称、版本和描述；直接调用 `CliArgs::parse()` 时
+/// `--help` 不会显示版本信息。
+///
+/// ```rust,no_run
+/// use clap::Parser;
+/// use hwhkit::{cli::CliArgs, WebServerBuilder};
+///
+/// #[derive(Parser)]
+/// #[command(version, about)]
+/// struct AppArgs {
+///     #[command(flatten)]
+///     server: CliArgs,
+/// }
+///
+/// # async fn run() -> hwhkit::Result<()> {
+/// let args = AppArgs::parse();
+/// let server = WebServerBuilder::new().cli_args(args.server)?.build().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, Parser)]
+pub struct CliArgs {
+    /// 配置文件路径
+    #[arg(short, long, env = "HWHKIT_CONFIG")]
+    pub config: Option<PathBuf>,
+
+    /// 服务器监听地址
+    #[arg(long, env = "HWHKIT_HOST")]
+    pub host: Option<String>,
+
+    /// 服务器监听端口
+    #[arg(short, long, env = "HWHKIT_PORT")]
+    pub port: Option<u16>,
+
+    /// 日志级别 (trace, debug, info, warn, error)
+    #[arg(long, env = "HWHKIT_LOG_LEVEL")]
+    pub log_level: Option<String>,
+}
+
+impl CliArgs {
+    /// 将命令行参数覆盖到配置上
+    pub fn apply(&self, config: &mut Config) {
+        if let Some(host) = &self.host {
+            config.server.host = host.clone();
+        }
+        if let Some(port) = self.port {
+            config.server.port = port;
+        }
+        if let Some(level) = &self.log_level {
+            config.middleware.logging.level = level.clone();
+        }
+    }
+
+    /// 加载 `--config` 指定的配置文件（未指定时使用默认配置）并应用覆盖
+    pub fn load_config(&self) -> Result<Config> {
+        let mut config = match &self.config {
+            Some(path) => Config::from_file(path)?,
+            None => Config::default(),
+        };
+        self.apply(&mut config);
+        Ok(config)
+    }
+}
+
+impl Config {
+    /// 从命令行参数加载配置
+    ///
+    /// 先读取 `--config` 指定的文件，再用 `--host`、`--port`、`--log-level` 覆盖
+    pub fn from_args() -> Result<Self> {
+        CliArgs::parse().load_config()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_overrides() {
+        let args = CliArgs::try_parse_from([
+            "app",
+            "--host",
+            "127.0.0.1",
+            "--port",
+            "8080",
+            "--log-level",
+            "debug",
+        ])
+        .unwrap();
+
+        let config = args.load_config().unwrap();
+        assert_eq!(config.server.host, "127.0.0.1");
+        assert_eq!(config.server.port, 8080);
+        assert_eq!(config.middleware.logging.level, "debug");
+    }
+
+    #[test]
+    fn test_cli_without_overrides_keeps_file_values() {
+        let mut config = Config::default();
+        config.server.port = 9000;
+
+        CliArgs::default().apply(&mut config);
+        assert_eq!(config.server.port, 9000);
+        assert_eq!(config.server.host, "0.0.0.0");
+    }
+}
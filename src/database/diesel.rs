@@ -0,0 +1,168 @@
+//! Diesel 集成模块
+//!
+//! 通过 `WebServerBuilder::diesel::<C>()` 启用后，构建服务器时根据 `[database]` 配置创建
+//! r2d2 连接池，处理器通过 `Extension<DieselPool<C>>` 获取。Diesel 是同步 API，查询通过
+//! [`run_blocking_db`] 在阻塞线程池中执行，不会占用异步工作线程。
+//!
+//! ```rust,ignore
+//! use axum::{Extension, Json};
+//! use diesel::{pg::PgConnection, prelude::*};
+//! use hwhkit::{database::diesel::{run_blocking_db, DieselPool}, Result};
+//!
+//! async fn list_users(Extension(pool): Extension<DieselPool<PgConnection>>) -> Result<Json<Vec<User>>> {
+//!     let users = run_blocking_db(&pool, |conn| users::table.load::<User>(conn)).await?;
+//!     Ok(Json(users))
+//! }
+//! ```
+//!
+//! 数据库后端需要在项目中启用，例如 `diesel = { version = "2.2", features = ["postgres", "r2d2"] }`。
+
+use crate::{
+    config::DatabaseConfig,
+    error::{Error, Result},
+};
+use axum::{Extension, Router};
+use diesel::r2d2::{ConnectionManager, Pool, R2D2Connection};
+use futures_util::future::BoxFuture;
+use std::{fmt, time::Duration};
+
+/// Diesel 连接池
+pub type DieselPool<C> = Pool<ConnectionManager<C>>;
+
+/// 根据配置创建连接池
+///
+/// 会阻塞直到建立 `min_connections` 个连接，在异步上下文中使用 [`connect`]
+///
+/// # Errors
+///
+/// 在 `connect_timeout_secs` 内无法建立连接时返回错误
+pub fn pool<C: R2D2Connection + 'static>(config: &DatabaseConfig) -> Result<DieselPool<C>> {
+    Pool::builder()
+        .max_size(config.max_connections)
+        .min_idle(Some(config.min_connections))
+        .connection_timeout(Duration::from_secs(config.connect_timeout_secs))
+        .idle_timeout(Some(Duration::from_secs(config.idle_timeout_secs)))
+        .build(ConnectionManager::new(config.effective_url()))
+        .map_err(|e| Error::Config(format!("无法创建数据库连接池: {}", e)))
+}
+
+/// 在阻塞线程池中创建连接池
+///
+/// # Errors
+///
+/// 在 `connect_timeout_secs` 内无法建立连接时返回错误
+pub async fn connect<C: R2D2Connection + 'static>(config: &DatabaseConfig) -> Result<DieselPool<C>> {
+    let config = config.clone();
+    let pool = tokio::task::spawn_blocking(move || pool::<C>(&config))
+        .await
+        .map_err(|e| Error::Internal(format!("创建数据库连接池失败: {}", e)))??;
+    tracing::info!("🗄️  已连接数据库（最大连接数 {}）", pool.max_size());
+    Ok(pool)
+}
+
+/// 从连接池获取连接，在阻塞线程池中执行 `f`
+///
+/// `f` 可以返回 Diesel 的 `QueryResult` 或任何能转换为 [`Error`] 的错误
+///
+/// # Errors
+///
+/// 无法获取连接时返回 503，`f` 返回错误时转换为 [`Error`]
+pub async fn run_blocking_db<C, F, T, E>(pool: &DieselPool<C>, f: F) -> Result<T>
+where
+    C: R2D2Connection + 'static,
+    F: FnOnce(&mut C) -> std::result::Result<T, E> + Send + 'static,
+    T: Send + 'static,
+    E: Into<Error> + Send + 'static,
+{
+    let pool = pool.clone();
+    tokio::task::spawn_blocking(move || {
+        let mut connection = pool
+            .get()
+            .map_err(|e| Error::ServiceUnavailable(format!("无法获取数据库连接: {}", e)))?;
+        f(&mut connection).map_err(Into::into)
+    })
+    .await
+    .map_err(|e| Error::Internal(format!("数据库任务失败: {}", e)))?
+}
+
+/// 创建连接池并返回将其提供给路由器的函数，由 `WebServerBuilder::diesel` 选择连接类型
+pub(crate) type DieselConnector = fn(DatabaseConfig) -> BoxFuture<'static, Result<DieselExtension>>;
+
+/// 指定连接类型的 [`DieselConnector`]
+pub(crate) fn connector<C: R2D2Connection + 'static>() -> DieselConnector {
+    |config| {
+        Box::pin(async move {
+            let pool = connect::<C>(&config).await?;
+            Ok(DieselExtension(Box::new(move |router: Router| {
+                router.layer(Extension(pool.clone()))
+            })))
+        })
+    }
+}
+
+/// 类型擦除后的连接池，将连接池作为请求扩展提供给路由器
+pub(crate) struct DieselExtension(Box<dyn Fn(Router) -> Router + Send + Sync>);
+
+impl DieselExtension {
+    pub(crate) fn extend(&self, router: Router) -> Router {
+        (self.0)(router)
+    }
+}
+
+impl fmt::Debug for DieselExtension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DieselExtension").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use diesel::{sql_query, RunQueryDsl, SqliteConnection};
+
+    fn config() -> DatabaseConfig {
+        DatabaseConfig {
+            enabled: true,
+            url: ":memory:".to_string(),
+            max_connections: 2,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_blocking_db() {
+        let pool = connect::<SqliteConnection>(&config()).await.unwrap();
+        assert_eq!(pool.max_size(), 2);
+
+        let rows = run_blocking_db(&pool, |conn| {
+            sql_query("CREATE TABLE users (name TEXT)").execute(conn)?;
+            sql_query("INSERT INTO users VALUES ('alice'), ('bob')").execute(conn)
+        })
+        .await
+        .unwrap();
+        assert_eq!(rows, 2);
+
+        let result = run_blocking_db(&pool, |conn| sql_query("SELECT * FROM missing").execute(conn)).await;
+        assert!(matches!(result, Err(Error::Diesel(_))));
+    }
+
+    #[tokio::test]
+    async fn test_connector_provides_pool() {
+        use axum::{body::Body, extract::Request, routing::get};
+        use tower::util::ServiceExt;
+
+        let extension = connector::<SqliteConnection>()(config()).await.unwrap();
+        let app = extension.extend(Router::new().route(
+            "/",
+            get(|Extension(pool): Extension<DieselPool<SqliteConnection>>| async move {
+                run_blocking_db(&pool, |conn| sql_query("SELECT 1").execute(conn))
+                    .await
+                    .map(|_| "ok")
+            }),
+        ));
+
+        let request = Request::builder().uri("/").body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+    }
+}
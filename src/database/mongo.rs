@@ -0,0 +1,135 @@
+//! MongoDB 集成模块
+//!
+//! 启用 `[mongodb]` 后，构建服务器时根据配置创建客户端，处理器通过 [`Mongo`] 提取器获取
+//! 配置的数据库。启用就绪探针时会登记 `mongodb` 检查（执行 `ping` 命令）。
+//!
+//! ```toml
+//! [mongodb]
+//! enabled = true
+//! uri = "mongodb://localhost:27017"  # 或使用 uri_file 从文件读取
+//! database = "app"
+//! ```
+//!
+//! ```rust
+//! use hwhkit::{database::mongo::Mongo, Json, Result};
+//! use mongodb::bson::{doc, Document};
+//!
+//! async fn find_user(db: Mongo) -> Result<Json<Option<Document>>> {
+//!     let user = db.collection::<Document>("users").find_one(doc! { "name": "alice" }).await?;
+//!     Ok(Json(user))
+//! }
+//! ```
+
+use crate::{
+    config::MongoConfig,
+    error::{Error, Result},
+};
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+use mongodb::{bson::doc, options::ClientOptions, Client, Database};
+use std::ops::Deref;
+
+/// 配置的 MongoDB 数据库
+///
+/// 可作为提取器在处理器中使用，解引用为 [`mongodb::Database`]
+#[derive(Debug, Clone)]
+pub struct Mongo(pub Database);
+
+impl Mongo {
+    /// 数据库所属的客户端
+    pub fn client(&self) -> &Client {
+        self.0.client()
+    }
+
+    /// 检查连通性
+    ///
+    /// # Errors
+    ///
+    /// 无法连接时返回服务不可用错误
+    pub async fn ping(&self) -> Result<()> {
+        self.0
+            .run_command(doc! { "ping": 1 })
+            .await
+            .map(|_| ())
+            .map_err(|e| Error::ServiceUnavailable(format!("无法连接 MongoDB: {}", e)))
+    }
+}
+
+impl Deref for Mongo {
+    type Target = Database;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Mongo {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+        parts
+            .extensions
+            .get::<Mongo>()
+            .cloned()
+            .ok_or_else(|| Error::Internal("未启用 MongoDB，请检查 [mongodb] 配置".to_string()))
+    }
+}
+
+/// 根据配置创建客户端
+///
+/// 客户端在首次操作时才建立连接，连通性由就绪探针检查
+///
+/// # Errors
+///
+/// 连接地址无效时返回配置错误
+pub async fn connect(config: &MongoConfig) -> Result<Mongo> {
+    let options = ClientOptions::parse(config.effective_uri())
+        .await
+        .map_err(|e| Error::Config(format!("无效的 MongoDB 连接地址: {}", e)))?;
+    let client = Client::with_options(options)
+        .map_err(|e| Error::Config(format!("无法创建 MongoDB 客户端: {}", e)))?;
+    tracing::info!("🍃 已创建 MongoDB 客户端（数据库 {}）", config.database);
+    Ok(Mongo(client.database(&config.database)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, extract::Request, routing::get, Extension, Router};
+    use tower::util::ServiceExt;
+
+    fn config() -> MongoConfig {
+        MongoConfig {
+            enabled: true,
+            // 不存在的服务器，缩短选择超时以便 ping 快速失败
+            uri: "mongodb://127.0.0.1:1/?serverSelectionTimeoutMS=100".to_string(),
+            database: "app".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mongo_extractor() {
+        let mongo = connect(&config()).await.unwrap();
+        let app = Router::new()
+            .route("/", get(|db: Mongo| async move { db.name().to_string() }))
+            .layer(Extension(mongo));
+
+        let request = Request::builder().uri("/").body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"app");
+    }
+
+    #[tokio::test]
+    async fn test_ping_unreachable() {
+        let mongo = connect(&config()).await.unwrap();
+        assert!(matches!(mongo.ping().await, Err(Error::ServiceUnavailable(_))));
+
+        let invalid = MongoConfig {
+            uri: "http://localhost".to_string(),
+            ..config()
+        };
+        assert!(connect(&invalid).await.is_err());
+    }
+}
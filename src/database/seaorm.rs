@@ -0,0 +1,77 @@
+//! SeaORM 集成模块
+//!
+//! 连接根据 `[database]` 配置创建，处理器通过 `Extension<DatabaseConnection>` 获取。
+//! 查询返回的 `DbErr` 可以直接用 `?` 转换为 [`Error`]，响应中不会包含 SQL 细节。
+//!
+//! ```rust
+//! use axum::Extension;
+//! use hwhkit::{database::seaorm::DatabaseConnection, Result};
+//!
+//! async fn ping(Extension(db): Extension<DatabaseConnection>) -> Result<&'static str> {
+//!     db.ping().await?;
+//!     Ok("ok")
+//! }
+//! ```
+//!
+//! 数据库驱动需要在项目中启用，例如 `sea-orm = { version = "1.1", features = ["sqlx-postgres"] }`。
+
+pub use sea_orm::DatabaseConnection;
+
+use crate::{config::DatabaseConfig, error::Result};
+use sea_orm::{ConnectOptions, Database};
+use std::time::Duration;
+
+/// 根据配置生成连接选项
+pub fn connect_options(config: &DatabaseConfig) -> ConnectOptions {
+    let mut options = ConnectOptions::new(config.effective_url());
+    options
+        .max_connections(config.max_connections)
+        .min_connections(config.min_connections)
+        .connect_timeout(Duration::from_secs(config.connect_timeout_secs))
+        .idle_timeout(Duration::from_secs(config.idle_timeout_secs));
+    options
+}
+
+/// 根据配置创建连接池
+///
+/// # Errors
+///
+/// 无法连接数据库时返回错误
+pub async fn connect(config: &DatabaseConfig) -> Result<DatabaseConnection> {
+    let connection = Database::connect(connect_options(config)).await?;
+    tracing::info!("🗄️  已连接数据库（最大连接数 {}）", config.max_connections);
+    Ok(connection)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connect_options() {
+        let config = DatabaseConfig {
+            enabled: true,
+            url: "postgres://localhost/app".to_string(),
+            resolved_url: Some("postgres://secret@localhost/app".to_string()),
+            max_connections: 20,
+            min_connections: 2,
+            ..Default::default()
+        };
+        let options = connect_options(&config);
+        assert_eq!(options.get_url(), "postgres://secret@localhost/app");
+        assert_eq!(options.get_max_connections(), Some(20));
+        assert_eq!(options.get_min_connections(), Some(2));
+        assert_eq!(options.get_connect_timeout(), Some(Duration::from_secs(30)));
+        assert_eq!(options.get_idle_timeout(), Some(Duration::from_secs(600)));
+    }
+
+    #[tokio::test]
+    async fn test_connect_error() {
+        let config = DatabaseConfig {
+            enabled: true,
+            url: "unknown://localhost/app".to_string(),
+            ..Default::default()
+        };
+        assert!(connect(&config).await.is_err());
+    }
+}
@@ -0,0 +1,163 @@
+//! 内嵌静态资源模块
+//!
+//! 使用 [`rust_embed`] 把前端构建产物编译进二进制，部署时不需要磁盘上的静态文件目录：
+//!
+//! ```rust,ignore
+//! use hwhkit::{embed::RustEmbed, WebServerBuilder};
+//!
+//! // 未直接依赖 rust-embed 时需要通过 crate_path 指向重新导出的路径
+//! #[derive(RustEmbed)]
+//! #[folder = "dist/"]
+//! #[crate_path = "hwhkit::embed::rust_embed"]
+//! struct Assets;
+//!
+//! let server = WebServerBuilder::new()
+//!     .embedded_static::<Assets>("/static")
+//!     .build()
+//!     .await?;
+//! ```
+//!
+//! 响应带有按内容生成的强 `ETag` 和编译时的 `Last-Modified`，`If-None-Match` 匹配时返回 304；
+//! `Cache-Control` 使用 `[middleware.static_files.cache]` 的规则。
+
+use crate::{
+    config::StaticCacheConfig,
+    error::Result,
+    middleware::{cache_validation::etag_matches, static_files::CacheRules},
+};
+use axum::{
+    extract::Request,
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use std::{
+    marker::PhantomData,
+    sync::Arc,
+    time::{Duration, UNIX_EPOCH},
+};
+
+pub use rust_embed::{self, RustEmbed};
+
+/// 创建内嵌资源的路由，挂载在 `prefix` 下
+pub(crate) fn router<E: RustEmbed + Send + Sync + 'static>(
+    prefix: &str,
+    cache: &StaticCacheConfig,
+) -> Result<Router> {
+    let assets = Arc::new(EmbeddedAssets::<E> {
+        cache: CacheRules::new(cache)?,
+        _assets: PhantomData,
+    });
+    let service = get(move |request: Request| {
+        let assets = assets.clone();
+        async move { assets.serve(request) }
+    });
+
+    let prefix = prefix.trim_end_matches('/');
+    tracing::info!("启用内嵌静态资源: {}/", prefix);
+    Ok(if prefix.is_empty() {
+        Router::new().fallback_service(service)
+    } else {
+        Router::new().nest_service(prefix, service)
+    })
+}
+
+struct EmbeddedAssets<E> {
+    cache: CacheRules,
+    _assets: PhantomData<fn() -> E>,
+}
+
+impl<E: RustEmbed> EmbeddedAssets<E> {
+    fn serve(&self, request: Request) -> Response {
+        // 目录请求返回其中的 index.html
+        let path = request.uri().path().trim_start_matches('/');
+        let path = if path.is_empty() || path.ends_with('/') {
+            format!("{}index.html", path)
+        } else {
+            path.to_string()
+        };
+        let Some(file) = E::get(&path) else {
+            return StatusCode::NOT_FOUND.into_response();
+        };
+
+        let etag = format!(
+            "\"{}\"",
+            file.metadata.sha256_hash()[..16]
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>()
+        );
+        let not_modified = request
+            .headers()
+            .get(header::IF_NONE_MATCH)
+            .zip(HeaderValue::from_str(&etag).ok())
+            .is_some_and(|(if_none_match, etag)| etag_matches(if_none_match, &etag));
+
+        let mut response = if not_modified {
+            StatusCode::NOT_MODIFIED.into_response()
+        } else {
+            let content_type = mime_guess::from_path(&path).first_or_octet_stream();
+            ([(header::CONTENT_TYPE, content_type.to_string())], file.data).into_response()
+        };
+        let headers = response.headers_mut();
+        if let Ok(value) = HeaderValue::from_str(&etag) {
+            headers.insert(header::ETAG, value);
+        }
+        if let Some(modified) = file.metadata.last_modified() {
+            let modified = UNIX_EPOCH + Duration::from_secs(modified);
+            if let Ok(value) = HeaderValue::from_str(&httpdate::fmt_http_date(modified)) {
+                headers.insert(header::LAST_MODIFIED, value);
+            }
+        }
+        self.cache.apply(&path, &mut response);
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::CacheControlConfig;
+    use axum::body::Body;
+    use tower::ServiceExt;
+
+    #[derive(RustEmbed)]
+    #[folder = "res/docs/"]
+    struct Docs;
+
+    #[tokio::test]
+    async fn test_embedded_assets() {
+        let cache = StaticCacheConfig {
+            default: CacheControlConfig {
+                max_age: Some("1d".to_string()),
+                ..Default::default()
+            },
+            rules: Vec::new(),
+        };
+        let app = router::<Docs>("/assets", &cache).unwrap();
+        let send = |uri: &str, if_none_match: Option<&str>| {
+            let mut request = Request::builder().uri(uri);
+            if let Some(tag) = if_none_match {
+                request = request.header(header::IF_NONE_MATCH, tag);
+            }
+            app.clone().oneshot(request.body(Body::empty()).unwrap())
+        };
+
+        let response = send("/assets/README.md", None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "text/markdown");
+        assert_eq!(response.headers()[header::CACHE_CONTROL], "public, max-age=86400");
+        let etag = response.headers()[header::ETAG].to_str().unwrap().to_string();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], &Docs::get("README.md").unwrap().data[..]);
+
+        let response = send("/assets/README.md", Some(&etag)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        let response = send("/assets/LICENSE-redoc", None).await.unwrap();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/octet-stream");
+        let response = send("/assets/missing.js", None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -1,11 +1,20 @@
 //! 错误处理模块
 
 use axum::{
-    http::StatusCode,
+    extract::{Request, State},
+    http::{header, request, StatusCode},
+    middleware::Next,
     response::{IntoResponse, Response},
     Json,
 };
 use serde_json::json;
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 use thiserror::Error;
 
 /// HwhKit 的主要错误类型
@@ -33,17 +42,128 @@ pub enum Error {
     #[cfg(feature = "templates")]
     Template(#[from] tera::Error),
 
+    #[error("模板错误: {0}")]
+    #[cfg(feature = "templates")]
+    TemplateRender(String),
+
+    #[error("模板错误: {0}")]
+    #[cfg(feature = "askama")]
+    Askama(#[from] askama::Error),
+
     #[error("JWT 错误: {0}")]
     #[cfg(feature = "jwt")]
     Jwt(#[from] jsonwebtoken::errors::Error),
 
+    #[error("数据库错误: {0}")]
+    #[cfg(feature = "seaorm")]
+    SeaOrm(#[from] sea_orm::DbErr),
+
+    #[error("数据库错误: {0}")]
+    #[cfg(feature = "diesel")]
+    Diesel(#[from] diesel::result::Error),
+
+    #[error("数据库错误: {0}")]
+    #[cfg(feature = "mongodb")]
+    Mongo(#[from] mongodb::error::Error),
+
+    #[error("参数校验失败: {0}")]
+    #[cfg(feature = "validation")]
+    Validation(#[from] validator::ValidationErrors),
+
+    #[error("请求无效: {0}")]
+    BadRequest(String),
+
+    #[error("资源不存在: {0}")]
+    NotFound(String),
+
+    #[error("请求体过大: {0}")]
+    PayloadTooLarge(String),
+
+    #[error("未授权: {0}")]
+    Unauthorized(String),
+
+    #[error("禁止访问: {0}")]
+    Forbidden(String),
+
+    #[error("服务不可用: {0}")]
+    ServiceUnavailable(String),
+
+    #[error("网关错误: {0}")]
+    BadGateway(String),
+
+    #[error("网关超时: {0}")]
+    GatewayTimeout(String),
+
     #[error("内部错误: {0}")]
     Internal(String),
 }
 
-impl IntoResponse for Error {
-    fn into_response(self) -> Response {
-        let (status, error_message) = match self {
+/// 是否向客户端返回内部错误的详细信息，由 `[errors] expose_details` 设置
+static EXPOSE_DETAILS: AtomicBool = AtomicBool::new(cfg!(debug_assertions));
+
+/// 设置是否向客户端返回内部错误的详细信息，构建服务器时按 `[errors] expose_details` 调用
+pub(crate) fn set_expose_details(expose: bool) {
+    EXPOSE_DETAILS.store(expose, Ordering::Relaxed);
+}
+
+impl Error {
+    /// 是否为服务器内部错误，其信息可能包含路径、配置等内部细节
+    fn is_internal(&self) -> bool {
+        match self {
+            Error::Config(_)
+            | Error::Io(_)
+            | Error::TomlParsing(_)
+            | Error::ServerStart(_)
+            | Error::Middleware(_)
+            | Error::Internal(_) => true,
+            #[cfg(feature = "templates")]
+            Error::Template(_) | Error::TemplateRender(_) => true,
+            #[cfg(feature = "askama")]
+            Error::Askama(_) => true,
+            _ => false,
+        }
+    }
+
+    /// 返回给客户端的状态码和错误信息
+    ///
+    /// 未开启 `[errors] expose_details` 时内部错误只返回通用信息
+    pub(crate) fn status_and_message(&self) -> (StatusCode, String) {
+        self.status_and_message_with(EXPOSE_DETAILS.load(Ordering::Relaxed))
+    }
+
+    fn status_and_message_with(&self, expose_details: bool) -> (StatusCode, String) {
+        if self.is_internal() && !expose_details {
+            return (StatusCode::INTERNAL_SERVER_ERROR, "服务器内部错误".to_string());
+        }
+        let (status, message) = self.default_status_and_message();
+
+        // 信息是翻译键时使用当前请求语言的翻译
+        #[cfg(feature = "i18n")]
+        if let Some(text) = self.detail().and_then(crate::i18n::lookup) {
+            return (status, text);
+        }
+        (status, message)
+    }
+
+    /// 面向客户端的错误信息（不含前缀），可以作为翻译键
+    #[cfg(feature = "i18n")]
+    fn detail(&self) -> Option<&str> {
+        match self {
+            Error::BadRequest(detail)
+            | Error::NotFound(detail)
+            | Error::PayloadTooLarge(detail)
+            | Error::Unauthorized(detail)
+            | Error::Forbidden(detail)
+            | Error::ServiceUnavailable(detail)
+            | Error::BadGateway(detail)
+            | Error::GatewayTimeout(detail)
+            | Error::Internal(detail) => Some(detail),
+            _ => None,
+        }
+    }
+
+    fn default_status_and_message(&self) -> (StatusCode, String) {
+        match self {
             Error::Config(_) => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
             Error::Io(_) => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
             Error::Serialization(_) => (StatusCode::BAD_REQUEST, self.to_string()),
@@ -52,19 +172,161 @@ impl IntoResponse for Error {
             Error::Middleware(_) => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
             #[cfg(feature = "templates")]
             Error::Template(_) => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+            #[cfg(feature = "templates")]
+            Error::TemplateRender(_) => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+            #[cfg(feature = "askama")]
+            Error::Askama(_) => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
             #[cfg(feature = "jwt")]
             Error::Jwt(_) => (StatusCode::UNAUTHORIZED, "认证失败".to_string()),
+            // 不向客户端暴露 SQL 和连接信息
+            #[cfg(feature = "seaorm")]
+            Error::SeaOrm(_) => (StatusCode::INTERNAL_SERVER_ERROR, "数据库错误".to_string()),
+            #[cfg(feature = "diesel")]
+            Error::Diesel(_) => (StatusCode::INTERNAL_SERVER_ERROR, "数据库错误".to_string()),
+            #[cfg(feature = "mongodb")]
+            Error::Mongo(_) => (StatusCode::INTERNAL_SERVER_ERROR, "数据库错误".to_string()),
+            #[cfg(feature = "validation")]
+            Error::Validation(_) => (StatusCode::UNPROCESSABLE_ENTITY, "参数校验失败".to_string()),
+            Error::BadRequest(_) => (StatusCode::BAD_REQUEST, self.to_string()),
+            Error::NotFound(_) => (StatusCode::NOT_FOUND, self.to_string()),
+            Error::PayloadTooLarge(_) => (StatusCode::PAYLOAD_TOO_LARGE, self.to_string()),
+            Error::Unauthorized(_) => (StatusCode::UNAUTHORIZED, self.to_string()),
+            Error::Forbidden(_) => (StatusCode::FORBIDDEN, self.to_string()),
+            Error::ServiceUnavailable(_) => (StatusCode::SERVICE_UNAVAILABLE, self.to_string()),
+            Error::BadGateway(_) => (StatusCode::BAD_GATEWAY, self.to_string()),
+            Error::GatewayTimeout(_) => (StatusCode::GATEWAY_TIMEOUT, self.to_string()),
             Error::Internal(_) => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
+        }
+    }
+}
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        self.into_response_with_request_id(None)
+    }
+}
+
+impl Error {
+    /// 生成错误响应，响应体附带请求 ID，便于按用户反馈查找日志
+    pub(crate) fn into_response_with_request_id(self, request_id: Option<&str>) -> Response {
+        // 校验失败时列出每个字段的错误
+        #[cfg(feature = "validation")]
+        let fields = match &self {
+            Error::Validation(errors) => serde_json::to_value(errors).ok(),
+            _ => None,
         };
+        #[cfg(not(feature = "validation"))]
+        let fields: Option<serde_json::Value> = None;
+
+        let (status, error_message) = self.status_and_message();
+        // 隐藏详细信息时在日志中保留完整的错误
+        if self.is_internal() && !EXPOSE_DETAILS.load(Ordering::Relaxed) {
+            tracing::error!(request_id = request_id.unwrap_or("-"), "{}", self);
+        }
 
-        let body = Json(json!({
+        let mut body = json!({
             "error": error_message,
             "status": status.as_u16()
-        }));
+        });
+        if let Some(fields) = fields {
+            body["fields"] = fields;
+        }
+        if let Some(request_id) = request_id {
+            body["request_id"] = request_id.into();
+        }
+
+        let mut response = (status, Json(body)).into_response();
+        response.extensions_mut().insert(ErrorSource(Arc::new(self)));
+        response
+    }
+}
 
-        (status, body).into_response()
+/// 响应扩展：生成该响应的错误，由 [`error_handler_middleware`] 交给错误处理函数
+#[derive(Debug, Clone)]
+struct ErrorSource(Arc<Error>);
+
+type ErrorHandlerFn = dyn Fn(&Error, &request::Parts) -> Response + Send + Sync;
+
+/// 自定义错误处理函数，由 [`WebServerBuilder::on_error`](crate::WebServerBuilder::on_error) 注册
+#[derive(Clone)]
+pub struct ErrorHandler(Arc<ErrorHandlerFn>);
+
+impl ErrorHandler {
+    /// 创建错误处理函数
+    pub fn new<F>(handler: F) -> Self
+    where
+        F: Fn(&Error, &request::Parts) -> Response + Send + Sync + 'static,
+    {
+        Self(Arc::new(handler))
+    }
+}
+
+impl fmt::Debug for ErrorHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErrorHandler").finish_non_exhaustive()
     }
 }
 
+/// 使用自定义错误处理函数替换 [`Error`] 生成的响应
+///
+/// 原响应中其他中间件添加的响应头（如 CORS、`Set-Cookie`）在新响应没有设置时保留
+pub(crate) async fn error_handler_middleware(
+    State(ErrorHandler(handler)): State<ErrorHandler>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let (parts, body) = request.into_parts();
+    let (mut snapshot, ()) = Request::new(()).into_parts();
+    snapshot.method = parts.method.clone();
+    snapshot.uri = parts.uri.clone();
+    snapshot.version = parts.version;
+    snapshot.headers = parts.headers.clone();
+    snapshot.extensions = parts.extensions.clone();
+
+    let mut response = next.run(Request::from_parts(parts, body)).await;
+    let Some(ErrorSource(error)) = response.extensions_mut().remove::<ErrorSource>() else {
+        return response;
+    };
+
+    let mut replaced = handler(&error, &snapshot);
+    let headers = replaced.headers_mut();
+    for name in response.headers().keys() {
+        if name == header::CONTENT_TYPE || name == header::CONTENT_LENGTH || headers.contains_key(name) {
+            continue;
+        }
+        for value in response.headers().get_all(name) {
+            headers.append(name.clone(), value.clone());
+        }
+    }
+    let mut extensions = std::mem::take(response.extensions_mut());
+    extensions.extend(std::mem::take(replaced.extensions_mut()));
+    *replaced.extensions_mut() = extensions;
+    replaced
+}
+
 /// HwhKit 的结果类型
-pub type Result<T> = std::result::Result<T, Error>;
\ No newline at end of file
+pub type Result<T> = std::result::Result<T, Error>;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hide_internal_details() {
+        let error = Error::Config("无法读取 /etc/app/secret.toml".to_string());
+        assert_eq!(
+            error.status_and_message_with(false),
+            (StatusCode::INTERNAL_SERVER_ERROR, "服务器内部错误".to_string())
+        );
+        assert_eq!(
+            error.status_and_message_with(true).1,
+            "配置错误: 无法读取 /etc/app/secret.toml"
+        );
+
+        // 面向客户端的错误信息不受影响
+        let error = Error::NotFound("用户不存在".to_string());
+        assert_eq!(
+            error.status_and_message_with(false),
+            (StatusCode::NOT_FOUND, "资源不存在: 用户不存在".to_string())
+        );
+    }
+}
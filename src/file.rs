@@ -0,0 +1,380 @@
+//! 文件下载模块
+//!
+//! [`FileResponse`] 以流的方式返回文件，自动设置 `Content-Type`（按扩展名推断）、`Content-Length`
+//! 和 `Content-Disposition`，处理器不需要手动拼接响应头。
+//!
+//! ```rust
+//! use hwhkit::file::FileResponse;
+//!
+//! async fn download() -> FileResponse {
+//!     FileResponse::attachment("reports/2024-q1.pdf").filename("季度报告.pdf")
+//! }
+//!
+//! async fn preview() -> FileResponse {
+//!     FileResponse::inline("reports/2024-q1.pdf")
+//! }
+//! ```
+//!
+//! 通过 [`WebServerBuilder`](crate::WebServerBuilder) 构建的服务会处理请求中的 `Range` 头，
+//! 返回 206 部分内容，支持断点续传和音视频拖动。
+
+use crate::error::Error;
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use futures_util::{stream, StreamExt};
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio_util::io::ReaderStream;
+
+/// 浏览器处理文件的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Disposition {
+    /// 下载保存
+    Attachment,
+    /// 在浏览器中直接打开
+    Inline,
+}
+
+/// 文件响应
+#[derive(Debug, Clone)]
+pub struct FileResponse {
+    path: PathBuf,
+    disposition: Disposition,
+    filename: Option<String>,
+    content_type: Option<String>,
+}
+
+impl FileResponse {
+    /// 作为附件下载，默认文件名为路径中的文件名
+    pub fn attachment(path: impl Into<PathBuf>) -> Self {
+        Self::new(path.into(), Disposition::Attachment)
+    }
+
+    /// 在浏览器中直接打开（如 PDF 预览、图片）
+    pub fn inline(path: impl Into<PathBuf>) -> Self {
+        Self::new(path.into(), Disposition::Inline)
+    }
+
+    fn new(path: PathBuf, disposition: Disposition) -> Self {
+        Self {
+            path,
+            disposition,
+            filename: None,
+            content_type: None,
+        }
+    }
+
+    /// 设置客户端看到的文件名
+    pub fn filename(mut self, filename: impl Into<String>) -> Self {
+        self.filename = Some(filename.into());
+        self
+    }
+
+    /// 设置 Content-Type，默认按文件名的扩展名推断
+    pub fn content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+
+    /// 文件路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn download_name(&self) -> Option<String> {
+        self.filename
+            .clone()
+            .or_else(|| self.path.file_name().map(|name| name.to_string_lossy().to_string()))
+    }
+
+    fn resolve_content_type(&self, name: Option<&str>) -> String {
+        self.content_type.clone().unwrap_or_else(|| {
+            name.map(Path::new)
+                .unwrap_or(&self.path)
+                .extension()
+                .and_then(|ext| mime_guess::from_ext(&ext.to_string_lossy()).first())
+                .map(|mime| mime.to_string())
+                .unwrap_or_else(|| "application/octet-stream".to_string())
+        })
+    }
+}
+
+/// 生成 Content-Disposition，非 ASCII 文件名使用 RFC 5987 编码
+fn content_disposition(disposition: Disposition, filename: Option<&str>) -> String {
+    let kind = match disposition {
+        Disposition::Attachment => "attachment",
+        Disposition::Inline => "inline",
+    };
+    let Some(filename) = filename else {
+        return kind.to_string();
+    };
+
+    let fallback: String = filename
+        .chars()
+        .map(|c| match c {
+            ' '..='~' if c != '"' && c != '\\' => c,
+            _ => '_',
+        })
+        .collect();
+    if fallback == filename {
+        return format!("{}; filename=\"{}\"", kind, filename);
+    }
+
+    let encoded: String = filename
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect();
+    format!("{}; filename=\"{}\"; filename*=UTF-8''{}", kind, fallback, encoded)
+}
+
+impl IntoResponse for FileResponse {
+    fn into_response(self) -> Response {
+        let metadata = match std::fs::metadata(&self.path) {
+            Ok(metadata) if metadata.is_file() => metadata,
+            _ => return Error::NotFound(self.path.display().to_string()).into_response(),
+        };
+        let length = metadata.len();
+        let name = self.download_name();
+        let content_type = self.resolve_content_type(name.as_deref());
+        let disposition = content_disposition(self.disposition, name.as_deref());
+
+        let mut response = Response::new(file_body(self.path.clone(), 0, length));
+        let headers = response.headers_mut();
+        if let Ok(value) = HeaderValue::from_str(&content_type) {
+            headers.insert(header::CONTENT_TYPE, value);
+        }
+        headers.insert(header::CONTENT_LENGTH, HeaderValue::from(length));
+        headers.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+        if let Ok(modified) = metadata.modified() {
+            if let Ok(value) = HeaderValue::from_str(&httpdate::fmt_http_date(modified)) {
+                headers.insert(header::LAST_MODIFIED, value);
+            }
+        }
+        if let Ok(value) = HeaderValue::from_str(&disposition) {
+            headers.insert(header::CONTENT_DISPOSITION, value);
+        }
+        response.extensions_mut().insert(FileSource {
+            path: self.path,
+            length,
+        });
+        response
+    }
+}
+
+/// 附加在响应上的文件信息，供 [`range_middleware`] 截取部分内容
+#[derive(Debug, Clone)]
+struct FileSource {
+    path: PathBuf,
+    length: u64,
+}
+
+/// 从 `start` 开始读取 `length` 字节，首次读取时才打开文件
+fn file_body(path: PathBuf, start: u64, length: u64) -> Body {
+    let open = async move {
+        let mut file = tokio::fs::File::open(path).await?;
+        if start > 0 {
+            file.seek(SeekFrom::Start(start)).await?;
+        }
+        Ok::<_, std::io::Error>(file.take(length))
+    };
+    let stream = stream::once(open)
+        .map(|file| match file {
+            Ok(file) => ReaderStream::new(file).left_stream(),
+            Err(e) => stream::once(async move { Err(e) }).right_stream(),
+        })
+        .flatten();
+    Body::from_stream(stream)
+}
+
+/// 解析 `Range` 头
+///
+/// 只支持单个字节范围；格式无法识别或包含多个范围时返回 `None`，按完整文件响应。
+/// 范围超出文件长度时返回 `Some(None)`。
+fn parse_range(value: &str, length: u64) -> Option<Option<(u64, u64)>> {
+    let spec = value.trim().strip_prefix("bytes=")?;
+    if spec.contains(',') {
+        return None;
+    }
+    let (start, end) = spec.split_once('-')?;
+    let (start, end) = (start.trim(), end.trim());
+
+    let range = if start.is_empty() {
+        // 最后 N 个字节
+        let suffix: u64 = end.parse().ok()?;
+        (suffix > 0 && length > 0).then(|| (length.saturating_sub(suffix), length - 1))
+    } else {
+        let start: u64 = start.parse().ok()?;
+        let end = if end.is_empty() {
+            length.saturating_sub(1)
+        } else {
+            let end: u64 = end.parse().ok()?;
+            if end < start {
+                return None;
+            }
+            end.min(length.saturating_sub(1))
+        };
+        (start < length).then_some((start, end))
+    };
+    Some(range)
+}
+
+/// 按请求的 `Range` 头将 [`FileResponse`] 改写为 206 部分内容
+pub(crate) async fn range_middleware(request: Request, next: Next) -> Response {
+    let range = (request.method() == Method::GET)
+        .then(|| request.headers().get(header::RANGE))
+        .flatten()
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    let mut response = next.run(request).await;
+    let Some(range) = range else {
+        return response;
+    };
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+    let Some(source) = response.extensions_mut().remove::<FileSource>() else {
+        return response;
+    };
+
+    match parse_range(&range, source.length) {
+        None => response,
+        Some(None) => {
+            let mut response = StatusCode::RANGE_NOT_SATISFIABLE.into_response();
+            if let Ok(value) = HeaderValue::from_str(&format!("bytes */{}", source.length)) {
+                response.headers_mut().insert(header::CONTENT_RANGE, value);
+            }
+            response
+        }
+        Some(Some((start, end))) => {
+            let length = end - start + 1;
+            let (mut parts, _) = response.into_parts();
+            parts.status = StatusCode::PARTIAL_CONTENT;
+            parts.headers.insert(header::CONTENT_LENGTH, HeaderValue::from(length));
+            if let Ok(value) =
+                HeaderValue::from_str(&format!("bytes {}-{}/{}", start, end, source.length))
+            {
+                parts.headers.insert(header::CONTENT_RANGE, value);
+            }
+            Response::from_parts(parts, file_body(source.path, start, length))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::get, Router};
+    use tower::ServiceExt;
+
+    async fn read(response: Response) -> (StatusCode, axum::http::HeaderMap, Vec<u8>) {
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, headers, body.to_vec())
+    }
+
+    #[tokio::test]
+    async fn test_attachment() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.bin");
+        std::fs::write(&path, b"report body").unwrap();
+
+        let response = FileResponse::attachment(&path).filename("季度报告.pdf").into_response();
+        let (status, headers, body) = read(response).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, b"report body");
+        assert_eq!(headers[header::CONTENT_TYPE], "application/pdf");
+        assert_eq!(headers[header::CONTENT_LENGTH], "11");
+        assert!(headers.contains_key(header::LAST_MODIFIED));
+        assert_eq!(
+            headers[header::CONTENT_DISPOSITION],
+            "attachment; filename=\"____.pdf\"; filename*=UTF-8''%E5%AD%A3%E5%BA%A6%E6%8A%A5%E5%91%8A.pdf"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_inline_and_missing() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chart.png");
+        std::fs::write(&path, b"png").unwrap();
+
+        let (_, headers, _) = read(FileResponse::inline(&path).into_response()).await;
+        assert_eq!(headers[header::CONTENT_TYPE], "image/png");
+        assert_eq!(headers[header::CONTENT_DISPOSITION], "inline; filename=\"chart.png\"");
+
+        let (_, headers, _) = read(
+            FileResponse::attachment(&path)
+                .content_type("application/octet-stream")
+                .into_response(),
+        )
+        .await;
+        assert_eq!(headers[header::CONTENT_TYPE], "application/octet-stream");
+
+        let (status, _, _) = read(FileResponse::attachment(dir.path().join("missing")).into_response()).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _, _) = read(FileResponse::attachment(dir.path()).into_response()).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("bytes=0-4", 10), Some(Some((0, 4))));
+        assert_eq!(parse_range("bytes=5-", 10), Some(Some((5, 9))));
+        assert_eq!(parse_range("bytes=-3", 10), Some(Some((7, 9))));
+        assert_eq!(parse_range("bytes=-30", 10), Some(Some((0, 9))));
+        assert_eq!(parse_range("bytes=8-100", 10), Some(Some((8, 9))));
+        assert_eq!(parse_range("bytes=10-", 10), Some(None));
+        assert_eq!(parse_range("bytes=0-1,4-5", 10), None);
+        assert_eq!(parse_range("items=0-1", 10), None);
+        assert_eq!(parse_range("bytes=5-2", 10), None);
+    }
+
+    #[tokio::test]
+    async fn test_range_requests() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("video.mp4");
+        std::fs::write(&path, b"0123456789").unwrap();
+
+        let app = Router::new()
+            .route("/video", get(move || async move { FileResponse::inline(path) }))
+            .layer(axum::middleware::from_fn(range_middleware));
+        let request = |range: Option<&str>| {
+            let mut builder = Request::builder().uri("/video");
+            if let Some(range) = range {
+                builder = builder.header(header::RANGE, range);
+            }
+            builder.body(Body::empty()).unwrap()
+        };
+
+        let (status, headers, body) = read(app.clone().oneshot(request(None)).await.unwrap()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(headers[header::ACCEPT_RANGES], "bytes");
+        assert_eq!(body, b"0123456789");
+
+        let (status, headers, body) =
+            read(app.clone().oneshot(request(Some("bytes=2-5"))).await.unwrap()).await;
+        assert_eq!(status, StatusCode::PARTIAL_CONTENT);
+        assert_eq!(headers[header::CONTENT_RANGE], "bytes 2-5/10");
+        assert_eq!(headers[header::CONTENT_LENGTH], "4");
+        assert_eq!(headers[header::CONTENT_TYPE], "video/mp4");
+        assert_eq!(body, b"2345");
+
+        let (status, _, body) = read(app.clone().oneshot(request(Some("bytes=-3"))).await.unwrap()).await;
+        assert_eq!(status, StatusCode::PARTIAL_CONTENT);
+        assert_eq!(body, b"789");
+
+        let (status, headers, _) = read(app.oneshot(request(Some("bytes=20-"))).await.unwrap()).await;
+        assert_eq!(status, StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(headers[header::CONTENT_RANGE], "bytes */10");
+    }
+}
@@ -0,0 +1,251 @@
+//! 共享 HTTP 客户端模块（需要 `http_client` feature）
+//!
+//! 构建服务器时按 `[http_client]` 创建 [`HttpClient`] 并登记为服务，处理器直接把它作为提取器使用，
+//! 后台任务可以通过 `WebServer::service::<HttpClient>()` 获取：
+//!
+//! ```rust
+//! use hwhkit::{http_client::HttpClient, Result};
+//!
+//! async fn weather(client: HttpClient) -> Result<String> {
+//!     let response = client
+//!         .get("https://api.example.com/weather")
+//!         .query(&[("city", "beijing")])
+//!         .send()
+//!         .await?;
+//!     Ok(response.text().await.unwrap_or_default())
+//! }
+//! ```
+//!
+//! ```toml
+//! [http_client]
+//! timeout_secs = 30
+//! connect_timeout_secs = 10
+//! user_agent = "my-app/1.0"
+//! proxy = "http://proxy.internal:3128"
+//! ```
+//!
+//! 每个请求在 `http_client` span 中发送，记录方法、地址（不含查询参数）、状态码和耗时，
+//! 并写入当前请求的追踪上下文。连接失败返回 502，超时返回 504。
+
+use crate::{
+    config::HttpClientConfig,
+    error::{Error, Result},
+    inject::Services,
+    middleware::trace_context::TraceContext,
+};
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{request::Parts, Method},
+};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tracing::Instrument;
+
+/// 共享 HTTP 客户端，克隆后共用连接池
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+    inner: reqwest::Client,
+}
+
+impl HttpClient {
+    /// 根据配置创建客户端
+    ///
+    /// # Errors
+    ///
+    /// 代理地址无效或客户端创建失败时返回配置错误
+    pub fn new(config: &HttpClientConfig) -> Result<Self> {
+        let user_agent = if config.user_agent.is_empty() {
+            concat!("hwhkit/", env!("CARGO_PKG_VERSION"))
+        } else {
+            config.user_agent.as_str()
+        };
+        let mut builder = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .connect_timeout(Duration::from_secs(config.connect_timeout_secs))
+            .pool_idle_timeout(Duration::from_secs(config.pool_idle_timeout_secs))
+            .user_agent(user_agent);
+        if let Some(proxy) = &config.proxy {
+            let proxy = reqwest::Proxy::all(proxy)
+                .map_err(|e| Error::Config(format!("无效的 HTTP 客户端代理 '{}': {}", proxy, e)))?;
+            builder = builder.proxy(proxy);
+        }
+        let inner = builder
+            .build()
+            .map_err(|e| Error::Config(format!("无法创建 HTTP 客户端: {}", e)))?;
+        Ok(Self { inner })
+    }
+
+    /// 底层的 reqwest 客户端，通过它发送的请求不会被记录和传播追踪上下文
+    pub fn client(&self) -> &reqwest::Client {
+        &self.inner
+    }
+
+    /// 创建请求
+    pub fn request(&self, method: Method, url: &str) -> HttpRequest {
+        HttpRequest {
+            method: method.clone(),
+            url: url.to_string(),
+            builder: self.inner.request(method, url),
+        }
+    }
+
+    /// 创建 GET 请求
+    pub fn get(&self, url: &str) -> HttpRequest {
+        self.request(Method::GET, url)
+    }
+
+    /// 创建 POST 请求
+    pub fn post(&self, url: &str) -> HttpRequest {
+        self.request(Method::POST, url)
+    }
+
+    /// 创建 PUT 请求
+    pub fn put(&self, url: &str) -> HttpRequest {
+        self.request(Method::PUT, url)
+    }
+
+    /// 创建 PATCH 请求
+    pub fn patch(&self, url: &str) -> HttpRequest {
+        self.request(Method::PATCH, url)
+    }
+
+    /// 创建 DELETE 请求
+    pub fn delete(&self, url: &str) -> HttpRequest {
+        self.request(Method::DELETE, url)
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for HttpClient {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+        parts
+            .extensions
+            .get::<Services>()
+            .and_then(Services::get::<HttpClient>)
+            .cloned()
+            .ok_or_else(|| Error::Internal("HTTP 客户端未登记".to_string()))
+    }
+}
+
+/// 待发送的请求
+#[derive(Debug)]
+pub struct HttpRequest {
+    method: Method,
+    url: String,
+    builder: reqwest::RequestBuilder,
+}
+
+impl HttpRequest {
+    /// 添加请求头
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.builder = self.builder.header(name, value);
+        self
+    }
+
+    /// 设置 `Authorization: Bearer` 请求头
+    pub fn bearer_auth(mut self, token: &str) -> Self {
+        self.builder = self.builder.bearer_auth(token);
+        self
+    }
+
+    /// 添加查询参数
+    pub fn query<T: Serialize + ?Sized>(mut self, query: &T) -> Self {
+        self.builder = self.builder.query(query);
+        self
+    }
+
+    /// 以 JSON 作为请求体
+    pub fn json<T: Serialize + ?Sized>(mut self, body: &T) -> Self {
+        self.builder = self.builder.json(body);
+        self
+    }
+
+    /// 设置请求体
+    pub fn body(mut self, body: impl Into<reqwest::Body>) -> Self {
+        self.builder = self.builder.body(body);
+        self
+    }
+
+    /// 覆盖配置中的超时时间
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.builder = self.builder.timeout(timeout);
+        self
+    }
+
+    /// 发送请求
+    ///
+    /// 只在连接失败、超时等情况下返回错误，上游返回的错误状态码需要调用方检查
+    ///
+    /// # Errors
+    ///
+    /// 请求超时返回 [`Error::GatewayTimeout`]，其余失败返回 [`Error::BadGateway`]
+    pub async fn send(self) -> Result<reqwest::Response> {
+        // 查询参数可能包含密钥，不写入日志
+        let url = self.url.split('?').next().unwrap_or_default().to_string();
+        let span = tracing::info_span!(
+            "http_client",
+            method = %self.method,
+            url = %url,
+            status = tracing::field::Empty,
+        );
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        if let Some(context) = TraceContext::current() {
+            context.inject(&mut headers);
+        }
+        let method = self.method;
+        let request = self.builder.headers(headers).send();
+
+        async move {
+            let started = Instant::now();
+            match request.await {
+                Ok(response) => {
+                    tracing::Span::current().record("status", response.status().as_u16());
+                    tracing::debug!(
+                        "外部请求 {} {} -> {} ({} ms)",
+                        method,
+                        url,
+                        response.status().as_u16(),
+                        started.elapsed().as_millis()
+                    );
+                    Ok(response)
+                }
+                Err(e) if e.is_timeout() => {
+                    tracing::warn!("外部请求 {} {} 超时", method, url);
+                    Err(Error::GatewayTimeout("外部服务响应超时".to_string()))
+                }
+                Err(e) => {
+                    tracing::warn!("外部请求 {} {} 失败: {}", method, url, e);
+                    Err(Error::BadGateway("外部服务请求失败".to_string()))
+                }
+            }
+        }
+        .instrument(span)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_proxy() {
+        let config = HttpClientConfig {
+            proxy: Some("not a url".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(HttpClient::new(&config), Err(Error::Config(_))));
+        assert!(HttpClient::new(&HttpClientConfig::default()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_connection_failure() {
+        let client = HttpClient::new(&HttpClientConfig::default()).unwrap();
+        let result = client.get("http://127.0.0.1:1/unreachable?token=secret").send().await;
+        assert!(matches!(result, Err(Error::BadGateway(_))));
+    }
+}
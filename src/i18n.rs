@@ -0,0 +1,566 @@
+//! 多语言模块
+//!
+//! 启用 `[middleware.i18n]` 后，从翻译目录加载每种语言的 Fluent（`.ftl`）和 gettext（`.po`）翻译：
+//!
+//! ```text
+//! locales/
+//! ├── zh-CN/
+//! │   └── main.ftl      # welcome = 欢迎，{ $name }！
+//! └── en/
+//!     └── messages.po   # msgid "welcome" / msgstr "Welcome, {name}!"
+//! ```
+//!
+//! 每个请求按查询参数（`?lang=en`）、Cookie、`Accept-Language` 和默认语言的顺序选择语言，
+//! 处理函数通过 [`Locale`] 获取当前语言，通过 [`t`] 或 [`t!`](crate::t) 翻译文本：
+//!
+//! ```rust
+//! use hwhkit::{i18n::Locale, t};
+//!
+//! async fn hello(locale: Locale) -> String {
+//!     t!(locale, "welcome", name = "Alice")
+//! }
+//! ```
+//!
+//! 模板中使用 `t` 函数：
+//!
+//! - Tera：`{{ t(key="welcome", name=user.name) }}`
+//! - MiniJinja：`{{ t("welcome", name=user.name) }}`
+//! - Handlebars：`{{t "welcome" name=user.name}}`
+//!
+//! 当前语言没有对应的翻译时依次使用默认语言的翻译和键本身。gettext 翻译中的 `{name}` 会被替换为参数值，
+//! 不支持 `msgctxt` 和复数形式（使用 `msgstr[0]`）。
+//!
+//! 错误响应同样会被翻译：[`Error`] 和 [`ApiError`](crate::response::ApiError) 的信息是已有翻译的键时
+//! （如 `Error::NotFound("user-not-found".into())`），响应中的 `error` 使用当前语言的翻译。
+
+use crate::{
+    config::I18nConfig,
+    error::{Error, Result},
+    middleware::cookies::get_cookie,
+};
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request, State},
+    http::{header, request::Parts, HeaderMap, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource, FluentValue};
+use std::{collections::BTreeMap, collections::HashMap, fmt, path::Path, sync::Arc};
+use unic_langid::LanguageIdentifier;
+
+tokio::task_local! {
+    static CURRENT: (Arc<Translations>, String);
+}
+
+/// 使用当前请求的语言翻译文本，`args` 为 JSON 对象形式的参数
+///
+/// 不在 i18n 中间件处理的请求中调用时返回键本身
+pub fn t(key: &str, args: &serde_json::Value) -> String {
+    let empty = serde_json::Map::new();
+    let args = args.as_object().unwrap_or(&empty);
+    CURRENT
+        .try_with(|(translations, locale)| translations.translate(locale, key, args))
+        .unwrap_or_else(|_| key.to_string())
+}
+
+/// 使用指定语言翻译文本，供 [`t!`](crate::t) 使用
+///
+/// 不在 i18n 中间件处理的请求中调用时返回键本身
+pub fn translate(locale: &str, key: &str, args: &[(&str, serde_json::Value)]) -> String {
+    let args: serde_json::Map<_, _> = args.iter().map(|(name, value)| (name.to_string(), value.clone())).collect();
+    CURRENT
+        .try_with(|(translations, _)| translations.translate(locale, key, &args))
+        .unwrap_or_else(|_| key.to_string())
+}
+
+/// 把 [`t!`](crate::t) 的参数转换为 JSON 值
+#[doc(hidden)]
+pub fn arg<T: serde::Serialize + ?Sized>(value: &T) -> serde_json::Value {
+    serde_json::to_value(value).unwrap_or_default()
+}
+
+/// 使用当前请求的语言查找翻译，没有对应翻译时返回 `None`，用于翻译错误信息
+pub(crate) fn lookup(key: &str) -> Option<String> {
+    CURRENT
+        .try_with(|(translations, locale)| translations.lookup(locale, key, &serde_json::Map::new()))
+        .ok()
+        .flatten()
+}
+
+/// 使用指定语言翻译文本
+///
+/// 参数使用 `name = value` 形式，值可以是任意可序列化的类型：
+///
+/// ```rust
+/// use hwhkit::t;
+///
+/// let text = t!("en", "welcome", name = "Alice", count = 3);
+/// // 不在 i18n 中间件处理的请求中时返回键本身
+/// assert_eq!(text, "welcome");
+/// ```
+#[macro_export]
+macro_rules! t {
+    ($locale:expr, $key:expr $(, $name:ident = $value:expr)* $(,)?) => {
+        $crate::i18n::translate(
+            ::std::convert::AsRef::<str>::as_ref(&$locale),
+            $key,
+            &[$((stringify!($name), $crate::i18n::arg(&$value))),*],
+        )
+    };
+}
+
+/// 当前请求的语言
+///
+/// 只能在 i18n 中间件处理的请求中调用，否则返回 `None`
+pub fn current_locale() -> Option<String> {
+    CURRENT.try_with(|(_, locale)| locale.clone()).ok()
+}
+
+/// 在指定语言下执行，期间 [`t`] 和模板中的 `t` 函数使用该语言
+pub async fn scope<F: std::future::Future>(translations: Arc<Translations>, locale: String, f: F) -> F::Output {
+    CURRENT.scope((translations, locale), f).await
+}
+
+/// 当前请求的语言提取器
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Locale(pub String);
+
+impl AsRef<str> for Locale {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Locale {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+        parts
+            .extensions
+            .get::<Locale>()
+            .cloned()
+            .ok_or_else(|| Error::Internal("i18n 中间件未启用".to_string()))
+    }
+}
+
+/// 一种语言的翻译
+struct Catalog {
+    fluent: FluentBundle<FluentResource>,
+    gettext: HashMap<String, String>,
+}
+
+impl Catalog {
+    fn format(&self, key: &str, args: &serde_json::Map<String, serde_json::Value>) -> Option<String> {
+        if let Some(pattern) = self.fluent.get_message(key).and_then(|message| message.value()) {
+            let mut fluent_args = FluentArgs::new();
+            for (name, value) in args {
+                let value = match value {
+                    serde_json::Value::Number(n) => n.as_f64().map(FluentValue::from).unwrap_or(FluentValue::None),
+                    serde_json::Value::String(s) => FluentValue::from(s.as_str()),
+                    other => FluentValue::from(other.to_string()),
+                };
+                fluent_args.set(name.as_str(), value);
+            }
+            let mut errors = Vec::new();
+            let text = self.fluent.format_pattern(pattern, Some(&fluent_args), &mut errors);
+            if !errors.is_empty() {
+                tracing::debug!("翻译 {} 时出现错误: {:?}", key, errors);
+            }
+            return Some(text.into_owned());
+        }
+
+        self.gettext.get(key).map(|text| {
+            args.iter().fold(text.clone(), |text, (name, value)| {
+                let value = match value {
+                    serde_json::Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                text.replace(&format!("{{{}}}", name), &value)
+            })
+        })
+    }
+}
+
+/// 所有语言的翻译
+pub struct Translations {
+    default_locale: String,
+    cookie_name: String,
+    query_param: String,
+    catalogs: BTreeMap<String, Catalog>,
+}
+
+impl fmt::Debug for Translations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Translations")
+            .field("default_locale", &self.default_locale)
+            .field("locales", &self.locales())
+            .finish()
+    }
+}
+
+impl Translations {
+    /// 从翻译目录加载，每个子目录对应一种语言
+    pub fn load(config: &I18nConfig) -> Result<Self> {
+        let mut catalogs = BTreeMap::new();
+        for entry in std::fs::read_dir(&config.dir)? {
+            let dir = entry?.path();
+            if !dir.is_dir() {
+                continue;
+            }
+            let locale = dir.file_name().unwrap_or_default().to_string_lossy().to_string();
+            catalogs.insert(locale.clone(), load_catalog(&locale, &dir)?);
+        }
+
+        if !catalogs.contains_key(&config.default_locale) {
+            return Err(Error::Config(format!(
+                "默认语言 {} 在 {} 中没有翻译",
+                config.default_locale, config.dir
+            )));
+        }
+        tracing::info!("✅ 已加载翻译: {:?}", catalogs.keys().collect::<Vec<_>>());
+
+        Ok(Self {
+            default_locale: config.default_locale.clone(),
+            cookie_name: config.cookie_name.clone(),
+            query_param: config.query_param.clone(),
+            catalogs,
+        })
+    }
+
+    /// 所有语言
+    pub fn locales(&self) -> Vec<&str> {
+        self.catalogs.keys().map(String::as_str).collect()
+    }
+
+    /// 默认语言
+    pub fn default_locale(&self) -> &str {
+        &self.default_locale
+    }
+
+    /// 翻译文本，依次尝试指定语言、默认语言，都没有时返回键本身
+    pub fn translate(&self, locale: &str, key: &str, args: &serde_json::Map<String, serde_json::Value>) -> String {
+        self.lookup(locale, key, args).unwrap_or_else(|| key.to_string())
+    }
+
+    /// 查找翻译，依次尝试指定语言、默认语言，都没有时返回 `None`
+    pub fn lookup(
+        &self,
+        locale: &str,
+        key: &str,
+        args: &serde_json::Map<String, serde_json::Value>,
+    ) -> Option<String> {
+        [locale, self.default_locale.as_str()]
+            .into_iter()
+            .filter_map(|locale| self.catalogs.get(locale))
+            .find_map(|catalog| catalog.format(key, args))
+    }
+
+    /// 按查询参数、Cookie、`Accept-Language` 和默认语言的顺序选择请求的语言
+    ///
+    /// `query` 为请求 URI 中的查询字符串（不含 `?`）
+    pub fn negotiate(&self, query: Option<&str>, headers: &HeaderMap) -> String {
+        let from_query = query
+            .and_then(|query| serde_urlencoded::from_str::<Vec<(String, String)>>(query).ok())
+            .and_then(|params| params.into_iter().find(|(name, _)| *name == self.query_param))
+            .and_then(|(_, tag)| self.find(&tag));
+        if let Some(locale) = from_query {
+            return locale;
+        }
+        if let Some(locale) = get_cookie(headers, &self.cookie_name).and_then(|tag| self.find(&tag)) {
+            return locale;
+        }
+
+        let accept_language = headers
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        let mut tags: Vec<(&str, f32)> = accept_language
+            .split(',')
+            .filter_map(|item| {
+                let mut parts = item.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .and_then(|q| q.parse().ok())
+                    .unwrap_or(1.0);
+                (!tag.is_empty() && tag != "*" && quality > 0.0).then_some((tag, quality))
+            })
+            .collect();
+        // 稳定排序，权重相同时保持请求中的顺序
+        tags.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        tags.into_iter()
+            .find_map(|(tag, _)| self.find(tag))
+            .unwrap_or_else(|| self.default_locale.clone())
+    }
+
+    /// 查找语言标签对应的翻译，没有完全匹配时按主语言匹配（如 `en-US` 使用 `en`）
+    fn find(&self, tag: &str) -> Option<String> {
+        let primary = |tag: &str| tag.split(['-', '_']).next().unwrap_or_default().to_ascii_lowercase();
+        self.catalogs
+            .keys()
+            .find(|locale| locale.eq_ignore_ascii_case(tag))
+            .or_else(|| self.catalogs.keys().find(|locale| locale.eq_ignore_ascii_case(&primary(tag))))
+            .or_else(|| self.catalogs.keys().find(|locale| primary(locale) == primary(tag)))
+            .cloned()
+    }
+}
+
+/// 加载一种语言目录中的 `.ftl` 和 `.po` 文件
+fn load_catalog(locale: &str, dir: &Path) -> Result<Catalog> {
+    let id: LanguageIdentifier = locale
+        .parse()
+        .map_err(|e| Error::Config(format!("无效的语言标识 {}: {}", locale, e)))?;
+    let mut fluent = FluentBundle::new_concurrent(vec![id]);
+    // 不插入 Unicode 隔离符，避免出现在 HTML 和 JSON 中
+    fluent.set_use_isolating(false);
+    let mut gettext = HashMap::new();
+
+    let mut files: Vec<_> = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<_>>()?;
+    files.sort();
+    for path in files {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("ftl") => {
+                let resource = FluentResource::try_new(std::fs::read_to_string(&path)?)
+                    .map_err(|(_, errors)| Error::Config(format!("{}: {:?}", path.display(), errors)))?;
+                fluent
+                    .add_resource(resource)
+                    .map_err(|errors| Error::Config(format!("{}: {:?}", path.display(), errors)))?;
+            }
+            Some("po") => gettext.extend(parse_po(&std::fs::read_to_string(&path)?)),
+            _ => {}
+        }
+    }
+
+    Ok(Catalog { fluent, gettext })
+}
+
+/// 解析 gettext `.po` 文件中的 `msgid` / `msgstr`，跳过未翻译的条目
+fn parse_po(source: &str) -> HashMap<String, String> {
+    #[derive(PartialEq)]
+    enum Field {
+        Id,
+        Str,
+        Other,
+    }
+
+    fn unquote(value: &str) -> String {
+        let value = value.trim();
+        let value = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')).unwrap_or(value);
+        let mut result = String::with_capacity(value.len());
+        let mut chars = value.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                result.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('n') => result.push('\n'),
+                Some('t') => result.push('\t'),
+                Some(other) => result.push(other),
+                None => {}
+            }
+        }
+        result
+    }
+
+    let mut entries = HashMap::new();
+    let (mut id, mut text) = (String::new(), String::new());
+    let mut field = Field::Other;
+    for line in source.lines().map(str::trim) {
+        if let Some(rest) = line.strip_prefix("msgid ") {
+            if !id.is_empty() && !text.is_empty() {
+                entries.insert(std::mem::take(&mut id), std::mem::take(&mut text));
+            }
+            (id, text, field) = (unquote(rest), String::new(), Field::Id);
+        } else if let Some(rest) = line.strip_prefix("msgstr ").or_else(|| line.strip_prefix("msgstr[0] ")) {
+            (text, field) = (unquote(rest), Field::Str);
+        } else if line.starts_with('"') {
+            match field {
+                Field::Id => id.push_str(&unquote(line)),
+                Field::Str => text.push_str(&unquote(line)),
+                Field::Other => {}
+            }
+        } else {
+            // 注释、msgctxt、msgid_plural 和其他复数形式
+            field = Field::Other;
+        }
+    }
+    if !id.is_empty() && !text.is_empty() {
+        entries.insert(id, text);
+    }
+    entries
+}
+
+/// 多语言中间件，选择请求的语言并提供给提取器和模板
+pub async fn i18n_middleware(
+    State(translations): State<Arc<Translations>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let locale = translations.negotiate(request.uri().query(), request.headers());
+    request.extensions_mut().insert(Locale(locale.clone()));
+
+    let mut response = scope(translations, locale.clone(), next.run(request)).await;
+
+    let headers = response.headers_mut();
+    if !headers.contains_key(header::CONTENT_LANGUAGE) {
+        if let Ok(value) = HeaderValue::from_str(&locale) {
+            headers.insert(header::CONTENT_LANGUAGE, value);
+        }
+    }
+    headers.append(header::VARY, HeaderValue::from_static("accept-language"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get, Router};
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    fn translations() -> (tempfile::TempDir, Translations) {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |path: &str, content: &str| {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write("zh-CN/main.ftl", "welcome = 欢迎，{ $name }！\nonly-zh = 仅中文\n");
+        write(
+            "en/messages.po",
+            "# header\nmsgid \"\"\nmsgstr \"Content-Type: text/plain\\n\"\n\n\
+             msgid \"welcome\"\nmsgstr \"\"\n\"Welcome, \"\n\"{name}!\"\n\n\
+             msgid \"untranslated\"\nmsgstr \"\"\n",
+        );
+        write("en-GB/main.ftl", "welcome = Good day, { $name }!\n");
+        write("en/errors.ftl", "user-not-found = User not found\n");
+
+        let config = I18nConfig {
+            enabled: true,
+            dir: dir.path().to_string_lossy().to_string(),
+            ..Default::default()
+        };
+        let translations = Translations::load(&config).unwrap();
+        (dir, translations)
+    }
+
+    #[test]
+    fn test_translate() {
+        let (_dir, translations) = translations();
+        assert_eq!(translations.locales(), vec!["en", "en-GB", "zh-CN"]);
+
+        let args = json!({ "name": "Alice" });
+        let args = args.as_object().unwrap();
+        assert_eq!(translations.translate("zh-CN", "welcome", args), "欢迎，Alice！");
+        assert_eq!(translations.translate("en", "welcome", args), "Welcome, Alice!");
+        assert_eq!(translations.translate("en-GB", "welcome", args), "Good day, Alice!");
+        // 回退到默认语言和键本身
+        assert_eq!(translations.translate("en", "only-zh", args), "仅中文");
+        assert_eq!(translations.translate("en", "untranslated", args), "untranslated");
+        assert_eq!(translations.translate("fr", "missing", args), "missing");
+    }
+
+    #[test]
+    fn test_negotiate() {
+        let (_dir, translations) = translations();
+        let negotiate = |cookie: Option<&str>, accept: Option<&str>| {
+            let mut headers = HeaderMap::new();
+            if let Some(cookie) = cookie {
+                headers.insert(header::COOKIE, HeaderValue::from_str(cookie).unwrap());
+            }
+            if let Some(accept) = accept {
+                headers.insert(header::ACCEPT_LANGUAGE, HeaderValue::from_str(accept).unwrap());
+            }
+            translations.negotiate(None, &headers)
+        };
+
+        assert_eq!(negotiate(None, None), "zh-CN");
+        assert_eq!(negotiate(None, Some("fr, en-gb;q=0.8, en;q=0.9")), "en");
+        assert_eq!(negotiate(None, Some("en-US")), "en");
+        assert_eq!(negotiate(None, Some("zh")), "zh-CN");
+        assert_eq!(negotiate(None, Some("fr, *;q=0.5")), "zh-CN");
+        assert_eq!(negotiate(Some("lang=en-GB"), Some("en")), "en-GB");
+        // Cookie 中的语言没有翻译时使用 Accept-Language
+        assert_eq!(negotiate(Some("lang=fr"), Some("en")), "en");
+
+        // 查询参数优先于 Cookie 和 Accept-Language
+        let mut headers = HeaderMap::new();
+        headers.insert(header::COOKIE, HeaderValue::from_static("lang=en"));
+        assert_eq!(translations.negotiate(Some("page=2&lang=en-GB"), &headers), "en-GB");
+        assert_eq!(translations.negotiate(Some("lang=fr"), &headers), "en");
+    }
+
+    #[test]
+    fn test_load_requires_default_locale() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("en")).unwrap();
+        let config = I18nConfig {
+            dir: dir.path().to_string_lossy().to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(Translations::load(&config), Err(Error::Config(_))));
+    }
+
+    #[tokio::test]
+    async fn test_middleware() {
+        let (_dir, translations) = translations();
+        let app = Router::new()
+            .route(
+                "/",
+                get(|Locale(locale): Locale| async move {
+                    format!("{}:{}", locale, t("welcome", &json!({ "name": "Bob" })))
+                }),
+            )
+            .route(
+                "/macro",
+                get(|locale: Locale| async move {
+                    format!("{}|{}", crate::t!(locale, "welcome", name = "Carol"), crate::t!("en-GB", "welcome", name = "Carol"))
+                }),
+            )
+            .route("/error", get(|| async { Err::<(), _>(Error::NotFound("user-not-found".to_string())) }))
+            .layer(axum::middleware::from_fn_with_state(Arc::new(translations), i18n_middleware));
+
+        let request = Request::builder()
+            .uri("/")
+            .header(header::ACCEPT_LANGUAGE, "en-US,en;q=0.9")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.headers()[header::CONTENT_LANGUAGE], "en");
+        assert_eq!(response.headers()[header::VARY], "accept-language");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"en:Welcome, Bob!");
+
+        let request = Request::builder().uri("/macro?lang=en").body(Body::empty()).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"Welcome, Carol!|Good day, Carol!");
+
+        // 错误信息是翻译键时使用当前语言的翻译
+        let request = Request::builder()
+            .uri("/error")
+            .header(header::ACCEPT_LANGUAGE, "en")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"], "User not found");
+
+        // 中间件外返回键本身
+        assert_eq!(t("welcome", &json!({})), "welcome");
+        assert_eq!(current_locale(), None);
+    }
+}
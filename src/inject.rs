@@ -0,0 +1,201 @@
+//! 依赖注入模块
+//!
+//! 通过 `WebServerBuilder::provide` 登记服务，处理器通过 [`Inject`] 提取器按类型获取，
+//! 不需要为每个项目定义状态结构体。每种类型只保存一个实例，重复登记时替换。
+//!
+//! ```rust
+//! use hwhkit::{get, inject::Inject, Router, WebServerBuilder};
+//!
+//! #[derive(Clone)]
+//! struct Mailer {
+//!     from: String,
+//! }
+//!
+//! async fn send(Inject(mailer): Inject<Mailer>) -> String {
+//!     format!("sent from {}", mailer.from)
+//! }
+//!
+//! let builder = WebServerBuilder::new()
+//!     .provide(Mailer { from: "noreply@example.com".to_string() })
+//!     .routes(Router::new().route("/send", get(send)));
+//! ```
+//!
+//! 服务需要实现 `Clone`，较大的服务可以包装在 `Arc` 中登记。
+//!
+//! 应用自定义的配置节 `[extensions.<name>]` 通过 `WebServerBuilder::config_section` 登记后，
+//! 处理器通过 [`AppConfig`] 提取器获取反序列化后的配置：
+//!
+//! ```rust
+//! use hwhkit::{get, inject::AppConfig, Deserialize, Router, WebServerBuilder};
+//!
+//! #[derive(Deserialize)]
+//! struct PaymentsConfig {
+//!     #[serde(default)]
+//!     sandbox: bool,
+//! }
+//!
+//! async fn mode(AppConfig(payments): AppConfig<PaymentsConfig>) -> &'static str {
+//!     if payments.sandbox { "sandbox" } else { "live" }
+//! }
+//!
+//! let builder = WebServerBuilder::new()
+//!     .config_section::<PaymentsConfig>("payments")
+//!     .routes(Router::new().route("/mode", get(mode)));
+//! ```
+
+use crate::error::{Error, Result};
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{request::Parts, Extensions},
+};
+use std::{ops::Deref, sync::Arc};
+
+/// 已登记的服务
+#[derive(Debug, Clone, Default)]
+pub struct Services(Arc<Extensions>);
+
+impl Services {
+    /// 根据登记的服务创建
+    pub fn new(services: Extensions) -> Self {
+        Self(Arc::new(services))
+    }
+
+    /// 按类型获取服务
+    pub fn get<T: Clone + Send + Sync + 'static>(&self) -> Option<&T> {
+        self.0.get::<T>()
+    }
+}
+
+/// 按类型注入已登记的服务
+///
+/// 服务未登记时返回 500
+#[derive(Debug, Clone)]
+pub struct Inject<T>(pub T);
+
+impl<T> Deref for Inject<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[async_trait]
+impl<S, T> FromRequestParts<S> for Inject<T>
+where
+    S: Send + Sync,
+    T: Clone + Send + Sync + 'static,
+{
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+        parts
+            .extensions
+            .get::<Services>()
+            .and_then(Services::get::<T>)
+            .cloned()
+            .map(Inject)
+            .ok_or_else(|| {
+                Error::Internal(format!("未登记服务 {}", std::any::type_name::<T>()))
+            })
+    }
+}
+
+/// 按类型获取已登记的应用配置节
+///
+/// 配置节在构建服务器时反序列化，配置无效时构建失败；未登记时返回 500
+#[derive(Debug)]
+pub struct AppConfig<T>(pub Arc<T>);
+
+impl<T> Clone for AppConfig<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T> Deref for AppConfig<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[async_trait]
+impl<S, T> FromRequestParts<S> for AppConfig<T>
+where
+    S: Send + Sync,
+    T: Send + Sync + 'static,
+{
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+        parts
+            .extensions
+            .get::<Services>()
+            .and_then(Services::get::<AppConfig<T>>)
+            .cloned()
+            .ok_or_else(|| {
+                Error::Internal(format!("未登记配置节 {}", std::any::type_name::<T>()))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, extract::Request, http::StatusCode, routing::get, Extension, Router};
+    use tower::util::ServiceExt;
+
+    #[derive(Clone)]
+    struct Mailer {
+        from: String,
+    }
+
+    #[derive(Clone)]
+    struct Payments;
+
+    #[derive(Debug, PartialEq)]
+    struct Limits {
+        max: u32,
+    }
+
+    fn app() -> Router {
+        let mut services = Extensions::new();
+        services.insert(Mailer {
+            from: "noreply@example.com".to_string(),
+        });
+        services.insert(AppConfig(Arc::new(Limits { max: 5 })));
+        Router::new()
+            .route("/mailer", get(|mailer: Inject<Mailer>| async move { mailer.from.clone() }))
+            .route("/payments", get(|_: Inject<Payments>| async { "ok" }))
+            .route("/limits", get(|limits: AppConfig<Limits>| async move { limits.max.to_string() }))
+            .route("/missing", get(|_: AppConfig<Mailer>| async { "ok" }))
+            .layer(Extension(Services::new(services)))
+    }
+
+    #[tokio::test]
+    async fn test_inject() {
+        let request = Request::builder().uri("/mailer").body(Body::empty()).unwrap();
+        let response = app().oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"noreply@example.com");
+
+        let request = Request::builder().uri("/payments").body(Body::empty()).unwrap();
+        let response = app().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_app_config() {
+        let request = Request::builder().uri("/limits").body(Body::empty()).unwrap();
+        let response = app().oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"5");
+
+        let request = Request::builder().uri("/missing").body(Body::empty()).unwrap();
+        let response = app().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
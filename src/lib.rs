@@ -12,7 +12,7 @@
 //!
 //! ## 快速开始
 //!
-//! ```rust
+//! ```rust,no_run
 //! use hwhkit::WebServerBuilder;
 //!
 //! #[tokio::main]
@@ -27,15 +27,76 @@
 //! }
 //! ```
 
+pub mod admin;
+pub mod assets;
+pub mod auth;
+pub mod batch;
 pub mod builder;
+pub mod cache;
 pub mod config;
+pub mod database;
 pub mod error;
+pub mod file;
+pub mod inject;
 pub mod middleware;
+pub mod migration;
+pub mod module;
+pub mod negotiate;
+pub mod readiness;
+pub mod resilience;
+pub mod response;
+pub mod routing;
+pub mod scaffold;
 pub mod server;
+pub mod shutdown;
+pub mod sse;
+pub mod watchdog;
+
+#[cfg(feature = "askama")]
+pub mod askama;
+
+#[cfg(feature = "cli")]
+pub mod cli;
+
+#[cfg(feature = "embed")]
+pub mod embed;
+
+#[cfg(feature = "http_client")]
+pub mod http_client;
+
+#[cfg(feature = "i18n")]
+pub mod i18n;
+
+#[cfg(feature = "mail")]
+pub mod mail;
+
+#[cfg(feature = "openapi")]
+pub mod openapi;
+
+#[cfg(feature = "proxy")]
+pub mod proxy;
 
 #[cfg(feature = "templates")]
 pub mod templates;
 
+#[cfg(feature = "testing")]
+pub mod test;
+
+#[cfg(feature = "uploads")]
+pub mod uploads;
+
+#[cfg(feature = "validation")]
+pub mod validation;
+
+#[cfg(feature = "webhooks")]
+pub mod webhooks;
+
+#[cfg(feature = "ws")]
+pub mod websocket;
+
+#[cfg(feature = "xml")]
+pub mod xml;
+
 pub use builder::WebServerBuilder;
 pub use config::Config;
 pub use error::{Error, Result};
@@ -43,6 +104,7 @@ pub use server::WebServer;
 
 // 重新导出常用的类型
 pub use axum::{
+    async_trait,
     extract::{Json, Path, Query, State},
     http::{Method, StatusCode},
     response::{Html, IntoResponse},
@@ -0,0 +1,533 @@
+//! 邮件发送模块
+//!
+//! 启用 `[mail]` 后，构建服务器时根据配置创建 [`Mailer`]，处理器可以直接把它作为提取器使用：
+//!
+//! ```toml
+//! [mail]
+//! enabled = true
+//! host = "smtp.example.com"
+//! port = 587
+//! username = "noreply@example.com"
+//! password_file = "/run/secrets/smtp_password"
+//! from = "App <noreply@example.com>"
+//! ```
+//!
+//! ```rust
+//! use hwhkit::{mail::{Email, Mailer}, Result};
+//!
+//! async fn register(mailer: Mailer) -> Result<&'static str> {
+//!     let email = Email::new()
+//!         .to("alice@example.com")
+//!         .subject("欢迎注册")
+//!         .text("感谢注册！");
+//!     mailer.queue(email)?;
+//!     Ok("ok")
+//! }
+//! ```
+//!
+//! [`Mailer::send`] 等待 SMTP 服务器接收后返回；[`Mailer::queue`] 把邮件放入进程内的发送队列后立即返回，
+//! 由后台任务发送，失败时按 `retries` 和 `retry_delay_ms` 退避重试。队列不做持久化，
+//! 进程退出时尚未发送的邮件会丢失。
+//!
+//! 启用 `templates` feature 时，[`Mailer::render`] 使用服务器的模板引擎渲染 HTML 正文。
+
+use crate::{
+    config::{MailConfig, MailTls},
+    error::{Error, Result},
+};
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+use lettre::{
+    message::{header::ContentType, Mailbox, MultiPart, SinglePart},
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Tokio1Executor,
+};
+use std::{fmt, sync::Arc, time::Duration};
+use tokio::sync::mpsc;
+
+pub use lettre::Message;
+
+/// 待发送的邮件
+#[derive(Debug, Clone, Default)]
+pub struct Email {
+    from: Option<String>,
+    to: Vec<String>,
+    cc: Vec<String>,
+    bcc: Vec<String>,
+    reply_to: Option<String>,
+    subject: String,
+    text: Option<String>,
+    html: Option<String>,
+}
+
+impl Email {
+    /// 创建空邮件
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 覆盖默认发件人
+    pub fn from(mut self, from: impl Into<String>) -> Self {
+        self.from = Some(from.into());
+        self
+    }
+
+    /// 添加收件人，如 `alice@example.com` 或 `Alice <alice@example.com>`
+    pub fn to(mut self, to: impl Into<String>) -> Self {
+        self.to.push(to.into());
+        self
+    }
+
+    /// 添加抄送
+    pub fn cc(mut self, cc: impl Into<String>) -> Self {
+        self.cc.push(cc.into());
+        self
+    }
+
+    /// 添加密送
+    pub fn bcc(mut self, bcc: impl Into<String>) -> Self {
+        self.bcc.push(bcc.into());
+        self
+    }
+
+    /// 设置回复地址
+    pub fn reply_to(mut self, reply_to: impl Into<String>) -> Self {
+        self.reply_to = Some(reply_to.into());
+        self
+    }
+
+    /// 设置主题
+    pub fn subject(mut self, subject: impl Into<String>) -> Self {
+        self.subject = subject.into();
+        self
+    }
+
+    /// 设置纯文本正文
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.text = Some(text.into());
+        self
+    }
+
+    /// 设置 HTML 正文，同时设置纯文本正文时作为备选内容一起发送
+    pub fn html(mut self, html: impl Into<String>) -> Self {
+        self.html = Some(html.into());
+        self
+    }
+}
+
+fn mailbox(address: &str) -> Result<Mailbox> {
+    address
+        .parse()
+        .map_err(|e| Error::BadRequest(format!("无效的邮件地址 '{}': {}", address, e)))
+}
+
+/// 邮件发送方式
+///
+/// 默认通过 SMTP 发送，测试中可以替换为记录邮件的实现
+#[async_trait]
+pub trait MailTransport: Send + Sync + 'static {
+    /// 发送一封邮件
+    async fn send(&self, message: Message) -> Result<()>;
+}
+
+#[async_trait]
+impl MailTransport for AsyncSmtpTransport<Tokio1Executor> {
+    async fn send(&self, message: Message) -> Result<()> {
+        AsyncTransport::send(self, message)
+            .await
+            .map(|_| ())
+            .map_err(|e| Error::Internal(format!("邮件发送失败: {}", e)))
+    }
+}
+
+/// 根据配置创建 SMTP 客户端，创建时不会建立连接
+fn smtp_transport(config: &MailConfig) -> Result<AsyncSmtpTransport<Tokio1Executor>> {
+    let builder = match config.tls {
+        MailTls::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host),
+        MailTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host),
+        MailTls::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host)),
+    }
+    .map_err(|e| Error::Config(format!("无效的 SMTP 配置: {}", e)))?;
+
+    let mut builder = builder
+        .port(config.port)
+        .timeout(Some(Duration::from_secs(config.timeout_secs)));
+    if !config.username.is_empty() {
+        builder = builder.credentials(Credentials::new(
+            config.username.clone(),
+            config.effective_password().to_string(),
+        ));
+    }
+    Ok(builder.build())
+}
+
+/// 邮件客户端
+///
+/// 启用 `[mail]` 配置后会作为请求扩展提供给处理函数，克隆后共享同一个发送队列
+#[derive(Clone)]
+pub struct Mailer {
+    from: Mailbox,
+    transport: Arc<dyn MailTransport>,
+    queue: mpsc::Sender<Message>,
+    #[cfg(feature = "templates")]
+    templates: Option<Arc<crate::templates::TemplateEngine>>,
+}
+
+impl Mailer {
+    /// 根据配置创建通过 SMTP 发送的客户端
+    ///
+    /// 需要在 tokio 运行时中调用，发送队列由后台任务处理
+    ///
+    /// # Errors
+    ///
+    /// SMTP 配置或默认发件人无效时返回配置错误
+    pub fn new(config: &MailConfig) -> Result<Self> {
+        Self::with_transport(config, smtp_transport(config)?)
+    }
+
+    /// 使用自定义的发送方式创建客户端，`host` 等 SMTP 配置不会被使用
+    ///
+    /// # Errors
+    ///
+    /// 默认发件人无效时返回配置错误
+    pub fn with_transport(config: &MailConfig, transport: impl MailTransport) -> Result<Self> {
+        let from = config
+            .from
+            .parse()
+            .map_err(|e| Error::Config(format!("无效的默认发件人 '{}': {}", config.from, e)))?;
+        let transport: Arc<dyn MailTransport> = Arc::new(transport);
+        let (queue, receiver) = mpsc::channel(config.queue_size.max(1));
+        tokio::spawn(deliver_queued(
+            transport.clone(),
+            receiver,
+            config.retries,
+            Duration::from_millis(config.retry_delay_ms),
+        ));
+
+        Ok(Self {
+            from,
+            transport,
+            queue,
+            #[cfg(feature = "templates")]
+            templates: None,
+        })
+    }
+
+    /// 设置渲染邮件正文使用的模板引擎
+    #[cfg(feature = "templates")]
+    pub fn with_templates(mut self, engine: Arc<crate::templates::TemplateEngine>) -> Self {
+        self.templates = Some(engine);
+        self
+    }
+
+    /// 使用模板渲染 HTML 正文，模板名称与页面模板相同，如 `mail/welcome.html`
+    ///
+    /// 不使用页面布局；纯文本正文仍然可以通过 [`Email::text`] 设置
+    ///
+    /// # Errors
+    ///
+    /// 未启用模板或渲染失败时返回错误
+    #[cfg(feature = "templates")]
+    pub fn render<T: serde::Serialize>(&self, email: Email, template: &str, context: &T) -> Result<Email> {
+        let engine = self
+            .templates
+            .as_ref()
+            .ok_or_else(|| Error::Config("邮件模板需要启用 [middleware.templates]".to_string()))?;
+        Ok(email.html(engine.render(template, context)?))
+    }
+
+    /// 生成待发送的邮件消息
+    ///
+    /// # Errors
+    ///
+    /// 地址无效或没有收件人时返回错误
+    pub fn message(&self, email: Email) -> Result<Message> {
+        if email.to.is_empty() && email.cc.is_empty() && email.bcc.is_empty() {
+            return Err(Error::BadRequest("邮件没有收件人".to_string()));
+        }
+
+        let from = match &email.from {
+            Some(from) => mailbox(from)?,
+            None => self.from.clone(),
+        };
+        let mut builder = Message::builder().from(from).subject(email.subject);
+        for to in &email.to {
+            builder = builder.to(mailbox(to)?);
+        }
+        for cc in &email.cc {
+            builder = builder.cc(mailbox(cc)?);
+        }
+        for bcc in &email.bcc {
+            builder = builder.bcc(mailbox(bcc)?);
+        }
+        if let Some(reply_to) = &email.reply_to {
+            builder = builder.reply_to(mailbox(reply_to)?);
+        }
+
+        let message = match (email.text, email.html) {
+            (Some(text), Some(html)) => builder.multipart(MultiPart::alternative_plain_html(text, html)),
+            (Some(text), None) => builder.singlepart(SinglePart::plain(text)),
+            (None, Some(html)) => builder.singlepart(SinglePart::html(html)),
+            (None, None) => builder.header(ContentType::TEXT_PLAIN).body(String::new()),
+        };
+        message.map_err(|e| Error::BadRequest(format!("无效的邮件: {}", e)))
+    }
+
+    /// 立即发送邮件，等待服务器接收后返回，失败时不重试
+    pub async fn send(&self, email: Email) -> Result<()> {
+        let message = self.message(email)?;
+        self.transport.send(message).await
+    }
+
+    /// 把邮件放入发送队列，由后台任务发送并在失败时重试
+    ///
+    /// # Errors
+    ///
+    /// 邮件无效时返回错误，队列已满时返回 503
+    pub fn queue(&self, email: Email) -> Result<()> {
+        let message = self.message(email)?;
+        self.queue.try_send(message).map_err(|e| match e {
+            mpsc::error::TrySendError::Full(_) => Error::ServiceUnavailable("邮件发送队列已满".to_string()),
+            mpsc::error::TrySendError::Closed(_) => Error::Internal("邮件发送队列已关闭".to_string()),
+        })
+    }
+}
+
+impl fmt::Debug for Mailer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Mailer").field("from", &self.from).finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Mailer {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+        parts
+            .extensions
+            .get::<Mailer>()
+            .cloned()
+            .ok_or_else(|| Error::Internal("邮件发送未配置".to_string()))
+    }
+}
+
+/// 依次发送队列中的邮件，失败时按指数退避重试
+async fn deliver_queued(
+    transport: Arc<dyn MailTransport>,
+    mut receiver: mpsc::Receiver<Message>,
+    retries: u32,
+    retry_delay: Duration,
+) {
+    while let Some(message) = receiver.recv().await {
+        let recipients = message
+            .envelope()
+            .to()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut delay = retry_delay;
+        for attempt in 0..=retries {
+            match transport.send(message.clone()).await {
+                Ok(()) => break,
+                Err(e) if attempt < retries => {
+                    tracing::warn!("发送邮件到 {} 失败，{:?} 后重试: {}", recipients, delay, e);
+                    tokio::time::sleep(delay).await;
+                    delay = delay.saturating_mul(2);
+                }
+                Err(e) => tracing::error!("发送邮件到 {} 失败，已放弃: {}", recipients, e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        Mutex,
+    };
+
+    /// 记录邮件的发送方式，前 `failures` 次发送失败
+    #[derive(Clone, Default)]
+    struct Outbox {
+        sent: Arc<Mutex<Vec<Message>>>,
+        attempts: Arc<AtomicU32>,
+        failures: u32,
+    }
+
+    #[async_trait]
+    impl MailTransport for Outbox {
+        async fn send(&self, message: Message) -> Result<()> {
+            if self.attempts.fetch_add(1, Ordering::SeqCst) < self.failures {
+                return Err(Error::Internal("连接失败".to_string()));
+            }
+            self.sent.lock().unwrap().push(message);
+            Ok(())
+        }
+    }
+
+    fn config() -> MailConfig {
+        MailConfig {
+            enabled: true,
+            host: "localhost".to_string(),
+            from: "App <noreply@example.com>".to_string(),
+            retries: 2,
+            retry_delay_ms: 1,
+            ..MailConfig::default()
+        }
+    }
+
+    fn formatted(message: &Message) -> String {
+        String::from_utf8(message.formatted()).unwrap()
+    }
+
+    async fn wait_for_attempts(outbox: &Outbox, attempts: u32) {
+        for _ in 0..100 {
+            if outbox.attempts.load(Ordering::SeqCst) >= attempts {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        // 等待最后一次发送的结果写入
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    #[tokio::test]
+    async fn test_send() {
+        let outbox = Outbox::default();
+        let mailer = Mailer::with_transport(&config(), outbox.clone()).unwrap();
+        let email = Email::new()
+            .to("Alice <alice@example.com>")
+            .cc("bob@example.com")
+            .reply_to("support@example.com")
+            .subject("Welcome")
+            .text("hello")
+            .html("<p>hello</p>");
+        mailer.send(email).await.unwrap();
+
+        let sent = outbox.sent.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        let body = formatted(&sent[0]);
+        assert!(body.contains("From: App <noreply@example.com>"));
+        assert!(body.contains("To: Alice <alice@example.com>"));
+        assert!(body.contains("Cc: bob@example.com"));
+        assert!(body.contains("Reply-To: support@example.com"));
+        assert!(body.contains("Subject: Welcome"));
+        assert!(body.contains("multipart/alternative"));
+        assert!(body.contains("<p>hello</p>"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_email() {
+        let mailer = Mailer::with_transport(&config(), Outbox::default()).unwrap();
+        assert!(matches!(mailer.message(Email::new().text("hi")), Err(Error::BadRequest(_))));
+        assert!(matches!(
+            mailer.message(Email::new().to("not an address").text("hi")),
+            Err(Error::BadRequest(_))
+        ));
+
+        let config = MailConfig {
+            from: "invalid".to_string(),
+            ..config()
+        };
+        assert!(matches!(Mailer::with_transport(&config, Outbox::default()), Err(Error::Config(_))));
+    }
+
+    #[tokio::test]
+    async fn test_queue_retry() {
+        let outbox = Outbox {
+            failures: 2,
+            ..Outbox::default()
+        };
+        let mailer = Mailer::with_transport(&config(), outbox.clone()).unwrap();
+        mailer.queue(Email::new().to("alice@example.com").text("hi")).unwrap();
+
+        wait_for_attempts(&outbox, 3).await;
+        assert_eq!(outbox.attempts.load(Ordering::SeqCst), 3);
+        assert_eq!(outbox.sent.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_queue_gives_up() {
+        let outbox = Outbox {
+            failures: 10,
+            ..Outbox::default()
+        };
+        let mailer = Mailer::with_transport(&config(), outbox.clone()).unwrap();
+        mailer.queue(Email::new().to("alice@example.com").text("first")).unwrap();
+        mailer.queue(Email::new().to("bob@example.com").text("second")).unwrap();
+
+        // 每封邮件最多尝试 1 + retries 次，放弃后继续发送下一封
+        wait_for_attempts(&outbox, 6).await;
+        assert_eq!(outbox.attempts.load(Ordering::SeqCst), 6);
+        assert!(outbox.sent.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_queue_full() {
+        let config = MailConfig {
+            queue_size: 1,
+            retry_delay_ms: 60_000,
+            ..config()
+        };
+        let outbox = Outbox {
+            failures: 10,
+            ..Outbox::default()
+        };
+        let mailer = Mailer::with_transport(&config, outbox.clone()).unwrap();
+        let email = || Email::new().to("alice@example.com").text("hi");
+        mailer.queue(email()).unwrap();
+        // 第一封邮件在重试等待中，第二封占满队列
+        wait_for_attempts(&outbox, 1).await;
+        mailer.queue(email()).unwrap();
+        assert!(matches!(mailer.queue(email()), Err(Error::ServiceUnavailable(_))));
+    }
+
+    #[test]
+    fn test_smtp_transport() {
+        assert!(smtp_transport(&config()).is_ok());
+        let config = MailConfig {
+            tls: MailTls::None,
+            port: 1025,
+            username: "user".to_string(),
+            password: "secret".to_string(),
+            ..config()
+        };
+        assert!(smtp_transport(&config).is_ok());
+    }
+
+    #[cfg(feature = "templates")]
+    #[tokio::test]
+    async fn test_render() {
+        use crate::{config::TemplateConfig, templates::TemplateEngine};
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("mail")).unwrap();
+        std::fs::write(dir.path().join("mail/welcome.html"), "<p>Hello {{ name }}</p>").unwrap();
+        let engine = TemplateEngine::new(&TemplateConfig {
+            enabled: true,
+            dir: dir.path().to_string_lossy().to_string(),
+            ..TemplateConfig::default()
+        })
+        .unwrap();
+        let outbox = Outbox::default();
+        let mailer = Mailer::with_transport(&config(), outbox.clone()).unwrap();
+        assert!(matches!(mailer.render(Email::new(), "mail/welcome.html", &()), Err(Error::Config(_))));
+
+        let mailer = mailer.with_templates(Arc::new(engine));
+        let email = mailer
+            .render(
+                Email::new().to("alice@example.com").subject("Welcome").text("Hello Alice"),
+                "mail/welcome.html",
+                &serde_json::json!({ "name": "Alice" }),
+            )
+            .unwrap();
+        assert_eq!(email.html.as_deref(), Some("<p>Hello Alice</p>"));
+        mailer.send(email).await.unwrap();
+        assert!(formatted(&outbox.sent.lock().unwrap()[0]).contains("<p>Hello Alice</p>"));
+
+        assert!(mailer.render(Email::new(), "mail/missing.html", &()).is_err());
+    }
+}
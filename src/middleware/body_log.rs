@@ -0,0 +1,280 @@
+//! 请求/响应体日志模块
+//!
+//! 调试 API 对接时记录请求头、请求体和响应体。只缓冲 `content_types` 中的类型
+//! （流式响应如 `text/event-stream` 默认不记录），每个 body 最多记录 `max_bytes` 字节。
+//!
+//! 记录前按 `redact` 规则脱敏：
+//!
+//! - `/user/password` 形式的 JSON Pointer 只替换该位置的值
+//! - `password`、`authorization` 等名称替换同名的头部、JSON 字段（任意层级）和表单字段
+//!
+//! ```toml
+//! [middleware.logging.bodies]
+//! enabled = true
+//! max_bytes = 4096
+//! content_types = ["application/json", "application/x-www-form-urlencoded"]
+//! redact = ["authorization", "password", "/payment/card_number"]
+//! ```
+
+use super::request_id::RequestId;
+use crate::config::BodyLogConfig;
+use axum::{
+    body::{Body, Bytes},
+    extract::{Request, State},
+    http::{header, HeaderMap},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde_json::Value;
+use std::sync::Arc;
+
+/// 请求/响应体日志使用的 tracing target
+pub const BODY_LOG_TARGET: &str = "hwhkit::body";
+
+/// 脱敏后的替换值
+pub const REDACTED: &str = "[REDACTED]";
+
+/// 请求/响应体日志中间件
+pub async fn body_log_middleware(
+    State(config): State<Arc<BodyLogConfig>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().clone();
+    let uri = request.uri().clone();
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(|id| id.to_string())
+        .unwrap_or_else(|| "-".to_string());
+    let request_headers = redact_headers(&config, request.headers());
+
+    let (parts, body) = request.into_parts();
+    let (body, request_body) = match capture(&config, &parts.headers, body).await {
+        Ok(captured) => captured,
+        Err(e) => return e.into_response(),
+    };
+    let response = next.run(Request::from_parts(parts, body)).await;
+
+    let (parts, body) = response.into_parts();
+    let (body, response_body) = match capture(&config, &parts.headers, body).await {
+        Ok(captured) => captured,
+        Err(e) => return e.into_response(),
+    };
+
+    tracing::info!(
+        target: BODY_LOG_TARGET,
+        method = %method,
+        uri = %uri,
+        request_id = %request_id,
+        status = parts.status.as_u16(),
+        request_headers = %request_headers,
+        request_body = request_body.as_deref().unwrap_or("-"),
+        response_body = response_body.as_deref().unwrap_or("-"),
+        "body"
+    );
+
+    Response::from_parts(parts, body)
+}
+
+/// 缓冲匹配内容类型的 body，返回原样重建的 body 和脱敏后的日志文本
+async fn capture(
+    config: &BodyLogConfig,
+    headers: &HeaderMap,
+    body: Body,
+) -> crate::error::Result<(Body, Option<String>)> {
+    let Some(content_type) = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .filter(|content_type| {
+            config
+                .content_types
+                .iter()
+                .any(|prefix| content_type.starts_with(prefix.as_str()))
+        })
+    else {
+        return Ok((body, None));
+    };
+
+    let bytes = axum::body::to_bytes(body, usize::MAX)
+        .await
+        .map_err(|e| crate::error::Error::BadRequest(format!("无法读取 body: {}", e)))?;
+    let logged = redact_body(config, content_type, &bytes);
+    Ok((Body::from(bytes), Some(truncate(logged, config.max_bytes))))
+}
+
+/// 脱敏后的请求头，以 JSON 对象输出
+fn redact_headers(config: &BodyLogConfig, headers: &HeaderMap) -> Value {
+    let headers = headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if is_redacted_name(config, name.as_str()) {
+                REDACTED.to_string()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).to_string()
+            };
+            (name.to_string(), Value::String(value))
+        })
+        .collect();
+    Value::Object(headers)
+}
+
+fn is_redacted_name(config: &BodyLogConfig, name: &str) -> bool {
+    config
+        .redact
+        .iter()
+        .any(|rule| !rule.starts_with('/') && rule.eq_ignore_ascii_case(name))
+}
+
+/// 按内容类型脱敏 body，无法解析时原样输出
+fn redact_body(config: &BodyLogConfig, content_type: &str, bytes: &Bytes) -> String {
+    if content_type.starts_with("application/json") {
+        if let Ok(mut value) = serde_json::from_slice::<Value>(bytes) {
+            for rule in &config.redact {
+                match rule.strip_prefix('/') {
+                    Some(_) => {
+                        if let Some(target) = value.pointer_mut(rule) {
+                            *target = Value::String(REDACTED.to_string());
+                        }
+                    }
+                    None => redact_field(&mut value, rule),
+                }
+            }
+            return value.to_string();
+        }
+    }
+
+    if content_type.starts_with("application/x-www-form-urlencoded") {
+        if let Ok(fields) = serde_urlencoded::from_bytes::<Vec<(String, String)>>(bytes) {
+            let fields: Vec<(String, String)> = fields
+                .into_iter()
+                .map(|(name, value)| {
+                    let value = if is_redacted_name(config, &name) {
+                        REDACTED.to_string()
+                    } else {
+                        value
+                    };
+                    (name, value)
+                })
+                .collect();
+            if let Ok(encoded) = serde_urlencoded::to_string(&fields) {
+                return encoded;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(bytes).to_string()
+}
+
+/// 替换任意层级中名称匹配的字段
+fn redact_field(value: &mut Value, name: &str) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if key.eq_ignore_ascii_case(name) {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact_field(value, name);
+                }
+            }
+        }
+        Value::Array(values) => values.iter_mut().for_each(|value| redact_field(value, name)),
+        _ => {}
+    }
+}
+
+/// 截断到不超过 `max_bytes` 字节的字符边界
+fn truncate(mut text: String, max_bytes: usize) -> String {
+    if text.len() > max_bytes {
+        let mut end = max_bytes;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+        text.push_str("...");
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config() -> BodyLogConfig {
+        BodyLogConfig {
+            enabled: true,
+            redact: vec!["password".to_string(), "authorization".to_string(), "/card/number".to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_redact_json() {
+        let body = json!({
+            "user": "alice",
+            "password": "hunter2",
+            "nested": [{ "Password": "x" }],
+            "card": { "number": "4111", "brand": "visa" },
+        });
+        let logged = redact_body(&config(), "application/json", &Bytes::from(body.to_string()));
+        let logged: Value = serde_json::from_str(&logged).unwrap();
+        assert_eq!(logged["user"], "alice");
+        assert_eq!(logged["password"], REDACTED);
+        assert_eq!(logged["nested"][0]["Password"], REDACTED);
+        assert_eq!(logged["card"]["number"], REDACTED);
+        assert_eq!(logged["card"]["brand"], "visa");
+    }
+
+    #[test]
+    fn test_redact_form_and_headers() {
+        let logged = redact_body(
+            &config(),
+            "application/x-www-form-urlencoded",
+            &Bytes::from("user=alice&password=hunter2"),
+        );
+        assert_eq!(logged, "user=alice&password=%5BREDACTED%5D");
+
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", "Bearer secret".parse().unwrap());
+        headers.insert("accept", "application/json".parse().unwrap());
+        let logged = redact_headers(&config(), &headers);
+        assert_eq!(logged["authorization"], REDACTED);
+        assert_eq!(logged["accept"], "application/json");
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("hello".to_string(), 10), "hello");
+        assert_eq!(truncate("hello".to_string(), 3), "hel...");
+        // 不在多字节字符中间截断
+        assert_eq!(truncate("你好".to_string(), 4), "你...");
+    }
+
+    #[tokio::test]
+    async fn test_bodies_are_forwarded_intact() {
+        use axum::{routing::post, Router};
+        use tower::util::ServiceExt;
+
+        let app = Router::new()
+            .route("/echo", post(|body: String| async move { body }))
+            .layer(axum::middleware::from_fn_with_state(
+                Arc::new(BodyLogConfig {
+                    max_bytes: 4,
+                    content_types: vec!["text/plain".to_string()],
+                    ..config()
+                }),
+                body_log_middleware,
+            ));
+
+        let request = Request::builder()
+            .method("POST")
+            .uri("/echo")
+            .header("content-type", "text/plain")
+            .body(Body::from("hello world"))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"hello world");
+    }
+}
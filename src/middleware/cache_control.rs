@@ -0,0 +1,157 @@
+//! 按路径设置缓存策略模块
+//!
+//! 按配置为 GET/HEAD 请求的成功响应（以及 304）写入 `Cache-Control`，运维可以在不改代码的情况下
+//! 调整 CDN 和浏览器的缓存行为。按顺序使用第一条匹配的规则，处理函数已经设置的
+//! `Cache-Control`（如 [`CachePolicy`](crate::cache::CachePolicy)）不会被覆盖。
+//!
+//! ```toml
+//! [[middleware.cache_control]]
+//! path = "/api/v1/catalog/*"
+//! policy = "public, max-age=300"
+//!
+//! [[middleware.cache_control]]
+//! path = "/api/*"
+//! policy = "no-store"
+//! ```
+
+use super::matcher::path_matches;
+use crate::{
+    config::RouteCacheControlConfig,
+    error::{Error, Result},
+};
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+
+/// 按路径匹配的缓存策略
+#[derive(Debug, Clone, Default)]
+pub struct CacheControlRules {
+    rules: Vec<(String, HeaderValue)>,
+}
+
+impl CacheControlRules {
+    /// 根据配置创建
+    ///
+    /// # Errors
+    ///
+    /// 路径为空或策略不是合法的头部值时返回配置错误
+    pub fn new(rules: &[RouteCacheControlConfig]) -> Result<Self> {
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                if !rule.path.starts_with('/') {
+                    return Err(Error::Config(format!("缓存策略路径必须以 / 开头: {}", rule.path)));
+                }
+                let policy = rule.policy.trim();
+                let value = HeaderValue::from_str(policy)
+                    .ok()
+                    .filter(|_| !policy.is_empty())
+                    .ok_or_else(|| Error::Config(format!("无效的缓存策略 '{}': {}", rule.path, rule.policy)))?;
+                Ok((rule.path.clone(), value))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { rules })
+    }
+
+    /// 查找匹配请求路径的策略，使用第一个匹配项
+    fn find(&self, path: &str) -> Option<&HeaderValue> {
+        self.rules
+            .iter()
+            .find(|(pattern, _)| path_matches(pattern, path))
+            .map(|(_, value)| value)
+    }
+}
+
+/// 按路径设置缓存策略中间件
+pub async fn cache_control_middleware(
+    State(rules): State<Arc<CacheControlRules>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let policy = match *request.method() {
+        Method::GET | Method::HEAD => rules.find(request.uri().path()).cloned(),
+        _ => None,
+    };
+    let mut response = next.run(request).await;
+
+    let Some(policy) = policy else {
+        return response;
+    };
+    let status = response.status();
+    if (status.is_success() || status == StatusCode::NOT_MODIFIED)
+        && !response.headers().contains_key(header::CACHE_CONTROL)
+    {
+        response.headers_mut().insert(header::CACHE_CONTROL, policy);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get, Router};
+    use tower::util::ServiceExt;
+
+    fn rule(path: &str, policy: &str) -> RouteCacheControlConfig {
+        RouteCacheControlConfig {
+            path: path.to_string(),
+            policy: policy.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cache_control_rules() {
+        let rules = CacheControlRules::new(&[
+            rule("/api/v1/catalog/*", "public, max-age=300"),
+            rule("/api/*", "no-store"),
+        ])
+        .unwrap();
+        let app = Router::new()
+            .route("/api/v1/catalog/items", get(|| async { "items" }).post(|| async { "created" }))
+            .route("/api/v1/users", get(|| async { "users" }))
+            .route("/api/v1/catalog/missing", get(|| async { StatusCode::NOT_FOUND }))
+            .route(
+                "/api/v1/catalog/custom",
+                get(|| async { ([(header::CACHE_CONTROL, "private, max-age=60")], "custom") }),
+            )
+            .route("/health", get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(Arc::new(rules), cache_control_middleware));
+
+        let cache_control = |method: Method, uri: &'static str| {
+            let app = app.clone();
+            async move {
+                let request = Request::builder().method(method).uri(uri).body(Body::empty()).unwrap();
+                let response = app.oneshot(request).await.unwrap();
+                response
+                    .headers()
+                    .get(header::CACHE_CONTROL)
+                    .map(|value| value.to_str().unwrap().to_string())
+            }
+        };
+
+        assert_eq!(
+            cache_control(Method::GET, "/api/v1/catalog/items").await.as_deref(),
+            Some("public, max-age=300")
+        );
+        assert_eq!(cache_control(Method::GET, "/api/v1/users").await.as_deref(), Some("no-store"));
+        // 处理函数设置的值、错误响应、非 GET 请求和未匹配的路径不受影响
+        assert_eq!(
+            cache_control(Method::GET, "/api/v1/catalog/custom").await.as_deref(),
+            Some("private, max-age=60")
+        );
+        assert_eq!(cache_control(Method::GET, "/api/v1/catalog/missing").await, None);
+        assert_eq!(cache_control(Method::POST, "/api/v1/catalog/items").await, None);
+        assert_eq!(cache_control(Method::GET, "/health").await, None);
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        assert!(matches!(CacheControlRules::new(&[rule("api/*", "no-store")]), Err(Error::Config(_))));
+        assert!(matches!(CacheControlRules::new(&[rule("/api/*", "")]), Err(Error::Config(_))));
+        assert!(matches!(CacheControlRules::new(&[rule("/api/*", "max-age=1\nx")]), Err(Error::Config(_))));
+    }
+}
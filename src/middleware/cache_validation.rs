@@ -0,0 +1,268 @@
+//! 条件请求模块
+//!
+//! 为 GET 响应生成 `ETag`，请求的 `If-None-Match` 匹配时返回 304 并省略响应体：
+//!
+//! - 静态文件和 `FileResponse` 按修改时间和大小生成，不读取文件内容
+//! - `content_types` 中的响应（如模板渲染的 HTML）和 `routes` 匹配的路径（如轮询的 JSON 接口）
+//!   按内容的 SHA-256 生成
+//!
+//! 已经设置了 `ETag` 的响应只做比较。静态文件的 `Last-Modified` 和 `If-Modified-Since`
+//! 由静态文件服务处理。
+//!
+//! ```toml
+//! [middleware.cache_validation]
+//! enabled = true
+//! weak = false
+//! static_files = true
+//! content_types = ["text/html"]
+//! routes = ["/api/v1/*"]
+//! max_body_size = 1048576
+//! ```
+
+use super::matcher::path_matches;
+use crate::config::CacheValidationConfig;
+use axum::{
+    body::{Body, HttpBody},
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+use std::{sync::Arc, time::UNIX_EPOCH};
+
+/// 条件请求中间件
+pub async fn cache_validation_middleware(
+    State(config): State<Arc<CacheValidationConfig>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().clone();
+    if method != Method::GET && method != Method::HEAD {
+        return next.run(request).await;
+    }
+    let if_none_match = request.headers().get(header::IF_NONE_MATCH).cloned();
+    let hashed_route = config
+        .routes
+        .iter()
+        .any(|pattern| path_matches(pattern, request.uri().path()));
+
+    let response = next.run(request).await;
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let body = if parts.headers.contains_key(header::ETAG) {
+        body
+    } else if let Some(tag) = config.static_files.then(|| file_tag(&parts.headers, &body)).flatten() {
+        insert_etag(&mut parts.headers, &tag, config.weak);
+        body
+    } else if method == Method::GET && is_hashed(&config, hashed_route, &parts.headers, &body) {
+        let bytes = match axum::body::to_bytes(body, config.max_body_size).await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                return crate::error::Error::Internal(format!("无法读取响应体: {}", e)).into_response()
+            }
+        };
+        insert_etag(&mut parts.headers, &content_tag(&bytes), config.weak);
+        Body::from(bytes)
+    } else {
+        body
+    };
+
+    let matched = match (&if_none_match, parts.headers.get(header::ETAG)) {
+        (Some(if_none_match), Some(etag)) => etag_matches(if_none_match, etag),
+        _ => false,
+    };
+    if !matched {
+        return Response::from_parts(parts, body);
+    }
+
+    parts.status = StatusCode::NOT_MODIFIED;
+    for name in [
+        header::CONTENT_LENGTH,
+        header::CONTENT_TYPE,
+        header::CONTENT_ENCODING,
+        header::CONTENT_DISPOSITION,
+        header::ACCEPT_RANGES,
+    ] {
+        parts.headers.remove(name);
+    }
+    Response::from_parts(parts, Body::empty())
+}
+
+/// 按 `Last-Modified` 和响应体大小生成的标签
+fn file_tag(headers: &HeaderMap, body: &Body) -> Option<String> {
+    let modified = headers
+        .get(header::LAST_MODIFIED)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| httpdate::parse_http_date(value).ok())?
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    let length = headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+        .or_else(|| body.size_hint().exact())?;
+    Some(format!("{:x}-{:x}", modified, length))
+}
+
+/// 按内容哈希的标签，取 SHA-256 的前 16 字节
+fn content_tag(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)[..16]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// 路径或响应类型匹配且响应体大小已知、不超过限制
+fn is_hashed(config: &CacheValidationConfig, hashed_route: bool, headers: &HeaderMap, body: &Body) -> bool {
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    (hashed_route
+        || config
+            .content_types
+            .iter()
+            .any(|prefix| content_type.starts_with(prefix.as_str())))
+        && body
+            .size_hint()
+            .exact()
+            .is_some_and(|size| size <= config.max_body_size as u64)
+}
+
+fn insert_etag(headers: &mut HeaderMap, tag: &str, weak: bool) {
+    let etag = if weak {
+        format!("W/\"{}\"", tag)
+    } else {
+        format!("\"{}\"", tag)
+    };
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        headers.insert(header::ETAG, value);
+    }
+}
+
+/// `If-None-Match` 使用弱比较：忽略 `W/` 前缀
+pub(crate) fn etag_matches(if_none_match: &HeaderValue, etag: &HeaderValue) -> bool {
+    let (Ok(if_none_match), Ok(etag)) = (if_none_match.to_str(), etag.to_str()) else {
+        return false;
+    };
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    let etag = opaque(etag);
+    if_none_match
+        .split(',')
+        .any(|candidate| candidate.trim() == "*" || opaque(candidate) == etag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{response::Html, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app(config: CacheValidationConfig) -> Router {
+        Router::new()
+            .route("/page", get(|| async { Html("<h1>hello</h1>") }))
+            .route("/json", get(|| async { axum::Json(serde_json::json!({ "ok": true })) }))
+            .route("/api/items", get(|| async { axum::Json(serde_json::json!([1, 2, 3])) }))
+            .route(
+                "/file",
+                get(|| async {
+                    (
+                        [
+                            (header::LAST_MODIFIED, "Wed, 21 Oct 2015 07:28:00 GMT"),
+                            (header::CONTENT_TYPE, "text/css"),
+                        ],
+                        "body{}",
+                    )
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                Arc::new(config),
+                cache_validation_middleware,
+            ))
+    }
+
+    async fn send(app: &Router, uri: &str, if_none_match: Option<&str>) -> Response {
+        let mut request = Request::builder().uri(uri);
+        if let Some(tag) = if_none_match {
+            request = request.header(header::IF_NONE_MATCH, tag);
+        }
+        app.clone().oneshot(request.body(Body::empty()).unwrap()).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_content_etag() {
+        let app = app(CacheValidationConfig {
+            enabled: true,
+            ..Default::default()
+        });
+
+        let response = send(&app, "/page", None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag = response.headers()[header::ETAG].to_str().unwrap().to_string();
+        assert!(etag.starts_with('"') && etag.len() == 34, "{}", etag);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"<h1>hello</h1>");
+
+        let response = send(&app, "/page", Some(&etag)).await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::ETAG], etag.as_str());
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert!(body.is_empty());
+
+        let response = send(&app, "/page", Some("\"other\", W/\"another\"")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // 不在 content_types 中的响应不生成 ETag
+        let response = send(&app, "/json", None).await;
+        assert!(response.headers().get(header::ETAG).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_route_etag() {
+        let app = app(CacheValidationConfig {
+            enabled: true,
+            routes: vec!["/api/*".to_string()],
+            ..Default::default()
+        });
+
+        // 匹配的路径按内容哈希，不限响应类型
+        let response = send(&app, "/api/items", None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let etag = response.headers()[header::ETAG].to_str().unwrap().to_string();
+        assert_eq!(etag, format!("\"{}\"", content_tag(b"[1,2,3]")));
+
+        let response = send(&app, "/api/items", Some(&etag)).await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert!(response.headers().get(header::CONTENT_TYPE).is_none());
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert!(body.is_empty());
+
+        // 其他路径仍按响应类型判断
+        let response = send(&app, "/json", None).await;
+        assert!(response.headers().get(header::ETAG).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_file_etag_and_weak() {
+        let app = app(CacheValidationConfig {
+            enabled: true,
+            weak: true,
+            ..Default::default()
+        });
+
+        let response = send(&app, "/file", None).await;
+        let etag = response.headers()[header::ETAG].to_str().unwrap().to_string();
+        assert_eq!(etag, "W/\"56273e80-6\"");
+
+        // 弱比较忽略 W/ 前缀
+        let response = send(&app, "/file", Some("\"56273e80-6\"")).await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        let response = send(&app, "/file", Some("*")).await;
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    }
+}
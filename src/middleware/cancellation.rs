@@ -0,0 +1,200 @@
+//! 请求取消模块
+//!
+//! 客户端断开连接时，hyper 会丢弃正在执行的请求 future。该模块在此时触发
+//! `RequestCancellation`，使处理器启动的后台任务、下游调用和数据库查询也能及时停止。
+//!
+//! ```rust,no_run
+//! use hwhkit::middleware::cancellation::RequestCancellation;
+//!
+//! async fn report(cancel: RequestCancellation) -> String {
+//!     // 客户端断开后查询会被中止
+//!     let handle = cancel.spawn(async { "耗时查询的结果".to_string() });
+//!     handle.await.ok().flatten().unwrap_or_default()
+//! }
+//! ```
+
+use crate::error::{Error, Result};
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request},
+    http::request::Parts,
+    middleware::Next,
+    response::Response,
+};
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+use tokio::{sync::Notify, task::JoinHandle};
+
+#[derive(Debug, Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+/// 请求取消信号
+///
+/// 客户端在响应生成前断开连接时被触发
+#[derive(Debug, Clone, Default)]
+pub struct RequestCancellation {
+    inner: Arc<Inner>,
+}
+
+impl RequestCancellation {
+    /// 创建新的取消信号
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 触发取消
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        self.inner.notify.notify_waiters();
+    }
+
+    /// 是否已被取消
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// 等待取消
+    pub async fn cancelled(&self) {
+        let notified = self.inner.notify.notified();
+        tokio::pin!(notified);
+        // 先注册等待再检查状态，避免错过通知
+        notified.as_mut().enable();
+        if self.is_cancelled() {
+            return;
+        }
+        notified.await;
+    }
+
+    /// 执行 future，请求被取消时提前返回 `None`
+    pub async fn run<F: Future>(&self, future: F) -> Option<F::Output> {
+        tokio::select! {
+            _ = self.cancelled() => None,
+            output = future => Some(output),
+        }
+    }
+
+    /// 启动后台任务，请求被取消时任务被中止并返回 `None`
+    pub fn spawn<F>(&self, future: F) -> JoinHandle<Option<F::Output>>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let cancel = self.clone();
+        tokio::spawn(async move { cancel.run(future).await })
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for RequestCancellation {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+        parts
+            .extensions
+            .get::<RequestCancellation>()
+            .cloned()
+            .ok_or_else(|| Error::Internal("请求取消中间件未启用".to_string()))
+    }
+}
+
+/// 在 drop 时触发取消，除非请求已正常完成
+struct CancelOnDrop {
+    cancel: RequestCancellation,
+    completed: bool,
+}
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        if !self.completed {
+            tracing::debug!("客户端断开连接，请求已取消");
+            self.cancel.cancel();
+        }
+    }
+}
+
+/// 请求取消中间件，将 `RequestCancellation` 写入请求扩展
+pub async fn cancellation_middleware(mut request: Request, next: Next) -> Response {
+    let cancel = RequestCancellation::new();
+    request.extensions_mut().insert(cancel.clone());
+
+    let mut guard = CancelOnDrop {
+        cancel,
+        completed: false,
+    };
+    let response = next.run(request).await;
+    guard.completed = true;
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get, Router};
+    use std::time::Duration;
+    use tower::util::ServiceExt;
+
+    #[tokio::test]
+    async fn test_client_disconnect_cancels_spawned_task() {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let tx = Arc::new(std::sync::Mutex::new(Some(tx)));
+
+        let app = Router::new()
+            .route(
+                "/slow",
+                get(move |cancel: RequestCancellation| {
+                    let tx = tx.clone();
+                    async move {
+                        let handle = cancel.spawn(tokio::time::sleep(Duration::from_secs(60)));
+                        tx.lock().unwrap().take().unwrap().send(handle).unwrap();
+                        tokio::time::sleep(Duration::from_secs(60)).await;
+                        "done"
+                    }
+                }),
+            )
+            .layer(axum::middleware::from_fn(cancellation_middleware));
+
+        // 模拟客户端断开：请求 future 在完成前被丢弃
+        let request = Request::builder().uri("/slow").body(Body::empty()).unwrap();
+        let call = tokio::spawn(app.oneshot(request));
+        let handle = rx.await.unwrap();
+        call.abort();
+
+        let result = tokio::time::timeout(Duration::from_secs(1), handle)
+            .await
+            .expect("spawned task should stop after disconnect")
+            .unwrap();
+        assert!(result.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_completed_request_not_cancelled() {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let tx = Arc::new(std::sync::Mutex::new(Some(tx)));
+
+        let app = Router::new()
+            .route(
+                "/",
+                get(move |cancel: RequestCancellation| {
+                    let tx = tx.clone();
+                    async move {
+                        tx.lock().unwrap().take().unwrap().send(cancel).unwrap();
+                        "ok"
+                    }
+                }),
+            )
+            .layer(axum::middleware::from_fn(cancellation_middleware));
+
+        let request = Request::builder().uri("/").body(Body::empty()).unwrap();
+        app.oneshot(request).await.unwrap();
+        assert!(!rx.await.unwrap().is_cancelled());
+    }
+}
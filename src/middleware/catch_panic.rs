@@ -0,0 +1,157 @@
+//! panic 捕获模块
+//!
+//! 处理函数 panic 时不再断开连接，而是返回 500 JSON 错误，响应体中附带请求 ID：
+//!
+//! ```json
+//! {"error": "内部错误: 请求处理失败", "status": 500, "request_id": "..."}
+//! ```
+//!
+//! 同时记录包含方法、路径、请求 ID 和 panic 信息的 `error` 级别事件，
+//! 并调用通过 [`WebServerBuilder::on_panic`](crate::WebServerBuilder::on_panic) 注册的回调，
+//! 便于上报到错误收集服务。默认启用，可以通过 `[middleware.catch_panic] enabled = false` 关闭。
+
+use super::{isolation::panic_message, request_id::RequestId};
+use crate::error::Error;
+use axum::{
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+use futures_util::FutureExt;
+use std::{fmt, panic::AssertUnwindSafe, sync::Arc};
+
+/// 一次被捕获的 panic
+#[derive(Debug, Clone)]
+pub struct PanicReport {
+    /// 请求方法
+    pub method: Method,
+    /// 请求路径
+    pub path: String,
+    /// 请求 ID，未启用请求 ID 中间件时为 `None`
+    pub request_id: Option<String>,
+    /// panic 信息
+    pub message: String,
+}
+
+type PanicHook = dyn Fn(&PanicReport) + Send + Sync;
+
+/// panic 回调列表
+#[derive(Clone, Default)]
+pub struct PanicHooks(Vec<Arc<PanicHook>>);
+
+impl PanicHooks {
+    /// 注册 panic 回调
+    pub fn push<F: Fn(&PanicReport) + Send + Sync + 'static>(&mut self, hook: F) {
+        self.0.push(Arc::new(hook));
+    }
+
+    /// 是否没有注册回调
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn notify(&self, report: &PanicReport) {
+        for hook in &self.0 {
+            // 回调本身 panic 时不影响错误响应
+            if let Err(panic) = std::panic::catch_unwind(AssertUnwindSafe(|| hook(report))) {
+                tracing::error!("panic 回调发生 panic: {}", panic_message(&*panic));
+            }
+        }
+    }
+}
+
+impl fmt::Debug for PanicHooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PanicHooks").field("len", &self.0.len()).finish()
+    }
+}
+
+/// panic 捕获中间件
+pub async fn catch_panic_middleware(
+    State(hooks): State<Arc<PanicHooks>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(|id| id.as_str().to_string());
+
+    let panic = match AssertUnwindSafe(next.run(request)).catch_unwind().await {
+        Ok(response) => return response,
+        Err(panic) => panic,
+    };
+
+    let report = PanicReport {
+        method,
+        path,
+        request_id,
+        message: panic_message(&*panic).to_string(),
+    };
+    tracing::error!(
+        method = %report.method,
+        path = %report.path,
+        request_id = report.request_id.as_deref().unwrap_or("-"),
+        panic = %report.message,
+        "请求处理发生 panic"
+    );
+    hooks.notify(&report);
+
+    Error::Internal("请求处理失败".to_string()).into_response_with_request_id(report.request_id.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::StatusCode, routing::get, Router};
+    use std::sync::Mutex;
+    use tower::util::ServiceExt;
+
+    #[tokio::test]
+    async fn test_catch_panic() {
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let mut hooks = PanicHooks::default();
+        let recorded = reports.clone();
+        hooks.push(move |report: &PanicReport| recorded.lock().unwrap().push(report.clone()));
+        hooks.push(|_: &PanicReport| panic!("回调失败"));
+
+        let app = Router::new()
+            .route("/ok", get(|| async { "ok" }))
+            .route(
+                "/panic",
+                get(|| async {
+                    if true {
+                        panic!("boom");
+                    }
+                    "unreachable"
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(Arc::new(hooks), catch_panic_middleware))
+            .layer(axum::middleware::from_fn(
+                |mut request: Request, next: Next| async move {
+                    request.extensions_mut().insert(RequestId::parse("req-1").unwrap());
+                    next.run(request).await
+                },
+            ));
+
+        let request = Request::builder().uri("/panic").body(Body::empty()).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["status"], 500);
+        assert_eq!(body["request_id"], "req-1");
+
+        let reports = reports.lock().unwrap().clone();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].path, "/panic");
+        assert_eq!(reports[0].request_id.as_deref(), Some("req-1"));
+        assert_eq!(reports[0].message, "boom");
+
+        let request = Request::builder().uri("/ok").body(Body::empty()).unwrap();
+        assert_eq!(app.oneshot(request).await.unwrap().status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,269 @@
+//! 客户端地址模块
+//!
+//! [`ClientIp`] 提取器返回请求的真实客户端地址：直接连接的地址属于 `[server.proxy]` 中受信任的代理时，
+//! 从转发头（默认 `X-Forwarded-For`）的最右侧开始跳过受信任的代理，取第一个不受信任的地址；
+//! 否则使用连接的对端地址。解析结果同时写入追踪 span 和访问日志的 `client_ip` 字段。
+//!
+//! ```toml
+//! [server.proxy]
+//! trusted_proxies = ["10.0.0.0/8", "127.0.0.1"]
+//! header = "x-forwarded-for"
+//! ```
+//!
+//! ```rust
+//! use hwhkit::middleware::client_ip::ClientIp;
+//!
+//! async fn whoami(ClientIp(ip): ClientIp) -> String {
+//!     ip.to_string()
+//! }
+//! ```
+
+use crate::{
+    config::ProxyConfig,
+    error::{Error, Result},
+};
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts, Request, State},
+    http::{request::Parts, HeaderMap, HeaderName},
+    middleware::Next,
+    response::Response,
+};
+use std::{
+    fmt,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
+
+/// 真实客户端地址
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientIp(pub IpAddr);
+
+impl fmt::Display for ClientIp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ClientIp {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+        if let Some(ip) = parts.extensions.get::<ClientIp>() {
+            return Ok(*ip);
+        }
+        parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| ClientIp(addr.ip().to_canonical()))
+            .ok_or_else(|| Error::Internal("无法获取客户端地址".to_string()))
+    }
+}
+
+/// 受信任的代理
+#[derive(Debug, Clone)]
+pub struct TrustedProxies {
+    networks: Vec<(IpAddr, u8)>,
+    header: HeaderName,
+}
+
+impl TrustedProxies {
+    /// 根据配置创建
+    ///
+    /// # Errors
+    ///
+    /// 地址、前缀长度或转发头名称无效时返回配置错误
+    pub fn new(config: &ProxyConfig) -> Result<Self> {
+        let networks = config
+            .trusted_proxies
+            .iter()
+            .map(|network| parse_network(network))
+            .collect::<Result<_>>()?;
+        let header = HeaderName::from_bytes(config.header.as_bytes())
+            .map_err(|e| Error::Config(format!("无效的转发头 '{}': {}", config.header, e)))?;
+        Ok(Self { networks, header })
+    }
+
+    /// 地址是否属于受信任的代理
+    pub fn is_trusted(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        self.networks.iter().any(|&(network, prefix)| match (network, ip) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - prefix as u32).unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - prefix as u32).unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        })
+    }
+
+    /// 根据连接的对端地址和转发头解析客户端地址
+    pub fn resolve(&self, peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+        let mut client = peer.to_canonical();
+        if !self.is_trusted(client) {
+            return client;
+        }
+        let hops = headers
+            .get_all(&self.header)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .collect::<Vec<_>>();
+        for hop in hops.into_iter().rev() {
+            // 无法解析的地址不可信，使用转发它的代理
+            let Some(ip) = parse_hop(hop) else {
+                break;
+            };
+            client = ip;
+            if !self.is_trusted(ip) {
+                break;
+            }
+        }
+        client
+    }
+}
+
+/// 解析 `10.0.0.0/8` 或单个地址
+fn parse_network(network: &str) -> Result<(IpAddr, u8)> {
+    let invalid = || Error::Config(format!("无效的受信任代理地址: {}", network));
+    let (ip, prefix) = match network.split_once('/') {
+        Some((ip, prefix)) => (ip, Some(prefix.parse::<u8>().map_err(|_| invalid())?)),
+        None => (network, None),
+    };
+    let ip = ip.trim().parse::<IpAddr>().map_err(|_| invalid())?.to_canonical();
+    let max = if ip.is_ipv4() { 32 } else { 128 };
+    let prefix = prefix.unwrap_or(max);
+    if prefix > max {
+        return Err(invalid());
+    }
+    Ok((ip, prefix))
+}
+
+/// 解析转发头中的一个地址，兼容带端口的写法（`1.2.3.4:5678`、`[::1]:5678`）
+fn parse_hop(hop: &str) -> Option<IpAddr> {
+    let hop = hop.trim();
+    hop.parse::<IpAddr>()
+        .ok()
+        .or_else(|| hop.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+        .map(|ip| ip.to_canonical())
+}
+
+/// 客户端地址中间件
+///
+/// 服务器通过 `into_make_service_with_connect_info` 提供对端地址时，解析客户端地址并写入请求扩展
+pub async fn client_ip_middleware(
+    State(proxies): State<Arc<TrustedProxies>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    if let Some(peer) = peer {
+        let ip = proxies.resolve(peer, request.headers());
+        request.extensions_mut().insert(ClientIp(ip));
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get, Router};
+    use tower::util::ServiceExt;
+
+    fn proxies(trusted: &[&str]) -> TrustedProxies {
+        TrustedProxies::new(&ProxyConfig {
+            trusted_proxies: trusted.iter().map(|network| network.to_string()).collect(),
+            ..ProxyConfig::default()
+        })
+        .unwrap()
+    }
+
+    fn forwarded(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", value.parse().unwrap());
+        headers
+    }
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_is_trusted() {
+        let proxies = proxies(&["10.0.0.0/8", "192.168.1.1", "fd00::/8"]);
+        assert!(proxies.is_trusted(ip("10.1.2.3")));
+        assert!(proxies.is_trusted(ip("192.168.1.1")));
+        assert!(!proxies.is_trusted(ip("192.168.1.2")));
+        assert!(proxies.is_trusted(ip("fd12::1")));
+        assert!(proxies.is_trusted(ip("::ffff:10.0.0.1")));
+        assert!(!proxies.is_trusted(ip("11.0.0.1")));
+
+        for invalid in ["10.0.0.0/33", "localhost", "10.0.0.0/x"] {
+            let config = ProxyConfig {
+                trusted_proxies: vec![invalid.to_string()],
+                ..ProxyConfig::default()
+            };
+            assert!(matches!(TrustedProxies::new(&config), Err(Error::Config(_))), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_resolve() {
+        let proxies = proxies(&["10.0.0.0/8"]);
+
+        // 不受信任的对端地址忽略转发头，防止伪造
+        assert_eq!(proxies.resolve(ip("203.0.113.9"), &forwarded("1.1.1.1")), ip("203.0.113.9"));
+        // 跳过受信任的代理，取第一个不受信任的地址
+        assert_eq!(
+            proxies.resolve(ip("10.0.0.1"), &forwarded("1.1.1.1, 203.0.113.7, 10.0.0.2")),
+            ip("203.0.113.7")
+        );
+        assert_eq!(proxies.resolve(ip("10.0.0.1"), &forwarded("203.0.113.7:4321")), ip("203.0.113.7"));
+        // 全部是受信任的代理时使用最左侧的地址
+        assert_eq!(proxies.resolve(ip("10.0.0.1"), &forwarded("10.0.0.3, 10.0.0.2")), ip("10.0.0.3"));
+        // 无法解析的地址不可信
+        assert_eq!(proxies.resolve(ip("10.0.0.1"), &forwarded("1.1.1.1, unknown")), ip("10.0.0.1"));
+        assert_eq!(proxies.resolve(ip("10.0.0.1"), &HeaderMap::new()), ip("10.0.0.1"));
+    }
+
+    #[tokio::test]
+    async fn test_client_ip_extractor() {
+        let app = Router::new()
+            .route("/", get(|ClientIp(ip): ClientIp| async move { ip.to_string() }))
+            .layer(axum::middleware::from_fn_with_state(
+                Arc::new(proxies(&["127.0.0.1"])),
+                client_ip_middleware,
+            ));
+        let call = |peer: &str, forwarded: Option<&str>| {
+            let mut request = Request::builder().uri("/");
+            if let Some(value) = forwarded {
+                request = request.header("x-forwarded-for", value);
+            }
+            let mut request = request.body(Body::empty()).unwrap();
+            request
+                .extensions_mut()
+                .insert(ConnectInfo(peer.parse::<SocketAddr>().unwrap()));
+            let app = app.clone();
+            async move {
+                let response = app.oneshot(request).await.unwrap();
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                String::from_utf8(body.to_vec()).unwrap()
+            }
+        };
+
+        assert_eq!(call("127.0.0.1:5000", Some("198.51.100.4")).await, "198.51.100.4");
+        assert_eq!(call("198.51.100.8:5000", Some("1.2.3.4")).await, "198.51.100.8");
+
+        // 没有对端地址时返回 500
+        let request = Request::builder().uri("/").body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
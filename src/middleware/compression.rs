@@ -0,0 +1,117 @@
+//! 响应压缩中间件模块
+
+use crate::{config::CompressionConfig, error::Result};
+use axum::Router;
+
+/// 应用响应压缩中间件
+///
+/// 需要启用 `compression` feature，否则返回配置错误
+#[cfg(feature = "compression")]
+pub fn apply_compression(app: Router, config: &CompressionConfig) -> Result<Router> {
+    use crate::config::CompressionAlgorithm;
+    use axum::http::{header, Extensions, HeaderMap, StatusCode, Version};
+    use std::sync::Arc;
+    use tower_http::compression::{
+        predicate::{NotForContentType, Predicate, SizeAbove},
+        CompressionLayer,
+    };
+
+    let excluded: Arc<Vec<String>> = Arc::new(config.exclude_content_types.clone());
+    let not_excluded = move |_: StatusCode, _: Version, headers: &HeaderMap, _: &Extensions| {
+        headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(|content_type| !excluded.iter().any(|prefix| content_type.starts_with(prefix.as_str())))
+            .unwrap_or(true)
+    };
+
+    // gRPC 和 SSE 响应总是不压缩
+    let predicate = SizeAbove::new(config.min_size)
+        .and(NotForContentType::GRPC)
+        .and(NotForContentType::SSE)
+        .and(not_excluded);
+
+    let enabled = |algorithm| config.algorithms.contains(&algorithm);
+    let layer = CompressionLayer::new()
+        .gzip(enabled(CompressionAlgorithm::Gzip))
+        .br(enabled(CompressionAlgorithm::Br))
+        .zstd(enabled(CompressionAlgorithm::Zstd))
+        .compress_when(predicate);
+
+    Ok(app.layer(layer))
+}
+
+/// 应用响应压缩中间件
+///
+/// 需要启用 `compression` feature，否则返回配置错误
+#[cfg(not(feature = "compression"))]
+pub fn apply_compression(_app: Router, _config: &CompressionConfig) -> Result<Router> {
+    Err(crate::error::Error::Config(
+        "响应压缩需要启用 `compression` feature".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "compression")]
+    #[tokio::test]
+    async fn test_compression_negotiation() {
+        use crate::config::CompressionAlgorithm;
+        use axum::{body::Body, http::Request, routing::get};
+        use tower::util::ServiceExt;
+
+        let config = CompressionConfig {
+            enabled: true,
+            algorithms: vec![CompressionAlgorithm::Gzip],
+            min_size: 16,
+            ..Default::default()
+        };
+        let app = Router::new()
+            .route("/text", get(|| async { "hello ".repeat(100) }))
+            .route("/small", get(|| async { "hi" }))
+            .route(
+                "/image",
+                get(|| async { ([("content-type", "image/png")], vec![0u8; 1024]) }),
+            );
+        let app = apply_compression(app, &config).unwrap();
+
+        let request = |uri: &str, encoding: &str| {
+            Request::builder()
+                .uri(uri)
+                .header("accept-encoding", encoding)
+                .body(Body::empty())
+                .unwrap()
+        };
+        let encoding = |response: &axum::response::Response| {
+            response
+                .headers()
+                .get("content-encoding")
+                .map(|v| v.to_str().unwrap().to_string())
+        };
+
+        let response = app.clone().oneshot(request("/text", "gzip, br")).await.unwrap();
+        assert_eq!(encoding(&response).as_deref(), Some("gzip"));
+
+        // 未启用的算法不参与协商
+        let response = app.clone().oneshot(request("/text", "br")).await.unwrap();
+        assert_eq!(encoding(&response), None);
+
+        let response = app.clone().oneshot(request("/small", "gzip")).await.unwrap();
+        assert_eq!(encoding(&response), None);
+
+        let response = app.oneshot(request("/image", "gzip")).await.unwrap();
+        assert_eq!(encoding(&response), None);
+    }
+
+    #[cfg(not(feature = "compression"))]
+    #[test]
+    fn test_compression_requires_feature() {
+        let config = CompressionConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        assert!(apply_compression(Router::new(), &config).is_err());
+    }
+}
@@ -0,0 +1,643 @@
+//! Cookie 工具模块
+//!
+//! 启用 `cookies` feature 后，处理器可以使用 [`CookieJar`] 提取器读写 Cookie，签名和加密使用
+//! `[cookies]` 中配置的密钥。第一个密钥用于签名和加密，其余密钥只用于校验，便于轮换：
+//!
+//! ```toml
+//! [cookies]
+//! keys = ["新密钥，至少 32 字节……", "旧密钥，至少 32 字节……"]
+//! ```
+//!
+//! 少量客户端状态（如界面偏好）可以使用 [`Cookie<T>`] 以 JSON 形式保存在签名的 Cookie 中，
+//! 不需要会话存储。
+//!
+//! 会话和 CSRF 中间件使用同一组密钥：会话未设置 `secret` 时使用这些密钥，
+//! 配置了密钥时 CSRF 令牌 Cookie 同样经过签名。
+
+use axum::http::{header, HeaderMap};
+
+#[cfg(feature = "cookies")]
+pub use cookie::Cookie as RawCookie;
+#[cfg(feature = "cookies")]
+pub use jar::{Cookie, CookieData, CookieJar, CookieKeys, PrivateCookies, SignedCookies};
+#[cfg(feature = "cookies")]
+pub(crate) use jar::CookieSettings;
+#[cfg(feature = "sessions")]
+pub(crate) use jar::parse_cookies;
+
+/// 从请求的 `Cookie` 头中读取指定名称的值
+pub fn get_cookie(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.trim_matches('"').to_string())
+}
+
+#[cfg(feature = "cookies")]
+mod jar {
+    use crate::{
+        config::{CookiesConfig, SameSite},
+        error::{Error, Result},
+        inject::Services,
+    };
+    use axum::{
+        async_trait,
+        extract::FromRequestParts,
+        http::{header, request::Parts, HeaderMap, HeaderValue},
+        response::{IntoResponseParts, ResponseParts},
+    };
+    use cookie::{time::Duration, Cookie as RawCookie, Key};
+    use serde::{de::DeserializeOwned, Serialize};
+    use std::{convert::Infallible, fmt, sync::Arc};
+
+    /// 浏览器接受的单个 Cookie 的最大长度
+    const MAX_COOKIE_BYTES: usize = 4096;
+
+    /// 解析请求中的所有 Cookie
+    pub(crate) fn parse_cookies(headers: &HeaderMap) -> impl Iterator<Item = RawCookie<'static>> + '_ {
+        headers
+            .get_all(header::COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(';'))
+            .filter_map(|pair| RawCookie::parse_encoded(pair.trim().to_string()).ok())
+    }
+
+    /// Cookie 签名/加密密钥，第一个密钥用于签名和加密，所有密钥都可用于校验
+    #[derive(Clone)]
+    pub struct CookieKeys {
+        keys: Arc<Vec<Key>>,
+    }
+
+    impl CookieKeys {
+        /// 从密钥创建
+        ///
+        /// # Errors
+        ///
+        /// 没有密钥或任一密钥短于 32 字节时返回配置错误
+        pub fn new<I, S>(secrets: I) -> Result<Self>
+        where
+            I: IntoIterator<Item = S>,
+            S: AsRef<str>,
+        {
+            let keys = secrets
+                .into_iter()
+                .map(|secret| {
+                    let secret = secret.as_ref();
+                    if secret.len() < 32 {
+                        return Err(Error::Config("Cookie 密钥至少需要 32 字节".to_string()));
+                    }
+                    Ok(Key::derive_from(secret.as_bytes()))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            if keys.is_empty() {
+                return Err(Error::Config("未配置 Cookie 密钥".to_string()));
+            }
+            Ok(Self { keys: Arc::new(keys) })
+        }
+
+        /// 根据 `[cookies]` 配置创建
+        ///
+        /// # Errors
+        ///
+        /// 没有密钥或任一密钥短于 32 字节时返回配置错误
+        pub fn from_config(config: &CookiesConfig) -> Result<Self> {
+            Self::new(config.effective_keys())
+        }
+
+        /// 使用第一个密钥签名 Cookie 的值
+        pub fn sign(&self, cookie: RawCookie<'static>) -> RawCookie<'static> {
+            let mut jar = cookie::CookieJar::new();
+            let name = cookie.name().to_string();
+            jar.signed_mut(&self.keys[0]).add(cookie);
+            jar.get(&name).cloned().expect("刚加入的 Cookie 一定存在")
+        }
+
+        /// 校验签名，返回原始值的 Cookie
+        pub fn verify(&self, cookie: &RawCookie<'_>) -> Option<RawCookie<'static>> {
+            self.keys.iter().find_map(|key| {
+                let mut jar = cookie::CookieJar::new();
+                jar.add_original(cookie.clone().into_owned());
+                jar.signed(key).get(cookie.name())
+            })
+        }
+
+        /// 使用第一个密钥加密 Cookie 的值
+        pub fn encrypt(&self, cookie: RawCookie<'static>) -> RawCookie<'static> {
+            let mut jar = cookie::CookieJar::new();
+            let name = cookie.name().to_string();
+            jar.private_mut(&self.keys[0]).add(cookie);
+            jar.get(&name).cloned().expect("刚加入的 Cookie 一定存在")
+        }
+
+        /// 解密 Cookie，返回原始值的 Cookie
+        pub fn decrypt(&self, cookie: &RawCookie<'_>) -> Option<RawCookie<'static>> {
+            self.keys.iter().find_map(|key| {
+                let mut jar = cookie::CookieJar::new();
+                jar.add_original(cookie.clone().into_owned());
+                jar.private(key).get(cookie.name())
+            })
+        }
+    }
+
+    impl fmt::Debug for CookieKeys {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("CookieKeys").field("keys", &self.keys.len()).finish()
+        }
+    }
+
+    /// 登记为服务的 Cookie 密钥和类型化 Cookie 的默认属性
+    #[derive(Debug, Clone)]
+    pub(crate) struct CookieSettings {
+        keys: CookieKeys,
+        same_site: SameSite,
+        secure: bool,
+    }
+
+    impl CookieSettings {
+        pub(crate) fn new(config: &CookiesConfig) -> Result<Self> {
+            Ok(Self {
+                keys: CookieKeys::from_config(config)?,
+                same_site: config.same_site,
+                secure: config.secure,
+            })
+        }
+    }
+
+    /// Cookie 提取器
+    ///
+    /// 修改后作为响应的一部分返回，新增和删除的 Cookie 会写入 `Set-Cookie`
+    ///
+    /// ```rust
+    /// use hwhkit::middleware::cookies::{CookieJar, RawCookie};
+    ///
+    /// async fn remember(mut jar: CookieJar) -> (CookieJar, &'static str) {
+    ///     let visits = jar
+    ///         .signed()
+    ///         .get("visits")
+    ///         .and_then(|cookie| cookie.value().parse::<u32>().ok())
+    ///         .unwrap_or(0);
+    ///     jar.signed().add(RawCookie::build(("visits", (visits + 1).to_string())).path("/"));
+    ///     (jar, "ok")
+    /// }
+    /// ```
+    #[derive(Debug, Clone)]
+    pub struct CookieJar {
+        jar: cookie::CookieJar,
+        keys: CookieKeys,
+        same_site: SameSite,
+        secure: bool,
+    }
+
+    impl CookieJar {
+        /// 从请求头创建，类型化 Cookie 使用 `SameSite=Lax`，不设置 `Secure`
+        pub fn new(headers: &HeaderMap, keys: CookieKeys) -> Self {
+            let mut jar = cookie::CookieJar::new();
+            parse_cookies(headers).for_each(|cookie| jar.add_original(cookie));
+            Self {
+                jar,
+                keys,
+                same_site: SameSite::default(),
+                secure: false,
+            }
+        }
+
+        /// 读取未签名的 Cookie
+        pub fn get(&self, name: &str) -> Option<&RawCookie<'static>> {
+            self.jar.get(name)
+        }
+
+        /// 添加未签名的 Cookie
+        pub fn add(&mut self, cookie: impl Into<RawCookie<'static>>) {
+            self.jar.add(cookie);
+        }
+
+        /// 删除路径为 `/` 的 Cookie
+        pub fn remove(&mut self, name: &str) {
+            self.jar.remove(RawCookie::build(name.to_string()).path("/"));
+        }
+
+        /// 签名的 Cookie，客户端可以读取但无法篡改
+        pub fn signed(&mut self) -> SignedCookies<'_> {
+            SignedCookies { jar: self }
+        }
+
+        /// 加密的 Cookie，客户端无法读取和篡改
+        pub fn private(&mut self) -> PrivateCookies<'_> {
+            PrivateCookies { jar: self }
+        }
+    }
+
+    #[async_trait]
+    impl<S: Send + Sync> FromRequestParts<S> for CookieJar {
+        type Rejection = Error;
+
+        async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+            let settings = parts
+                .extensions
+                .get::<Services>()
+                .and_then(Services::get::<CookieSettings>)
+                .cloned()
+                .ok_or_else(|| Error::Internal("未配置 Cookie 密钥，请设置 [cookies] keys".to_string()))?;
+            Ok(Self {
+                same_site: settings.same_site,
+                secure: settings.secure,
+                ..Self::new(&parts.headers, settings.keys)
+            })
+        }
+    }
+
+    impl IntoResponseParts for CookieJar {
+        type Error = Infallible;
+
+        fn into_response_parts(self, mut res: ResponseParts) -> std::result::Result<ResponseParts, Self::Error> {
+            for cookie in self.jar.delta() {
+                // 值中可能包含 `;` 等字符，需要百分号编码
+                if let Ok(value) = HeaderValue::from_str(&cookie.encoded().to_string()) {
+                    res.headers_mut().append(header::SET_COOKIE, value);
+                }
+            }
+            Ok(res)
+        }
+    }
+
+    /// 保存在 [`Cookie<T>`] 中的数据
+    ///
+    /// ```rust
+    /// use hwhkit::{middleware::cookies::CookieData, Deserialize, Serialize};
+    ///
+    /// #[derive(Default, Serialize, Deserialize)]
+    /// struct Preferences {
+    ///     theme: String,
+    /// }
+    ///
+    /// impl CookieData for Preferences {
+    ///     const NAME: &'static str = "prefs";
+    ///     const MAX_AGE: Option<u64> = Some(365 * 24 * 3600);
+    /// }
+    /// ```
+    pub trait CookieData: Serialize + DeserializeOwned + Send + 'static {
+        /// Cookie 名称
+        const NAME: &'static str;
+        /// 有效期（秒），`None` 时为浏览器关闭即失效的会话 Cookie
+        const MAX_AGE: Option<u64> = None;
+        /// 是否加密；默认只签名，客户端可以读取但无法篡改
+        const ENCRYPT: bool = false;
+    }
+
+    /// 以 JSON 形式保存在签名（或加密）Cookie 中的类型化数据
+    ///
+    /// Cookie 不存在、签名无效或无法反序列化时 [`get`](Self::get) 返回 `None`。修改后作为响应的一部分返回，
+    /// 写入的 Cookie 带有 `Path=/` 和 `HttpOnly`，`SameSite` 和 `Secure` 取自 `[cookies]` 配置：
+    ///
+    /// ```rust
+    /// use hwhkit::{middleware::cookies::{Cookie, CookieData}, Deserialize, Serialize};
+    ///
+    /// #[derive(Clone, Default, Serialize, Deserialize)]
+    /// struct Preferences {
+    ///     theme: String,
+    /// }
+    ///
+    /// impl CookieData for Preferences {
+    ///     const NAME: &'static str = "prefs";
+    /// }
+    ///
+    /// async fn dark_mode(mut prefs: Cookie<Preferences>) -> hwhkit::Result<(Cookie<Preferences>, &'static str)> {
+    ///     let mut value = prefs.get().cloned().unwrap_or_default();
+    ///     value.theme = "dark".to_string();
+    ///     prefs.set(value)?;
+    ///     Ok((prefs, "ok"))
+    /// }
+    /// ```
+    #[derive(Debug)]
+    pub struct Cookie<T> {
+        jar: CookieJar,
+        value: Option<T>,
+    }
+
+    impl<T: CookieData> Cookie<T> {
+        /// 从 [`CookieJar`] 中读取，之后的修改同样写入这个 `CookieJar`
+        pub fn from_jar(mut jar: CookieJar) -> Self {
+            let cookie = if T::ENCRYPT {
+                jar.private().get(T::NAME)
+            } else {
+                jar.signed().get(T::NAME)
+            };
+            let value = cookie.and_then(|cookie| match serde_json::from_str(cookie.value()) {
+                Ok(value) => Some(value),
+                Err(e) => {
+                    tracing::debug!("忽略无法解析的 Cookie {}: {}", T::NAME, e);
+                    None
+                }
+            });
+            Self { jar, value }
+        }
+
+        /// 当前的值
+        pub fn get(&self) -> Option<&T> {
+            self.value.as_ref()
+        }
+
+        /// 取出当前的值
+        pub fn into_inner(self) -> Option<T> {
+            self.value
+        }
+
+        /// 设置新的值
+        ///
+        /// # Errors
+        ///
+        /// 序列化失败或签名（加密）后超过 4096 字节时返回内部错误
+        pub fn set(&mut self, value: T) -> Result<()> {
+            let json = serde_json::to_string(&value)?;
+            let mut cookie = RawCookie::build((T::NAME, json))
+                .path("/")
+                .http_only(true)
+                .secure(self.jar.secure)
+                .same_site(match self.jar.same_site {
+                    SameSite::Strict => cookie::SameSite::Strict,
+                    SameSite::Lax => cookie::SameSite::Lax,
+                    SameSite::None => cookie::SameSite::None,
+                });
+            if let Some(max_age) = T::MAX_AGE {
+                cookie = cookie.max_age(Duration::seconds(max_age as i64));
+            }
+            let cookie = if T::ENCRYPT {
+                self.jar.keys.encrypt(cookie.build())
+            } else {
+                self.jar.keys.sign(cookie.build())
+            };
+
+            let size = cookie.encoded().to_string().len();
+            if size > MAX_COOKIE_BYTES {
+                return Err(Error::Internal(format!(
+                    "Cookie {} 长度为 {} 字节，超过 {} 字节的限制",
+                    T::NAME,
+                    size,
+                    MAX_COOKIE_BYTES
+                )));
+            }
+            self.jar.jar.add(cookie);
+            self.value = Some(value);
+            Ok(())
+        }
+
+        /// 删除 Cookie
+        pub fn remove(&mut self) {
+            self.jar.remove(T::NAME);
+            self.value = None;
+        }
+    }
+
+    #[async_trait]
+    impl<T: CookieData, S: Send + Sync> FromRequestParts<S> for Cookie<T> {
+        type Rejection = Error;
+
+        async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self> {
+            Ok(Self::from_jar(CookieJar::from_request_parts(parts, state).await?))
+        }
+    }
+
+    impl<T> IntoResponseParts for Cookie<T> {
+        type Error = Infallible;
+
+        fn into_response_parts(self, res: ResponseParts) -> std::result::Result<ResponseParts, Self::Error> {
+            self.jar.into_response_parts(res)
+        }
+    }
+
+    /// 签名的 Cookie
+    #[derive(Debug)]
+    pub struct SignedCookies<'a> {
+        jar: &'a mut CookieJar,
+    }
+
+    impl SignedCookies<'_> {
+        /// 读取并校验 Cookie，签名无效时返回 `None`
+        pub fn get(&self, name: &str) -> Option<RawCookie<'static>> {
+            self.jar.keys.verify(self.jar.jar.get(name)?)
+        }
+
+        /// 添加签名的 Cookie
+        pub fn add(&mut self, cookie: impl Into<RawCookie<'static>>) {
+            let cookie = self.jar.keys.sign(cookie.into());
+            self.jar.jar.add(cookie);
+        }
+
+        /// 删除路径为 `/` 的 Cookie
+        pub fn remove(&mut self, name: &str) {
+            self.jar.remove(name);
+        }
+    }
+
+    /// 加密的 Cookie
+    #[derive(Debug)]
+    pub struct PrivateCookies<'a> {
+        jar: &'a mut CookieJar,
+    }
+
+    impl PrivateCookies<'_> {
+        /// 读取并解密 Cookie，无法解密时返回 `None`
+        pub fn get(&self, name: &str) -> Option<RawCookie<'static>> {
+            self.jar.keys.decrypt(self.jar.jar.get(name)?)
+        }
+
+        /// 添加加密的 Cookie
+        pub fn add(&mut self, cookie: impl Into<RawCookie<'static>>) {
+            let cookie = self.jar.keys.encrypt(cookie.into());
+            self.jar.jar.add(cookie);
+        }
+
+        /// 删除路径为 `/` 的 Cookie
+        pub fn remove(&mut self, name: &str) {
+            self.jar.remove(name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_get_cookie() {
+        let mut headers = HeaderMap::new();
+        headers.append(header::COOKIE, HeaderValue::from_static("a=1; b=\"two\""));
+        headers.append(header::COOKIE, HeaderValue::from_static("c=3"));
+
+        assert_eq!(get_cookie(&headers, "a").as_deref(), Some("1"));
+        assert_eq!(get_cookie(&headers, "b").as_deref(), Some("two"));
+        assert_eq!(get_cookie(&headers, "c").as_deref(), Some("3"));
+        assert_eq!(get_cookie(&headers, "d"), None);
+    }
+
+    #[cfg(feature = "cookies")]
+    mod jar {
+        use super::*;
+        use axum::response::IntoResponse;
+
+        const OLD: &str = "0123456789abcdef0123456789abcdef-old";
+        const NEW: &str = "0123456789abcdef0123456789abcdef-new";
+
+        /// 把响应中的 `Set-Cookie` 转换为请求的 `Cookie` 头
+        fn round_trip(jar: impl axum::response::IntoResponseParts<Error = std::convert::Infallible>) -> HeaderMap {
+            let response = (jar, "ok").into_response();
+            let mut headers = HeaderMap::new();
+            for value in response.headers().get_all(header::SET_COOKIE) {
+                let pair = value.to_str().unwrap().split(';').next().unwrap().to_string();
+                headers.append(header::COOKIE, HeaderValue::from_str(&pair).unwrap());
+            }
+            headers
+        }
+
+        #[test]
+        fn test_signed_and_private() {
+            let keys = CookieKeys::new([NEW]).unwrap();
+            let mut jar = CookieJar::new(&HeaderMap::new(), keys.clone());
+            jar.add(RawCookie::new("plain", "1"));
+            jar.signed().add(RawCookie::new("signed", "user=42"));
+            jar.private().add(RawCookie::new("private", "secret; value"));
+
+            let headers = round_trip(jar);
+            assert!(!get_cookie(&headers, "private").unwrap().contains("secret"));
+            let mut jar = CookieJar::new(&headers, keys.clone());
+            assert_eq!(jar.get("plain").unwrap().value(), "1");
+            assert_eq!(jar.signed().get("signed").unwrap().value(), "user=42");
+            assert_eq!(jar.private().get("private").unwrap().value(), "secret; value");
+            // 未签名的 Cookie 无法通过校验
+            assert!(jar.signed().get("plain").is_none());
+
+            // 篡改签名的 Cookie
+            let mut tampered = headers.clone();
+            let signed = get_cookie(&headers, "signed").unwrap().replace("42", "43");
+            tampered.insert(header::COOKIE, HeaderValue::from_str(&format!("signed={}", signed)).unwrap());
+            let mut jar = CookieJar::new(&tampered, keys);
+            assert!(jar.signed().get("signed").is_none());
+        }
+
+        #[test]
+        fn test_key_rotation() {
+            let mut jar = CookieJar::new(&HeaderMap::new(), CookieKeys::new([OLD]).unwrap());
+            jar.signed().add(RawCookie::new("signed", "42"));
+            jar.private().add(RawCookie::new("private", "42"));
+            let headers = round_trip(jar);
+
+            // 旧密钥签发的 Cookie 在轮换后仍然有效
+            let mut jar = CookieJar::new(&headers, CookieKeys::new([NEW, OLD]).unwrap());
+            assert_eq!(jar.signed().get("signed").unwrap().value(), "42");
+            assert_eq!(jar.private().get("private").unwrap().value(), "42");
+
+            // 移除旧密钥后失效
+            let mut jar = CookieJar::new(&headers, CookieKeys::new([NEW]).unwrap());
+            assert!(jar.signed().get("signed").is_none());
+            assert!(jar.private().get("private").is_none());
+        }
+
+        #[test]
+        fn test_remove() {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::COOKIE, HeaderValue::from_static("plain=1"));
+            let mut jar = CookieJar::new(&headers, CookieKeys::new([NEW]).unwrap());
+            jar.remove("plain");
+            let response = (jar, "ok").into_response();
+            let set_cookie = response.headers()[header::SET_COOKIE].to_str().unwrap();
+            assert!(set_cookie.starts_with("plain=;"));
+            assert!(set_cookie.contains("Max-Age=0"));
+        }
+
+        #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Prefs {
+            theme: String,
+            note: String,
+        }
+
+        impl CookieData for Prefs {
+            const NAME: &'static str = "prefs";
+            const MAX_AGE: Option<u64> = Some(3600);
+        }
+
+        #[derive(Debug, serde::Serialize, serde::Deserialize)]
+        struct Secret(String);
+
+        impl CookieData for Secret {
+            const NAME: &'static str = "secret";
+            const ENCRYPT: bool = true;
+        }
+
+        #[test]
+        fn test_typed_cookie() {
+            let keys = CookieKeys::new([NEW]).unwrap();
+            let mut prefs = Cookie::<Prefs>::from_jar(CookieJar::new(&HeaderMap::new(), keys.clone()));
+            assert!(prefs.get().is_none());
+            let value = Prefs {
+                theme: "dark".to_string(),
+                note: "a; b=\"c\"".to_string(),
+            };
+            prefs.set(value.clone()).unwrap();
+
+            let response = (prefs, "ok").into_response();
+            let set_cookie = response.headers()[header::SET_COOKIE].to_str().unwrap();
+            for attribute in ["HttpOnly", "SameSite=Lax", "Path=/", "Max-Age=3600"] {
+                assert!(set_cookie.contains(attribute), "缺少 {}: {}", attribute, set_cookie);
+            }
+            assert!(!set_cookie.contains("Secure"));
+
+            let mut headers = HeaderMap::new();
+            let pair = set_cookie.split(';').next().unwrap();
+            headers.insert(header::COOKIE, HeaderValue::from_str(pair).unwrap());
+            let mut prefs = Cookie::<Prefs>::from_jar(CookieJar::new(&headers, keys.clone()));
+            assert_eq!(prefs.get(), Some(&value));
+
+            prefs.remove();
+            let response = (prefs, "ok").into_response();
+            assert!(response.headers()[header::SET_COOKIE].to_str().unwrap().contains("Max-Age=0"));
+
+            // 未签名或内容不是合法 JSON 的 Cookie 被忽略
+            let mut jar = CookieJar::new(&HeaderMap::new(), keys.clone());
+            jar.signed().add(RawCookie::new("prefs", "not json"));
+            let headers = round_trip(jar);
+            assert!(Cookie::<Prefs>::from_jar(CookieJar::new(&headers, keys.clone())).get().is_none());
+            let mut headers = HeaderMap::new();
+            headers.insert(header::COOKIE, HeaderValue::from_static("prefs=%7B%7D"));
+            assert!(Cookie::<Prefs>::from_jar(CookieJar::new(&headers, keys)).get().is_none());
+        }
+
+        #[test]
+        fn test_encrypted_typed_cookie() {
+            let keys = CookieKeys::new([NEW]).unwrap();
+            let mut secret = Cookie::<Secret>::from_jar(CookieJar::new(&HeaderMap::new(), keys.clone()));
+            secret.set(Secret("token-42".to_string())).unwrap();
+            let headers = round_trip(secret);
+            assert!(!get_cookie(&headers, "secret").unwrap().contains("token-42"));
+
+            let secret = Cookie::<Secret>::from_jar(CookieJar::new(&headers, keys));
+            assert_eq!(secret.into_inner().unwrap().0, "token-42");
+        }
+
+        #[test]
+        fn test_typed_cookie_size_limit() {
+            let keys = CookieKeys::new([NEW]).unwrap();
+            let mut prefs = Cookie::<Prefs>::from_jar(CookieJar::new(&HeaderMap::new(), keys));
+            let result = prefs.set(Prefs {
+                theme: "x".repeat(5000),
+                note: String::new(),
+            });
+            assert!(matches!(result, Err(crate::error::Error::Internal(_))));
+            assert!(prefs.get().is_none());
+
+            let response = (prefs, "ok").into_response();
+            assert!(response.headers().get(header::SET_COOKIE).is_none());
+        }
+
+        #[test]
+        fn test_invalid_keys() {
+            assert!(matches!(CookieKeys::new(["short"]), Err(crate::error::Error::Config(_))));
+            assert!(matches!(CookieKeys::new(Vec::<String>::new()), Err(crate::error::Error::Config(_))));
+        }
+    }
+}
@@ -1,11 +1,17 @@
 //! CORS 中间件模块
 
 use crate::{config::CorsConfig, error::{Error, Result}};
-use axum::http::{HeaderValue, Method};
-use tower_http::cors::{Any, CorsLayer};
+use axum::http::{HeaderName, HeaderValue, Method};
+use std::time::Duration;
+use tower_http::cors::{AllowHeaders, Any, CorsLayer};
 
 /// 创建 CORS 中间件层
+///
+/// # Errors
+///
+/// 源、方法或头部无效，或允许凭据时使用了通配的源时返回配置错误
 pub fn create_cors_layer(config: &CorsConfig) -> Result<CorsLayer> {
+    config.validate()?;
     let mut cors = CorsLayer::new();
 
     // 设置允许的源
@@ -36,25 +42,45 @@ pub fn create_cors_layer(config: &CorsConfig) -> Result<CorsLayer> {
         .collect();
     cors = cors.allow_methods(methods?);
 
-    // 设置允许的头部
+    // 设置允许的头部，允许凭据时通配符不生效，改为回显预检请求中的头部
     if config.headers.contains(&"*".to_string()) {
-        cors = cors.allow_headers(Any);
+        cors = if config.allow_credentials {
+            cors.allow_headers(AllowHeaders::mirror_request())
+        } else {
+            cors.allow_headers(Any)
+        };
     } else {
-        let headers: Result<Vec<axum::http::HeaderName>> = config
-            .headers
-            .iter()
-            .map(|header| {
-                header.parse().map_err(|e| {
-                    Error::Config(format!("无效的 HTTP 头部 '{}': {}", header, e))
-                })
-            })
-            .collect();
-        cors = cors.allow_headers(headers?);
+        cors = cors.allow_headers(parse_headers(&config.headers)?);
+    }
+
+    // 设置暴露给浏览器脚本的头部
+    if config.expose_headers.contains(&"*".to_string()) {
+        cors = cors.expose_headers(Any);
+    } else if !config.expose_headers.is_empty() {
+        cors = cors.expose_headers(parse_headers(&config.expose_headers)?);
+    }
+
+    if config.allow_credentials {
+        cors = cors.allow_credentials(true);
+    }
+    if let Some(max_age) = config.max_age {
+        cors = cors.max_age(Duration::from_secs(max_age));
     }
 
     Ok(cors)
 }
 
+fn parse_headers(headers: &[String]) -> Result<Vec<HeaderName>> {
+    headers
+        .iter()
+        .map(|header| {
+            header.parse().map_err(|e| {
+                Error::Config(format!("无效的 HTTP 头部 '{}': {}", header, e))
+            })
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -66,6 +92,7 @@ mod tests {
             origins: vec!["http://localhost:3000".to_string()],
             methods: vec!["GET".to_string(), "POST".to_string()],
             headers: vec!["Content-Type".to_string()],
+            ..Default::default()
         };
 
         let result = create_cors_layer(&config);
@@ -79,9 +106,66 @@ mod tests {
             origins: vec!["*".to_string()],
             methods: vec!["GET".to_string()],
             headers: vec!["*".to_string()],
+            ..Default::default()
         };
 
         let result = create_cors_layer(&config);
         assert!(result.is_ok());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_cors_credentials_require_explicit_origins() {
+        let config = CorsConfig {
+            allow_credentials: true,
+            ..Default::default()
+        };
+        assert!(matches!(create_cors_layer(&config), Err(Error::Config(_))));
+
+        let config = CorsConfig {
+            origins: vec!["https://app.example.com".to_string()],
+            allow_credentials: true,
+            expose_headers: vec!["*".to_string()],
+            ..Default::default()
+        };
+        assert!(matches!(create_cors_layer(&config), Err(Error::Config(_))));
+    }
+
+    #[tokio::test]
+    async fn test_cors_credentials_and_max_age() {
+        use axum::{body::Body, http::Request, routing::get, Router};
+        use tower::ServiceExt;
+
+        let config = CorsConfig {
+            origins: vec!["https://app.example.com".to_string()],
+            allow_credentials: true,
+            max_age: Some(600),
+            expose_headers: vec!["x-request-id".to_string()],
+            ..Default::default()
+        };
+        let app = Router::new()
+            .route("/", get(|| async { "ok" }))
+            .layer(create_cors_layer(&config).unwrap());
+
+        let preflight = Request::builder()
+            .method(Method::OPTIONS)
+            .uri("/")
+            .header("origin", "https://app.example.com")
+            .header("access-control-request-method", "GET")
+            .header("access-control-request-headers", "x-custom")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(preflight).await.unwrap();
+        let headers = response.headers();
+        assert_eq!(headers["access-control-allow-credentials"], "true");
+        assert_eq!(headers["access-control-max-age"], "600");
+        assert_eq!(headers["access-control-allow-headers"], "x-custom");
+
+        let request = Request::builder()
+            .uri("/")
+            .header("origin", "https://app.example.com")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.headers()["access-control-expose-headers"], "x-request-id");
+    }
+}
@@ -0,0 +1,328 @@
+//! CSRF 防护中间件模块
+//!
+//! 使用双重提交 Cookie：首次访问时下发随机令牌 Cookie，`POST`/`PUT`/`PATCH`/`DELETE`
+//! 请求必须通过请求头或表单字段回传相同的令牌。模板中可以使用 `{{ csrf_token() }}` 获取令牌。
+//!
+//! 启用 `cookies` feature 并配置 `[cookies] keys` 后，令牌 Cookie 经过签名，
+//! 子域名等无法获得密钥的一方不能植入自己的令牌。
+
+use super::{constant_time_eq, cookies::get_cookie, matcher::path_matches};
+use crate::{
+    config::{CookiesConfig, CsrfConfig},
+    error::{Error, Result},
+};
+#[cfg(feature = "cookies")]
+use super::cookies::{CookieKeys, RawCookie};
+use axum::{
+    async_trait,
+    body::Body,
+    extract::{FromRequestParts, Request, State},
+    http::{header, request::Parts, HeaderValue, Method},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+
+/// 读取表单令牌时的最大请求体大小，与 axum 默认的请求体限制一致
+const MAX_FORM_BYTES: usize = 2 * 1024 * 1024;
+
+tokio::task_local! {
+    static CURRENT_TOKEN: String;
+}
+
+/// 当前请求的 CSRF 令牌
+///
+/// 只能在 CSRF 中间件处理的请求中调用，否则返回 `None`
+pub fn current_token() -> Option<String> {
+    CURRENT_TOKEN.try_with(Clone::clone).ok()
+}
+
+/// CSRF 令牌提取器
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsrfToken(pub String);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for CsrfToken {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+        parts
+            .extensions
+            .get::<CsrfToken>()
+            .cloned()
+            .ok_or_else(|| Error::Internal("CSRF 中间件未启用".to_string()))
+    }
+}
+
+/// CSRF 防护中间件状态
+#[derive(Debug, Clone)]
+pub struct CsrfProtection {
+    config: CsrfConfig,
+    #[cfg(feature = "cookies")]
+    keys: Option<CookieKeys>,
+}
+
+impl CsrfProtection {
+    /// 根据配置创建，`cookies` 中配置了密钥时签名令牌 Cookie
+    ///
+    /// # Errors
+    ///
+    /// Cookie 密钥无效时返回配置错误
+    pub fn new(config: &CsrfConfig, cookies: &CookiesConfig) -> Result<Self> {
+        #[cfg(not(feature = "cookies"))]
+        let _ = cookies;
+        Ok(Self {
+            config: config.clone(),
+            #[cfg(feature = "cookies")]
+            keys: if cookies.effective_keys().is_empty() {
+                None
+            } else {
+                Some(CookieKeys::from_config(cookies)?)
+            },
+        })
+    }
+
+    /// 校验 Cookie 中的令牌（签名时校验签名）
+    fn is_valid_cookie(&self, value: &str) -> bool {
+        #[cfg(feature = "cookies")]
+        if let Some(keys) = &self.keys {
+            let cookie = RawCookie::new(self.config.cookie_name.clone(), value.to_string());
+            return keys.verify(&cookie).is_some_and(|cookie| is_valid_token(cookie.value()));
+        }
+        is_valid_token(value)
+    }
+
+    /// 生成新的令牌，即写入 Cookie 的值
+    fn generate(&self) -> String {
+        let token = generate_token();
+        #[cfg(feature = "cookies")]
+        if let Some(keys) = &self.keys {
+            let cookie = RawCookie::new(self.config.cookie_name.clone(), token);
+            return keys.sign(cookie).value().to_string();
+        }
+        token
+    }
+}
+
+/// CSRF 防护中间件
+pub async fn csrf_middleware(
+    State(csrf): State<Arc<CsrfProtection>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let config = &csrf.config;
+    let cookie_token = get_cookie(request.headers(), &config.cookie_name).filter(|t| csrf.is_valid_cookie(t));
+
+    let request = if requires_verification(config, request.method(), request.uri().path()) {
+        match verify(config, cookie_token.as_deref(), request).await {
+            Ok(request) => request,
+            Err(e) => {
+                tracing::warn!("CSRF 校验失败: {}", e);
+                return e.into_response();
+            }
+        }
+    } else {
+        request
+    };
+
+    let (token, is_new) = match cookie_token {
+        Some(token) => (token, false),
+        None => (csrf.generate(), true),
+    };
+
+    let mut request = request;
+    request.extensions_mut().insert(CsrfToken(token.clone()));
+
+    let mut response = CURRENT_TOKEN.scope(token.clone(), next.run(request)).await;
+
+    if is_new {
+        // 前端脚本需要读取令牌，因此不设置 HttpOnly
+        let mut cookie = format!("{}={}; Path=/; SameSite=Strict", config.cookie_name, token);
+        if config.secure {
+            cookie.push_str("; Secure");
+        }
+        if let Ok(value) = HeaderValue::from_str(&cookie) {
+            response.headers_mut().append(header::SET_COOKIE, value);
+        }
+    }
+
+    response
+}
+
+/// 判断请求是否需要校验令牌
+fn requires_verification(config: &CsrfConfig, method: &Method, path: &str) -> bool {
+    let unsafe_method = matches!(*method, Method::POST | Method::PUT | Method::PATCH | Method::DELETE);
+    unsafe_method && !config.exempt_paths.iter().any(|pattern| path_matches(pattern, path))
+}
+
+/// 校验请求头或表单字段中的令牌，读取表单时会重建请求体
+async fn verify(config: &CsrfConfig, cookie_token: Option<&str>, request: Request) -> Result<Request> {
+    let cookie_token =
+        cookie_token.ok_or_else(|| Error::Forbidden("缺少 CSRF Cookie".to_string()))?;
+
+    let header_token = request
+        .headers()
+        .get(&config.header_name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    let is_form = request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("application/x-www-form-urlencoded"));
+
+    let (submitted, request) = match header_token {
+        Some(token) => (Some(token), request),
+        None if is_form => {
+            let (parts, body) = request.into_parts();
+            let bytes = axum::body::to_bytes(body, MAX_FORM_BYTES)
+                .await
+                .map_err(|e| Error::BadRequest(format!("无法读取请求体: {}", e)))?;
+            let token = serde_urlencoded::from_bytes::<Vec<(String, String)>>(&bytes)
+                .ok()
+                .and_then(|fields| {
+                    fields
+                        .into_iter()
+                        .find(|(key, _)| *key == config.form_field)
+                        .map(|(_, value)| value)
+                });
+            (token, Request::from_parts(parts, Body::from(bytes)))
+        }
+        None => (None, request),
+    };
+
+    match submitted {
+        Some(token) if constant_time_eq(token.as_bytes(), cookie_token.as_bytes()) => Ok(request),
+        Some(_) => Err(Error::Forbidden("CSRF 令牌不匹配".to_string())),
+        None => Err(Error::Forbidden("缺少 CSRF 令牌".to_string())),
+    }
+}
+
+fn generate_token() -> String {
+    (0..32).map(|_| format!("{:02x}", fastrand::u8(..))).collect()
+}
+
+fn is_valid_token(token: &str) -> bool {
+    token.len() == 64 && token.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, routing::get, Router};
+    use tower::util::ServiceExt;
+
+    fn app() -> Router {
+        app_with_keys(&CookiesConfig::default())
+    }
+
+    fn app_with_keys(cookies: &CookiesConfig) -> Router {
+        let config = CsrfConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let csrf = Arc::new(CsrfProtection::new(&config, cookies).unwrap());
+        Router::new()
+            .route(
+                "/form",
+                get(|| async { current_token().unwrap_or_default() }).post(|| async { "saved" }),
+            )
+            .route("/api/items", axum::routing::post(|| async { "created" }))
+            .layer(axum::middleware::from_fn_with_state(csrf, csrf_middleware))
+    }
+
+    async fn issue_token() -> String {
+        issue_token_from(&app()).await
+    }
+
+    async fn issue_token_from(app: &Router) -> String {
+        let response = app
+            .clone()
+            .oneshot(Request::builder().uri("/form").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let cookie = response.headers()[header::SET_COOKIE].to_str().unwrap().to_string();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let token = String::from_utf8(body.to_vec()).unwrap();
+        assert!(cookie.starts_with(&format!("csrf_token={};", token)));
+        token
+    }
+
+    fn post(uri: &str, cookie: Option<&str>) -> axum::http::request::Builder {
+        let builder = Request::builder().method(Method::POST).uri(uri);
+        match cookie {
+            Some(token) => builder.header(header::COOKIE, format!("csrf_token={}", token)),
+            None => builder,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_csrf_header_and_form_token() {
+        let token = issue_token().await;
+
+        let request = post("/form", Some(&token))
+            .header("x-csrf-token", &token)
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(app().oneshot(request).await.unwrap().status(), StatusCode::OK);
+
+        let request = post("/form", Some(&token))
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::from(format!("name=a&_csrf={}", token)))
+            .unwrap();
+        assert_eq!(app().oneshot(request).await.unwrap().status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_csrf_rejects_missing_or_mismatched_token() {
+        let token = issue_token().await;
+
+        let request = post("/form", Some(&token)).body(Body::empty()).unwrap();
+        assert_eq!(app().oneshot(request).await.unwrap().status(), StatusCode::FORBIDDEN);
+
+        let request = post("/form", Some(&token))
+            .header("x-csrf-token", "0".repeat(64))
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(app().oneshot(request).await.unwrap().status(), StatusCode::FORBIDDEN);
+
+        let request = post("/form", None)
+            .header("x-csrf-token", &token)
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(app().oneshot(request).await.unwrap().status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_csrf_exempt_api_routes() {
+        let request = post("/api/items", None).body(Body::empty()).unwrap();
+        assert_eq!(app().oneshot(request).await.unwrap().status(), StatusCode::OK);
+    }
+
+    #[cfg(feature = "cookies")]
+    #[tokio::test]
+    async fn test_signed_token_cookie() {
+        let cookies = CookiesConfig {
+            keys: vec!["0123456789abcdef0123456789abcdef".to_string()],
+            ..Default::default()
+        };
+        let app = app_with_keys(&cookies);
+        let token = issue_token_from(&app).await;
+        assert!(token.len() > 64, "签名后的令牌包含签名");
+
+        let request = post("/form", Some(&token))
+            .header("x-csrf-token", &token)
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(app.clone().oneshot(request).await.unwrap().status(), StatusCode::OK);
+
+        // 未签名的令牌 Cookie 无效，即使请求头中的令牌相同
+        let forged = "0".repeat(64);
+        let request = post("/form", Some(&forged))
+            .header("x-csrf-token", &forged)
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(app.oneshot(request).await.unwrap().status(), StatusCode::FORBIDDEN);
+    }
+}
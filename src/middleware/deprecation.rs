@@ -0,0 +1,193 @@
+//! 接口弃用模块
+//!
+//! 为已弃用的接口添加 `Deprecation`（RFC 9745）、`Sunset`（RFC 8594）和
+//! `Link: <...>; rel="deprecation"` 响应头，并在响应扩展中标记 [`DeprecatedRoute`]，
+//! 由指标中间件统计 `http_deprecated_requests_total`，便于按调用量推动客户端迁移。
+//!
+//! ```toml
+//! [[middleware.deprecations]]
+//! path = "/api/v1/*"
+//! since = "2025-01-01"
+//! sunset = "2025-12-31"
+//! link = "https://example.com/docs/migrate-v2"
+//! ```
+
+use super::matcher::path_matches;
+use crate::{
+    config::DeprecatedRouteConfig,
+    error::{Error, Result},
+};
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// `Deprecation` 响应头
+pub const DEPRECATION: HeaderName = HeaderName::from_static("deprecation");
+/// `Sunset` 响应头
+pub const SUNSET: HeaderName = HeaderName::from_static("sunset");
+
+/// 响应扩展：该响应来自已弃用的接口
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeprecatedRoute {
+    /// 匹配到的路径模式
+    pub pattern: String,
+}
+
+/// 已弃用接口列表
+#[derive(Debug, Clone, Default)]
+pub struct Deprecations {
+    routes: Vec<(String, HeaderMap)>,
+}
+
+impl Deprecations {
+    /// 根据配置创建，预先生成每个接口的响应头
+    ///
+    /// # Errors
+    ///
+    /// 日期不是 `YYYY-MM-DD` 格式或链接包含非法字符时返回配置错误
+    pub fn new(routes: &[DeprecatedRouteConfig]) -> Result<Self> {
+        let routes = routes
+            .iter()
+            .map(|route| Ok((route.path.clone(), headers(route)?)))
+            .collect::<Result<_>>()?;
+        Ok(Self { routes })
+    }
+
+    /// 是否没有任何已弃用的接口
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    /// 查找匹配请求路径的弃用配置，使用第一个匹配项
+    fn find(&self, path: &str) -> Option<&(String, HeaderMap)> {
+        self.routes
+            .iter()
+            .find(|(pattern, _)| path_matches(pattern, path))
+    }
+}
+
+fn headers(route: &DeprecatedRouteConfig) -> Result<HeaderMap> {
+    let mut headers = HeaderMap::new();
+
+    let deprecation = match &route.since {
+        Some(since) => {
+            let secs = parse_date(since)?.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+            format!("@{}", secs)
+        }
+        None => "true".to_string(),
+    };
+    headers.insert(DEPRECATION, HeaderValue::from_str(&deprecation).expect("数字和 @ 是合法的头部值"));
+
+    if let Some(sunset) = &route.sunset {
+        let value = httpdate::fmt_http_date(parse_date(sunset)?);
+        headers.insert(SUNSET, HeaderValue::from_str(&value).expect("HTTP 日期是合法的头部值"));
+    }
+
+    if let Some(link) = &route.link {
+        let value = HeaderValue::from_str(&format!("<{}>; rel=\"deprecation\"", link))
+            .map_err(|_| Error::Config(format!("无效的弃用文档链接: {}", link)))?;
+        headers.insert(axum::http::header::LINK, value);
+    }
+
+    Ok(headers)
+}
+
+/// 解析 `YYYY-MM-DD` 格式的日期（UTC 零点）
+fn parse_date(date: &str) -> Result<SystemTime> {
+    let invalid = || Error::Config(format!("无效的日期 '{}'，应为 YYYY-MM-DD 格式", date));
+
+    let mut parts = date.splitn(3, '-').map(|part| part.parse::<i64>().map_err(|_| invalid()));
+    let (year, month, day) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(y), Some(m), Some(d)) => (y?, m?, d?),
+        _ => return Err(invalid()),
+    };
+    if !(1970..=9999).contains(&year) || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return Err(invalid());
+    }
+
+    // 公历日期转换为距 1970-01-01 的天数
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+
+    Ok(UNIX_EPOCH + Duration::from_secs(days as u64 * 86400))
+}
+
+/// 接口弃用中间件
+pub async fn deprecation_middleware(
+    State(deprecations): State<Arc<Deprecations>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some((pattern, headers)) = deprecations.find(request.uri().path()) else {
+        return next.run(request).await;
+    };
+
+    let mut response = next.run(request).await;
+    response.headers_mut().extend(headers.clone());
+    response.extensions_mut().insert(DeprecatedRoute {
+        pattern: pattern.clone(),
+    });
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get, Router};
+    use tower::util::ServiceExt;
+
+    #[test]
+    fn test_parse_date() {
+        let secs = |date| parse_date(date).unwrap().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        assert_eq!(secs("1970-01-01"), 0);
+        assert_eq!(secs("2024-02-29"), 1709164800);
+        assert!(parse_date("2024-13-01").is_err());
+        assert!(parse_date("tomorrow").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_deprecation_headers() {
+        let deprecations = Deprecations::new(&[DeprecatedRouteConfig {
+            path: "/api/v1/*".to_string(),
+            since: Some("2024-01-01".to_string()),
+            sunset: Some("2024-12-31".to_string()),
+            link: Some("https://example.com/migrate".to_string()),
+        }])
+        .unwrap();
+        let app = Router::new()
+            .route("/api/v1/users", get(|| async { "v1" }))
+            .route("/api/v2/users", get(|| async { "v2" }))
+            .layer(axum::middleware::from_fn_with_state(
+                Arc::new(deprecations),
+                deprecation_middleware,
+            ));
+
+        let request = Request::builder().uri("/api/v1/users").body(Body::empty()).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let headers = response.headers();
+        assert_eq!(headers[DEPRECATION], "@1704067200");
+        assert_eq!(headers[SUNSET], "Tue, 31 Dec 2024 00:00:00 GMT");
+        assert_eq!(headers["link"], "<https://example.com/migrate>; rel=\"deprecation\"");
+        assert_eq!(
+            response.extensions().get::<DeprecatedRoute>().map(|r| r.pattern.as_str()),
+            Some("/api/v1/*")
+        );
+
+        let request = Request::builder().uri("/api/v2/users").body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert!(response.headers().get(DEPRECATION).is_none());
+        assert!(response.extensions().get::<DeprecatedRoute>().is_none());
+    }
+}
@@ -0,0 +1,254 @@
+//! 自定义错误页面模块
+//!
+//! 按 `[errors.pages]` 把错误状态码映射到模板。请求的 `Accept` 头偏好 HTML（浏览器）时，
+//! 错误响应替换为渲染后的页面；API 客户端仍然收到 JSON 错误。未匹配任何路由的 404、
+//! 认证失败的 401 等中间件产生的错误同样经过这里。
+//!
+//! ```toml
+//! [errors.pages]
+//! 404 = "errors/404.html"
+//! 500 = "errors/500.html"
+//! ```
+//!
+//! 模板上下文包含 `status`、`reason`（如 `Not Found`）、`title`（如 `404 Not Found`）、
+//! `message`（JSON 错误中的 `error`）和 `path`，配置了布局时在布局中渲染。处理函数自己返回的 HTML 响应保持不变。
+
+use crate::{
+    config::ErrorsConfig,
+    error::{Error, Result},
+    templates::TemplateEngine,
+};
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use serde_json::json;
+use std::{collections::HashMap, sync::Arc};
+
+/// 读取错误响应体的上限
+const MAX_ERROR_BODY: usize = 64 * 1024;
+
+/// 状态码到错误页面模板的映射
+#[derive(Debug)]
+pub struct ErrorPages {
+    engine: Arc<TemplateEngine>,
+    pages: HashMap<StatusCode, String>,
+}
+
+impl ErrorPages {
+    /// 根据配置创建
+    ///
+    /// # Errors
+    ///
+    /// 状态码无效或模板不存在时返回配置错误
+    pub fn new(config: &ErrorsConfig, engine: Arc<TemplateEngine>) -> Result<Self> {
+        let names = engine.get_template_names();
+        let mut pages = HashMap::new();
+        for (status, template) in &config.pages {
+            let code = status
+                .parse::<u16>()
+                .ok()
+                .filter(|code| (400..=599).contains(code))
+                .and_then(|code| StatusCode::from_u16(code).ok())
+                .ok_or_else(|| Error::Config(format!("无效的错误页面状态码 '{}': 需要 400~599", status)))?;
+            if !names.contains(template) {
+                return Err(Error::Config(format!("错误页面模板不存在: {}", template)));
+            }
+            pages.insert(code, template.clone());
+        }
+        Ok(Self { engine, pages })
+    }
+}
+
+/// 客户端是否偏好 HTML：`Accept` 中 `text/html` 的 q 值不低于 `application/json`
+///
+/// `*/*` 或没有 `Accept` 头的请求视为 API 调用
+fn accepts_html(headers: &HeaderMap) -> bool {
+    let accept = headers
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    let (mut html, mut json) = (0.0f32, 0.0f32);
+    for item in accept.split(',') {
+        let mut params = item.split(';');
+        let media_type = params.next().unwrap_or_default().trim().to_ascii_lowercase();
+        let quality = params
+            .find_map(|param| param.trim().strip_prefix("q="))
+            .and_then(|q| q.parse::<f32>().ok())
+            .unwrap_or(1.0);
+        match media_type.as_str() {
+            "text/html" | "application/xhtml+xml" => html = html.max(quality),
+            "application/json" => json = json.max(quality),
+            _ => {}
+        }
+    }
+    html > 0.0 && html >= json
+}
+
+fn is_html(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/html"))
+}
+
+/// 自定义错误页面中间件
+pub async fn error_pages_middleware(
+    State(pages): State<Arc<ErrorPages>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let html = accepts_html(request.headers());
+    let path = request.uri().path().to_string();
+    let mut response = next.run(request).await;
+
+    let status = response.status();
+    let Some(template) = pages.pages.get(&status) else {
+        return response;
+    };
+    response
+        .headers_mut()
+        .append(header::VARY, HeaderValue::from_static("accept"));
+    if !html || is_html(response.headers()) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let body = axum::body::to_bytes(body, MAX_ERROR_BODY).await.unwrap_or_default();
+    let message = serde_json::from_slice::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|value| value.get("error").and_then(|e| e.as_str()).map(str::to_string));
+    let context = json!({
+        "status": status.as_u16(),
+        "reason": status.canonical_reason(),
+        "title": status.to_string(),
+        "message": message,
+        "path": path,
+    });
+
+    match pages.engine.render_page(template, &context) {
+        Ok(page) => {
+            parts.headers.remove(header::CONTENT_LENGTH);
+            parts
+                .headers
+                .insert(header::CONTENT_TYPE, HeaderValue::from_static("text/html; charset=utf-8"));
+            Response::from_parts(parts, Body::from(page))
+        }
+        Err(e) => {
+            tracing::error!("错误页面 {} 渲染失败: {}", template, e);
+            Response::from_parts(parts, Body::from(body))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TemplateConfig;
+    use axum::{response::IntoResponse, routing::get, Router};
+    use tower::util::ServiceExt;
+
+    fn app(dir: &std::path::Path) -> Router {
+        std::fs::create_dir_all(dir.join("errors")).unwrap();
+        std::fs::write(
+            dir.join("errors/404.html"),
+            "<h1>{{ title }}</h1><p>{{ path }}|{{ message }}</p>",
+        )
+        .unwrap();
+        let engine = TemplateEngine::new(&TemplateConfig {
+            enabled: true,
+            dir: dir.to_string_lossy().to_string(),
+            ..TemplateConfig::default()
+        })
+        .unwrap();
+        let config = ErrorsConfig {
+            pages: HashMap::from([("404".to_string(), "errors/404.html".to_string())]),
+            ..ErrorsConfig::default()
+        };
+        let pages = ErrorPages::new(&config, Arc::new(engine)).unwrap();
+
+        Router::new()
+            .route("/users/:id", get(|| async { Error::NotFound("用户不存在".to_string()).into_response() }))
+            .route("/page", get(|| async { (StatusCode::NOT_FOUND, axum::response::Html("<p>自定义</p>")) }))
+            .layer(axum::middleware::from_fn_with_state(Arc::new(pages), error_pages_middleware))
+    }
+
+    async fn get_page(app: &Router, uri: &str, accept: &str) -> (StatusCode, HeaderMap, String) {
+        let request = Request::builder()
+            .uri(uri)
+            .header(header::ACCEPT, accept)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let (parts, body) = response.into_parts();
+        let body = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+        (parts.status, parts.headers, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[test]
+    fn test_accepts_html() {
+        let accepts = |accept: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::ACCEPT, HeaderValue::from_str(accept).unwrap());
+            accepts_html(&headers)
+        };
+        assert!(accepts("text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8"));
+        assert!(!accepts("application/json"));
+        assert!(!accepts("*/*"));
+        assert!(!accepts("application/json, text/html;q=0.5"));
+        assert!(!accepts("text/html;q=0"));
+        assert!(!accepts_html(&HeaderMap::new()));
+    }
+
+    #[tokio::test]
+    async fn test_error_pages() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = app(dir.path());
+        let browser = "text/html,application/xhtml+xml,*/*;q=0.8";
+
+        let (status, headers, body) = get_page(&app, "/users/1", browser).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(headers[header::CONTENT_TYPE], "text/html; charset=utf-8");
+        assert_eq!(body, "<h1>404 Not Found</h1><p>&#x2F;users&#x2F;1|资源不存在: 用户不存在</p>");
+
+        // 未匹配任何路由
+        let (status, _, body) = get_page(&app, "/missing", browser).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body, "<h1>404 Not Found</h1><p>&#x2F;missing|</p>");
+
+        // API 客户端仍然收到 JSON
+        let (status, headers, body) = get_page(&app, "/users/1", "application/json").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(headers[header::CONTENT_TYPE], "application/json");
+        assert!(body.contains("用户不存在"));
+
+        // 处理函数自己返回的 HTML 保持不变
+        let (_, _, body) = get_page(&app, "/page", browser).await;
+        assert_eq!(body, "<p>自定义</p>");
+    }
+
+    #[test]
+    fn test_invalid_pages() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("404.html"), "404").unwrap();
+        let engine = Arc::new(
+            TemplateEngine::new(&TemplateConfig {
+                enabled: true,
+                dir: dir.path().to_string_lossy().to_string(),
+                ..TemplateConfig::default()
+            })
+            .unwrap(),
+        );
+        let pages = |status: &str, template: &str| ErrorsConfig {
+            pages: HashMap::from([(status.to_string(), template.to_string())]),
+            ..ErrorsConfig::default()
+        };
+
+        assert!(ErrorPages::new(&pages("404", "404.html"), engine.clone()).is_ok());
+        assert!(matches!(ErrorPages::new(&pages("200", "404.html"), engine.clone()), Err(Error::Config(_))));
+        assert!(matches!(ErrorPages::new(&pages("404", "missing.html"), engine), Err(Error::Config(_))));
+    }
+}
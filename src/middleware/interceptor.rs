@@ -0,0 +1,242 @@
+//! 请求/响应拦截器模块
+//!
+//! 拦截器是编写 tower 层之外的简化选择：只需要实现一个异步方法即可检查或修改
+//! 请求头、响应头等。执行顺序和启用状态由 `[middleware.interceptors]` 配置。
+//!
+//! ```rust
+//! use hwhkit::{
+//!     async_trait,
+//!     middleware::interceptor::ResponseInterceptor,
+//!     Result, WebServerBuilder,
+//! };
+//! use axum::http::{response::Parts, HeaderValue};
+//!
+//! struct PoweredBy;
+//!
+//! #[async_trait]
+//! impl ResponseInterceptor for PoweredBy {
+//!     fn name(&self) -> &str {
+//!         "powered-by"
+//!     }
+//!
+//!     async fn intercept_response(&self, parts: &mut Parts) -> Result<()> {
+//!         parts.headers.insert("x-powered-by", HeaderValue::from_static("hwhkit"));
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let builder = WebServerBuilder::new().response_interceptor(PoweredBy);
+//! ```
+
+use crate::{config::InterceptorConfig, error::Result};
+use axum::{
+    async_trait,
+    extract::{Request, State},
+    http::{request, response},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::{fmt, sync::Arc};
+
+/// 请求拦截器
+#[async_trait]
+pub trait RequestInterceptor: Send + Sync + 'static {
+    /// 拦截器名称，用于在配置中排序或禁用
+    fn name(&self) -> &str;
+
+    /// 处理请求，返回错误时直接以该错误响应，不再调用后续拦截器和处理器
+    async fn intercept_request(&self, parts: &mut request::Parts) -> Result<()>;
+}
+
+/// 响应拦截器
+#[async_trait]
+pub trait ResponseInterceptor: Send + Sync + 'static {
+    /// 拦截器名称，用于在配置中排序或禁用
+    fn name(&self) -> &str;
+
+    /// 处理响应，返回错误时以该错误替换原响应
+    async fn intercept_response(&self, parts: &mut response::Parts) -> Result<()>;
+}
+
+/// 已注册的拦截器
+#[derive(Clone, Default)]
+pub struct Interceptors {
+    request: Vec<Arc<dyn RequestInterceptor>>,
+    response: Vec<Arc<dyn ResponseInterceptor>>,
+}
+
+impl Interceptors {
+    /// 注册请求拦截器
+    pub fn add_request<I: RequestInterceptor>(&mut self, interceptor: I) {
+        self.request.push(Arc::new(interceptor));
+    }
+
+    /// 注册响应拦截器
+    pub fn add_response<I: ResponseInterceptor>(&mut self, interceptor: I) {
+        self.response.push(Arc::new(interceptor));
+    }
+
+    /// 是否没有注册任何拦截器
+    pub fn is_empty(&self) -> bool {
+        self.request.is_empty() && self.response.is_empty()
+    }
+
+    /// 按配置排序并移除被禁用的拦截器
+    ///
+    /// `order` 中列出的拦截器按列出顺序最先执行，其余按注册顺序执行
+    pub fn configured(&self, config: &InterceptorConfig) -> Self {
+        Self {
+            request: arrange(&self.request, config, |i| i.name()),
+            response: arrange(&self.response, config, |i| i.name()),
+        }
+    }
+}
+
+fn arrange<T: ?Sized>(
+    items: &[Arc<T>],
+    config: &InterceptorConfig,
+    name: impl Fn(&T) -> &str,
+) -> Vec<Arc<T>> {
+    let rank = |item: &Arc<T>| {
+        config
+            .order
+            .iter()
+            .position(|n| n == name(item))
+            .unwrap_or(config.order.len())
+    };
+
+    let mut arranged: Vec<Arc<T>> = items
+        .iter()
+        .filter(|item| !config.disabled.iter().any(|n| n == name(item)))
+        .cloned()
+        .collect();
+    // 稳定排序，未列出的拦截器保持注册顺序
+    arranged.sort_by_key(rank);
+    arranged
+}
+
+impl fmt::Debug for Interceptors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Interceptors")
+            .field("request", &self.request.iter().map(|i| i.name()).collect::<Vec<_>>())
+            .field("response", &self.response.iter().map(|i| i.name()).collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// 拦截器中间件
+pub async fn interceptor_middleware(
+    State(interceptors): State<Arc<Interceptors>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let (mut parts, body) = request.into_parts();
+    for interceptor in &interceptors.request {
+        if let Err(e) = interceptor.intercept_request(&mut parts).await {
+            tracing::debug!("请求被拦截器 {} 拒绝: {}", interceptor.name(), e);
+            return e.into_response();
+        }
+    }
+
+    let response = next.run(Request::from_parts(parts, body)).await;
+
+    let (mut parts, body) = response.into_parts();
+    for interceptor in &interceptors.response {
+        if let Err(e) = interceptor.intercept_response(&mut parts).await {
+            tracing::debug!("响应被拦截器 {} 替换: {}", interceptor.name(), e);
+            return e.into_response();
+        }
+    }
+    Response::from_parts(parts, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use axum::{
+        body::Body,
+        http::{HeaderValue, StatusCode},
+        routing::get,
+        Router,
+    };
+    use tower::util::ServiceExt;
+
+    struct AppendHeader(&'static str);
+
+    #[async_trait]
+    impl ResponseInterceptor for AppendHeader {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        async fn intercept_response(&self, parts: &mut response::Parts) -> Result<()> {
+            parts
+                .headers
+                .append("x-trail", HeaderValue::from_static(self.0));
+            Ok(())
+        }
+    }
+
+    struct RequireApiKey;
+
+    #[async_trait]
+    impl RequestInterceptor for RequireApiKey {
+        fn name(&self) -> &str {
+            "api-key"
+        }
+
+        async fn intercept_request(&self, parts: &mut request::Parts) -> Result<()> {
+            if parts.headers.contains_key("x-api-key") {
+                Ok(())
+            } else {
+                Err(Error::Unauthorized("缺少 API Key".to_string()))
+            }
+        }
+    }
+
+    fn app(config: &InterceptorConfig) -> Router {
+        let mut interceptors = Interceptors::default();
+        interceptors.add_request(RequireApiKey);
+        interceptors.add_response(AppendHeader("first"));
+        interceptors.add_response(AppendHeader("second"));
+        interceptors.add_response(AppendHeader("third"));
+
+        Router::new()
+            .route("/", get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(
+                Arc::new(interceptors.configured(config)),
+                interceptor_middleware,
+            ))
+    }
+
+    fn request(api_key: bool) -> Request {
+        let builder = Request::builder().uri("/");
+        let builder = if api_key { builder.header("x-api-key", "k") } else { builder };
+        builder.body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_request_interceptor_short_circuits() {
+        let response = app(&InterceptorConfig::default()).oneshot(request(false)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_interceptor_order_and_disable() {
+        let config = InterceptorConfig {
+            order: vec!["third".to_string()],
+            disabled: vec!["second".to_string(), "api-key".to_string()],
+        };
+        let response = app(&config).oneshot(request(false)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let trail: Vec<_> = response
+            .headers()
+            .get_all("x-trail")
+            .iter()
+            .map(|v| v.to_str().unwrap())
+            .collect();
+        assert_eq!(trail, ["third", "first"]);
+    }
+}
@@ -0,0 +1,283 @@
+//! 请求隔离模块
+//!
+//! 在受监控的作用域中执行每个请求，将单个请求的故障限制在该请求内部：
+//!
+//! - 处理函数 panic 时返回 500，并记录 panic 信息，连接和其他请求不受影响
+//! - 超过 `deadline_ms` 仍未完成的请求被中止并返回 503
+//! - 单个请求分配的内存超过 `max_allocation_bytes` 时被中止并返回 503
+//!
+//! 默认启用的 [`catch_panic`](super::catch_panic) 中间件位于内层，会先处理 panic 并调用 panic 回调。
+//!
+//! 截止时间在处理函数的 `.await` 点生效，不包含 `.await` 的 CPU 密集循环无法被中断，
+//! 这类计算应放入 `tokio::task::spawn_blocking`。
+//!
+//! 内存限制需要将 [`GuardedAllocator`] 注册为全局分配器：
+//!
+//! ```rust
+//! use hwhkit::middleware::isolation::GuardedAllocator;
+//!
+//! #[global_allocator]
+//! static ALLOCATOR: GuardedAllocator = GuardedAllocator::new();
+//! ```
+
+use crate::{config::IsolationConfig, error::Error};
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use futures_util::FutureExt;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    any::Any,
+    cell::Cell,
+    future::Future,
+    panic::AssertUnwindSafe,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+
+static ALLOCATOR_INSTALLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// 当前线程正在轮询的请求的内存计数
+    static CURRENT_BUDGET: Cell<Option<*const AllocationBudget>> = const { Cell::new(None) };
+}
+
+/// 单个请求的内存计数
+#[derive(Debug, Default)]
+struct AllocationBudget {
+    allocated: AtomicUsize,
+}
+
+/// 按请求统计内存分配的全局分配器
+///
+/// 只在请求被轮询期间记账，分配本身委托给系统分配器
+#[derive(Debug, Default)]
+pub struct GuardedAllocator;
+
+impl GuardedAllocator {
+    /// 创建分配器
+    pub const fn new() -> Self {
+        Self
+    }
+
+    /// 是否已注册为全局分配器
+    pub fn is_installed() -> bool {
+        ALLOCATOR_INSTALLED.load(Ordering::Relaxed)
+    }
+
+    fn record(delta: isize) {
+        // 线程销毁期间 thread local 可能已不可用，此时忽略记账
+        let _ = CURRENT_BUDGET.try_with(|current| {
+            if let Some(budget) = current.get() {
+                // SAFETY: 指针只在 `Isolated::poll` 期间设置，对应的计数由该 future 持有
+                let budget = unsafe { &*budget };
+                if delta >= 0 {
+                    budget.allocated.fetch_add(delta as usize, Ordering::Relaxed);
+                } else {
+                    let _ = budget.allocated.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+                        Some(n.saturating_sub(delta.unsigned_abs()))
+                    });
+                }
+            }
+        });
+    }
+}
+
+// SAFETY: 分配和释放全部委托给 `System`，记账不会分配内存也不会 panic
+unsafe impl GlobalAlloc for GuardedAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATOR_INSTALLED.store(true, Ordering::Relaxed);
+        Self::record(layout.size() as isize);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        Self::record(-(layout.size() as isize));
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        ALLOCATOR_INSTALLED.store(true, Ordering::Relaxed);
+        Self::record(layout.size() as isize);
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        Self::record(new_size as isize - layout.size() as isize);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+/// 中止请求的原因
+#[derive(Debug, PartialEq, Eq)]
+enum Aborted {
+    Allocation(usize),
+}
+
+/// 轮询期间为分配器设置当前请求的计数，超出限制时中止
+struct Isolated<F> {
+    inner: Pin<Box<F>>,
+    budget: Arc<AllocationBudget>,
+    limit: Option<usize>,
+}
+
+impl<F: Future> Future for Isolated<F> {
+    type Output = Result<F::Output, Aborted>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let budget = Arc::as_ptr(&self.budget);
+        let previous = CURRENT_BUDGET.with(|current| current.replace(Some(budget)));
+        let result = self.inner.as_mut().poll(cx);
+        CURRENT_BUDGET.with(|current| current.set(previous));
+
+        let allocated = self.budget.allocated.load(Ordering::Relaxed);
+        match (result, self.limit) {
+            (Poll::Ready(output), _) => Poll::Ready(Ok(output)),
+            (Poll::Pending, Some(limit)) if allocated > limit => {
+                Poll::Ready(Err(Aborted::Allocation(allocated)))
+            }
+            (Poll::Pending, _) => Poll::Pending,
+        }
+    }
+}
+
+/// 请求隔离中间件
+pub async fn isolation_middleware(
+    State(config): State<Arc<IsolationConfig>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+
+    let isolated = Isolated {
+        inner: Box::pin(AssertUnwindSafe(next.run(request)).catch_unwind()),
+        budget: Arc::default(),
+        limit: (config.max_allocation_bytes > 0).then_some(config.max_allocation_bytes),
+    };
+
+    let result = if config.deadline_ms > 0 {
+        match tokio::time::timeout(Duration::from_millis(config.deadline_ms), isolated).await {
+            Ok(result) => result,
+            Err(_) => {
+                tracing::error!("请求超过截止时间 {}ms 被中止: {} {}", config.deadline_ms, method, path);
+                return Error::ServiceUnavailable("请求处理超时".to_string()).into_response();
+            }
+        }
+    } else {
+        isolated.await
+    };
+
+    match result {
+        Ok(Ok(response)) => response,
+        Ok(Err(panic)) => {
+            tracing::error!("请求处理发生 panic: {} {}: {}", method, path, panic_message(&*panic));
+            Error::Internal("请求处理失败".to_string()).into_response()
+        }
+        Err(Aborted::Allocation(bytes)) => {
+            tracing::error!(
+                "请求内存分配 {} 字节超过限制 {} 字节被中止: {} {}",
+                bytes,
+                config.max_allocation_bytes,
+                method,
+                path
+            );
+            Error::ServiceUnavailable("请求使用的内存超出限制".to_string()).into_response()
+        }
+    }
+}
+
+/// 提取 panic 信息
+pub fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("未知 panic")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::StatusCode, routing::get, Router};
+    use tower::util::ServiceExt;
+
+    fn app(config: IsolationConfig) -> Router {
+        Router::new()
+            .route("/ok", get(|| async { "ok" }))
+            .route(
+                "/panic",
+                get(|| async {
+                    if true {
+                        panic!("boom");
+                    }
+                    "unreachable"
+                }),
+            )
+            .route(
+                "/slow",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    "slow"
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                Arc::new(config),
+                isolation_middleware,
+            ))
+    }
+
+    async fn status(app: &Router, uri: &str) -> StatusCode {
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        app.clone().oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_panic_and_deadline_are_contained() {
+        let app = app(IsolationConfig {
+            enabled: true,
+            deadline_ms: 50,
+            ..Default::default()
+        });
+
+        assert_eq!(status(&app, "/panic").await, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(status(&app, "/slow").await, StatusCode::SERVICE_UNAVAILABLE);
+        // 同一个服务继续处理其他请求
+        assert_eq!(status(&app, "/ok").await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_allocation_limit() {
+        let budget = Arc::new(AllocationBudget::default());
+        let isolated = Isolated {
+            inner: Box::pin(async {
+                // 模拟分配器在轮询期间记账
+                GuardedAllocator::record(4096);
+                tokio::task::yield_now().await;
+                "done"
+            }),
+            budget: budget.clone(),
+            limit: Some(1024),
+        };
+
+        assert_eq!(isolated.await, Err(Aborted::Allocation(4096)));
+        assert_eq!(
+            CURRENT_BUDGET.with(|current| current.get()),
+            None,
+            "轮询结束后应清除当前请求的计数"
+        );
+    }
+
+    #[test]
+    fn test_panic_message() {
+        let panic = std::panic::catch_unwind(|| panic!("boom {}", 1)).unwrap_err();
+        assert_eq!(panic_message(&*panic), "boom 1");
+    }
+}
@@ -0,0 +1,309 @@
+//! JWKS 模块
+//!
+//! 从 Auth0、Keycloak、Cognito 等身份服务的 JWKS 地址获取公钥，按 token 头部的
+//! `kid` 选择密钥验证签名。密钥在本地缓存，超过 `refresh_secs` 或遇到未知的 `kid`
+//! 时重新获取（两次获取至少间隔 [`MIN_REFRESH_INTERVAL`]，避免伪造的 `kid` 放大请求）。
+//!
+//! ```toml
+//! [middleware.jwt.jwks]
+//! url = "https://example.auth0.com/.well-known/jwks.json"
+//! audience = "https://api.example.com"
+//! issuer = "https://example.auth0.com/"
+//!
+//! # 身份服务不可用时停止获取，已缓存的密钥仍可使用
+//! [middleware.jwt.jwks.circuit_breaker]
+//! enabled = true
+//! ```
+
+use super::{jwt::Claims, trace_context::TraceContext};
+use crate::{
+    config::JwksConfig,
+    error::{Error, Result},
+    resilience::CircuitBreaker,
+};
+use jsonwebtoken::{
+    decode, decode_header,
+    jwk::JwkSet,
+    Algorithm, DecodingKey, Validation,
+};
+use serde_json::{Map, Value};
+use std::{
+    collections::HashMap,
+    fmt,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::RwLock;
+
+/// 两次获取 JWKS 的最小间隔
+pub const MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// JWKS 中的一个密钥
+#[derive(Clone)]
+struct JwksKey {
+    key: DecodingKey,
+    /// JWK 声明的算法，未声明时使用 token 头部的算法
+    algorithm: Option<Algorithm>,
+}
+
+#[derive(Default)]
+struct KeyCache {
+    keys: HashMap<String, JwksKey>,
+    fetched_at: Option<Instant>,
+}
+
+/// 基于 JWKS 的 token 验证器
+#[derive(Clone)]
+pub struct JwksVerifier {
+    config: JwksConfig,
+    url: String,
+    http: reqwest::Client,
+    cache: Arc<RwLock<KeyCache>>,
+    breaker: Option<CircuitBreaker>,
+}
+
+impl fmt::Debug for JwksVerifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JwksVerifier").field("url", &self.url).finish()
+    }
+}
+
+impl JwksVerifier {
+    /// 根据配置创建验证器
+    ///
+    /// # Errors
+    ///
+    /// 未配置 `url` 时返回配置错误
+    pub fn new(config: &JwksConfig) -> Result<Self> {
+        let url = config
+            .url
+            .clone()
+            .ok_or_else(|| Error::Config("JWKS 需要配置 url".to_string()))?;
+        Ok(Self {
+            config: config.clone(),
+            url,
+            http: reqwest::Client::new(),
+            cache: Arc::default(),
+            breaker: config
+                .circuit_breaker
+                .enabled
+                .then(|| CircuitBreaker::new("jwks", &config.circuit_breaker)),
+        })
+    }
+
+    /// 验证 token，返回声明
+    pub async fn verify(&self, token: &str) -> Result<Claims> {
+        let header = decode_header(token).map_err(Error::Jwt)?;
+        let kid = header
+            .kid
+            .ok_or_else(|| Error::Unauthorized("token 缺少 kid".to_string()))?;
+        let key = self.key(&kid).await?;
+
+        // JWKS 只包含公钥，拒绝 HMAC 算法以防算法混淆
+        let algorithm = key.algorithm.unwrap_or(header.alg);
+        let hmac = matches!(algorithm, Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512);
+        if header.alg != algorithm || hmac {
+            return Err(Error::Unauthorized(format!("不支持的 token 算法: {:?}", header.alg)));
+        }
+
+        let mut validation = Validation::new(algorithm);
+        match &self.config.audience {
+            Some(audience) => validation.set_audience(&[audience]),
+            None => validation.validate_aud = false,
+        }
+        if let Some(issuer) = &self.config.issuer {
+            validation.set_issuer(&[issuer]);
+        }
+
+        let claims = decode::<Map<String, Value>>(token, &key.key, &validation)
+            .map_err(Error::Jwt)?
+            .claims;
+        Ok(claims_from_map(&claims))
+    }
+
+    /// 按 `kid` 查找密钥，缓存过期或未找到时重新获取
+    async fn key(&self, kid: &str) -> Result<JwksKey> {
+        let refresh_after = Duration::from_secs(self.config.refresh_secs);
+        {
+            let cache = self.cache.read().await;
+            let fresh = cache.fetched_at.is_some_and(|at| at.elapsed() < refresh_after);
+            let recent = cache.fetched_at.is_some_and(|at| at.elapsed() < MIN_REFRESH_INTERVAL);
+            match cache.keys.get(kid) {
+                Some(key) if fresh => return Ok(key.clone()),
+                None if recent => return Err(Error::Unauthorized(format!("未知的 kid: {}", kid))),
+                _ => {}
+            }
+        }
+
+        let mut cache = self.cache.write().await;
+        // 等待写锁期间其他请求可能已经完成获取
+        if cache.fetched_at.is_none_or(|at| at.elapsed() >= MIN_REFRESH_INTERVAL) {
+            match self.fetch().await {
+                Ok(keys) => {
+                    cache.keys = keys;
+                    cache.fetched_at = Some(Instant::now());
+                }
+                // 获取失败时继续使用已缓存的密钥
+                Err(e) if !cache.keys.is_empty() => tracing::warn!("刷新 JWKS 失败: {}", e),
+                Err(e) => return Err(e),
+            }
+        }
+
+        cache
+            .keys
+            .get(kid)
+            .cloned()
+            .ok_or_else(|| Error::Unauthorized(format!("未知的 kid: {}", kid)))
+    }
+
+    async fn fetch(&self) -> Result<HashMap<String, JwksKey>> {
+        let set = match &self.breaker {
+            Some(breaker) => breaker.call(self.fetch_set()).await?,
+            None => self.fetch_set().await?,
+        };
+
+        let keys: HashMap<_, _> = set
+            .keys
+            .iter()
+            .filter_map(|jwk| {
+                let kid = jwk.common.key_id.clone()?;
+                let key = DecodingKey::from_jwk(jwk).ok()?;
+                let algorithm = jwk
+                    .common
+                    .key_algorithm
+                    .and_then(|alg| Algorithm::from_str(&alg.to_string()).ok());
+                Some((kid, JwksKey { key, algorithm }))
+            })
+            .collect();
+
+        tracing::debug!("已获取 JWKS: {} 个密钥", keys.len());
+        Ok(keys)
+    }
+
+    async fn fetch_set(&self) -> Result<JwkSet> {
+        self.http
+            .get(&self.url)
+            .headers(TraceContext::outgoing_headers())
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| Error::ServiceUnavailable(format!("无法获取 JWKS: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| Error::ServiceUnavailable(format!("无法解析 JWKS: {}", e)))
+    }
+}
+
+/// 将身份服务签发的声明转换为 [`Claims`]
+///
+/// `aud` 为数组时取第一个值，缺少的字段使用默认值，其他声明保存在 `extra` 中
+fn claims_from_map(claims: &Map<String, Value>) -> Claims {
+    let string = |name: &str| match claims.get(name) {
+        Some(Value::String(value)) => value.clone(),
+        Some(Value::Array(values)) => values
+            .first()
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+        _ => String::new(),
+    };
+    let number = |name: &str| claims.get(name).and_then(Value::as_u64).unwrap_or_default() as usize;
+
+    Claims {
+        sub: string("sub"),
+        exp: number("exp"),
+        iat: number("iat"),
+        aud: string("aud"),
+        iss: string("iss"),
+        extra: claims
+            .iter()
+            .filter(|(name, _)| !matches!(name.as_str(), "sub" | "exp" | "iat" | "aud" | "iss"))
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::get, Json, Router};
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/jwt");
+
+    async fn mock_jwks(fetches: Arc<AtomicUsize>) -> String {
+        let jwks: Value =
+            serde_json::from_str(&std::fs::read_to_string(format!("{}/rsa_jwks.json", FIXTURES)).unwrap())
+                .unwrap();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/jwks.json", listener.local_addr().unwrap());
+        let app = Router::new().route(
+            "/jwks.json",
+            get(move || {
+                fetches.fetch_add(1, Ordering::SeqCst);
+                async move { Json(jwks) }
+            }),
+        );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        url
+    }
+
+    fn token(kid: &str, audience: &str) -> String {
+        let key = std::fs::read(format!("{}/rsa_private.pem", FIXTURES)).unwrap();
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some(kid.to_string());
+        let exp = chrono::Utc::now().timestamp() + 3600;
+        let claims = serde_json::json!({
+            "sub": "auth0|123",
+            "aud": [audience, "https://example.auth0.com/userinfo"],
+            "iss": "https://example.auth0.com/",
+            "exp": exp,
+            "iat": exp - 3600,
+            "scope": "read:users",
+        });
+        encode(&header, &claims, &EncodingKey::from_rsa_pem(&key).unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_jwks_verification() {
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let verifier = JwksVerifier::new(&JwksConfig {
+            url: Some(mock_jwks(fetches.clone()).await),
+            audience: Some("https://api.example.com".to_string()),
+            issuer: Some("https://example.auth0.com/".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let claims = verifier.verify(&token("test-key", "https://api.example.com")).await.unwrap();
+        assert_eq!(claims.sub, "auth0|123");
+        assert_eq!(claims.aud, "https://api.example.com");
+        assert_eq!(claims.claim::<String>("scope").as_deref(), Some("read:users"));
+
+        // 密钥已缓存
+        verifier.verify(&token("test-key", "https://api.example.com")).await.unwrap();
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        assert!(verifier.verify(&token("test-key", "https://other.example.com")).await.is_err());
+        // 未知的 kid 在最小间隔内不会触发重新获取
+        assert!(verifier.verify(&token("unknown", "https://api.example.com")).await.is_err());
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_jwks_rejects_hmac_tokens() {
+        let verifier = JwksVerifier::new(&JwksConfig {
+            url: Some(mock_jwks(Arc::default()).await),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let mut header = Header::new(Algorithm::HS256);
+        header.kid = Some("test-key".to_string());
+        let claims = serde_json::json!({ "sub": "x", "exp": chrono::Utc::now().timestamp() + 60 });
+        let forged = encode(&header, &claims, &EncodingKey::from_secret(b"secret")).unwrap();
+        assert!(verifier.verify(&forged).await.is_err());
+    }
+}
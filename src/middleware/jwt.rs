@@ -1,25 +1,81 @@
 //! JWT 认证中间件模块
 
-use crate::{config::JwtConfig, error::{Error, Result}};
+use crate::{
+    config::{JwtAlgorithm, JwtConfig},
+    error::{Error, Result},
+};
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{request::Parts, HeaderMap},
+    Router,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[cfg(feature = "jwt")]
 use axum::{
-    extract::Request,
-    http::{HeaderMap, StatusCode},
+    extract::{Request, State},
+    http::Method,
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
 };
-use serde::{Deserialize, Serialize};
+#[cfg(feature = "jwt")]
+use crate::middleware::matcher::path_matches;
+#[cfg(feature = "jwt")]
+use std::sync::Arc;
 
 #[cfg(feature = "jwt")]
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
 
 /// JWT 声明
-#[derive(Debug, Serialize, Deserialize)]
+///
+/// 受保护路径上的处理函数可以直接提取 `Claims` 获取当前用户，
+/// 签发时附加的自定义声明（角色、租户等）保存在 `extra` 中
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String,  // Subject (用户 ID)
     pub exp: usize,   // Expiration time
     pub iat: usize,   // Issued at
     pub aud: String,  // Audience
     pub iss: String,  // Issuer
+    /// 自定义声明
+    #[serde(flatten, default)]
+    pub extra: Map<String, Value>,
+}
+
+impl Claims {
+    /// 读取单个自定义声明，不存在或类型不匹配时返回 `None`
+    pub fn claim<T: DeserializeOwned>(&self, name: &str) -> Option<T> {
+        self.extra
+            .get(name)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+    }
+
+    /// 将全部自定义声明反序列化为应用定义的类型
+    pub fn extra_as<T: DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_value(Value::Object(self.extra.clone())).map_err(Error::from)
+    }
+}
+
+/// 访问令牌的 audience
+#[cfg(feature = "jwt")]
+const ACCESS_AUDIENCE: &str = "hwhkit";
+/// 刷新令牌的 audience，刷新令牌不能作为访问令牌使用
+#[cfg(feature = "jwt")]
+const REFRESH_AUDIENCE: &str = "hwhkit-refresh";
+
+/// 访问令牌和刷新令牌
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenPair {
+    /// 短期有效的访问令牌
+    pub access_token: String,
+    /// 长期有效的刷新令牌
+    pub refresh_token: String,
+    /// 令牌类型，固定为 `Bearer`
+    pub token_type: String,
+    /// 访问令牌过期时间（秒）
+    pub expires_in: u64,
 }
 
 /// JWT 认证状态
@@ -27,54 +83,162 @@ pub struct Claims {
 pub struct JwtAuth {
     pub secret: String,
     pub expires_in: u64,
+    pub refresh_expires_in: u64,
+    pub algorithm: JwtAlgorithm,
+    #[cfg_attr(not(feature = "jwt"), allow(dead_code))]
+    public_key: Option<String>,
+    #[cfg_attr(not(feature = "jwt"), allow(dead_code))]
+    private_key: Option<String>,
 }
 
 impl JwtAuth {
     /// 创建新的 JWT 认证实例
+    ///
+    /// 非对称算法优先使用 `Config::resolve_secret_files` 读取到的密钥，
+    /// 未读取时直接读取密钥文件
     pub fn new(config: &JwtConfig) -> Self {
+        let pem = |resolved: &Option<String>, path: &Option<String>| {
+            resolved
+                .clone()
+                .or_else(|| path.as_ref().and_then(|path| std::fs::read_to_string(path).ok()))
+        };
+
         Self {
-            secret: config.secret.clone(),
+            secret: config.effective_secret().to_string(),
             expires_in: config.expires_in,
+            refresh_expires_in: config.refresh.expires_in,
+            algorithm: config.algorithm,
+            public_key: pem(&config.resolved_public_key, &config.public_key_path),
+            private_key: pem(&config.resolved_private_key, &config.private_key_path),
         }
     }
 
     /// 生成 JWT token
     #[cfg(feature = "jwt")]
     pub fn generate_token(&self, user_id: &str) -> Result<String> {
+        self.encode_claims(user_id, ACCESS_AUDIENCE, self.expires_in, Map::new())
+    }
+
+    /// 生成附带自定义声明的 JWT token
+    ///
+    /// `extra` 必须序列化为 JSON 对象，其字段与标准声明并列写入 token，
+    /// 验证后通过 [`Claims::claim`] 或 [`Claims::extra_as`] 读取
+    #[cfg(feature = "jwt")]
+    pub fn generate_token_with_claims<T: Serialize>(&self, user_id: &str, extra: &T) -> Result<String> {
+        self.encode_claims(user_id, ACCESS_AUDIENCE, self.expires_in, extra_claims(extra)?)
+    }
+
+    /// 验证 JWT token
+    #[cfg(feature = "jwt")]
+    pub fn verify_token(&self, token: &str) -> Result<Claims> {
+        self.decode_claims(token, ACCESS_AUDIENCE)
+    }
+
+    /// 生成访问令牌和刷新令牌
+    #[cfg(feature = "jwt")]
+    pub fn generate_token_pair(&self, user_id: &str) -> Result<TokenPair> {
+        self.token_pair(user_id, Map::new())
+    }
+
+    /// 生成附带自定义声明的访问令牌和刷新令牌
+    ///
+    /// 刷新时新令牌沿用刷新令牌中的自定义声明
+    #[cfg(feature = "jwt")]
+    pub fn generate_token_pair_with_claims<T: Serialize>(&self, user_id: &str, extra: &T) -> Result<TokenPair> {
+        self.token_pair(user_id, extra_claims(extra)?)
+    }
+
+    /// 使用刷新令牌换取新的令牌对
+    ///
+    /// 刷新令牌同时轮换，旧的刷新令牌在过期前仍然有效
+    #[cfg(feature = "jwt")]
+    pub fn refresh(&self, refresh_token: &str) -> Result<TokenPair> {
+        let claims = self.decode_claims(refresh_token, REFRESH_AUDIENCE)?;
+        self.token_pair(&claims.sub, claims.extra)
+    }
+
+    #[cfg(feature = "jwt")]
+    fn token_pair(&self, user_id: &str, extra: Map<String, Value>) -> Result<TokenPair> {
+        Ok(TokenPair {
+            access_token: self.encode_claims(user_id, ACCESS_AUDIENCE, self.expires_in, extra.clone())?,
+            refresh_token: self.encode_claims(user_id, REFRESH_AUDIENCE, self.refresh_expires_in, extra)?,
+            token_type: "Bearer".to_string(),
+            expires_in: self.expires_in,
+        })
+    }
+
+    #[cfg(feature = "jwt")]
+    fn encode_claims(
+        &self,
+        user_id: &str,
+        audience: &str,
+        expires_in: u64,
+        extra: Map<String, Value>,
+    ) -> Result<String> {
         let now = chrono::Utc::now();
         let iat = now.timestamp() as usize;
-        let exp = (now + chrono::Duration::seconds(self.expires_in as i64)).timestamp() as usize;
+        let exp = (now + chrono::Duration::seconds(expires_in as i64)).timestamp() as usize;
 
         let claims = Claims {
             sub: user_id.to_string(),
             exp,
             iat,
-            aud: "hwhkit".to_string(),
+            aud: audience.to_string(),
             iss: "hwhkit".to_string(),
+            extra,
         };
 
-        encode(
-            &Header::default(),
-            &claims,
-            &EncodingKey::from_secret(self.secret.as_ref()),
-        )
-        .map_err(Error::Jwt)
+        encode(&Header::new(self.jwt_algorithm()), &claims, &self.encoding_key()?).map_err(Error::Jwt)
     }
 
-    /// 验证 JWT token
     #[cfg(feature = "jwt")]
-    pub fn verify_token(&self, token: &str) -> Result<Claims> {
-        let mut validation = Validation::default();
-        validation.set_audience(&["hwhkit"]);
+    fn decode_claims(&self, token: &str, audience: &str) -> Result<Claims> {
+        let mut validation = Validation::new(self.jwt_algorithm());
+        validation.set_audience(&[audience]);
         validation.set_issuer(&["hwhkit"]);
 
-        decode::<Claims>(
-            token,
-            &DecodingKey::from_secret(self.secret.as_ref()),
-            &validation,
-        )
-        .map(|token_data| token_data.claims)
-        .map_err(Error::Jwt)
+        decode::<Claims>(token, &self.decoding_key()?, &validation)
+            .map(|token_data| token_data.claims)
+            .map_err(Error::Jwt)
+    }
+
+    #[cfg(feature = "jwt")]
+    fn jwt_algorithm(&self) -> Algorithm {
+        match self.algorithm {
+            JwtAlgorithm::Hs256 => Algorithm::HS256,
+            JwtAlgorithm::Rs256 => Algorithm::RS256,
+            JwtAlgorithm::Es256 => Algorithm::ES256,
+        }
+    }
+
+    #[cfg(feature = "jwt")]
+    fn encoding_key(&self) -> Result<EncodingKey> {
+        let pem = || {
+            self.private_key
+                .as_deref()
+                .map(str::as_bytes)
+                .ok_or_else(|| Error::Config("签发 token 需要配置 private_key_path".to_string()))
+        };
+        match self.algorithm {
+            JwtAlgorithm::Hs256 => Ok(EncodingKey::from_secret(self.secret.as_ref())),
+            JwtAlgorithm::Rs256 => EncodingKey::from_rsa_pem(pem()?).map_err(Error::Jwt),
+            JwtAlgorithm::Es256 => EncodingKey::from_ec_pem(pem()?).map_err(Error::Jwt),
+        }
+    }
+
+    #[cfg(feature = "jwt")]
+    fn decoding_key(&self) -> Result<DecodingKey> {
+        let pem = || {
+            self.public_key
+                .as_deref()
+                .map(str::as_bytes)
+                .ok_or_else(|| Error::Config("验证 token 需要配置 public_key_path".to_string()))
+        };
+        match self.algorithm {
+            JwtAlgorithm::Hs256 => Ok(DecodingKey::from_secret(self.secret.as_ref())),
+            JwtAlgorithm::Rs256 => DecodingKey::from_rsa_pem(pem()?).map_err(Error::Jwt),
+            JwtAlgorithm::Es256 => DecodingKey::from_ec_pem(pem()?).map_err(Error::Jwt),
+        }
     }
 
     /// 从请求头中提取 token
@@ -92,26 +256,177 @@ impl JwtAuth {
     }
 }
 
+/// 将自定义声明序列化为 JSON 对象，不允许覆盖标准声明
+#[cfg(feature = "jwt")]
+fn extra_claims<T: Serialize>(extra: &T) -> Result<Map<String, Value>> {
+    match serde_json::to_value(extra)? {
+        Value::Object(map) => {
+            if let Some(name) = ["sub", "exp", "iat", "aud", "iss"]
+                .into_iter()
+                .find(|name| map.contains_key(*name))
+            {
+                return Err(Error::BadRequest(format!("自定义声明不能覆盖标准声明 `{}`", name)));
+            }
+            Ok(map)
+        }
+        Value::Null => Ok(Map::new()),
+        _ => Err(Error::BadRequest("自定义声明必须是 JSON 对象".to_string())),
+    }
+}
+
+/// JWT 认证中间件状态
+#[cfg(feature = "jwt")]
+#[derive(Debug, Clone)]
+pub struct JwtGuard {
+    auth: JwtAuth,
+    protected_paths: Vec<String>,
+    refresh_path: Option<String>,
+    #[cfg(feature = "jwks")]
+    jwks: Option<super::jwks::JwksVerifier>,
+}
+
+#[cfg(feature = "jwt")]
+impl JwtGuard {
+    /// 根据配置创建认证中间件状态
+    ///
+    /// # Errors
+    ///
+    /// 配置了 JWKS 但未启用 `jwks` feature 时返回配置错误
+    pub fn new(config: &JwtConfig) -> Result<Self> {
+        #[cfg(not(feature = "jwks"))]
+        if config.jwks.url.is_some() {
+            return Err(Error::Config("JWKS 验证需要启用 `jwks` feature".to_string()));
+        }
+
+        Ok(Self {
+            auth: JwtAuth::new(config),
+            protected_paths: config.protected_paths.clone(),
+            refresh_path: config.refresh.enabled.then(|| config.refresh.path.clone()),
+            #[cfg(feature = "jwks")]
+            jwks: match config.jwks.url {
+                Some(_) => Some(super::jwks::JwksVerifier::new(&config.jwks)?),
+                None => None,
+            },
+        })
+    }
+
+    /// 验证 token，配置了 JWKS 时使用远程公钥
+    pub async fn verify(&self, token: &str) -> Result<Claims> {
+        #[cfg(feature = "jwks")]
+        if let Some(jwks) = &self.jwks {
+            return jwks.verify(token).await;
+        }
+        self.auth.verify_token(token)
+    }
+
+    /// 请求路径是否需要认证
+    ///
+    /// 刷新接口使用刷新令牌认证，不受访问令牌保护
+    pub fn is_protected(&self, path: &str) -> bool {
+        self.refresh_path.as_deref() != Some(path)
+            && self
+                .protected_paths
+                .iter()
+                .any(|pattern| path_matches(pattern, path))
+    }
+}
+
 /// JWT 认证中间件
+///
+/// 受保护路径上缺少或无效的 token 返回 401，验证通过后将 [`Claims`] 放入请求扩展。
+/// CORS 预检请求（`OPTIONS`）不做认证
 #[cfg(feature = "jwt")]
 pub async fn jwt_auth_middleware(
+    State(guard): State<Arc<JwtGuard>>,
     mut request: Request,
     next: Next,
-) -> std::result::Result<Response, StatusCode> {
-    // 这里需要从某种状态或扩展中获取 JWT 配置
-    // 为了简化，我们跳过实际的验证
-    tracing::warn!("JWT 认证中间件: 功能暂未完全实现");
-    Ok(next.run(request).await)
+) -> Response {
+    if request.method() == Method::OPTIONS || !guard.is_protected(request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    let claims = match guard.auth.extract_token_from_header(request.headers()) {
+        Ok(token) => guard.verify(&token).await,
+        Err(e) => Err(e),
+    };
+
+    match claims {
+        Ok(claims) => {
+            request.extensions_mut().insert(claims);
+            next.run(request).await
+        }
+        Err(e) => {
+            tracing::debug!("JWT 认证失败 {}: {}", request.uri().path(), e);
+            Error::Unauthorized("缺少或无效的访问令牌".to_string()).into_response()
+        }
+    }
 }
 
-/// 无 JWT 功能时的占位中间件
+/// 应用 JWT 认证中间件
+///
+/// 需要启用 `jwt` feature，否则返回配置错误
+#[cfg(feature = "jwt")]
+pub fn apply_jwt(app: Router, config: &JwtConfig) -> Result<Router> {
+    let guard = Arc::new(JwtGuard::new(config)?);
+    Ok(app.layer(axum::middleware::from_fn_with_state(guard, jwt_auth_middleware)))
+}
+
+/// 应用 JWT 认证中间件
+///
+/// 需要启用 `jwt` feature，否则返回配置错误
 #[cfg(not(feature = "jwt"))]
-pub async fn jwt_auth_middleware(
-    request: Request,
-    next: Next,
-) -> std::result::Result<Response, StatusCode> {
-    tracing::warn!("JWT 功能未启用，跳过认证");
-    Ok(next.run(request).await)
+pub fn apply_jwt(_app: Router, _config: &JwtConfig) -> Result<Router> {
+    Err(Error::Config("JWT 认证需要启用 `jwt` feature".to_string()))
+}
+
+/// 刷新接口的请求体
+#[derive(Debug, Deserialize)]
+pub struct RefreshRequest {
+    /// 刷新令牌
+    pub refresh_token: String,
+}
+
+/// 刷新令牌接口
+///
+/// 在 `refresh.path` 上接收 `POST {"refresh_token": "..."}`，返回新的 [`TokenPair`]，
+/// 刷新令牌无效或过期时返回 401。需要启用 `jwt` feature，否则返回配置错误
+#[cfg(feature = "jwt")]
+pub fn refresh_router(config: &JwtConfig) -> Result<Router> {
+    let auth = Arc::new(JwtAuth::new(config));
+    Ok(Router::new().route(
+        &config.refresh.path,
+        axum::routing::post(refresh_handler).with_state(auth),
+    ))
+}
+
+/// 刷新令牌接口
+///
+/// 在 `refresh.path` 上接收 `POST {"refresh_token": "..."}`，返回新的 [`TokenPair`]，
+/// 刷新令牌无效或过期时返回 401。需要启用 `jwt` feature，否则返回配置错误
+#[cfg(not(feature = "jwt"))]
+pub fn refresh_router(_config: &JwtConfig) -> Result<Router> {
+    Err(Error::Config("JWT 刷新接口需要启用 `jwt` feature".to_string()))
+}
+
+#[cfg(feature = "jwt")]
+async fn refresh_handler(
+    State(auth): State<Arc<JwtAuth>>,
+    axum::Json(request): axum::Json<RefreshRequest>,
+) -> Result<axum::Json<TokenPair>> {
+    auth.refresh(&request.refresh_token).map(axum::Json)
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Claims {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+        parts
+            .extensions
+            .get::<Claims>()
+            .cloned()
+            .ok_or_else(|| Error::Unauthorized("请求未经过 JWT 认证".to_string()))
+    }
 }
 
 #[cfg(test)]
@@ -124,6 +439,7 @@ mod tests {
             enabled: true,
             secret: "test-secret".to_string(),
             expires_in: 3600,
+            ..Default::default()
         };
 
         let jwt_auth = JwtAuth::new(&config);
@@ -138,6 +454,7 @@ mod tests {
             enabled: true,
             secret: "test-secret".to_string(),
             expires_in: 3600,
+            ..Default::default()
         };
 
         let jwt_auth = JwtAuth::new(&config);
@@ -148,4 +465,177 @@ mod tests {
         assert_eq!(claims.aud, "hwhkit");
         assert_eq!(claims.iss, "hwhkit");
     }
-}
\ No newline at end of file
+
+    #[cfg(feature = "jwt")]
+    #[tokio::test]
+    async fn test_protected_paths_require_token() {
+        use axum::{body::Body, http::StatusCode, routing::get};
+        use tower::util::ServiceExt;
+
+        let config = JwtConfig {
+            enabled: true,
+            secret: "test-secret".to_string(),
+            protected_paths: vec!["/api/v1/*".to_string()],
+            ..Default::default()
+        };
+        let app = apply_jwt(
+            Router::new()
+                .route("/api/v1/me", get(|claims: Claims| async move { claims.sub }))
+                .route("/public", get(|| async { "ok" })),
+            &config,
+        )
+        .unwrap();
+
+        let send = |uri: &str, token: Option<String>| {
+            let mut request = Request::builder().uri(uri);
+            if let Some(token) = token {
+                request = request.header("authorization", format!("Bearer {}", token));
+            }
+            app.clone().oneshot(request.body(Body::empty()).unwrap())
+        };
+
+        let response = send("/api/v1/me", None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = send("/api/v1/me", Some("invalid".to_string())).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let token = JwtAuth::new(&config).generate_token("user123").unwrap();
+        let response = send("/api/v1/me", Some(token)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"user123");
+
+        let response = send("/public", None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[cfg(feature = "jwt")]
+    #[test]
+    fn test_token_pair_and_refresh() {
+        let jwt_auth = JwtAuth::new(&JwtConfig {
+            enabled: true,
+            secret: "test-secret".to_string(),
+            ..Default::default()
+        });
+
+        let pair = jwt_auth.generate_token_pair("user123").unwrap();
+        assert_eq!(pair.token_type, "Bearer");
+        assert_eq!(jwt_auth.verify_token(&pair.access_token).unwrap().sub, "user123");
+        // 刷新令牌不能作为访问令牌使用，访问令牌也不能用于刷新
+        assert!(jwt_auth.verify_token(&pair.refresh_token).is_err());
+        assert!(jwt_auth.refresh(&pair.access_token).is_err());
+
+        let refreshed = jwt_auth.refresh(&pair.refresh_token).unwrap();
+        assert_eq!(jwt_auth.verify_token(&refreshed.access_token).unwrap().sub, "user123");
+    }
+
+    #[cfg(feature = "jwt")]
+    #[tokio::test]
+    async fn test_refresh_endpoint() {
+        use axum::{body::Body, http::StatusCode};
+        use tower::util::ServiceExt;
+
+        let mut config = JwtConfig {
+            enabled: true,
+            secret: "test-secret".to_string(),
+            protected_paths: vec!["/*".to_string()],
+            ..Default::default()
+        };
+        config.refresh.enabled = true;
+        let app = apply_jwt(refresh_router(&config).unwrap(), &config).unwrap();
+        let pair = JwtAuth::new(&config).generate_token_pair("user123").unwrap();
+
+        let send = |token: &str| {
+            let request = Request::builder()
+                .method("POST")
+                .uri("/auth/refresh")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::json!({ "refresh_token": token }).to_string()))
+                .unwrap();
+            app.clone().oneshot(request)
+        };
+
+        let response = send(&pair.refresh_token).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let refreshed: TokenPair = serde_json::from_slice(&body).unwrap();
+        assert!(JwtAuth::new(&config).verify_token(&refreshed.access_token).is_ok());
+
+        let response = send(&pair.access_token).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[cfg(feature = "jwt")]
+    #[test]
+    fn test_custom_claims() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Tenant {
+            tenant_id: u64,
+            roles: Vec<String>,
+        }
+
+        let jwt_auth = JwtAuth::new(&JwtConfig {
+            enabled: true,
+            secret: "test-secret".to_string(),
+            ..Default::default()
+        });
+        let tenant = Tenant {
+            tenant_id: 42,
+            roles: vec!["admin".to_string()],
+        };
+
+        let token = jwt_auth.generate_token_with_claims("user123", &tenant).unwrap();
+        let claims = jwt_auth.verify_token(&token).unwrap();
+        assert_eq!(claims.sub, "user123");
+        assert_eq!(claims.claim::<u64>("tenant_id"), Some(42));
+        assert_eq!(claims.claim::<String>("tenant_id"), None);
+        assert_eq!(claims.extra_as::<Tenant>().unwrap(), tenant);
+
+        // 刷新后保留自定义声明
+        let pair = jwt_auth.generate_token_pair_with_claims("user123", &tenant).unwrap();
+        let refreshed = jwt_auth.refresh(&pair.refresh_token).unwrap();
+        let claims = jwt_auth.verify_token(&refreshed.access_token).unwrap();
+        assert_eq!(claims.extra_as::<Tenant>().unwrap(), tenant);
+
+        // 不带自定义声明的 token 没有额外字段
+        let claims = jwt_auth.verify_token(&jwt_auth.generate_token("user123").unwrap()).unwrap();
+        assert!(claims.extra.is_empty());
+
+        assert!(jwt_auth
+            .generate_token_with_claims("user123", &serde_json::json!({ "sub": "admin" }))
+            .is_err());
+        assert!(jwt_auth.generate_token_with_claims("user123", &["admin"]).is_err());
+    }
+
+    #[cfg(feature = "jwt")]
+    #[test]
+    fn test_asymmetric_algorithms() {
+        let fixture = |name: &str| Some(format!("{}/tests/fixtures/jwt/{}", env!("CARGO_MANIFEST_DIR"), name));
+
+        for (algorithm, prefix) in [(JwtAlgorithm::Rs256, "rsa"), (JwtAlgorithm::Es256, "ec")] {
+            let issuer = JwtAuth::new(&JwtConfig {
+                enabled: true,
+                algorithm,
+                public_key_path: fixture(&format!("{}_public.pem", prefix)),
+                private_key_path: fixture(&format!("{}_private.pem", prefix)),
+                ..Default::default()
+            });
+            let token = issuer.generate_token("user123").unwrap();
+
+            // 其他服务只需要公钥即可验证
+            let verifier = JwtAuth::new(&JwtConfig {
+                enabled: true,
+                algorithm,
+                public_key_path: fixture(&format!("{}_public.pem", prefix)),
+                ..Default::default()
+            });
+            assert_eq!(verifier.verify_token(&token).unwrap().sub, "user123");
+            assert!(verifier.generate_token("user123").is_err());
+
+            // 使用共享密钥签发的 token 不能通过验证
+            let forged = JwtAuth::new(&JwtConfig::default()).generate_token("user123").unwrap();
+            assert!(verifier.verify_token(&forged).is_err());
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! 并发限制模块
+//!
+//! 按 `[server.limits]` 限制同时处理的请求数。开启 `load_shed` 时，达到上限后的请求直接返回
+//! 503 和 `Retry-After`，而不是无限排队拖垮服务：
+//!
+//! ```toml
+//! [server.limits]
+//! max_concurrent_requests = 512
+//! load_shed = true
+//! retry_after_secs = 1
+//! ```
+
+use crate::{config::LimitsConfig, error::Error};
+use axum::{
+    error_handling::HandleErrorLayer,
+    http::{header, HeaderValue},
+    response::{IntoResponse, Response},
+    BoxError, Router,
+};
+use tower::{limit::GlobalConcurrencyLimitLayer, load_shed::error::Overloaded, ServiceBuilder};
+
+/// 应用并发限制，`max_concurrent_requests` 为 0 时不做处理
+pub fn apply_limits(app: Router, config: &LimitsConfig) -> Router {
+    if config.max_concurrent_requests == 0 {
+        return app;
+    }
+    let limit = GlobalConcurrencyLimitLayer::new(config.max_concurrent_requests);
+    if !config.load_shed {
+        return app.layer(limit);
+    }
+
+    let retry_after = HeaderValue::from(config.retry_after_secs);
+    app.layer(
+        ServiceBuilder::new()
+            .layer(HandleErrorLayer::new(move |error: BoxError| {
+                let retry_after = retry_after.clone();
+                async move { overloaded(error, retry_after) }
+            }))
+            .load_shed()
+            .layer(limit),
+    )
+}
+
+fn overloaded(error: BoxError, retry_after: HeaderValue) -> Response {
+    if !error.is::<Overloaded>() {
+        return Error::Internal(format!("请求处理失败: {}", error)).into_response();
+    }
+    tracing::warn!("并发请求数已达上限，拒绝请求");
+    let mut response = Error::ServiceUnavailable("服务器繁忙，请稍后重试".to_string()).into_response();
+    response.headers_mut().insert(header::RETRY_AFTER, retry_after);
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, extract::Request, http::StatusCode, routing::get};
+    use std::time::Duration;
+    use tokio::sync::Notify;
+    use tower::util::ServiceExt;
+
+    /// `/slow` 在收到通知前一直占用并发名额
+    fn app(config: &LimitsConfig, release: std::sync::Arc<Notify>) -> Router {
+        let router = Router::new()
+            .route(
+                "/slow",
+                get(move || {
+                    let release = release.clone();
+                    async move {
+                        release.notified().await;
+                        "slow"
+                    }
+                }),
+            )
+            .route("/fast", get(|| async { "fast" }));
+        apply_limits(router, config)
+    }
+
+    fn request(uri: &str) -> Request {
+        Request::builder().uri(uri).body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_load_shed() {
+        let release = std::sync::Arc::new(Notify::new());
+        let app = app(
+            &LimitsConfig {
+                max_concurrent_requests: 1,
+                retry_after_secs: 5,
+                ..LimitsConfig::default()
+            },
+            release.clone(),
+        );
+
+        let slow = tokio::spawn(app.clone().oneshot(request("/slow")));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let response = app.clone().oneshot(request("/fast")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "5");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["status"], 503);
+
+        // 名额释放后恢复处理
+        release.notify_one();
+        assert_eq!(slow.await.unwrap().unwrap().status(), StatusCode::OK);
+        let response = app.oneshot(request("/fast")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_queue_without_load_shed() {
+        let release = std::sync::Arc::new(Notify::new());
+        let app = app(
+            &LimitsConfig {
+                max_concurrent_requests: 1,
+                load_shed: false,
+                ..LimitsConfig::default()
+            },
+            release.clone(),
+        );
+
+        let slow = tokio::spawn(app.clone().oneshot(request("/slow")));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // 排队等待，名额释放后完成
+        let fast = tokio::spawn(app.oneshot(request("/fast")));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!fast.is_finished());
+        release.notify_one();
+        assert_eq!(slow.await.unwrap().unwrap().status(), StatusCode::OK);
+        assert_eq!(fast.await.unwrap().unwrap().status(), StatusCode::OK);
+    }
+}
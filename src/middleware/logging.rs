@@ -1,11 +1,183 @@
 //! 日志中间件模块
 
+use super::{client_ip::ClientIp, matcher::matched_route, request_id::RequestId, trace_context::TraceContext};
+use crate::{
+    config::{AccessLogConfig, LogBackend, LogConfig, LogFormat, LogRotation, LogTarget},
+    error::{Error, Result},
+};
 use axum::{
     extract::Request,
     middleware::Next,
     response::Response,
 };
-use std::time::Instant;
+use std::{path::Path, time::Instant};
+use tracing::{Level, Metadata, Subscriber};
+use tracing_appender::{
+    non_blocking::WorkerGuard,
+    rolling::{RollingFileAppender, Rotation},
+};
+use tracing_subscriber::{
+    filter::Targets,
+    fmt::{self, writer::BoxMakeWriter},
+    registry::LookupSpan,
+    Layer,
+};
+
+/// 访问日志事件使用的 tracing target
+///
+/// 应用日志会过滤掉该 target 的事件，访问日志层只接收该 target 的事件
+pub const ACCESS_LOG_TARGET: &str = "hwhkit::access";
+
+/// 根据配置的后端创建应用日志输出层（不含级别过滤）
+pub fn create_backend_layer<S>(config: &LogConfig) -> Result<Box<dyn Layer<S> + Send + Sync>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    match config.backend {
+        LogBackend::Console => {
+            let layer = fmt::layer()
+                .with_target(false)
+                .with_thread_ids(false)
+                .with_file(false)
+                .with_line_number(false);
+            // 文本格式在 span 上下文中输出 trace_id，JSON 格式输出在 `span` 字段中
+            Ok(match config.format {
+                LogFormat::Text => layer.boxed(),
+                LogFormat::Json => layer.json().with_span_list(false).boxed(),
+            })
+        }
+
+        #[cfg(feature = "syslog")]
+        LogBackend::Syslog => {
+            let writer = super::syslog::SyslogMakeWriter::new(&config.syslog)?;
+            Ok(fmt::layer()
+                .with_writer(writer)
+                .with_ansi(false)
+                .with_level(false)
+                .without_time()
+                .boxed())
+        }
+
+        #[cfg(not(feature = "syslog"))]
+        LogBackend::Syslog => Err(Error::Config(
+            "syslog 日志后端未启用，请启用 'syslog' 特性".to_string(),
+        )),
+
+        #[cfg(feature = "journald")]
+        LogBackend::Journald => {
+            let layer = tracing_journald::layer().map_err(|e| {
+                Error::Config(format!("无法连接 systemd-journald: {}", e))
+            })?;
+            Ok(layer.with_syslog_identifier(config.syslog.app_name.clone()).boxed())
+        }
+
+        #[cfg(not(feature = "journald"))]
+        LogBackend::Journald => Err(Error::Config(
+            "journald 日志后端未启用，请启用 'journald' 特性".to_string(),
+        )),
+    }
+}
+
+/// 日志和追踪后台任务的 guard，随服务器一起释放
+#[derive(Debug, Default)]
+pub struct LogGuards {
+    /// 后台日志写入线程
+    pub workers: Vec<WorkerGuard>,
+    /// OpenTelemetry 导出器
+    #[cfg(feature = "otel")]
+    pub otel: Option<super::otel::OtelGuard>,
+}
+
+/// 判断事件是否属于应用日志（即不是访问日志事件）
+pub fn is_app_event(meta: &Metadata<'_>) -> bool {
+    meta.target() != ACCESS_LOG_TARGET
+}
+
+/// 创建访问日志输出层
+///
+/// 输出到文件时使用后台线程写入，返回的 `WorkerGuard` 需要在服务器运行期间保持存活，
+/// 被 drop 时会刷新尚未写出的日志。
+pub fn create_access_log_layer<S>(
+    config: &LogConfig,
+) -> Result<(Box<dyn Layer<S> + Send + Sync>, Option<WorkerGuard>)>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let access_log = &config.access_log;
+    let mut guard = None;
+
+    let writer = match access_log.target {
+        LogTarget::Stdout => BoxMakeWriter::new(std::io::stdout),
+        LogTarget::Stderr => BoxMakeWriter::new(std::io::stderr),
+        LogTarget::File => {
+            let (writer, worker_guard) =
+                tracing_appender::non_blocking(create_rolling_appender(access_log)?);
+            guard = Some(worker_guard);
+            BoxMakeWriter::new(writer)
+        }
+
+        #[cfg(feature = "syslog")]
+        LogTarget::Syslog => {
+            BoxMakeWriter::new(super::syslog::SyslogMakeWriter::new(&config.syslog)?)
+        }
+
+        #[cfg(not(feature = "syslog"))]
+        LogTarget::Syslog => {
+            return Err(Error::Config(
+                "访问日志输出到 syslog 需要启用 'syslog' 特性".to_string(),
+            ));
+        }
+    };
+
+    let layer = fmt::layer()
+        .with_writer(writer)
+        .with_target(false)
+        .with_ansi(matches!(access_log.target, LogTarget::Stdout | LogTarget::Stderr));
+    let filter = Targets::new().with_target(ACCESS_LOG_TARGET, Level::INFO);
+
+    let layer = match (&access_log.format, &access_log.target) {
+        // syslog 消息头已包含时间和优先级
+        (LogFormat::Text, LogTarget::Syslog) => layer
+            .without_time()
+            .with_level(false)
+            .with_filter(filter)
+            .boxed(),
+        (LogFormat::Text, _) => layer.with_filter(filter).boxed(),
+        (LogFormat::Json, _) => layer.json().flatten_event(true).with_filter(filter).boxed(),
+    };
+
+    Ok((layer, guard))
+}
+
+/// 根据配置创建可轮转的日志文件写入器
+fn create_rolling_appender(config: &AccessLogConfig) -> Result<RollingFileAppender> {
+    let path = Path::new(&config.path);
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| Error::Config(format!("无效的访问日志路径: {}", config.path)))?;
+    let directory = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+
+    std::fs::create_dir_all(directory).map_err(|e| {
+        Error::Config(format!("无法创建访问日志目录 {:?}: {}", directory, e))
+    })?;
+
+    let rotation = match config.rotation {
+        LogRotation::Minutely => Rotation::MINUTELY,
+        LogRotation::Hourly => Rotation::HOURLY,
+        LogRotation::Daily => Rotation::DAILY,
+        LogRotation::Never => Rotation::NEVER,
+    };
+
+    RollingFileAppender::builder()
+        .rotation(rotation)
+        .filename_prefix(file_name)
+        .build(directory)
+        .map_err(|e| Error::Config(format!("无法打开访问日志文件 {}: {}", config.path, e)))
+}
 
 /// 请求日志中间件
 pub async fn request_logging_middleware(
@@ -15,6 +187,7 @@ pub async fn request_logging_middleware(
     let start = Instant::now();
     let method = request.method().clone();
     let uri = request.uri().clone();
+    let route = matched_route(request.extensions()).unwrap_or("-").to_string();
     let user_agent = request
         .headers()
         .get("user-agent")
@@ -24,6 +197,7 @@ pub async fn request_logging_middleware(
     tracing::info!(
         method = %method,
         uri = %uri,
+        route = %route,
         user_agent = %user_agent,
         "请求开始"
     );
@@ -36,6 +210,7 @@ pub async fn request_logging_middleware(
     tracing::info!(
         method = %method,
         uri = %uri,
+        route = %route,
         status = %status,
         duration = ?duration,
         "请求完成"
@@ -44,6 +219,60 @@ pub async fn request_logging_middleware(
     response
 }
 
+/// 访问日志中间件
+///
+/// 每个请求完成后以 `ACCESS_LOG_TARGET` 输出一条访问记录
+pub async fn access_log_middleware(
+    request: Request,
+    next: Next,
+) -> Response {
+    let start = Instant::now();
+    let method = request.method().clone();
+    let uri = request.uri().clone();
+    let route = matched_route(request.extensions()).unwrap_or("-").to_string();
+    let trace_id = request
+        .extensions()
+        .get::<TraceContext>()
+        .map(|context| context.trace_id.clone())
+        .unwrap_or_else(|| "-".to_string());
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(|id| id.to_string())
+        .unwrap_or_else(|| "-".to_string());
+    let client_ip = request
+        .extensions()
+        .get::<ClientIp>()
+        .map(|ip| ip.to_string())
+        .unwrap_or_else(|| "-".to_string());
+    let version = request.version();
+    let user_agent = request
+        .headers()
+        .get("user-agent")
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or("-")
+        .to_string();
+
+    let response = next.run(request).await;
+
+    tracing::info!(
+        target: ACCESS_LOG_TARGET,
+        method = %method,
+        uri = %uri,
+        route = %route,
+        request_id = %request_id,
+        trace_id = %trace_id,
+        client_ip = %client_ip,
+        version = ?version,
+        status = response.status().as_u16(),
+        latency_ms = start.elapsed().as_millis() as u64,
+        user_agent = %user_agent,
+        "access"
+    );
+
+    response
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -77,4 +306,211 @@ mod tests {
         let response = app.oneshot(request).await.unwrap();
         assert_eq!(response.status(), StatusCode::OK);
     }
-}
\ No newline at end of file
+
+    /// 收集写入内容的测试 writer
+    #[derive(Clone, Default)]
+    struct CaptureWriter(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl CaptureWriter {
+        fn contents(&self) -> String {
+            String::from_utf8_lossy(&self.0.lock().unwrap()).to_string()
+        }
+    }
+
+    impl std::io::Write for CaptureWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn file_access_log_config(path: &Path, format: LogFormat) -> LogConfig {
+        LogConfig {
+            access_log: AccessLogConfig {
+                enabled: true,
+                target: LogTarget::File,
+                path: path.to_string_lossy().to_string(),
+                format,
+                rotation: LogRotation::Never,
+            },
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_access_log_middleware() {
+        use tracing_subscriber::{filter::filter_fn, prelude::*, Registry};
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let log_path = temp_dir.path().join("access.log");
+        let config = file_access_log_config(&log_path, LogFormat::Json);
+
+        let app_output = CaptureWriter::default();
+        let app_writer = app_output.clone();
+        let app_layer = fmt::layer()
+            .with_writer(move || app_writer.clone())
+            .with_ansi(false)
+            .with_filter(filter_fn(is_app_event));
+        let (access_layer, guard) = create_access_log_layer::<Registry>(&config).unwrap();
+
+        let subscriber = tracing_subscriber::registry().with(vec![app_layer.boxed(), access_layer]);
+        let default_guard = tracing::subscriber::set_default(subscriber);
+
+        let app = Router::new()
+            .route("/test", get(test_handler))
+            .layer(middleware::from_fn(access_log_middleware));
+
+        let mut request = Request::builder()
+            .method(Method::GET)
+            .uri("/test")
+            .body(Body::empty())
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(ClientIp("203.0.113.7".parse().unwrap()));
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        tracing::info!("应用事件");
+
+        // drop guard 以刷新后台写入线程
+        drop(default_guard);
+        drop(guard);
+
+        let access_output = std::fs::read_to_string(&log_path).unwrap();
+        let record: serde_json::Value =
+            serde_json::from_str(access_output.lines().next().unwrap()).unwrap();
+        assert_eq!(record["uri"], "/test");
+        assert_eq!(record["route"], "/test");
+        assert_eq!(record["status"], 200);
+        assert_eq!(record["client_ip"], "203.0.113.7");
+        assert!(!access_output.contains("应用事件"));
+
+        let app_output = app_output.contents();
+        assert!(app_output.contains("应用事件"));
+        assert!(!app_output.contains("/test"));
+    }
+
+    #[tokio::test]
+    async fn test_trace_id_log_correlation() {
+        use crate::{config::Config, middleware::MiddlewareManager};
+        use tracing_subscriber::{filter::filter_fn, prelude::*, Registry};
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.middleware.logging = file_access_log_config(&temp_dir.path().join("access.log"), LogFormat::Json);
+
+        let app_output = CaptureWriter::default();
+        let app_writer = app_output.clone();
+        let app_layer = fmt::layer()
+            .json()
+            .with_span_list(false)
+            .with_writer(move || app_writer.clone())
+            .with_filter(filter_fn(is_app_event));
+        let (access_layer, guard) =
+            create_access_log_layer::<Registry>(&config.middleware.logging).unwrap();
+        let subscriber = tracing_subscriber::registry().with(vec![app_layer.boxed(), access_layer]);
+        let default_guard = tracing::subscriber::set_default(subscriber);
+
+        let router = Router::new().route(
+            "/test",
+            get(|| async {
+                tracing::info!("处理请求");
+                "ok"
+            }),
+        );
+        let app = MiddlewareManager::new(config.clone())
+            .apply_middleware(router)
+            .await
+            .unwrap();
+
+        let trace_id = "4bf92f3577b34da6a3ce929d0e0e4736";
+        let request = Request::builder()
+            .uri("/test")
+            .header("traceparent", format!("00-{}-00f067aa0ba902b7-01", trace_id))
+            .header("x-request-id", "req-1")
+            .body(Body::empty())
+            .unwrap();
+        app.oneshot(request).await.unwrap();
+
+        drop(default_guard);
+        drop(guard);
+
+        let record: serde_json::Value = app_output
+            .contents()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .find(|record: &serde_json::Value| record["fields"]["message"] == "处理请求")
+            .expect("handler event should be logged");
+        assert_eq!(record["span"]["trace_id"], trace_id);
+        assert_eq!(record["span"]["span_id"].as_str().unwrap().len(), 16);
+        assert_eq!(record["span"]["request_id"], "req-1");
+
+        let access_output =
+            std::fs::read_to_string(temp_dir.path().join("access.log")).unwrap();
+        let access: serde_json::Value =
+            serde_json::from_str(access_output.lines().next().unwrap()).unwrap();
+        assert_eq!(access["trace_id"], trace_id);
+        assert_eq!(access["request_id"], "req-1");
+    }
+
+    #[test]
+    fn test_console_backend_layer() {
+        let config = LogConfig::default();
+        let layer = create_backend_layer::<tracing_subscriber::Registry>(&config);
+        assert!(layer.is_ok());
+    }
+
+    #[cfg(not(feature = "syslog"))]
+    #[test]
+    fn test_syslog_requires_feature() {
+        let config = LogConfig {
+            backend: LogBackend::Syslog,
+            ..Default::default()
+        };
+        let result = create_backend_layer::<tracing_subscriber::Registry>(&config);
+        assert!(matches!(result, Err(Error::Config(_))));
+
+        let mut config = LogConfig::default();
+        config.access_log.enabled = true;
+        config.access_log.target = LogTarget::Syslog;
+        let result = create_access_log_layer::<tracing_subscriber::Registry>(&config);
+        assert!(matches!(result, Err(Error::Config(_))));
+    }
+
+    #[cfg(not(feature = "journald"))]
+    #[test]
+    fn test_journald_requires_feature() {
+        let config = LogConfig {
+            backend: LogBackend::Journald,
+            ..Default::default()
+        };
+        let result = create_backend_layer::<tracing_subscriber::Registry>(&config);
+        assert!(matches!(result, Err(Error::Config(_))));
+    }
+
+    #[test]
+    fn test_access_log_file_layer() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config = file_access_log_config(&temp_dir.path().join("logs/access.log"), LogFormat::Text);
+
+        let result = create_access_log_layer::<tracing_subscriber::Registry>(&config);
+        assert!(result.is_ok());
+        assert!(temp_dir.path().join("logs").is_dir());
+    }
+
+    #[test]
+    fn test_access_log_unopenable_file() {
+        // 路径指向目录，无法作为日志文件打开，应返回错误而不是 panic
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(temp_dir.path().join("access.log")).unwrap();
+        let config = file_access_log_config(&temp_dir.path().join("access.log"), LogFormat::Text);
+
+        let result = create_access_log_layer::<tracing_subscriber::Registry>(&config);
+        assert!(matches!(result, Err(Error::Config(_))));
+    }
+}
@@ -0,0 +1,124 @@
+//! 路径匹配模块
+//!
+//! 配置中的路径模式支持两种写法：
+//!
+//! - 精确匹配：`/healthz`
+//! - 前缀匹配：`/api/v1/*`，匹配 `/api/v1` 本身及其下的所有路径
+
+use crate::{
+    config::RouteMiddlewareConfig,
+    error::{Error, Result},
+};
+use axum::{
+    extract::MatchedPath,
+    http::{Extensions, Method},
+};
+
+/// 判断请求路径是否匹配配置中的路径模式
+pub fn path_matches(pattern: &str, path: &str) -> bool {
+    match pattern.strip_suffix("/*") {
+        Some(prefix) => {
+            path == prefix
+                || (path.starts_with(prefix) && path[prefix.len()..].starts_with('/'))
+        }
+        None if pattern == "*" => true,
+        None => pattern == path,
+    }
+}
+
+/// 按路径和方法匹配请求
+///
+/// 未设置路径时匹配所有路径，未设置方法时匹配所有方法
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestMatcher {
+    paths: Vec<String>,
+    methods: Vec<Method>,
+}
+
+impl RequestMatcher {
+    /// 匹配所有请求
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加路径模式
+    pub fn path(mut self, pattern: &str) -> Self {
+        self.paths.push(pattern.to_string());
+        self
+    }
+
+    /// 添加方法
+    pub fn method(mut self, method: Method) -> Self {
+        self.methods.push(method);
+        self
+    }
+
+    /// 根据 `[[middleware.routes]]` 配置创建
+    ///
+    /// # Errors
+    ///
+    /// 方法名无效时返回配置错误
+    pub fn from_config(config: &RouteMiddlewareConfig) -> Result<Self> {
+        let methods = config
+            .methods
+            .iter()
+            .map(|method| {
+                Method::from_bytes(method.to_uppercase().as_bytes())
+                    .map_err(|_| Error::Config(format!("无效的 HTTP 方法 '{}'", method)))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            paths: vec![config.path.clone()],
+            methods,
+        })
+    }
+
+    /// 请求是否匹配
+    pub fn matches(&self, method: &Method, path: &str) -> bool {
+        (self.paths.is_empty() || self.paths.iter().any(|pattern| path_matches(pattern, path)))
+            && (self.methods.is_empty() || self.methods.contains(method))
+    }
+}
+
+/// 请求匹配到的路由模式，如 `/users/:id`
+///
+/// 未匹配任何路由（例如进入 fallback）时返回 `None`
+pub fn matched_route(extensions: &Extensions) -> Option<&str> {
+    extensions.get::<MatchedPath>().map(MatchedPath::as_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_match() {
+        assert!(path_matches("/healthz", "/healthz"));
+        assert!(!path_matches("/healthz", "/healthz/live"));
+    }
+
+    #[test]
+    fn test_prefix_match() {
+        assert!(path_matches("/api/v1/*", "/api/v1"));
+        assert!(path_matches("/api/v1/*", "/api/v1/users/5"));
+        assert!(!path_matches("/api/v1/*", "/api/v10"));
+        assert!(path_matches("/*", "/anything"));
+        assert!(path_matches("*", "/anything"));
+    }
+
+    #[test]
+    fn test_request_matcher() {
+        let matcher = RequestMatcher::new().path("/api/*").method(Method::GET);
+        assert!(matcher.matches(&Method::GET, "/api/users"));
+        assert!(!matcher.matches(&Method::POST, "/api/users"));
+        assert!(!matcher.matches(&Method::GET, "/public"));
+        assert!(RequestMatcher::new().matches(&Method::DELETE, "/anything"));
+
+        let config = RouteMiddlewareConfig {
+            path: "/api/*".to_string(),
+            methods: vec!["get".to_string()],
+            apply: Vec::new(),
+        };
+        assert_eq!(RequestMatcher::from_config(&config).unwrap(), matcher);
+    }
+}
@@ -0,0 +1,595 @@
+//! Prometheus 指标模块
+//!
+//! 按 `method`、`path`、`status` 记录 `http_requests_total` 计数和
+//! `http_request_duration_seconds`、`http_request_size_bytes`、`http_response_size_bytes` 直方图，
+//! 以及客户端断开导致的 `http_requests_cancelled_total` 计数、已弃用接口的
+//! `http_deprecated_requests_total` 计数、熔断器的 `circuit_breaker_state` 和
+//! `circuit_breaker_rejected_total`，连同进程资源使用情况以 Prometheus 文本格式导出。
+//! 直方图桶边界和 `path` 标签的基数上限由 `[telemetry.metrics]` 配置。
+//! `path` 标签优先使用匹配到的路由模式（如 `/users/:id`），而不是原始 URI。
+//! 请求和响应大小取自 `Content-Length` 或已知长度的 body，流式 body 不计入大小直方图。
+
+use super::{deprecation::DeprecatedRoute, matcher::matched_route};
+use crate::{config::MetricsConfig, resilience::CircuitState, watchdog::ProcessStats};
+use axum::{
+    body::HttpBody,
+    extract::{Request, State},
+    http::{header, HeaderMap},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::Write,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+/// 超出基数上限的路径使用的标签值
+pub const OTHER_PATH_LABEL: &str = "__other__";
+
+/// (method, path, status)
+type Labels = (String, String, u16);
+
+/// 单个标签组合的直方图数据
+#[derive(Debug, Clone)]
+struct Histogram {
+    /// 每个桶的计数（非累积），最后一个元素对应 `+Inf`
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new(buckets: usize) -> Self {
+        Self {
+            counts: vec![0; buckets + 1],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, buckets: &[f64], value: f64) {
+        let bucket = buckets
+            .iter()
+            .position(|bound| value <= *bound)
+            .unwrap_or(buckets.len());
+        self.counts[bucket] += 1;
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+#[derive(Debug, Default)]
+struct Registry {
+    /// 请求耗时直方图，计数同时用于 `http_requests_total`
+    durations: BTreeMap<Labels, Histogram>,
+    request_sizes: BTreeMap<Labels, Histogram>,
+    response_sizes: BTreeMap<Labels, Histogram>,
+    /// (method, path) -> 被取消的请求数
+    cancelled: BTreeMap<(String, String), u64>,
+    /// (method, path) -> 已弃用接口的请求数
+    deprecated: BTreeMap<(String, String), u64>,
+    paths: HashSet<String>,
+}
+
+/// HTTP 请求指标
+#[derive(Debug)]
+pub struct HttpMetrics {
+    buckets: Vec<f64>,
+    size_buckets: Vec<f64>,
+    path_templating: bool,
+    max_paths: usize,
+    registry: Mutex<Registry>,
+}
+
+impl HttpMetrics {
+    /// 根据配置创建指标收集器
+    pub fn new(config: &MetricsConfig) -> Self {
+        Self {
+            buckets: config.buckets.clone(),
+            size_buckets: config.size_buckets.clone(),
+            path_templating: config.path_templating,
+            max_paths: config.max_paths,
+            registry: Mutex::new(Registry::default()),
+        }
+    }
+
+    /// 记录一次请求
+    ///
+    /// `route` 为匹配到的路由模式，未匹配路由时根据配置对原始路径做模板化
+    pub fn observe(&self, method: &str, route: Option<&str>, path: &str, status: u16, seconds: f64) {
+        let Ok(mut registry) = self.registry.lock() else {
+            return;
+        };
+        let path = self.path_label(&mut registry, route, path);
+        registry
+            .durations
+            .entry((method.to_string(), path, status))
+            .or_insert_with(|| Histogram::new(self.buckets.len()))
+            .observe(&self.buckets, seconds);
+    }
+
+    /// 记录一次请求的请求体和响应体大小（字节），大小未知时传入 `None`
+    pub fn observe_sizes(
+        &self,
+        method: &str,
+        route: Option<&str>,
+        path: &str,
+        status: u16,
+        request_bytes: Option<u64>,
+        response_bytes: Option<u64>,
+    ) {
+        let Ok(mut registry) = self.registry.lock() else {
+            return;
+        };
+        let labels = (method.to_string(), self.path_label(&mut registry, route, path), status);
+
+        let registry = &mut *registry;
+        for (histograms, bytes) in [
+            (&mut registry.request_sizes, request_bytes),
+            (&mut registry.response_sizes, response_bytes),
+        ] {
+            if let Some(bytes) = bytes {
+                histograms
+                    .entry(labels.clone())
+                    .or_insert_with(|| Histogram::new(self.size_buckets.len()))
+                    .observe(&self.size_buckets, bytes as f64);
+            }
+        }
+    }
+
+    /// 记录一次因客户端断开而被取消的请求
+    pub fn observe_cancelled(&self, method: &str, route: Option<&str>, path: &str) {
+        let Ok(mut registry) = self.registry.lock() else {
+            return;
+        };
+        let path = self.path_label(&mut registry, route, path);
+        *registry.cancelled.entry((method.to_string(), path)).or_default() += 1;
+    }
+
+    /// 记录一次对已弃用接口的请求
+    pub fn observe_deprecated(&self, method: &str, route: Option<&str>, path: &str) {
+        let Ok(mut registry) = self.registry.lock() else {
+            return;
+        };
+        let path = self.path_label(&mut registry, route, path);
+        *registry.deprecated.entry((method.to_string(), path)).or_default() += 1;
+    }
+
+    /// 计算 `path` 标签，超出基数上限的新路径归入同一个标签值
+    fn path_label(&self, registry: &mut Registry, route: Option<&str>, path: &str) -> String {
+        let path = match route {
+            Some(route) => route.to_string(),
+            None if self.path_templating => template_path(path),
+            None => path.to_string(),
+        };
+
+        if registry.paths.contains(&path) {
+            path
+        } else if registry.paths.len() < self.max_paths {
+            registry.paths.insert(path.clone());
+            path
+        } else {
+            OTHER_PATH_LABEL.to_string()
+        }
+    }
+
+    /// 以 Prometheus 文本格式导出
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let Ok(registry) = self.registry.lock() else {
+            return out;
+        };
+
+        out.push_str("# HELP http_requests_total HTTP 请求数\n");
+        out.push_str("# TYPE http_requests_total counter\n");
+        for (labels, histogram) in &registry.durations {
+            let _ = writeln!(out, "http_requests_total{{{}}} {}", format_labels(labels), histogram.count);
+        }
+
+        render_histogram(
+            &mut out,
+            "http_request_duration_seconds",
+            "HTTP 请求处理耗时",
+            &self.buckets,
+            &registry.durations,
+        );
+        render_histogram(
+            &mut out,
+            "http_request_size_bytes",
+            "HTTP 请求体大小",
+            &self.size_buckets,
+            &registry.request_sizes,
+        );
+        render_histogram(
+            &mut out,
+            "http_response_size_bytes",
+            "HTTP 响应体大小",
+            &self.size_buckets,
+            &registry.response_sizes,
+        );
+
+        out.push_str("# HELP http_requests_cancelled_total 客户端断开导致取消的请求数\n");
+        out.push_str("# TYPE http_requests_cancelled_total counter\n");
+        for ((method, path), count) in &registry.cancelled {
+            let _ = writeln!(
+                out,
+                "http_requests_cancelled_total{{method=\"{}\",path=\"{}\"}} {}",
+                escape_label(method),
+                escape_label(path),
+                count
+            );
+        }
+
+        out.push_str("# HELP http_deprecated_requests_total 已弃用接口的请求数\n");
+        out.push_str("# TYPE http_deprecated_requests_total counter\n");
+        for ((method, path), count) in &registry.deprecated {
+            let _ = writeln!(
+                out,
+                "http_deprecated_requests_total{{method=\"{}\",path=\"{}\"}} {}",
+                escape_label(method),
+                escape_label(path),
+                count
+            );
+        }
+
+        out.push_str("# HELP circuit_breaker_state 熔断器状态（0 正常，1 熔断，2 半开）\n");
+        out.push_str("# TYPE circuit_breaker_state gauge\n");
+        let breakers = crate::resilience::registered();
+        for breaker in &breakers {
+            let state = match breaker.state() {
+                CircuitState::Closed => 0,
+                CircuitState::Open => 1,
+                CircuitState::HalfOpen => 2,
+            };
+            let _ = writeln!(
+                out,
+                "circuit_breaker_state{{name=\"{}\"}} {}",
+                escape_label(breaker.name()),
+                state
+            );
+        }
+        out.push_str("# HELP circuit_breaker_rejected_total 因熔断被拒绝的调用数\n");
+        out.push_str("# TYPE circuit_breaker_rejected_total counter\n");
+        for breaker in &breakers {
+            let _ = writeln!(
+                out,
+                "circuit_breaker_rejected_total{{name=\"{}\"}} {}",
+                escape_label(breaker.name()),
+                breaker.rejected()
+            );
+        }
+
+        // 进程资源在导出时实时采样
+        let stats = ProcessStats::sample();
+        if let Some(rss) = stats.rss_bytes {
+            out.push_str("# HELP process_resident_memory_bytes 常驻内存（字节）\n");
+            out.push_str("# TYPE process_resident_memory_bytes gauge\n");
+            let _ = writeln!(out, "process_resident_memory_bytes {}", rss);
+        }
+        if let Some(fds) = stats.open_fds {
+            out.push_str("# HELP process_open_fds 打开的文件描述符数量\n");
+            out.push_str("# TYPE process_open_fds gauge\n");
+            let _ = writeln!(out, "process_open_fds {}", fds);
+        }
+
+        out
+    }
+}
+
+fn format_labels((method, path, status): &Labels) -> String {
+    format!(
+        "method=\"{}\",path=\"{}\",status=\"{}\"",
+        escape_label(method),
+        escape_label(path),
+        status
+    )
+}
+
+fn render_histogram(
+    out: &mut String,
+    name: &str,
+    help: &str,
+    buckets: &[f64],
+    histograms: &BTreeMap<Labels, Histogram>,
+) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} histogram", name);
+
+    for (labels, histogram) in histograms {
+        let labels = format_labels(labels);
+        let mut cumulative = 0;
+        for (bound, count) in buckets.iter().zip(&histogram.counts) {
+            cumulative += count;
+            let _ = writeln!(out, "{}_bucket{{{},le=\"{}\"}} {}", name, labels, bound, cumulative);
+        }
+        let _ = writeln!(out, "{}_bucket{{{},le=\"+Inf\"}} {}", name, labels, histogram.count);
+        let _ = writeln!(out, "{}_sum{{{}}} {}", name, labels, histogram.sum);
+        let _ = writeln!(out, "{}_count{{{}}} {}", name, labels, histogram.count);
+    }
+}
+
+/// 已知的 body 大小：优先使用 `Content-Length`，其次使用 body 的精确长度
+fn body_size<B: HttpBody>(headers: &HeaderMap, body: &B) -> Option<u64> {
+    headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+        .or_else(|| body.size_hint().exact())
+}
+
+/// 指标记录中间件
+pub async fn metrics_middleware(
+    State(metrics): State<Arc<HttpMetrics>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().to_string();
+    let route = matched_route(request.extensions()).map(str::to_string);
+    let path = request.uri().path().to_string();
+    let request_bytes = body_size(request.headers(), request.body());
+    let start = Instant::now();
+
+    let mut guard = CancelledGuard {
+        metrics: &metrics,
+        method: &method,
+        route: route.as_deref(),
+        path: &path,
+        completed: false,
+    };
+    let response = next.run(request).await;
+    guard.completed = true;
+
+    if response.extensions().get::<DeprecatedRoute>().is_some() {
+        metrics.observe_deprecated(&method, route.as_deref(), &path);
+    }
+
+    let status = response.status().as_u16();
+    metrics.observe(&method, route.as_deref(), &path, status, start.elapsed().as_secs_f64());
+    metrics.observe_sizes(
+        &method,
+        route.as_deref(),
+        &path,
+        status,
+        request_bytes,
+        body_size(response.headers(), response.body()),
+    );
+    response
+}
+
+/// 请求 future 在完成前被丢弃（客户端断开）时记录取消
+struct CancelledGuard<'a> {
+    metrics: &'a HttpMetrics,
+    method: &'a str,
+    route: Option<&'a str>,
+    path: &'a str,
+    completed: bool,
+}
+
+impl Drop for CancelledGuard<'_> {
+    fn drop(&mut self) {
+        if !self.completed {
+            self.metrics.observe_cancelled(self.method, self.route, self.path);
+        }
+    }
+}
+
+/// 指标导出处理器
+pub async fn metrics_handler(State(metrics): State<Arc<HttpMetrics>>) -> impl IntoResponse {
+    (
+        [("content-type", "text/plain; version=0.0.4; charset=utf-8")],
+        metrics.render(),
+    )
+}
+
+/// 将路径中的 ID 段替换为 `:id`
+///
+/// 纯数字、UUID 和长度不少于 16 的十六进制串视为 ID。
+pub fn template_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| if is_id_segment(segment) { ":id" } else { segment })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn is_id_segment(segment: &str) -> bool {
+    if segment.is_empty() {
+        return false;
+    }
+    if segment.bytes().all(|b| b.is_ascii_digit()) {
+        return true;
+    }
+
+    let hex = segment.replace('-', "");
+    let is_uuid = segment.len() == 36 && hex.len() == 32;
+    (is_uuid || (segment == hex && hex.len() >= 16)) && hex.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(max_paths: usize) -> HttpMetrics {
+        HttpMetrics::new(&MetricsConfig {
+            enabled: true,
+            buckets: vec![0.1, 0.5],
+            max_paths,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_template_path() {
+        assert_eq!(template_path("/users/42/orders"), "/users/:id/orders");
+        assert_eq!(
+            template_path("/items/550e8400-e29b-41d4-a716-446655440000"),
+            "/items/:id"
+        );
+        assert_eq!(template_path("/blobs/0123456789abcdef0123"), "/blobs/:id");
+        assert_eq!(template_path("/api/v1/health"), "/api/v1/health");
+    }
+
+    #[test]
+    fn test_histogram_buckets() {
+        let metrics = metrics(10);
+        metrics.observe("GET", None, "/users/1", 200, 0.05);
+        metrics.observe("GET", None, "/users/2", 200, 0.3);
+        metrics.observe("GET", None, "/users/3", 200, 2.0);
+
+        let output = metrics.render();
+        let labels = "method=\"GET\",path=\"/users/:id\",status=\"200\"";
+        assert!(output.contains(&format!("http_request_duration_seconds_bucket{{{},le=\"0.1\"}} 1", labels)));
+        assert!(output.contains(&format!("http_request_duration_seconds_bucket{{{},le=\"0.5\"}} 2", labels)));
+        assert!(output.contains(&format!("http_request_duration_seconds_bucket{{{},le=\"+Inf\"}} 3", labels)));
+        assert!(output.contains(&format!("http_request_duration_seconds_count{{{}}} 3", labels)));
+    }
+
+    #[tokio::test]
+    async fn test_matched_route_label() {
+        use axum::{body::Body, http::Request, middleware, routing::get, Router};
+        use tower::util::ServiceExt;
+
+        let metrics = Arc::new(metrics(10));
+        let app = Router::new()
+            .route("/users/:name", get(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(metrics.clone(), metrics_middleware));
+
+        for name in ["alice", "bob"] {
+            let request = Request::builder()
+                .uri(format!("/users/{}", name))
+                .body(Body::empty())
+                .unwrap();
+            app.clone().oneshot(request).await.unwrap();
+        }
+
+        let output = metrics.render();
+        assert!(output.contains(
+            "http_request_duration_seconds_count{method=\"GET\",path=\"/users/:name\",status=\"200\"} 2"
+        ));
+        assert!(!output.contains("alice"));
+    }
+
+    #[tokio::test]
+    async fn test_request_count_and_sizes() {
+        use axum::{body::Body, http::Request, middleware, routing::post, Router};
+        use tower::util::ServiceExt;
+
+        let metrics = Arc::new(HttpMetrics::new(&MetricsConfig {
+            enabled: true,
+            size_buckets: vec![10.0, 1000.0],
+            ..Default::default()
+        }));
+        let app = Router::new()
+            .route("/echo", post(|body: String| async move { body.repeat(4) }))
+            .layer(middleware::from_fn_with_state(metrics.clone(), metrics_middleware));
+
+        let request = Request::builder()
+            .method("POST")
+            .uri("/echo")
+            .header("content-length", "5")
+            .body(Body::from("hello"))
+            .unwrap();
+        app.oneshot(request).await.unwrap();
+
+        let output = metrics.render();
+        let labels = "method=\"POST\",path=\"/echo\",status=\"200\"";
+        assert!(output.contains(&format!("http_requests_total{{{}}} 1", labels)));
+        assert!(output.contains(&format!("http_request_size_bytes_bucket{{{},le=\"10\"}} 1", labels)));
+        assert!(output.contains(&format!("http_request_size_bytes_sum{{{}}} 5", labels)));
+        assert!(output.contains(&format!("http_response_size_bytes_bucket{{{},le=\"10\"}} 0", labels)));
+        assert!(output.contains(&format!("http_response_size_bytes_sum{{{}}} 20", labels)));
+    }
+
+    #[test]
+    fn test_cancelled_counter() {
+        let metrics = metrics(10);
+        metrics.observe_cancelled("GET", Some("/reports/:id"), "/reports/7");
+        metrics.observe_cancelled("GET", Some("/reports/:id"), "/reports/8");
+
+        let output = metrics.render();
+        assert!(output.contains(
+            "http_requests_cancelled_total{method=\"GET\",path=\"/reports/:id\"} 2"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_deprecated_counter() {
+        use crate::{config::DeprecatedRouteConfig, middleware::deprecation};
+        use axum::{body::Body, http::Request, middleware, routing::get, Router};
+        use tower::util::ServiceExt;
+
+        let deprecations = deprecation::Deprecations::new(&[DeprecatedRouteConfig {
+            path: "/v1/*".to_string(),
+            since: None,
+            sunset: None,
+            link: None,
+        }])
+        .unwrap();
+        let metrics = Arc::new(metrics(10));
+        let app = Router::new()
+            .route("/v1/users", get(|| async { "ok" }))
+            .route("/v2/users", get(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(
+                Arc::new(deprecations),
+                deprecation::deprecation_middleware,
+            ))
+            .layer(middleware::from_fn_with_state(metrics.clone(), metrics_middleware));
+
+        for uri in ["/v1/users", "/v1/users", "/v2/users"] {
+            let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+            app.clone().oneshot(request).await.unwrap();
+        }
+
+        let output = metrics.render();
+        assert!(output.contains(
+            "http_deprecated_requests_total{method=\"GET\",path=\"/v1/users\"} 2"
+        ));
+        assert!(!output.contains("http_deprecated_requests_total{method=\"GET\",path=\"/v2/users\"}"));
+    }
+
+    #[test]
+    fn test_path_cardinality_limit() {
+        let metrics = metrics(2);
+        metrics.observe("GET", None, "/a", 200, 0.01);
+        metrics.observe("GET", None, "/b", 200, 0.01);
+        metrics.observe("GET", None, "/c", 200, 0.01);
+        metrics.observe("GET", None, "/a", 200, 0.01);
+
+        let output = metrics.render();
+        assert!(output.contains("path=\"/a\""));
+        assert!(output.contains("path=\"/b\""));
+        assert!(!output.contains("path=\"/c\""));
+        assert!(output.contains(&format!("path=\"{}\"", OTHER_PATH_LABEL)));
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_metrics() {
+        use crate::{config::CircuitBreakerConfig, resilience::CircuitBreaker};
+
+        let breaker = CircuitBreaker::new(
+            "metrics-test",
+            &CircuitBreakerConfig {
+                enabled: true,
+                failure_threshold: 1,
+                open_secs: 60,
+                half_open_probes: 1,
+            },
+        );
+        let _ = breaker
+            .call(async { Err::<(), _>(crate::Error::Internal("down".to_string())) })
+            .await;
+        let _ = breaker.call(async { Ok(()) }).await;
+
+        let output = metrics(10).render();
+        assert!(output.contains("circuit_breaker_state{name=\"metrics-test\"} 1"));
+        assert!(output.contains("circuit_breaker_rejected_total{name=\"metrics-test\"} 1"));
+    }
+}
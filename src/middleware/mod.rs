@@ -1,16 +1,125 @@
 //! 中间件管理模块
 
+pub mod body_log;
+pub mod cache_control;
+pub mod cache_validation;
+pub mod cancellation;
+pub mod catch_panic;
+pub mod client_ip;
+pub mod compression;
+pub mod cookies;
 pub mod cors;
+pub mod csrf;
+pub mod deprecation;
+#[cfg(feature = "templates")]
+pub mod error_pages;
+pub mod interceptor;
+pub mod isolation;
 pub mod jwt;
+pub mod limits;
 pub mod logging;
+pub mod matcher;
+pub mod metrics;
+#[cfg(feature = "otel")]
+pub mod otel;
+pub mod request_id;
+pub mod request_scope;
+pub mod sampling;
+pub mod scoped;
+pub mod security;
+pub mod session;
 pub mod static_files;
+pub mod trace_context;
+
+#[cfg(feature = "jwks")]
+pub mod jwks;
+#[cfg(feature = "syslog")]
+pub mod syslog;
 
 use crate::{
     builder::MiddlewareFactory,
     config::Config,
-    error::Result,
+    error::{Error, ErrorHandler, Result},
+};
+use axum::{
+    extract::Request,
+    response::IntoResponse,
+    routing::Route,
+    Router,
 };
-use axum::Router;
+use std::{collections::HashSet, convert::Infallible, fmt, sync::Arc};
+use tower::{Layer, Service};
+
+pub use matcher::RequestMatcher;
+
+/// 常量时间比较，避免通过响应时间猜测令牌
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// 只对匹配的请求应用中间件层
+///
+/// 其他请求跳过该层直接进入 `app`，例如只为 `/api/*` 的 GET 请求记录追踪：
+///
+/// ```rust
+/// use hwhkit::middleware::{when, RequestMatcher};
+/// use axum::{http::Method, routing::get, Router};
+/// use tower_http::trace::TraceLayer;
+///
+/// let app: Router = Router::new().route("/api/users", get(|| async { "users" }));
+/// let app = when(
+///     app,
+///     RequestMatcher::new().path("/api/*").method(Method::GET),
+///     TraceLayer::new_for_http(),
+/// );
+/// ```
+pub fn when<L>(app: Router, matcher: RequestMatcher, layer: L) -> Router
+where
+    L: Layer<Route> + Clone + Send + 'static,
+    L::Service: Service<Request> + Clone + Send + 'static,
+    <L::Service as Service<Request>>::Response: IntoResponse + 'static,
+    <L::Service as Service<Request>>::Error: Into<Infallible> + 'static,
+    <L::Service as Service<Request>>::Future: Send + 'static,
+{
+    let layered = app.clone().layer(layer);
+    let mut stacks = scoped::ScopedStacks::default();
+    stacks.push(matcher, layered);
+    app.layer(axum::middleware::from_fn_with_state(Arc::new(stacks), scoped::scoped_middleware))
+}
+
+/// 按条件应用的中间件层，通过 `WebServerBuilder::layer_when` 添加
+pub struct ConditionalLayer {
+    matcher: RequestMatcher,
+    apply: Box<dyn Fn(Router) -> Router + Send + Sync>,
+}
+
+impl ConditionalLayer {
+    /// 创建按条件应用的中间件层
+    pub fn new<L>(matcher: RequestMatcher, layer: L) -> Self
+    where
+        L: Layer<Route> + Clone + Send + Sync + 'static,
+        L::Service: Service<Request> + Clone + Send + 'static,
+        <L::Service as Service<Request>>::Response: IntoResponse + 'static,
+        <L::Service as Service<Request>>::Error: Into<Infallible> + 'static,
+        <L::Service as Service<Request>>::Future: Send + 'static,
+    {
+        let apply_matcher = matcher.clone();
+        Self {
+            matcher,
+            apply: Box::new(move |app| when(app, apply_matcher.clone(), layer.clone())),
+        }
+    }
+
+    fn apply(&self, app: Router) -> Router {
+        (self.apply)(app)
+    }
+}
+
+impl fmt::Debug for ConditionalLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConditionalLayer").field("matcher", &self.matcher).finish()
+    }
+}
 
 /// 中间件管理器
 /// 
@@ -19,6 +128,13 @@ use axum::Router;
 pub struct MiddlewareManager {
     config: Config,
     custom_middleware: Vec<Box<dyn MiddlewareFactory>>,
+    interceptors: interceptor::Interceptors,
+    error_handler: Option<ErrorHandler>,
+    panic_hooks: catch_panic::PanicHooks,
+    session_store: Option<std::sync::Arc<dyn session::SessionStore>>,
+    conditional_layers: Vec<ConditionalLayer>,
+    #[cfg(feature = "templates")]
+    template_engine: Option<Arc<crate::templates::TemplateEngine>>,
 }
 
 impl MiddlewareManager {
@@ -27,23 +143,110 @@ impl MiddlewareManager {
         Self {
             config,
             custom_middleware: Vec::new(),
+            interceptors: interceptor::Interceptors::default(),
+            error_handler: None,
+            panic_hooks: catch_panic::PanicHooks::default(),
+            session_store: None,
+            conditional_layers: Vec::new(),
+            #[cfg(feature = "templates")]
+            template_engine: None,
         }
     }
 
+    /// 添加按条件应用的中间件层
+    pub fn add_conditional_layer(&mut self, layer: ConditionalLayer) {
+        self.conditional_layers.push(layer);
+    }
+
     /// 添加自定义中间件
     pub fn add_custom_middleware(&mut self, middleware: Box<dyn MiddlewareFactory>) {
         self.custom_middleware.push(middleware);
     }
 
+    /// 设置请求/响应拦截器
+    pub fn set_interceptors(&mut self, interceptors: interceptor::Interceptors) {
+        self.interceptors = interceptors;
+    }
+
+    /// 设置错误处理函数，替换 [`Error`] 生成的默认响应
+    pub fn set_error_handler(&mut self, handler: ErrorHandler) {
+        self.error_handler = Some(handler);
+    }
+
+    /// 设置处理函数 panic 时调用的回调
+    pub fn set_panic_hooks(&mut self, hooks: catch_panic::PanicHooks) {
+        self.panic_hooks = hooks;
+    }
+
+    /// 设置会话存储，覆盖配置中的 `store`
+    pub fn set_session_store(&mut self, store: std::sync::Arc<dyn session::SessionStore>) {
+        self.session_store = Some(store);
+    }
+
+    /// 设置模板引擎，用于渲染 `[errors.pages]` 中的错误页面
+    #[cfg(feature = "templates")]
+    pub fn set_template_engine(&mut self, engine: Arc<crate::templates::TemplateEngine>) {
+        self.template_engine = Some(engine);
+    }
+
     /// 应用所有中间件到路由器
     pub async fn apply_middleware(&self, mut app: Router) -> Result<Router> {
-        // 应用日志中间件
-        if self.config.middleware.logging.requests {
+        // 按路径应用的中间件不再全局应用
+        let scoped = self.scoped_middleware()?;
+        let global = |name: &str| !scoped.contains(name);
+
+        let metrics = self
+            .config
+            .telemetry
+            .metrics
+            .enabled
+            .then(|| std::sync::Arc::new(metrics::HttpMetrics::new(&self.config.telemetry.metrics)));
+
+        // 指标导出路由需要在应用中间件之前注册
+        if let Some(metrics) = &metrics {
+            app = app.route(
+                &self.config.telemetry.metrics.path,
+                axum::routing::get(metrics::metrics_handler).with_state(metrics.clone()),
+            );
+        }
+
+        // 应用 panic 捕获中间件（最内层，panic 产生的错误响应仍经过其他中间件）
+        if self.config.middleware.catch_panic.enabled {
+            app = app.layer(axum::middleware::from_fn_with_state(
+                Arc::new(self.panic_hooks.clone()),
+                catch_panic::catch_panic_middleware,
+            ));
+        } else if !self.panic_hooks.is_empty() {
+            tracing::warn!("未启用 [middleware.catch_panic]，on_panic 注册的回调不会被调用");
+        }
+
+        // 应用请求隔离中间件（超时和内存超限产生的错误响应仍经过其他中间件）
+        if self.config.middleware.isolation.enabled && global("isolation") {
+            app = self.apply_named(app, "isolation")?;
+        }
+
+        // 应用请求/响应体日志中间件，位于日志中间件内层以便记录在请求 span 中
+        let bodies = &self.config.middleware.logging.bodies;
+        if bodies.enabled {
+            app = app.layer(axum::middleware::from_fn_with_state(
+                std::sync::Arc::new(bodies.clone()),
+                body_log::body_log_middleware,
+            ));
+        }
+
+        // 应用日志中间件，导出追踪时同样需要请求 span
+        if self.config.middleware.logging.requests || self.config.middleware.tracing.enabled {
             app = self.apply_logging_middleware(app)?;
         }
 
+        // 应用 JWT 认证中间件，位于 CORS 内层以便预检请求和 401 响应都带有 CORS 头
+        let jwt = &self.config.middleware.jwt;
+        if jwt.enabled && !jwt.protected_paths.is_empty() && global("jwt") {
+            app = jwt::apply_jwt(app, jwt)?;
+        }
+
         // 应用 CORS 中间件
-        if self.config.middleware.cors.enabled {
+        if self.config.middleware.cors.enabled && global("cors") {
             app = self.apply_cors_middleware(app)?;
         }
 
@@ -52,6 +255,108 @@ impl MiddlewareManager {
             app = self.apply_static_files_middleware(app).await?;
         }
 
+        // 应用按路径设置的缓存策略，位于条件请求中间件内层以便 304 响应同样带有缓存策略
+        if !self.config.middleware.cache_control.is_empty() {
+            let rules = cache_control::CacheControlRules::new(&self.config.middleware.cache_control)?;
+            app = app.layer(axum::middleware::from_fn_with_state(
+                Arc::new(rules),
+                cache_control::cache_control_middleware,
+            ));
+        }
+
+        // 应用条件请求中间件，位于压缩中间件内层以便按原始内容生成 ETag
+        let cache_validation = &self.config.middleware.cache_validation;
+        if cache_validation.enabled {
+            let mut cache_validation = cache_validation.clone();
+            // 压缩后的内容与原始内容不同，只能使用弱 ETag
+            cache_validation.weak |= self.config.middleware.compression.enabled;
+            app = app.layer(axum::middleware::from_fn_with_state(
+                Arc::new(cache_validation),
+                cache_validation::cache_validation_middleware,
+            ));
+        }
+
+        // 应用会话中间件
+        if self.config.middleware.session.enabled && global("session") {
+            app = self.apply_named(app, "session")?;
+        }
+
+        // 应用 CSRF 防护中间件
+        if self.config.middleware.csrf.enabled && global("csrf") {
+            app = self.apply_named(app, "csrf")?;
+        }
+
+        // 应用错误处理函数，位于错误页面中间件内层，认证、会话和 CSRF 产生的错误同样经过它
+        if let Some(handler) = &self.error_handler {
+            app = app.layer(axum::middleware::from_fn_with_state(
+                handler.clone(),
+                crate::error::error_handler_middleware,
+            ));
+        }
+
+        // 应用自定义错误页面中间件，位于多语言中间件内层以便错误页面使用 t()
+        if !self.config.errors.pages.is_empty() {
+            #[cfg(feature = "templates")]
+            {
+                let engine = self.template_engine.clone().ok_or_else(|| {
+                    Error::Config(
+                        "自定义错误页面需要启用 [middleware.templates] 且使用运行时模板引擎".to_string(),
+                    )
+                })?;
+                let pages = error_pages::ErrorPages::new(&self.config.errors, engine)?;
+                app = app.layer(axum::middleware::from_fn_with_state(
+                    Arc::new(pages),
+                    error_pages::error_pages_middleware,
+                ));
+            }
+            #[cfg(not(feature = "templates"))]
+            return Err(Error::Config("自定义错误页面需要启用 `templates` feature".to_string()));
+        }
+
+        // 应用多语言中间件
+        if self.config.middleware.i18n.enabled {
+            #[cfg(feature = "i18n")]
+            {
+                let translations = crate::i18n::Translations::load(&self.config.middleware.i18n)?;
+                app = app.layer(axum::middleware::from_fn_with_state(
+                    Arc::new(translations),
+                    crate::i18n::i18n_middleware,
+                ));
+            }
+            #[cfg(not(feature = "i18n"))]
+            return Err(Error::Config("多语言需要启用 `i18n` feature".to_string()));
+        }
+
+        // 应用响应压缩中间件
+        if self.config.middleware.compression.enabled && global("compression") {
+            app = self.apply_named(app, "compression")?;
+        }
+
+        // 按路径应用中间件（位于全局中间件外层，路径内的 CORS 头也会加到全局 JWT 的 401 响应上）
+        if !self.config.middleware.routes.is_empty() {
+            let mut stacks = scoped::ScopedStacks::default();
+            for route in &self.config.middleware.routes {
+                let matcher = RequestMatcher::from_config(route)?;
+                let mut stack = app.clone();
+                for name in scoped::SCOPED_MIDDLEWARE {
+                    if route.apply.iter().any(|n| n == name) {
+                        stack = self.apply_named(stack, name)?;
+                    }
+                }
+                tracing::info!("路径 {} 应用中间件: {:?}", route.path, route.apply);
+                stacks.push(matcher, stack);
+            }
+            app = app.layer(axum::middleware::from_fn_with_state(
+                Arc::new(stacks),
+                scoped::scoped_middleware,
+            ));
+        }
+
+        // 应用按条件添加的中间件层
+        for layer in &self.conditional_layers {
+            app = layer.apply(app);
+        }
+
         // 应用自定义中间件
         for middleware in &self.custom_middleware {
             tracing::info!("应用自定义中间件: {}", middleware.name());
@@ -59,22 +364,190 @@ impl MiddlewareManager {
             // 现在先简单处理
         }
 
+        // 应用拦截器
+        if !self.interceptors.is_empty() {
+            let interceptors = self.interceptors.configured(&self.config.middleware.interceptors);
+            tracing::info!("应用拦截器: {:?}", interceptors);
+            app = app.layer(axum::middleware::from_fn_with_state(
+                std::sync::Arc::new(interceptors),
+                interceptor::interceptor_middleware,
+            ));
+        }
+
+        // 应用接口弃用中间件（位于指标中间件内层，以便统计弃用接口的调用量）
+        if !self.config.middleware.deprecations.is_empty() {
+            let deprecations = deprecation::Deprecations::new(&self.config.middleware.deprecations)?;
+            app = app.layer(axum::middleware::from_fn_with_state(
+                std::sync::Arc::new(deprecations),
+                deprecation::deprecation_middleware,
+            ));
+        }
+
+        // 应用请求作用域中间件（拦截器和自定义中间件可以共享延迟计算的值）
+        app = app.layer(axum::middleware::from_fn(request_scope::request_scope_middleware));
+
+        // 应用并发限制（位于指标和访问日志中间件内层，以便统计被拒绝的请求）
+        app = limits::apply_limits(app, &self.config.server.limits);
+
+        // 应用安全响应头中间件（位于并发限制外层，被拒绝的请求同样带有安全响应头）
+        let security_headers = security::SecurityHeaders::new(&self.config.middleware.security)?;
+        if !security_headers.is_empty() {
+            app = app.layer(axum::middleware::from_fn_with_state(
+                std::sync::Arc::new(security_headers),
+                security::security_headers_middleware,
+            ));
+        }
+
+        // 应用指标中间件
+        if let Some(metrics) = metrics {
+            app = app.layer(axum::middleware::from_fn_with_state(
+                metrics,
+                metrics::metrics_middleware,
+            ));
+        }
+
+        // 应用访问日志中间件（最外层，覆盖 CORS 预检和静态文件等所有请求）
+        if self.config.middleware.logging.access_log.enabled {
+            app = self.apply_access_log_middleware(app)?;
+        }
+
+        // 应用请求取消中间件
+        app = app.layer(axum::middleware::from_fn(cancellation::cancellation_middleware));
+
+        // 解析客户端地址，供追踪 span 和访问日志使用
+        let proxies = client_ip::TrustedProxies::new(&self.config.server.proxy)?;
+        app = app.layer(axum::middleware::from_fn_with_state(
+            Arc::new(proxies),
+            client_ip::client_ip_middleware,
+        ));
+
+        // 应用追踪上下文和请求 ID 中间件（最外层，供追踪 span 和访问日志使用）
+        app = app.layer(axum::middleware::from_fn(trace_context::trace_context_middleware));
+        if self.config.middleware.request_id.enabled {
+            let header = request_id::header_name(&self.config.middleware.request_id)?;
+            app = app.layer(axum::middleware::from_fn_with_state(
+                header,
+                request_id::request_id_middleware,
+            ));
+        }
+
         Ok(app)
     }
 
+    /// 检查 `[[middleware.routes]]` 并返回按路径应用的中间件名称
+    fn scoped_middleware(&self) -> Result<HashSet<&str>> {
+        let mut names = HashSet::new();
+        for route in &self.config.middleware.routes {
+            for name in &route.apply {
+                if !scoped::SCOPED_MIDDLEWARE.contains(&name.as_str()) {
+                    return Err(Error::Config(format!(
+                        "路径 {} 配置了未知的中间件 '{}'，可选: {}",
+                        route.path,
+                        name,
+                        scoped::SCOPED_MIDDLEWARE.join(", ")
+                    )));
+                }
+                names.insert(name.as_str());
+            }
+        }
+        Ok(names)
+    }
+
+    /// 按名称应用中间件，名称见 [`scoped::SCOPED_MIDDLEWARE`]
+    ///
+    /// 按路径应用的 JWT 认证保护该路径下的所有请求
+    fn apply_named(&self, app: Router, name: &str) -> Result<Router> {
+        let middleware = &self.config.middleware;
+        match name {
+            "isolation" => {
+                let isolation = &middleware.isolation;
+                if isolation.max_allocation_bytes > 0 && !isolation::GuardedAllocator::is_installed() {
+                    tracing::warn!("未注册 GuardedAllocator 全局分配器，max_allocation_bytes 不会生效");
+                }
+                Ok(app.layer(axum::middleware::from_fn_with_state(
+                    std::sync::Arc::new(isolation.clone()),
+                    isolation::isolation_middleware,
+                )))
+            }
+            "jwt" => {
+                let config = crate::config::JwtConfig {
+                    protected_paths: vec!["*".to_string()],
+                    ..middleware.jwt.clone()
+                };
+                jwt::apply_jwt(app, &config)
+            }
+            "cors" => self.apply_cors_middleware(app),
+            "session" => session::apply_session(
+                app,
+                &middleware.session,
+                &self.config.cookies,
+                self.session_store.clone(),
+            ),
+            "csrf" => {
+                let csrf = csrf::CsrfProtection::new(&middleware.csrf, &self.config.cookies)?;
+                Ok(app.layer(axum::middleware::from_fn_with_state(
+                    std::sync::Arc::new(csrf),
+                    csrf::csrf_middleware,
+                )))
+            }
+            "compression" => compression::apply_compression(app, &middleware.compression),
+            _ => Err(Error::Config(format!("未知的中间件 '{}'", name))),
+        }
+    }
+
     /// 应用日志中间件
     fn apply_logging_middleware(&self, app: Router) -> Result<Router> {
         use tower_http::trace::TraceLayer;
         
+        let sampler = sampling::TraceSampler::new(&self.config.telemetry.sampling);
+
         let trace_layer = TraceLayer::new_for_http()
-            .make_span_with(|request: &axum::http::Request<_>| {
-                tracing::info_span!(
+            .make_span_with(move |request: &axum::http::Request<_>| {
+                // 未被采样的请求不创建追踪 span
+                if !sampler.should_sample(request.uri().path()) {
+                    return tracing::Span::none();
+                }
+
+                // span 内的所有日志都会带上 trace_id/span_id
+                let context = request
+                    .extensions()
+                    .get::<trace_context::TraceContext>()
+                    .cloned()
+                    .unwrap_or_else(|| trace_context::TraceContext::from_headers(request.headers()));
+
+                let request_id = request
+                    .extensions()
+                    .get::<request_id::RequestId>()
+                    .map(request_id::RequestId::as_str)
+                    .unwrap_or("-");
+
+                let client_ip = request
+                    .extensions()
+                    .get::<client_ip::ClientIp>()
+                    .map(ToString::to_string)
+                    .unwrap_or_else(|| "-".to_string());
+
+                let route = matcher::matched_route(request.extensions());
+                let span = tracing::info_span!(
                     "http_request",
                     method = %request.method(),
                     uri = %request.uri(),
-                )
+                    route = route.unwrap_or("-"),
+                    request_id = %request_id,
+                    client_ip = %client_ip,
+                    trace_id = %context.trace_id,
+                    span_id = %context.span_id,
+                    "otel.name" = %format!("{} {}", request.method(), route.unwrap_or(request.uri().path())),
+                    "otel.kind" = "server",
+                    "http.route" = route,
+                    "http.response.status_code" = tracing::field::Empty,
+                );
+                #[cfg(feature = "otel")]
+                otel::set_parent(&span, &context);
+                span
             })
-            .on_response(|response: &axum::http::Response<_>, latency: std::time::Duration, _span: &tracing::Span| {
+            .on_response(|response: &axum::http::Response<_>, latency: std::time::Duration, span: &tracing::Span| {
+                span.record("http.response.status_code", response.status().as_u16());
                 tracing::info!(
                     status = %response.status(),
                     latency = ?latency,
@@ -85,6 +558,11 @@ impl MiddlewareManager {
         Ok(app.layer(trace_layer))
     }
 
+    /// 应用访问日志中间件
+    fn apply_access_log_middleware(&self, app: Router) -> Result<Router> {
+        Ok(app.layer(axum::middleware::from_fn(logging::access_log_middleware)))
+    }
+
     /// 应用 CORS 中间件
     fn apply_cors_middleware(&self, app: Router) -> Result<Router> {
         let cors_config = &self.config.middleware.cors;
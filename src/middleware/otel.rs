@@ -0,0 +1,135 @@
+//! OpenTelemetry 追踪导出模块
+//!
+//! 启用 `[middleware.tracing]` 后，请求追踪 span 通过 OTLP/HTTP 导出，span 带有
+//! `http.route`、`http.response.status_code` 和 `request_id` 属性。请求携带 `traceparent`
+//! 时导出的 span 挂在上游 span 之下，与日志中的 `trace_id` 一致。
+//!
+//! ```toml
+//! [middleware.tracing]
+//! enabled = true
+//! endpoint = "http://otel-collector:4318/v1/traces"
+//! service_name = "my-api"
+//! sample_ratio = 0.1
+//! ```
+
+use super::trace_context::TraceContext;
+use crate::{
+    config::TracingConfig,
+    error::{Error, Result},
+};
+use opentelemetry::{
+    trace::{SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState, TracerProvider},
+    Context,
+};
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::{
+    trace::{Sampler, SdkTracerProvider},
+    Resource,
+};
+use std::fmt;
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::{registry::LookupSpan, Layer};
+
+/// 导出器的 guard，释放时导出剩余的 span 并关闭导出器
+pub struct OtelGuard {
+    provider: SdkTracerProvider,
+}
+
+impl fmt::Debug for OtelGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OtelGuard").finish_non_exhaustive()
+    }
+}
+
+impl Drop for OtelGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.provider.shutdown() {
+            eprintln!("关闭 OpenTelemetry 导出器失败: {}", e);
+        }
+    }
+}
+
+/// 创建 OTLP 导出层
+///
+/// 返回的 [`OtelGuard`] 需要在服务器运行期间保持存活
+///
+/// # Errors
+///
+/// 无法创建导出器时返回配置错误
+pub fn create_otel_layer<S>(
+    config: &TracingConfig,
+) -> Result<(Box<dyn Layer<S> + Send + Sync>, OtelGuard)>
+where
+    S: Subscriber + for<'a> LookupSpan<'a> + Send + Sync,
+{
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(&config.endpoint)
+        .build()
+        .map_err(|e| Error::Config(format!("无法创建 OTLP 导出器: {}", e)))?;
+
+    // 上游已做出采样决定时沿用，否则按比例采样
+    let sampler = Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(config.sample_ratio)));
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_sampler(sampler)
+        .with_resource(Resource::builder().with_service_name(config.service_name.clone()).build())
+        .build();
+
+    let tracer = provider.tracer("hwhkit");
+    let layer = tracing_opentelemetry::layer().with_tracer(tracer).boxed();
+    Ok((layer, OtelGuard { provider }))
+}
+
+/// 将请求 span 挂到 `traceparent` 指定的上游 span 之下
+pub fn set_parent(span: &tracing::Span, context: &TraceContext) {
+    let Some(parent_span_id) = &context.parent_span_id else {
+        return;
+    };
+    let (Ok(trace_id), Ok(span_id)) = (
+        TraceId::from_hex(&context.trace_id),
+        SpanId::from_hex(parent_span_id),
+    ) else {
+        return;
+    };
+
+    let flags = if context.sampled { TraceFlags::SAMPLED } else { TraceFlags::default() };
+    let state = context
+        .tracestate
+        .as_deref()
+        .and_then(|value| value.parse::<TraceState>().ok())
+        .unwrap_or_default();
+    let parent = SpanContext::new(trace_id, span_id, flags, true, state);
+    span.set_parent(Context::new().with_remote_span_context(parent));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::prelude::*;
+
+    #[test]
+    fn test_span_inherits_upstream_trace() {
+        let provider = SdkTracerProvider::builder().build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let context = TraceContext {
+                trace_id: "4bf92f3577b34da6a3ce929d0e0e4736".to_string(),
+                span_id: "00f067aa0ba902b8".to_string(),
+                parent_span_id: Some("00f067aa0ba902b7".to_string()),
+                sampled: true,
+                tracestate: Some("congo=t61rcWkgMzE".to_string()),
+            };
+            let span = tracing::info_span!("http_request");
+            set_parent(&span, &context);
+
+            let otel_context = span.context();
+            let span_context = otel_context.span().span_context().clone();
+            assert_eq!(span_context.trace_id().to_string(), context.trace_id);
+            assert_eq!(span_context.trace_state().get("congo"), Some("t61rcWkgMzE"));
+        });
+    }
+}
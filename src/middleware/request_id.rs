@@ -0,0 +1,154 @@
+//! 请求 ID 中间件模块
+//!
+//! 沿用客户端或上游代理传入的请求 ID，缺失或无效时生成 UUID v4。
+//! 请求 ID 会写入追踪 span 和访问日志，并在响应头中返回。
+
+use crate::{
+    config::RequestIdConfig,
+    error::{Error, Result},
+};
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request, State},
+    http::{request::Parts, HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use std::fmt;
+
+/// 请求 ID 最大长度，超出时重新生成
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// 请求 ID
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(String);
+
+impl RequestId {
+    /// 生成新的请求 ID（UUID v4）
+    pub fn generate() -> Self {
+        let mut bytes = [0u8; 16];
+        bytes.iter_mut().for_each(|b| *b = fastrand::u8(..));
+        bytes[6] = (bytes[6] & 0x0f) | 0x40;
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+        let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        Self(format!(
+            "{}-{}-{}-{}-{}",
+            &hex[0..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..32]
+        ))
+    }
+
+    /// 从请求头的值解析，只接受可见 ASCII 字符
+    pub fn parse(value: &str) -> Option<Self> {
+        let valid = !value.is_empty()
+            && value.len() <= MAX_REQUEST_ID_LEN
+            && value.bytes().all(|b| b.is_ascii_graphic());
+        valid.then(|| Self(value.to_string()))
+    }
+
+    /// 请求 ID 字符串
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for RequestId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for RequestId {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+        parts
+            .extensions
+            .get::<RequestId>()
+            .cloned()
+            .ok_or_else(|| Error::Internal("请求 ID 中间件未启用".to_string()))
+    }
+}
+
+/// 根据配置解析请求 ID 头名称
+pub fn header_name(config: &RequestIdConfig) -> Result<HeaderName> {
+    HeaderName::from_bytes(config.header.as_bytes())
+        .map_err(|e| Error::Config(format!("无效的请求 ID 头 '{}': {}", config.header, e)))
+}
+
+/// 请求 ID 中间件
+pub async fn request_id_middleware(
+    State(header): State<HeaderName>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let request_id = request
+        .headers()
+        .get(&header)
+        .and_then(|value| value.to_str().ok())
+        .and_then(RequestId::parse)
+        .unwrap_or_else(RequestId::generate);
+
+    request.extensions_mut().insert(request_id.clone());
+
+    let mut response = next.run(request).await;
+    if let Ok(value) = HeaderValue::from_str(request_id.as_str()) {
+        response.headers_mut().insert(header, value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get, Router};
+    use tower::util::ServiceExt;
+
+    fn app() -> Router {
+        let header = header_name(&RequestIdConfig::default()).unwrap();
+        Router::new()
+            .route("/", get(|id: RequestId| async move { id.to_string() }))
+            .layer(axum::middleware::from_fn_with_state(header, request_id_middleware))
+    }
+
+    async fn call(request: Request) -> (String, String) {
+        let response = app().oneshot(request).await.unwrap();
+        let header = response.headers()["x-request-id"].to_str().unwrap().to_string();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (header, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_request_id_generated() {
+        let (header, body) = call(Request::builder().uri("/").body(Body::empty()).unwrap()).await;
+        assert_eq!(header, body);
+        assert_eq!(header.len(), 36);
+        assert_eq!(&header[14..15], "4");
+    }
+
+    #[tokio::test]
+    async fn test_request_id_propagated() {
+        let request = Request::builder()
+            .uri("/")
+            .header("x-request-id", "upstream-123")
+            .body(Body::empty())
+            .unwrap();
+        let (header, body) = call(request).await;
+        assert_eq!(header, "upstream-123");
+        assert_eq!(body, "upstream-123");
+
+        // 无效的请求 ID 会被替换
+        let request = Request::builder()
+            .uri("/")
+            .header("x-request-id", "a".repeat(200))
+            .body(Body::empty())
+            .unwrap();
+        let (header, _) = call(request).await;
+        assert_eq!(header.len(), 36);
+    }
+}
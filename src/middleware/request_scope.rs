@@ -0,0 +1,272 @@
+//! 请求作用域模块
+//!
+//! 当前用户、租户设置等开销较大的查询在每个请求中只计算一次，结果在中间件、
+//! 拦截器、提取器和模板之间共享。
+//!
+//! ```rust
+//! use hwhkit::{
+//!     async_trait,
+//!     middleware::request_scope::{Lazy, LazyValue},
+//!     Result,
+//! };
+//! use axum::http::request::Parts;
+//!
+//! #[derive(Clone)]
+//! struct CurrentUser {
+//!     name: String,
+//! }
+//!
+//! #[async_trait]
+//! impl LazyValue for CurrentUser {
+//!     async fn compute(parts: &Parts) -> Result<Self> {
+//!         // 查询数据库等，同一请求中只执行一次
+//!         Ok(CurrentUser { name: "alice".to_string() })
+//!     }
+//! }
+//!
+//! async fn profile(Lazy(user): Lazy<CurrentUser>) -> String {
+//!     user.name
+//! }
+//! ```
+
+use crate::error::{Error, Result};
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request},
+    http::request::Parts,
+    middleware::Next,
+    response::Response,
+};
+use serde::Serialize;
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt,
+    future::Future,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::OnceCell;
+
+tokio::task_local! {
+    static CURRENT_SCOPE: RequestScope;
+}
+
+/// 当前请求的作用域
+///
+/// 只能在请求作用域中间件处理的请求中调用，否则返回 `None`
+pub fn current_scope() -> Option<RequestScope> {
+    CURRENT_SCOPE.try_with(Clone::clone).ok()
+}
+
+#[derive(Default)]
+struct ScopeState {
+    values: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+    globals: HashMap<String, serde_json::Value>,
+}
+
+/// 请求作用域
+///
+/// 按类型缓存值，请求内的所有克隆共享同一份数据
+#[derive(Clone, Default)]
+pub struct RequestScope {
+    state: Arc<Mutex<ScopeState>>,
+}
+
+impl RequestScope {
+    fn lock(&self) -> std::sync::MutexGuard<'_, ScopeState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn cell<T: Send + Sync + 'static>(&self) -> Arc<OnceCell<T>> {
+        let cell = self
+            .lock()
+            .values
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Arc::new(OnceCell::<T>::new()))
+            .clone();
+        // 按 TypeId 存取，类型必然一致
+        cell.downcast::<OnceCell<T>>()
+            .unwrap_or_else(|_| unreachable!("请求作用域中的类型不一致"))
+    }
+
+    /// 读取已计算的值
+    pub fn get<T: Clone + Send + Sync + 'static>(&self) -> Option<T> {
+        self.cell::<T>().get().cloned()
+    }
+
+    /// 读取值，尚未计算时调用 `init`
+    ///
+    /// 并发调用时 `init` 只会执行一次
+    pub async fn get_or_init<T, F, Fut>(&self, init: F) -> T
+    where
+        T: Clone + Send + Sync + 'static,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = T>,
+    {
+        self.cell::<T>().get_or_init(init).await.clone()
+    }
+
+    /// 读取值，尚未计算时调用 `init`，失败时不缓存
+    pub async fn get_or_try_init<T, E, F, Fut>(&self, init: F) -> std::result::Result<T, E>
+    where
+        T: Clone + Send + Sync + 'static,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = std::result::Result<T, E>>,
+    {
+        self.cell::<T>().get_or_try_init(init).await.cloned()
+    }
+
+    /// 将值提供给模板，模板中使用 `{{ request_value(name="...") }}` 读取
+    pub fn expose<V: Serialize>(&self, name: &str, value: &V) -> Result<()> {
+        let value = serde_json::to_value(value)?;
+        self.lock().globals.insert(name.to_string(), value);
+        Ok(())
+    }
+
+    /// 读取提供给模板的值
+    pub fn global(&self, name: &str) -> Option<serde_json::Value> {
+        self.lock().globals.get(name).cloned()
+    }
+}
+
+impl fmt::Debug for RequestScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.lock();
+        f.debug_struct("RequestScope")
+            .field("values", &state.values.len())
+            .field("globals", &state.globals.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for RequestScope {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+        parts
+            .extensions
+            .get::<RequestScope>()
+            .cloned()
+            .ok_or_else(|| Error::Internal("请求作用域中间件未启用".to_string()))
+    }
+}
+
+/// 按请求延迟计算的值
+#[async_trait]
+pub trait LazyValue: Clone + Send + Sync + 'static {
+    /// 计算值，每个请求最多成功执行一次
+    async fn compute(parts: &Parts) -> Result<Self>;
+}
+
+/// 延迟计算值的提取器
+///
+/// 中间件和拦截器中可以使用 `Lazy::<T>::resolve(&parts)` 读取同一个值
+#[derive(Debug, Clone)]
+pub struct Lazy<T>(pub T);
+
+impl<T: LazyValue> Lazy<T> {
+    /// 读取或计算当前请求中的值
+    pub async fn resolve(parts: &Parts) -> Result<T> {
+        let scope = parts
+            .extensions
+            .get::<RequestScope>()
+            .cloned()
+            .ok_or_else(|| Error::Internal("请求作用域中间件未启用".to_string()))?;
+        scope.get_or_try_init(|| T::compute(parts)).await
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync, T: LazyValue> FromRequestParts<S> for Lazy<T> {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+        Self::resolve(parts).await.map(Lazy)
+    }
+}
+
+/// 请求作用域中间件
+pub async fn request_scope_middleware(mut request: Request, next: Next) -> Response {
+    let scope = RequestScope::default();
+    request.extensions_mut().insert(scope.clone());
+    CURRENT_SCOPE.scope(scope, next.run(request)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get, Router};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tower::util::ServiceExt;
+
+    static COMPUTED: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Debug, Clone)]
+    struct Tenant(String);
+
+    #[async_trait]
+    impl LazyValue for Tenant {
+        async fn compute(parts: &Parts) -> Result<Self> {
+            COMPUTED.fetch_add(1, Ordering::SeqCst);
+            parts
+                .headers
+                .get("x-tenant")
+                .and_then(|v| v.to_str().ok())
+                .map(|v| Tenant(v.to_string()))
+                .ok_or_else(|| Error::BadRequest("缺少租户".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lazy_value_computed_once() {
+        async fn check_tenant(request: Request, next: Next) -> Response {
+            let (parts, body) = request.into_parts();
+            let tenant = Lazy::<Tenant>::resolve(&parts).await.unwrap();
+            assert_eq!(tenant.0, "acme");
+            next.run(Request::from_parts(parts, body)).await
+        }
+
+        let app = Router::new()
+            .route(
+                "/",
+                get(|Lazy(a): Lazy<Tenant>, Lazy(b): Lazy<Tenant>| async move {
+                    format!("{} {}", a.0, b.0)
+                }),
+            )
+            .layer(axum::middleware::from_fn(check_tenant))
+            .layer(axum::middleware::from_fn(request_scope_middleware));
+
+        let request = Request::builder()
+            .uri("/")
+            .header("x-tenant", "acme")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+        assert_eq!(&body[..], b"acme acme");
+        assert_eq!(COMPUTED.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failed_compute_not_cached() {
+        let scope = RequestScope::default();
+        let failed: std::result::Result<u32, &str> = scope.get_or_try_init(|| async { Err("boom") }).await;
+        assert!(failed.is_err());
+        assert_eq!(scope.get::<u32>(), None);
+
+        assert_eq!(scope.get_or_init(|| async { 7u32 }).await, 7);
+        assert_eq!(scope.get_or_init(|| async { 8u32 }).await, 7);
+    }
+
+    #[tokio::test]
+    async fn test_missing_middleware() {
+        let app = Router::new().route("/", get(|_: Lazy<Tenant>| async { "ok" }));
+        let response = app
+            .oneshot(Request::builder().uri("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
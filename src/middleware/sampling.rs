@@ -0,0 +1,82 @@
+//! 追踪采样模块
+//!
+//! 根据 `[telemetry.sampling]` 决定请求是否创建追踪 span，
+//! 支持全局采样率和按路由覆盖。
+
+use super::matcher::path_matches;
+use crate::config::SamplingConfig;
+
+/// 请求追踪采样器
+#[derive(Debug, Clone)]
+pub struct TraceSampler {
+    ratio: f64,
+    routes: Vec<(String, f64)>,
+}
+
+impl TraceSampler {
+    /// 根据配置创建采样器
+    pub fn new(config: &SamplingConfig) -> Self {
+        Self {
+            ratio: config.ratio,
+            routes: config
+                .routes
+                .iter()
+                .map(|route| (route.path.clone(), route.ratio))
+                .collect(),
+        }
+    }
+
+    /// 获取路径对应的采样率，按配置顺序使用第一个匹配的路由覆盖
+    pub fn ratio_for(&self, path: &str) -> f64 {
+        self.routes
+            .iter()
+            .find(|(pattern, _)| path_matches(pattern, path))
+            .map(|(_, ratio)| *ratio)
+            .unwrap_or(self.ratio)
+    }
+
+    /// 判断该路径的请求是否应被采样
+    pub fn should_sample(&self, path: &str) -> bool {
+        let ratio = self.ratio_for(path);
+        if ratio >= 1.0 {
+            true
+        } else if ratio <= 0.0 {
+            false
+        } else {
+            fastrand::f64() < ratio
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RouteSamplingConfig;
+
+    fn sampler() -> TraceSampler {
+        TraceSampler::new(&SamplingConfig {
+            ratio: 0.1,
+            routes: vec![
+                RouteSamplingConfig {
+                    path: "/checkout/*".to_string(),
+                    ratio: 1.0,
+                },
+                RouteSamplingConfig {
+                    path: "/healthz".to_string(),
+                    ratio: 0.0,
+                },
+            ],
+        })
+    }
+
+    #[test]
+    fn test_route_overrides() {
+        let sampler = sampler();
+        assert_eq!(sampler.ratio_for("/checkout/confirm"), 1.0);
+        assert_eq!(sampler.ratio_for("/healthz"), 0.0);
+        assert_eq!(sampler.ratio_for("/users"), 0.1);
+
+        assert!((0..100).all(|_| sampler.should_sample("/checkout")));
+        assert!((0..100).all(|_| !sampler.should_sample("/healthz")));
+    }
+}
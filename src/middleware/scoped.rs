@@ -0,0 +1,161 @@
+//! 按路径应用中间件模块
+//!
+//! `[[middleware.routes]]` 和 [`when`](super::when) 为不同的路径和方法配置不同的中间件组合。
+//! 每个条件构建一份应用了对应中间件的路由器，请求分派到第一个匹配的路由器，
+//! 未匹配任何条件的请求直接进入原路由器。
+
+use super::matcher::RequestMatcher;
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+    Router,
+};
+use std::sync::Arc;
+use tower::util::ServiceExt;
+
+/// 可以按路径应用的中间件，按从内到外的应用顺序排列
+pub const SCOPED_MIDDLEWARE: &[&str] = &["isolation", "jwt", "cors", "session", "csrf", "compression"];
+
+/// 按条件分派的中间件组合
+#[derive(Debug, Clone, Default)]
+pub struct ScopedStacks {
+    stacks: Vec<(RequestMatcher, Router)>,
+}
+
+impl ScopedStacks {
+    /// 添加匹配条件及应用了中间件的路由器
+    pub fn push(&mut self, matcher: RequestMatcher, router: Router) {
+        self.stacks.push((matcher, router));
+    }
+
+    fn find(&self, request: &Request) -> Option<&Router> {
+        self.stacks
+            .iter()
+            .find(|(matcher, _)| matcher.matches(request.method(), request.uri().path()))
+            .map(|(_, router)| router)
+    }
+}
+
+/// 按条件分派中间件
+pub async fn scoped_middleware(
+    State(stacks): State<Arc<ScopedStacks>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    match stacks.find(&request) {
+        Some(router) => match router.clone().oneshot(request).await {
+            Ok(response) => response,
+            Err(infallible) => match infallible {},
+        },
+        None => next.run(request).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        config::{Config, RouteMiddlewareConfig},
+        middleware::MiddlewareManager,
+    };
+    use axum::{body::Body, extract::Request, routing::get, Router};
+    use tower::util::ServiceExt;
+
+    fn config(apply: &[&str]) -> Config {
+        let mut config = Config::default();
+        config.middleware.cors.enabled = true;
+        config.middleware.cors.origins = vec!["https://example.com".to_string()];
+        config.middleware.routes = vec![RouteMiddlewareConfig {
+            path: "/api/*".to_string(),
+            methods: Vec::new(),
+            apply: apply.iter().map(|name| name.to_string()).collect(),
+        }];
+        config
+    }
+
+    #[tokio::test]
+    async fn test_middleware_applies_only_to_matching_paths() {
+        let router = Router::new()
+            .route("/api/users", get(|| async { "users" }))
+            .route("/public", get(|| async { "public" }));
+        let app = MiddlewareManager::new(config(&["cors"]))
+            .apply_middleware(router)
+            .await
+            .unwrap();
+
+        let allow_origin = |uri: &str| {
+            let request = Request::builder()
+                .uri(uri)
+                .header("origin", "https://example.com")
+                .body(Body::empty())
+                .unwrap();
+            let app = app.clone();
+            async move {
+                let response = app.oneshot(request).await.unwrap();
+                response.headers().get("access-control-allow-origin").cloned()
+            }
+        };
+
+        assert_eq!(allow_origin("/api/users").await.unwrap(), "https://example.com");
+        // CORS 只按路径应用，不再全局生效
+        assert!(allow_origin("/public").await.is_none());
+    }
+
+    #[cfg(feature = "jwt")]
+    #[tokio::test]
+    async fn test_scoped_jwt() {
+        use axum::http::StatusCode;
+
+        let router = Router::new()
+            .route("/api/users", get(|| async { "users" }))
+            .route("/public", get(|| async { "public" }));
+        let app = MiddlewareManager::new(config(&["jwt"]))
+            .apply_middleware(router)
+            .await
+            .unwrap();
+
+        let status = |uri: &str| {
+            let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+            let app = app.clone();
+            async move { app.oneshot(request).await.unwrap().status() }
+        };
+        assert_eq!(status("/api/users").await, StatusCode::UNAUTHORIZED);
+        assert_eq!(status("/public").await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_when() {
+        use crate::middleware::{when, RequestMatcher};
+        use axum::{http::Method, middleware::Next};
+
+        let router = Router::new()
+            .route("/api/users", get(|| async { "list" }).post(|| async { "create" }))
+            .route("/public", get(|| async { "public" }));
+        let app = when(
+            router,
+            RequestMatcher::new().path("/api/*").method(Method::GET),
+            axum::middleware::from_fn(|request: Request, next: Next| async move {
+                let mut response = next.run(request).await;
+                response.headers_mut().insert("x-layered", "1".parse().unwrap());
+                response
+            }),
+        );
+
+        let layered = |method: Method, uri: &str| {
+            let request = Request::builder().method(method).uri(uri).body(Body::empty()).unwrap();
+            let app = app.clone();
+            async move { app.oneshot(request).await.unwrap().headers().contains_key("x-layered") }
+        };
+        assert!(layered(Method::GET, "/api/users").await);
+        assert!(!layered(Method::POST, "/api/users").await);
+        assert!(!layered(Method::GET, "/public").await);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_middleware() {
+        let result = MiddlewareManager::new(config(&["rate_limit"]))
+            .apply_middleware(Router::new())
+            .await;
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,473 @@
+//! 安全响应头模块
+//!
+//! 启用 HSTS 后，HTTPS 请求的响应带有 `Strict-Transport-Security`，浏览器在有效期内只通过 HTTPS
+//! 访问本站。在反向代理后终止 TLS 时，代理设置的 `X-Forwarded-Proto: https` 同样视为 HTTPS 请求。
+//!
+//! 启用 CSP 后，所有响应带有 `Content-Security-Policy`（`report_only = true` 时为
+//! `Content-Security-Policy-Report-Only`），需要放宽策略的页面可以按路径覆盖部分指令：
+//!
+//! ```toml
+//! [middleware.security.hsts]
+//! enabled = true
+//! max_age = 31536000
+//! include_subdomains = true
+//! preload = false
+//!
+//! [middleware.security.csp]
+//! enabled = true
+//! default_src = ["'self'"]
+//! script_src = ["'self'", "https://cdn.example.com"]
+//! connect_src = ["'self'", "wss://example.com"]
+//! report_uri = "/csp-report"
+//!
+//! [[middleware.security.csp.routes]]
+//! path = "/admin/*"
+//! script_src = ["'self'", "'unsafe-inline'"]
+//! ```
+//!
+//! 处理函数也可以通过 [`ContentSecurityPolicy`] 为单个响应设置策略。处理函数已经设置的同名响应头
+//! 不会被覆盖。
+
+use super::matcher::path_matches;
+use crate::{
+    config::{CspConfig, CspRouteConfig, HstsConfig, SecurityConfig},
+    error::{Error, Result},
+};
+use axum::{
+    extract::{Request, State},
+    http::{header, uri::Scheme, HeaderName, HeaderValue},
+    middleware::Next,
+    response::{IntoResponseParts, Response, ResponseParts},
+};
+use std::{convert::Infallible, fmt, sync::Arc};
+
+/// 内容安全策略
+///
+/// ```rust
+/// use hwhkit::{middleware::security::ContentSecurityPolicy, Html};
+///
+/// async fn editor() -> (ContentSecurityPolicy, Html<&'static str>) {
+///     let policy = ContentSecurityPolicy::new()
+///         .default_src(["'self'"])
+///         .script_src(["'self'", "'unsafe-eval'"]);
+///     (policy, Html("<div id=\"editor\"></div>"))
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContentSecurityPolicy {
+    directives: Vec<(String, Vec<String>)>,
+    report_uri: Option<String>,
+    report_only: bool,
+}
+
+impl ContentSecurityPolicy {
+    /// 空策略，只包含显式设置的指令
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 根据 `[middleware.security.csp]` 配置创建
+    pub fn from_config(config: &CspConfig) -> Self {
+        Self {
+            directives: Vec::new(),
+            report_uri: config.report_uri.clone(),
+            report_only: config.report_only,
+        }
+        .default_src(&config.default_src)
+        .script_src(&config.script_src)
+        .style_src(&config.style_src)
+        .img_src(&config.img_src)
+        .connect_src(&config.connect_src)
+        .font_src(&config.font_src)
+        .frame_ancestors(&config.frame_ancestors)
+    }
+
+    /// 设置指令，替换已有的同名指令，来源列表为空时移除该指令
+    pub fn directive<I, S>(mut self, name: &str, sources: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let sources: Vec<String> = sources.into_iter().map(|source| source.as_ref().to_string()).collect();
+        let position = self.directives.iter().position(|(directive, _)| directive == name);
+        match (position, sources.is_empty()) {
+            (Some(index), true) => {
+                self.directives.remove(index);
+            }
+            (Some(index), false) => self.directives[index].1 = sources,
+            (None, true) => {}
+            (None, false) => self.directives.push((name.to_string(), sources)),
+        }
+        self
+    }
+
+    /// 设置 `default-src` 指令
+    pub fn default_src<I: IntoIterator<Item = S>, S: AsRef<str>>(self, sources: I) -> Self {
+        self.directive("default-src", sources)
+    }
+
+    /// 设置 `script-src` 指令
+    pub fn script_src<I: IntoIterator<Item = S>, S: AsRef<str>>(self, sources: I) -> Self {
+        self.directive("script-src", sources)
+    }
+
+    /// 设置 `style-src` 指令
+    pub fn style_src<I: IntoIterator<Item = S>, S: AsRef<str>>(self, sources: I) -> Self {
+        self.directive("style-src", sources)
+    }
+
+    /// 设置 `img-src` 指令
+    pub fn img_src<I: IntoIterator<Item = S>, S: AsRef<str>>(self, sources: I) -> Self {
+        self.directive("img-src", sources)
+    }
+
+    /// 设置 `connect-src` 指令
+    pub fn connect_src<I: IntoIterator<Item = S>, S: AsRef<str>>(self, sources: I) -> Self {
+        self.directive("connect-src", sources)
+    }
+
+    /// 设置 `font-src` 指令
+    pub fn font_src<I: IntoIterator<Item = S>, S: AsRef<str>>(self, sources: I) -> Self {
+        self.directive("font-src", sources)
+    }
+
+    /// 设置 `frame-ancestors` 指令
+    pub fn frame_ancestors<I: IntoIterator<Item = S>, S: AsRef<str>>(self, sources: I) -> Self {
+        self.directive("frame-ancestors", sources)
+    }
+
+    /// 设置违规报告地址
+    pub fn report_uri(mut self, uri: &str) -> Self {
+        self.report_uri = Some(uri.to_string());
+        self
+    }
+
+    /// 只报告违规而不拦截
+    pub fn report_only(mut self, report_only: bool) -> Self {
+        self.report_only = report_only;
+        self
+    }
+
+    /// 用按路径的配置覆盖指令
+    fn with_route(mut self, route: &CspRouteConfig) -> Self {
+        let overrides = [
+            ("default-src", &route.default_src),
+            ("script-src", &route.script_src),
+            ("style-src", &route.style_src),
+            ("img-src", &route.img_src),
+            ("connect-src", &route.connect_src),
+            ("font-src", &route.font_src),
+            ("frame-ancestors", &route.frame_ancestors),
+        ];
+        for (name, sources) in overrides {
+            if let Some(sources) = sources {
+                self = self.directive(name, sources);
+            }
+        }
+        if let Some(report_only) = route.report_only {
+            self.report_only = report_only;
+        }
+        self
+    }
+
+    /// 响应头名称
+    pub fn header_name(&self) -> HeaderName {
+        if self.report_only {
+            header::CONTENT_SECURITY_POLICY_REPORT_ONLY
+        } else {
+            header::CONTENT_SECURITY_POLICY
+        }
+    }
+
+    /// 生成响应头的值
+    ///
+    /// # Errors
+    ///
+    /// 来源为空、包含空白、`;` 或 `,` 时返回配置错误
+    pub fn header_value(&self) -> Result<HeaderValue> {
+        let sources = self
+            .directives
+            .iter()
+            .flat_map(|(_, sources)| sources)
+            .chain(&self.report_uri);
+        for source in sources {
+            if source.is_empty() || source.contains(|c: char| c.is_whitespace() || c == ';' || c == ',') {
+                return Err(Error::Config(format!("无效的 CSP 来源: '{}'", source)));
+            }
+        }
+        HeaderValue::from_str(&self.to_string()).map_err(|_| Error::Config(format!("无效的 CSP 策略: {}", self)))
+    }
+}
+
+impl fmt::Display for ContentSecurityPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut directives: Vec<String> = self
+            .directives
+            .iter()
+            .map(|(name, sources)| format!("{} {}", name, sources.join(" ")))
+            .collect();
+        if let Some(uri) = &self.report_uri {
+            directives.push(format!("report-uri {}", uri));
+        }
+        f.write_str(&directives.join("; "))
+    }
+}
+
+impl IntoResponseParts for ContentSecurityPolicy {
+    type Error = Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> std::result::Result<ResponseParts, Self::Error> {
+        match self.header_value() {
+            Ok(value) => {
+                res.headers_mut().insert(self.header_name(), value);
+            }
+            Err(e) => tracing::warn!("忽略无效的内容安全策略: {}", e),
+        }
+        Ok(res)
+    }
+}
+
+/// 预先生成的安全响应头
+#[derive(Debug, Clone, Default)]
+pub struct SecurityHeaders {
+    hsts: Option<HeaderValue>,
+    csp: Option<(HeaderName, HeaderValue)>,
+    csp_routes: Vec<(String, HeaderName, HeaderValue)>,
+}
+
+impl SecurityHeaders {
+    /// 根据配置生成
+    ///
+    /// # Errors
+    ///
+    /// CSP 来源无效或覆盖规则的路径不以 `/` 开头时返回配置错误
+    pub fn new(config: &SecurityConfig) -> Result<Self> {
+        let mut headers = Self {
+            hsts: config.hsts.enabled.then(|| hsts_value(&config.hsts)),
+            ..Default::default()
+        };
+
+        let csp = &config.csp;
+        if csp.enabled {
+            let policy = ContentSecurityPolicy::from_config(csp);
+            headers.csp = Some((policy.header_name(), policy.header_value()?));
+            for route in &csp.routes {
+                if !route.path.starts_with('/') {
+                    return Err(Error::Config(format!("CSP 覆盖规则的路径必须以 / 开头: {}", route.path)));
+                }
+                let policy = policy.clone().with_route(route);
+                headers
+                    .csp_routes
+                    .push((route.path.clone(), policy.header_name(), policy.header_value()?));
+            }
+        }
+        Ok(headers)
+    }
+
+    /// 是否需要设置任何响应头
+    pub fn is_empty(&self) -> bool {
+        self.hsts.is_none() && self.csp.is_none()
+    }
+
+    /// 查找请求路径适用的 CSP，使用第一个匹配的覆盖规则
+    fn csp(&self, path: &str) -> Option<(&HeaderName, &HeaderValue)> {
+        self.csp_routes
+            .iter()
+            .find(|(pattern, _, _)| path_matches(pattern, path))
+            .map(|(_, name, value)| (name, value))
+            .or_else(|| self.csp.as_ref().map(|(name, value)| (name, value)))
+    }
+}
+
+/// 安全响应头中间件
+pub async fn security_headers_middleware(
+    State(headers): State<Arc<SecurityHeaders>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let https = is_https(&request);
+    let csp = headers.csp(request.uri().path());
+    let mut response = next.run(request).await;
+    let response_headers = response.headers_mut();
+
+    if let Some(hsts) = headers.hsts.as_ref().filter(|_| https) {
+        response_headers
+            .entry(header::STRICT_TRANSPORT_SECURITY)
+            .or_insert_with(|| hsts.clone());
+    }
+
+    // 处理函数设置了任一种 CSP 响应头时保留其策略
+    if let Some((name, value)) = csp {
+        if !response_headers.contains_key(header::CONTENT_SECURITY_POLICY)
+            && !response_headers.contains_key(header::CONTENT_SECURITY_POLICY_REPORT_ONLY)
+        {
+            response_headers.insert(name.clone(), value.clone());
+        }
+    }
+    response
+}
+
+fn hsts_value(config: &HstsConfig) -> HeaderValue {
+    let mut value = format!("max-age={}", config.max_age);
+    if config.include_subdomains {
+        value.push_str("; includeSubDomains");
+    }
+    if config.preload {
+        value.push_str("; preload");
+    }
+    HeaderValue::from_str(&value).expect("HSTS 响应头只包含 ASCII 字符")
+}
+
+/// 请求是否通过 HTTPS 到达，反向代理终止 TLS 时以第一个 `X-Forwarded-Proto` 为准
+fn is_https(request: &Request) -> bool {
+    if request.uri().scheme() == Some(&Scheme::HTTPS) {
+        return true;
+    }
+    request
+        .headers()
+        .get("x-forwarded-proto")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(',').next())
+        .is_some_and(|proto| proto.trim().eq_ignore_ascii_case("https"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app(config: SecurityConfig) -> Router {
+        let headers = SecurityHeaders::new(&config).unwrap();
+        Router::new()
+            .route("/", get(|| async { "ok" }))
+            .route("/admin/editor", get(|| async { "ok" }))
+            .route(
+                "/custom",
+                get(|| async {
+                    (
+                        [(header::STRICT_TRANSPORT_SECURITY, "max-age=60")],
+                        ContentSecurityPolicy::new().default_src(["'none'"]),
+                        "ok",
+                    )
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                Arc::new(headers),
+                security_headers_middleware,
+            ))
+    }
+
+    async fn header(app: &Router, uri: &str, proto: Option<&str>, name: HeaderName) -> Option<String> {
+        let mut request = Request::builder().uri(uri);
+        if let Some(proto) = proto {
+            request = request.header("x-forwarded-proto", proto);
+        }
+        let response = app.clone().oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+        response
+            .headers()
+            .get(name)
+            .map(|value| value.to_str().unwrap().to_string())
+    }
+
+    #[tokio::test]
+    async fn test_hsts_only_over_https() {
+        let app = app(SecurityConfig {
+            hsts: HstsConfig {
+                enabled: true,
+                include_subdomains: true,
+                preload: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let hsts = |uri, proto| header(&app, uri, proto, header::STRICT_TRANSPORT_SECURITY);
+
+        assert_eq!(
+            hsts("/", Some("https")).await.as_deref(),
+            Some("max-age=31536000; includeSubDomains; preload")
+        );
+        assert!(hsts("/", Some("HTTPS, http")).await.is_some());
+        assert_eq!(hsts("/", Some("http")).await, None);
+        assert_eq!(hsts("/", None).await, None);
+        // 不覆盖处理函数设置的响应头
+        assert_eq!(hsts("/custom", Some("https")).await.as_deref(), Some("max-age=60"));
+    }
+
+    #[tokio::test]
+    async fn test_csp_route_override() {
+        let app = app(SecurityConfig {
+            csp: CspConfig {
+                enabled: true,
+                script_src: vec!["'self'".to_string(), "https://cdn.example.com".to_string()],
+                report_uri: Some("/csp-report".to_string()),
+                routes: vec![CspRouteConfig {
+                    path: "/admin/*".to_string(),
+                    report_only: Some(true),
+                    script_src: Some(vec!["'self'".to_string(), "'unsafe-inline'".to_string()]),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let csp = |uri| header(&app, uri, None, header::CONTENT_SECURITY_POLICY);
+        let report_only = |uri| header(&app, uri, None, header::CONTENT_SECURITY_POLICY_REPORT_ONLY);
+
+        assert_eq!(
+            csp("/").await.as_deref(),
+            Some("default-src 'self'; script-src 'self' https://cdn.example.com; report-uri /csp-report")
+        );
+        assert_eq!(report_only("/").await, None);
+        assert_eq!(csp("/admin/editor").await, None);
+        assert_eq!(
+            report_only("/admin/editor").await.as_deref(),
+            Some("default-src 'self'; script-src 'self' 'unsafe-inline'; report-uri /csp-report")
+        );
+        // 处理函数设置的策略优先
+        assert_eq!(csp("/custom").await.as_deref(), Some("default-src 'none'"));
+    }
+
+    #[test]
+    fn test_csp_builder() {
+        let policy = ContentSecurityPolicy::new()
+            .default_src(["'self'"])
+            .connect_src(["'self'", "wss://example.com"])
+            .frame_ancestors(["'none'"])
+            .default_src(["'none'"])
+            .connect_src(Vec::<String>::new());
+        assert_eq!(policy.to_string(), "default-src 'none'; frame-ancestors 'none'");
+        assert_eq!(policy.header_name(), header::CONTENT_SECURITY_POLICY);
+        assert_eq!(
+            policy.report_only(true).header_name(),
+            header::CONTENT_SECURITY_POLICY_REPORT_ONLY
+        );
+    }
+
+    #[test]
+    fn test_invalid_csp() {
+        let config = |script_src: &str, path: &str| SecurityConfig {
+            csp: CspConfig {
+                enabled: true,
+                script_src: vec![script_src.to_string()],
+                routes: vec![CspRouteConfig {
+                    path: path.to_string(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(SecurityHeaders::new(&config("'self'", "/admin/*")).is_ok());
+        assert!(matches!(
+            SecurityHeaders::new(&config("'self'; object-src *", "/admin/*")),
+            Err(Error::Config(_))
+        ));
+        assert!(matches!(SecurityHeaders::new(&config("'self'", "admin")), Err(Error::Config(_))));
+    }
+
+    #[test]
+    fn test_disabled() {
+        assert!(SecurityHeaders::new(&SecurityConfig::default()).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,725 @@
+//! 会话中间件模块
+//!
+//! 会话数据默认以 JSON 形式保存在签名（或加密）的 Cookie 中，处理器通过 `Session`
+//! 提取器读写。需要启用 `sessions` feature。
+//!
+//! 配置 `store = "memory"` 或 `store = "redis"`（需要 `redis-sessions` feature）后，
+//! Cookie 中只保存签名的会话 ID，数据保存在服务端，可以跨实例共享并在重启后保留。
+//! 其他存储（如数据库）可以实现 `SessionStore` 后通过 `WebServerBuilder::session_store` 注册。
+
+use crate::{
+    config::{CookiesConfig, SessionConfig},
+    error::Result,
+};
+use axum::{async_trait, Router};
+use serde_json::{Map, Value};
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[cfg(feature = "sessions")]
+pub use imp::Session;
+
+#[cfg(feature = "redis-sessions")]
+pub use redis_store::RedisStore;
+
+/// 会话数据
+pub type SessionData = Map<String, Value>;
+
+/// 服务端会话存储
+#[async_trait]
+pub trait SessionStore: fmt::Debug + Send + Sync + 'static {
+    /// 读取会话，不存在或已过期时返回 `None`
+    async fn load(&self, id: &str) -> Result<Option<SessionData>>;
+
+    /// 保存会话，`ttl_secs` 秒后过期
+    async fn save(&self, id: &str, data: &SessionData, ttl_secs: u64) -> Result<()>;
+
+    /// 删除会话
+    async fn delete(&self, id: &str) -> Result<()>;
+}
+
+/// 内存会话存储
+///
+/// 仅适用于单实例部署，进程重启后会话丢失
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    sessions: Mutex<HashMap<String, (u64, SessionData)>>,
+}
+
+impl MemoryStore {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, (u64, SessionData)>> {
+        self.sessions.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl SessionStore for MemoryStore {
+    async fn load(&self, id: &str) -> Result<Option<SessionData>> {
+        let now = now();
+        Ok(self
+            .lock()
+            .get(id)
+            .filter(|(exp, _)| *exp > now)
+            .map(|(_, data)| data.clone()))
+    }
+
+    async fn save(&self, id: &str, data: &SessionData, ttl_secs: u64) -> Result<()> {
+        let now = now();
+        let mut sessions = self.lock();
+        // 顺便清理过期会话
+        sessions.retain(|_, (exp, _)| *exp > now);
+        sessions.insert(id.to_string(), (now + ttl_secs, data.clone()));
+        Ok(())
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        self.lock().remove(id);
+        Ok(())
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// 根据配置创建服务端存储，`store = "cookie"` 时返回 `None`
+#[cfg(feature = "sessions")]
+fn configured_store(config: &SessionConfig) -> Result<Option<Arc<dyn SessionStore>>> {
+    use crate::config::SessionStoreKind;
+
+    match config.store {
+        SessionStoreKind::Cookie => Ok(None),
+        SessionStoreKind::Memory => Ok(Some(Arc::new(MemoryStore::default()))),
+        #[cfg(feature = "redis-sessions")]
+        SessionStoreKind::Redis => {
+            let url = config.url.as_deref().ok_or_else(|| {
+                crate::error::Error::Config("Redis 会话存储需要设置 url".to_string())
+            })?;
+            Ok(Some(Arc::new(RedisStore::new(url)?)))
+        }
+        #[cfg(not(feature = "redis-sessions"))]
+        SessionStoreKind::Redis => Err(crate::error::Error::Config(
+            "Redis 会话存储需要启用 `redis-sessions` feature".to_string(),
+        )),
+    }
+}
+
+#[cfg(feature = "redis-sessions")]
+mod redis_store {
+    use super::{SessionData, SessionStore};
+    use crate::error::{Error, Result};
+    use axum::async_trait;
+    use redis::{aio::ConnectionManager, AsyncCommands, Client};
+    use tokio::sync::OnceCell;
+
+    /// Redis 会话存储
+    ///
+    /// 首次使用时建立连接，断线后自动重连
+    pub struct RedisStore {
+        client: Client,
+        connection: OnceCell<ConnectionManager>,
+        prefix: String,
+    }
+
+    impl RedisStore {
+        /// 创建 Redis 存储，`url` 形如 `redis://127.0.0.1/`
+        pub fn new(url: &str) -> Result<Self> {
+            let client = Client::open(url)
+                .map_err(|e| Error::Config(format!("无效的 Redis 地址 '{}': {}", url, e)))?;
+            Ok(Self {
+                client,
+                connection: OnceCell::new(),
+                prefix: "hwhkit:session:".to_string(),
+            })
+        }
+
+        /// 设置键前缀，默认 `hwhkit:session:`
+        pub fn with_prefix(mut self, prefix: &str) -> Self {
+            self.prefix = prefix.to_string();
+            self
+        }
+
+        async fn connection(&self) -> Result<ConnectionManager> {
+            self.connection
+                .get_or_try_init(|| ConnectionManager::new(self.client.clone()))
+                .await
+                .cloned()
+                .map_err(redis_error)
+        }
+
+        fn key(&self, id: &str) -> String {
+            format!("{}{}", self.prefix, id)
+        }
+    }
+
+    impl std::fmt::Debug for RedisStore {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("RedisStore")
+                .field("prefix", &self.prefix)
+                .finish_non_exhaustive()
+        }
+    }
+
+    fn redis_error(e: redis::RedisError) -> Error {
+        Error::Internal(format!("Redis 会话存储错误: {}", e))
+    }
+
+    #[async_trait]
+    impl SessionStore for RedisStore {
+        async fn load(&self, id: &str) -> Result<Option<SessionData>> {
+            let value: Option<String> = self
+                .connection()
+                .await?
+                .get(self.key(id))
+                .await
+                .map_err(redis_error)?;
+            Ok(value.and_then(|v| serde_json::from_str(&v).ok()))
+        }
+
+        async fn save(&self, id: &str, data: &SessionData, ttl_secs: u64) -> Result<()> {
+            let value = serde_json::to_string(data)?;
+            self.connection()
+                .await?
+                .set_ex::<_, _, ()>(self.key(id), value, ttl_secs)
+                .await
+                .map_err(redis_error)
+        }
+
+        async fn delete(&self, id: &str) -> Result<()> {
+            self.connection()
+                .await?
+                .del::<_, ()>(self.key(id))
+                .await
+                .map_err(redis_error)
+        }
+    }
+}
+
+#[cfg(feature = "sessions")]
+mod imp {
+    use super::{now, SessionData, SessionStore};
+    use crate::{
+        config::{CookiesConfig, SameSite, SessionConfig},
+        error::{Error, Result},
+        middleware::cookies::{parse_cookies, CookieKeys},
+    };
+    use axum::{
+        async_trait,
+        extract::{FromRequestParts, Request, State},
+        http::{header, request::Parts, HeaderMap, HeaderValue},
+        middleware::Next,
+        response::Response,
+    };
+    use cookie::{time::Duration, Cookie};
+    use serde::{de::DeserializeOwned, Deserialize, Serialize};
+    use serde_json::Value;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, Default)]
+    struct SessionState {
+        data: SessionData,
+        modified: bool,
+        cleared: bool,
+    }
+
+    /// 会话
+    ///
+    /// 请求内的所有克隆共享同一份数据，修改会在响应时写回 Cookie
+    #[derive(Debug, Clone, Default)]
+    pub struct Session {
+        state: Arc<Mutex<SessionState>>,
+    }
+
+    impl Session {
+        fn from_data(data: SessionData) -> Self {
+            Self {
+                state: Arc::new(Mutex::new(SessionState {
+                    data,
+                    ..Default::default()
+                })),
+            }
+        }
+
+        fn lock(&self) -> std::sync::MutexGuard<'_, SessionState> {
+            self.state.lock().unwrap_or_else(|e| e.into_inner())
+        }
+
+        /// 读取会话值，不存在或类型不匹配时返回 `None`
+        pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+            let value = self.lock().data.get(key).cloned()?;
+            serde_json::from_value(value).ok()
+        }
+
+        /// 写入会话值
+        pub fn insert<T: Serialize>(&self, key: &str, value: T) -> Result<()> {
+            let value = serde_json::to_value(value)?;
+            let mut state = self.lock();
+            state.data.insert(key.to_string(), value);
+            state.modified = true;
+            Ok(())
+        }
+
+        /// 删除会话值
+        pub fn remove(&self, key: &str) -> Option<Value> {
+            let mut state = self.lock();
+            let value = state.data.remove(key);
+            if value.is_some() {
+                state.modified = true;
+            }
+            value
+        }
+
+        /// 清空会话并删除 Cookie
+        pub fn clear(&self) {
+            let mut state = self.lock();
+            state.data.clear();
+            state.cleared = true;
+            state.modified = true;
+        }
+
+        /// 会话是否为空
+        pub fn is_empty(&self) -> bool {
+            self.lock().data.is_empty()
+        }
+    }
+
+    #[async_trait]
+    impl<S: Send + Sync> FromRequestParts<S> for Session {
+        type Rejection = Error;
+
+        async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+            parts
+                .extensions
+                .get::<Session>()
+                .cloned()
+                .ok_or_else(|| Error::Internal("会话中间件未启用".to_string()))
+        }
+    }
+
+    /// Cookie 中保存的内容
+    #[derive(Serialize, Deserialize)]
+    struct Payload {
+        /// 过期时间（Unix 秒）
+        exp: u64,
+        data: SessionData,
+    }
+
+    /// 会话中间件状态
+    pub(super) struct SessionLayer {
+        config: SessionConfig,
+        keys: CookieKeys,
+        store: Option<Arc<dyn SessionStore>>,
+    }
+
+    impl SessionLayer {
+        pub(super) fn new(
+            config: &SessionConfig,
+            cookies: &CookiesConfig,
+            store: Option<Arc<dyn SessionStore>>,
+        ) -> Result<Self> {
+            let secret = config.effective_secret();
+            let keys = if !secret.is_empty() {
+                if secret.len() < 32 {
+                    return Err(Error::Config("会话密钥至少需要 32 字节".to_string()));
+                }
+                CookieKeys::new([secret])?
+            } else if !cookies.effective_keys().is_empty() {
+                CookieKeys::from_config(cookies)?
+            } else {
+                return Err(Error::Config("会话需要设置 secret 或 [cookies] keys".to_string()));
+            };
+
+            Ok(Self {
+                config: config.clone(),
+                keys,
+                store,
+            })
+        }
+
+        /// 读取会话，返回服务端会话 ID 和数据
+        async fn load(&self, headers: &HeaderMap) -> (Option<String>, Option<SessionData>) {
+            let Some(value) = self.read_cookie(headers) else {
+                return (None, None);
+            };
+
+            let Some(store) = &self.store else {
+                let payload = serde_json::from_str::<Payload>(&value).ok();
+                return (None, payload.filter(|p| p.exp > now()).map(|p| p.data));
+            };
+
+            match store.load(&value).await {
+                Ok(Some(data)) => (Some(value), Some(data)),
+                // 会话已过期，保存时使用新的 ID
+                Ok(None) => (None, None),
+                Err(e) => {
+                    tracing::warn!("读取会话失败: {}", e);
+                    (None, None)
+                }
+            }
+        }
+
+        /// 保存会话，返回 Set-Cookie 的值
+        async fn save(&self, id: Option<String>, data: SessionData) -> Option<String> {
+            let Some(store) = &self.store else {
+                let payload = Payload {
+                    exp: now() + self.config.ttl_secs,
+                    data,
+                };
+                return self.write_cookie(serde_json::to_string(&payload).ok()?);
+            };
+
+            let id = id.unwrap_or_else(generate_id);
+            if let Err(e) = store.save(&id, &data, self.config.ttl_secs).await {
+                tracing::warn!("保存会话失败: {}", e);
+                return None;
+            }
+            self.write_cookie(id)
+        }
+
+        /// 删除服务端会话，返回删除 Cookie 的 Set-Cookie 值
+        async fn destroy(&self, id: Option<String>) -> String {
+            if let (Some(store), Some(id)) = (&self.store, id) {
+                if let Err(e) = store.delete(&id).await {
+                    tracing::warn!("删除会话失败: {}", e);
+                }
+            }
+            self.removal()
+        }
+
+        /// 读取并校验会话 Cookie
+        fn read_cookie(&self, headers: &HeaderMap) -> Option<String> {
+            // 同名 Cookie 以最后一个为准
+            let cookie = parse_cookies(headers)
+                .filter(|cookie| cookie.name() == self.config.cookie_name)
+                .last()?;
+            let cookie = if self.config.encrypt {
+                self.keys.decrypt(&cookie)
+            } else {
+                self.keys.verify(&cookie)
+            }?;
+
+            Some(cookie.value().to_string())
+        }
+
+        /// 生成签名（或加密）的会话 Cookie
+        fn write_cookie(&self, value: String) -> Option<String> {
+            let cookie = self
+                .cookie(value)
+                .max_age(Duration::seconds(self.config.ttl_secs as i64))
+                .build();
+            let cookie = if self.config.encrypt {
+                self.keys.encrypt(cookie)
+            } else {
+                self.keys.sign(cookie)
+            };
+
+            // 值中可能包含 `;` 等字符，需要百分号编码
+            Some(cookie.encoded().to_string())
+        }
+
+        fn removal(&self) -> String {
+            self.cookie(String::new())
+                .max_age(Duration::ZERO)
+                .build()
+                .to_string()
+        }
+
+        fn cookie(&self, value: String) -> cookie::CookieBuilder<'static> {
+            let same_site = match self.config.same_site {
+                SameSite::Strict => cookie::SameSite::Strict,
+                SameSite::Lax => cookie::SameSite::Lax,
+                SameSite::None => cookie::SameSite::None,
+            };
+
+            Cookie::build((self.config.cookie_name.clone(), value))
+                .path("/")
+                .http_only(true)
+                .secure(self.config.secure)
+                .same_site(same_site)
+        }
+    }
+
+    /// 生成会话 ID，Cookie 经过签名，客户端无法伪造
+    fn generate_id() -> String {
+        (0..32).map(|_| format!("{:02x}", fastrand::u8(..))).collect()
+    }
+
+    /// 会话中间件
+    pub(super) async fn session_middleware(
+        State(layer): State<Arc<SessionLayer>>,
+        mut request: Request,
+        next: Next,
+    ) -> Response {
+        let (id, loaded) = layer.load(request.headers()).await;
+        let had_cookie = loaded.is_some();
+        let session = Session::from_data(loaded.unwrap_or_default());
+        request.extensions_mut().insert(session.clone());
+
+        let mut response = next.run(request).await;
+
+        let (cleared, modified, data) = {
+            let state = session.lock();
+            (state.cleared && state.data.is_empty(), state.modified, state.data.clone())
+        };
+        let set_cookie = if cleared && had_cookie {
+            Some(layer.destroy(id).await)
+        } else if cleared {
+            None
+        } else if modified {
+            layer.save(id, data).await
+        } else {
+            None
+        };
+
+        if let Some(value) = set_cookie.and_then(|v| HeaderValue::from_str(&v).ok()) {
+            response.headers_mut().append(header::SET_COOKIE, value);
+        }
+        response
+    }
+}
+
+/// 应用会话中间件
+///
+/// `store` 为 `None` 时按配置选择存储后端，会话未设置 `secret` 时使用 `cookies` 中的密钥。
+/// 需要启用 `sessions` feature，否则返回配置错误
+#[cfg(feature = "sessions")]
+pub fn apply_session(
+    app: Router,
+    config: &SessionConfig,
+    cookies: &CookiesConfig,
+    store: Option<Arc<dyn SessionStore>>,
+) -> Result<Router> {
+    let store = match store {
+        Some(store) => Some(store),
+        None => configured_store(config)?,
+    };
+    let layer = Arc::new(imp::SessionLayer::new(config, cookies, store)?);
+    Ok(app.layer(axum::middleware::from_fn_with_state(layer, imp::session_middleware)))
+}
+
+/// 应用会话中间件
+///
+/// `store` 为 `None` 时按配置选择存储后端，会话未设置 `secret` 时使用 `cookies` 中的密钥。
+/// 需要启用 `sessions` feature，否则返回配置错误
+#[cfg(not(feature = "sessions"))]
+pub fn apply_session(
+    _app: Router,
+    _config: &SessionConfig,
+    _cookies: &CookiesConfig,
+    _store: Option<Arc<dyn SessionStore>>,
+) -> Result<Router> {
+    Err(crate::error::Error::Config(
+        "会话需要启用 `sessions` feature".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "sessions")]
+    mod sessions {
+        use super::*;
+        use axum::{body::Body, http::Request, routing::get};
+        use tower::util::ServiceExt;
+
+        fn app(config: &SessionConfig) -> Router {
+            app_with_keys(config, &CookiesConfig::default())
+        }
+
+        fn app_with_keys(config: &SessionConfig, cookies: &CookiesConfig) -> Router {
+            let router = Router::new()
+                .route(
+                    "/login",
+                    get(|session: Session| async move {
+                        session.insert("user_id", 42).unwrap();
+                        session.insert("note", "a; b=c").unwrap();
+                        "ok"
+                    }),
+                )
+                .route(
+                    "/me",
+                    get(|session: Session| async move {
+                        match (session.get::<u64>("user_id"), session.get::<String>("note")) {
+                            (Some(id), Some(note)) => format!("{} {}", id, note),
+                            _ => String::new(),
+                        }
+                    }),
+                )
+                .route(
+                    "/logout",
+                    get(|session: Session| async move {
+                        session.clear();
+                        "bye"
+                    }),
+                );
+            apply_session(router, config, cookies, None).unwrap()
+        }
+
+        fn config(encrypt: bool) -> SessionConfig {
+            SessionConfig {
+                enabled: true,
+                secret: "0123456789abcdef0123456789abcdef".to_string(),
+                encrypt,
+                ..Default::default()
+            }
+        }
+
+        async fn get_with_cookie(app: &Router, uri: &str, cookie: Option<&str>) -> (Option<String>, String) {
+            let mut request = Request::builder().uri(uri);
+            if let Some(cookie) = cookie {
+                request = request.header("cookie", cookie);
+            }
+            let response = app
+                .clone()
+                .oneshot(request.body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let set_cookie = response
+                .headers()
+                .get("set-cookie")
+                .map(|v| v.to_str().unwrap().to_string());
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            (set_cookie, String::from_utf8(body.to_vec()).unwrap())
+        }
+
+        #[tokio::test]
+        async fn test_session_roundtrip() {
+            for encrypt in [true, false] {
+                let app = app(&config(encrypt));
+
+                let (set_cookie, _) = get_with_cookie(&app, "/login", None).await;
+                let set_cookie = set_cookie.expect("login should set cookie");
+                assert!(set_cookie.contains("HttpOnly"));
+                assert!(set_cookie.contains("SameSite=Lax"));
+                assert_eq!(set_cookie.contains("user_id"), !encrypt);
+
+                let cookie = set_cookie.split(';').next().unwrap();
+                let (unchanged, body) = get_with_cookie(&app, "/me", Some(cookie)).await;
+                assert_eq!(body, "42 a; b=c");
+                assert!(unchanged.is_none(), "未修改的会话不应重写 Cookie");
+
+                let (removal, _) = get_with_cookie(&app, "/logout", Some(cookie)).await;
+                assert!(removal.unwrap().contains("Max-Age=0"));
+            }
+        }
+
+        #[tokio::test]
+        async fn test_tampered_cookie_rejected() {
+            let app = app(&config(false));
+            let (set_cookie, _) = get_with_cookie(&app, "/login", None).await;
+            let cookie = set_cookie.unwrap().split(';').next().unwrap().replace("42", "43");
+
+            let (_, body) = get_with_cookie(&app, "/me", Some(&cookie)).await;
+            assert_eq!(body, "");
+        }
+
+        #[tokio::test]
+        async fn test_shared_cookie_keys() {
+            let old = "0123456789abcdef0123456789abcdef-old".to_string();
+            let new = "0123456789abcdef0123456789abcdef-new".to_string();
+            let config = SessionConfig {
+                secret: String::new(),
+                ..config(true)
+            };
+            let keys = |keys: Vec<String>| CookiesConfig {
+                keys,
+                ..Default::default()
+            };
+            assert!(apply_session(Router::new(), &config, &CookiesConfig::default(), None).is_err());
+
+            let (set_cookie, _) = get_with_cookie(&app_with_keys(&config, &keys(vec![old.clone()])), "/login", None).await;
+            let cookie = set_cookie.unwrap().split(';').next().unwrap().to_string();
+
+            // 轮换后旧密钥加密的会话仍然有效
+            let rotated = app_with_keys(&config, &keys(vec![new.clone(), old]));
+            let (_, body) = get_with_cookie(&rotated, "/me", Some(&cookie)).await;
+            assert_eq!(body, "42 a; b=c");
+
+            let (_, body) = get_with_cookie(&app_with_keys(&config, &keys(vec![new])), "/me", Some(&cookie)).await;
+            assert_eq!(body, "");
+        }
+
+        #[tokio::test]
+        async fn test_server_side_store() {
+            let store = Arc::new(MemoryStore::default());
+            let config = config(false);
+            let router = Router::new()
+                .route(
+                    "/login",
+                    get(|session: Session| async move {
+                        session.insert("user_id", 42).unwrap();
+                        "ok"
+                    }),
+                )
+                .route(
+                    "/me",
+                    get(|session: Session| async move {
+                        session.get::<u64>("user_id").map(|id| id.to_string()).unwrap_or_default()
+                    }),
+                )
+                .route(
+                    "/logout",
+                    get(|session: Session| async move {
+                        session.clear();
+                        "bye"
+                    }),
+                );
+            let app = apply_session(router, &config, &CookiesConfig::default(), Some(store.clone())).unwrap();
+
+            let (set_cookie, _) = get_with_cookie(&app, "/login", None).await;
+            let cookie = set_cookie.unwrap().split(';').next().unwrap().to_string();
+            assert!(!cookie.contains("user_id"), "Cookie 中只应保存会话 ID");
+            assert_eq!(store.lock().len(), 1);
+
+            let (_, body) = get_with_cookie(&app, "/me", Some(&cookie)).await;
+            assert_eq!(body, "42");
+
+            get_with_cookie(&app, "/logout", Some(&cookie)).await;
+            assert!(store.lock().is_empty());
+            let (_, body) = get_with_cookie(&app, "/me", Some(&cookie)).await;
+            assert_eq!(body, "");
+        }
+
+        #[cfg(not(feature = "redis-sessions"))]
+        #[test]
+        fn test_redis_store_requires_feature() {
+            let config = SessionConfig {
+                store: crate::config::SessionStoreKind::Redis,
+                url: Some("redis://127.0.0.1/".to_string()),
+                ..config(true)
+            };
+            assert!(apply_session(Router::new(), &config, &CookiesConfig::default(), None).is_err());
+        }
+
+        #[tokio::test]
+        async fn test_expired_session_ignored() {
+            let app = app(&SessionConfig {
+                ttl_secs: 0,
+                ..config(true)
+            });
+            let (set_cookie, _) = get_with_cookie(&app, "/login", None).await;
+            let cookie = set_cookie.unwrap().split(';').next().unwrap().to_string();
+
+            let (_, body) = get_with_cookie(&app, "/me", Some(&cookie)).await;
+            assert_eq!(body, "");
+        }
+    }
+
+    #[cfg(not(feature = "sessions"))]
+    #[test]
+    fn test_session_requires_feature() {
+        let config = SessionConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        assert!(apply_session(Router::new(), &config, &CookiesConfig::default(), None).is_err());
+    }
+}
@@ -1,9 +1,21 @@
 //! 静态文件中间件模块
 
-use crate::{config::StaticConfig, error::{Error, Result}};
-use axum::{Router, routing::get_service};
-use std::path::Path;
-use tower_http::services::ServeDir;
+use crate::{
+    config::{CacheControlConfig, CompressionAlgorithm, StaticCacheConfig, StaticConfig},
+    error::{Error, Result},
+    middleware::matcher::path_matches,
+};
+use axum::{
+    extract::{Request, State},
+    handler::HandlerWithoutStateExt,
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::{from_fn_with_state, Next},
+    response::{IntoResponse, Response},
+    Router,
+};
+use std::{path::Path, sync::Arc};
+use tower::{ServiceBuilder, ServiceExt};
+use tower_http::services::{ServeDir, ServeFile};
 
 /// 应用静态文件服务
 pub async fn apply_static_files(app: Router, config: &StaticConfig) -> Result<Router> {
@@ -17,25 +29,186 @@ pub async fn apply_static_files(app: Router, config: &StaticConfig) -> Result<Ro
         )));
     }
 
-    // 创建静态文件服务
-    let serve_dir = ServeDir::new(&config.dir);
-    
-    // 添加到路由
-    let static_route = format!("{}/*file", config.prefix.trim_end_matches('/'));
+    // 创建静态文件服务（ServeDir 自带 Range 支持）
+    let mut serve_dir = ServeDir::new(&config.dir);
+    for algorithm in &config.precompressed {
+        serve_dir = match algorithm {
+            CompressionAlgorithm::Gzip => serve_dir.precompressed_gzip(),
+            CompressionAlgorithm::Br => serve_dir.precompressed_br(),
+            CompressionAlgorithm::Zstd => serve_dir.precompressed_zstd(),
+        };
+    }
+    let cache = Arc::new(CacheRules::new(&config.cache)?);
     
+    // 去掉前缀后交给 ServeDir
+    let prefix = config.prefix.trim_end_matches('/');
+
     tracing::info!(
-        "启用静态文件服务: {} -> {}", 
-        static_route, 
+        "启用静态文件服务: {}/ -> {}", 
+        prefix, 
         config.dir
     );
 
-    Ok(app.route(&static_route, get_service(serve_dir)))
+    if !config.spa_fallback {
+        let service = ServiceBuilder::new()
+            .layer(from_fn_with_state(cache, cache_headers))
+            .service(serve_dir);
+        return Ok(if prefix.is_empty() {
+            app.fallback_service(service)
+        } else {
+            app.nest_service(prefix, service)
+        });
+    }
+
+    // 未匹配的页面请求返回 index.html，由前端路由处理
+    let mut index = ServeFile::new(static_dir.join("index.html"));
+    for algorithm in &config.precompressed {
+        index = match algorithm {
+            CompressionAlgorithm::Gzip => index.precompressed_gzip(),
+            CompressionAlgorithm::Br => index.precompressed_br(),
+            CompressionAlgorithm::Zstd => index.precompressed_zstd(),
+        };
+    }
+    let exclude = Arc::new(config.spa_exclude.clone());
+    let spa = {
+        let cache = cache.clone();
+        move |request: Request| spa_fallback(index.clone(), exclude.clone(), cache.clone(), request)
+    };
+    if prefix.is_empty() {
+        let service = ServiceBuilder::new()
+            .layer(from_fn_with_state(cache, cache_headers))
+            .service(
+                serve_dir
+                    .call_fallback_on_method_not_allowed(true)
+                    .fallback(spa.into_service()),
+            );
+        Ok(app.fallback_service(service))
+    } else {
+        let service = ServiceBuilder::new()
+            .layer(from_fn_with_state(cache, cache_headers))
+            .service(serve_dir);
+        Ok(app.nest_service(prefix, service).fallback(spa))
+    }
+}
+
+/// 为 GET/HEAD 请求返回 index.html，排除的路径和其他方法仍然返回 404
+async fn spa_fallback(
+    index: ServeFile,
+    exclude: Arc<Vec<String>>,
+    cache: Arc<CacheRules>,
+    request: Request,
+) -> Response {
+    let path = request.uri().path();
+    let is_page = matches!(*request.method(), Method::GET | Method::HEAD)
+        && !exclude.iter().any(|pattern| path_matches(pattern, path));
+    if !is_page {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+    let mut response = match index.oneshot(request).await {
+        Ok(response) => response.into_response(),
+        Err(e) => match e {},
+    };
+    cache.apply("index.html", &mut response);
+    response
+}
+
+/// 按文件路径选择 `Cache-Control`
+#[derive(Debug)]
+pub(crate) struct CacheRules {
+    default: Option<HeaderValue>,
+    rules: Vec<(String, Option<HeaderValue>)>,
+}
+
+impl CacheRules {
+    pub(crate) fn new(config: &StaticCacheConfig) -> Result<Self> {
+        let value = |policy: &CacheControlConfig| -> Result<Option<HeaderValue>> {
+            policy
+                .header_value()?
+                .map(|value| {
+                    HeaderValue::from_str(&value)
+                        .map_err(|e| Error::Config(format!("无效的 Cache-Control: {}", e)))
+                })
+                .transpose()
+        };
+        Ok(Self {
+            default: value(&config.default)?,
+            rules: config
+                .rules
+                .iter()
+                .map(|rule| Ok((rule.pattern.clone(), value(&rule.policy)?)))
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    /// 为成功的响应写入缓存头，已设置的不覆盖
+    pub(crate) fn apply(&self, path: &str, response: &mut Response) {
+        let status = response.status();
+        if !(status.is_success() || status == StatusCode::NOT_MODIFIED)
+            || response.headers().contains_key(header::CACHE_CONTROL)
+        {
+            return;
+        }
+        // 目录请求由 ServeDir 返回其中的 index.html
+        let path = path.trim_start_matches('/');
+        let path = if path.is_empty() || path.ends_with('/') {
+            format!("{}index.html", path)
+        } else {
+            path.to_string()
+        };
+        let value = self
+            .rules
+            .iter()
+            .find(|(pattern, _)| {
+                if pattern.contains('/') {
+                    glob_matches(pattern.trim_start_matches('/'), &path)
+                } else {
+                    glob_matches(pattern, path.rsplit('/').next().unwrap_or(&path))
+                }
+            })
+            .map(|(_, value)| value)
+            .unwrap_or(&self.default);
+        if let Some(value) = value {
+            response.headers_mut().insert(header::CACHE_CONTROL, value.clone());
+        }
+    }
+}
+
+async fn cache_headers(State(cache): State<Arc<CacheRules>>, request: Request, next: Next) -> Response {
+    let path = request.uri().path().to_string();
+    let mut response = next.run(request).await;
+    cache.apply(&path, &mut response);
+    response
+}
+
+/// 路径通配符匹配：`*` 匹配除 `/` 外的任意字符，`**` 匹配任意字符
+fn glob_matches(pattern: &str, path: &str) -> bool {
+    match pattern.strip_prefix("**") {
+        Some(rest) => {
+            // `**/` 也可以匹配零层目录
+            rest.strip_prefix('/').is_some_and(|rest| glob_matches(rest, path))
+                || (0..=path.len())
+                    .filter(|&i| path.is_char_boundary(i))
+                    .any(|i| glob_matches(rest, &path[i..]))
+        }
+        None => match pattern.strip_prefix('*') {
+            Some(rest) => (0..=path.len())
+                .filter(|&i| path.is_char_boundary(i) && !path[..i].contains('/'))
+                .any(|i| glob_matches(rest, &path[i..])),
+            None => match (pattern.chars().next(), path.chars().next()) {
+                (None, None) => true,
+                (Some(p), Some(c)) if p == c => glob_matches(&pattern[p.len_utf8()..], &path[c.len_utf8()..]),
+                _ => false,
+            },
+        },
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use axum::{body::Body, http::{header, Request, StatusCode}};
     use tempfile::TempDir;
+    use tower::ServiceExt;
 
     #[tokio::test]
     async fn test_static_files_with_existing_dir() {
@@ -44,6 +217,7 @@ mod tests {
             enabled: true,
             dir: temp_dir.path().to_string_lossy().to_string(),
             prefix: "/static".to_string(),
+            ..Default::default()
         };
 
         let app = Router::new();
@@ -57,10 +231,213 @@ mod tests {
             enabled: true,
             dir: "/nonexistent/directory".to_string(),
             prefix: "/static".to_string(),
+            ..Default::default()
         };
 
         let app = Router::new();
         let result = apply_static_files(app, &config).await;
         assert!(result.is_err());
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_static_files_range() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("app.js"), "0123456789").unwrap();
+        let config = StaticConfig {
+            enabled: true,
+            dir: temp_dir.path().to_string_lossy().to_string(),
+            prefix: "/static".to_string(),
+            ..Default::default()
+        };
+        let app = apply_static_files(Router::new(), &config).await.unwrap();
+
+        let response = app
+            .clone()
+            .oneshot(Request::builder().uri("/static/app.js").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::ACCEPT_RANGES], "bytes");
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/static/app.js")
+                    .header(header::RANGE, "bytes=3-5")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.headers()[header::CONTENT_RANGE], "bytes 3-5/10");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"345");
+    }
+
+    #[tokio::test]
+    async fn test_spa_fallback() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("index.html"), "<div id=app></div>").unwrap();
+        std::fs::write(temp_dir.path().join("app.js"), "console.log(1)").unwrap();
+
+        for prefix in ["/static", "/"] {
+            let config = StaticConfig {
+                enabled: true,
+                dir: temp_dir.path().to_string_lossy().to_string(),
+                prefix: prefix.to_string(),
+                spa_fallback: true,
+                ..Default::default()
+            };
+            let app = Router::new().route("/api/users", axum::routing::get(|| async { "users" }));
+            let app = apply_static_files(app, &config).await.unwrap();
+            let send = |method: Method, uri: &str| {
+                app.clone()
+                    .oneshot(Request::builder().method(method).uri(uri).body(Body::empty()).unwrap())
+            };
+
+            let response = send(Method::GET, "/dashboard/settings").await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            assert_eq!(&body[..], b"<div id=app></div>");
+
+            let asset = format!("{}/app.js", prefix.trim_end_matches('/'));
+            let body = axum::body::to_bytes(send(Method::GET, &asset).await.unwrap().into_body(), usize::MAX)
+                .await
+                .unwrap();
+            assert_eq!(&body[..], b"console.log(1)");
+
+            let response = send(Method::GET, "/api/users").await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(send(Method::GET, "/api/missing").await.unwrap().status(), StatusCode::NOT_FOUND);
+            assert_eq!(send(Method::POST, "/dashboard").await.unwrap().status(), StatusCode::NOT_FOUND);
+        }
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("*.html", "index.html"));
+        assert!(!glob_matches("*.html", "index.htm"));
+        assert!(glob_matches("assets/*", "assets/app.3f2a.js"));
+        assert!(!glob_matches("assets/*", "assets/fonts/a.woff2"));
+        assert!(glob_matches("assets/**", "assets/fonts/a.woff2"));
+        assert!(glob_matches("**/*.woff2", "a.woff2"));
+        assert!(glob_matches("**/*.woff2", "assets/fonts/a.woff2"));
+        assert!(!glob_matches("**/*.woff2", "assets/fonts/a.woff"));
+    }
+
+    #[tokio::test]
+    async fn test_cache_control() {
+        use crate::config::{CacheControlConfig, StaticCacheRule};
+
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir(temp_dir.path().join("assets")).unwrap();
+        std::fs::write(temp_dir.path().join("index.html"), "<div id=app></div>").unwrap();
+        std::fs::write(temp_dir.path().join("assets/app.3f2a.js"), "console.log(1)").unwrap();
+        std::fs::write(temp_dir.path().join("robots.txt"), "User-agent: *").unwrap();
+
+        let config = StaticConfig {
+            enabled: true,
+            dir: temp_dir.path().to_string_lossy().to_string(),
+            prefix: "/".to_string(),
+            spa_fallback: true,
+            cache: StaticCacheConfig {
+                default: CacheControlConfig {
+                    max_age: Some("1h".to_string()),
+                    ..Default::default()
+                },
+                rules: vec![
+                    StaticCacheRule {
+                        pattern: "assets/*".to_string(),
+                        policy: CacheControlConfig {
+                            max_age: Some("1y".to_string()),
+                            immutable: true,
+                            no_cache: false,
+                        },
+                    },
+                    StaticCacheRule {
+                        pattern: "*.html".to_string(),
+                        policy: CacheControlConfig {
+                            no_cache: true,
+                            ..Default::default()
+                        },
+                    },
+                ],
+            },
+            ..Default::default()
+        };
+        let app = apply_static_files(Router::new(), &config).await.unwrap();
+        let cache_control = |uri: &'static str| {
+            let app = app.clone();
+            async move {
+                let response = app
+                    .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                    .await
+                    .unwrap();
+                response
+                    .headers()
+                    .get(header::CACHE_CONTROL)
+                    .map(|value| value.to_str().unwrap().to_string())
+            }
+        };
+
+        assert_eq!(
+            cache_control("/assets/app.3f2a.js").await.as_deref(),
+            Some("public, max-age=31536000, immutable")
+        );
+        assert_eq!(cache_control("/robots.txt").await.as_deref(), Some("public, max-age=3600"));
+        assert_eq!(cache_control("/").await.as_deref(), Some("no-cache"));
+        assert_eq!(cache_control("/dashboard").await.as_deref(), Some("no-cache"));
+        assert_eq!(cache_control("/api/missing").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_precompressed() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("app.js"), "console.log(1)").unwrap();
+        std::fs::write(temp_dir.path().join("app.js.br"), "brotli").unwrap();
+        std::fs::write(temp_dir.path().join("app.js.gz"), "gzip").unwrap();
+        let config = StaticConfig {
+            enabled: true,
+            dir: temp_dir.path().to_string_lossy().to_string(),
+            precompressed: vec![CompressionAlgorithm::Br, CompressionAlgorithm::Gzip],
+            ..Default::default()
+        };
+        let app = apply_static_files(Router::new(), &config).await.unwrap();
+        let fetch = |accept_encoding: &'static str| {
+            let app = app.clone();
+            async move {
+                let response = app
+                    .oneshot(
+                        Request::builder()
+                            .uri("/static/app.js")
+                            .header(header::ACCEPT_ENCODING, accept_encoding)
+                            .body(Body::empty())
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                let encoding = response
+                    .headers()
+                    .get(header::CONTENT_ENCODING)
+                    .map(|value| value.to_str().unwrap().to_string());
+                let content_type = response.headers()[header::CONTENT_TYPE].to_str().unwrap().to_string();
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                (encoding, content_type, String::from_utf8(body.to_vec()).unwrap())
+            }
+        };
+
+        let (encoding, content_type, body) = fetch("br, gzip").await;
+        assert_eq!(encoding.as_deref(), Some("br"));
+        assert_eq!(content_type, "text/javascript");
+        assert_eq!(body, "brotli");
+
+        let (encoding, _, body) = fetch("gzip").await;
+        assert_eq!(encoding.as_deref(), Some("gzip"));
+        assert_eq!(body, "gzip");
+
+        let (encoding, _, body) = fetch("identity").await;
+        assert_eq!(encoding, None);
+        assert_eq!(body, "console.log(1)");
+    }
+}
@@ -0,0 +1,306 @@
+//! Syslog 日志后端模块
+//!
+//! 将日志事件按 RFC5424 格式发送到本地 Unix 套接字或远程 UDP 地址
+
+use crate::{config::SyslogConfig, error::{Error, Result}};
+use std::{
+    io::{self, Write},
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs, UdpSocket},
+    sync::{Arc, Mutex},
+};
+use tracing::{Level, Metadata};
+use tracing_subscriber::fmt::MakeWriter;
+
+#[cfg(unix)]
+use std::os::unix::net::UnixDatagram;
+
+/// Syslog 传输套接字
+#[derive(Debug)]
+enum SyslogSocket {
+    /// 本地 Unix 数据报套接字，syslog 守护进程重启后需要重新连接
+    #[cfg(unix)]
+    Unix {
+        path: String,
+        socket: Mutex<UnixDatagram>,
+    },
+    Udp(UdpSocket),
+}
+
+impl SyslogSocket {
+    fn connect(address: &str) -> Result<Self> {
+        let connect_error =
+            |e: io::Error| Error::Config(format!("无法连接 syslog 地址 '{}': {}", address, e));
+
+        if let Some(addr) = address.strip_prefix("udp://") {
+            let target = addr
+                .to_socket_addrs()
+                .map_err(connect_error)?
+                .next()
+                .ok_or_else(|| Error::Config(format!("无法解析 syslog 地址: {}", address)))?;
+
+            // 本地地址族需要与目标地址一致
+            let local: SocketAddr = if target.is_ipv6() {
+                (Ipv6Addr::UNSPECIFIED, 0).into()
+            } else {
+                (Ipv4Addr::UNSPECIFIED, 0).into()
+            };
+            let socket = UdpSocket::bind(local)?;
+            socket.connect(target).map_err(connect_error)?;
+            return Ok(Self::Udp(socket));
+        }
+
+        #[cfg(unix)]
+        {
+            let socket = connect_unix(address).map_err(connect_error)?;
+            Ok(Self::Unix {
+                path: address.to_string(),
+                socket: Mutex::new(socket),
+            })
+        }
+
+        #[cfg(not(unix))]
+        Err(Error::Config(format!(
+            "当前平台仅支持 udp:// 形式的 syslog 地址: {}",
+            address
+        )))
+    }
+
+    fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            #[cfg(unix)]
+            Self::Unix { path, socket } => {
+                let mut socket = socket.lock().unwrap_or_else(|e| e.into_inner());
+                match socket.send(buf) {
+                    Ok(len) => Ok(len),
+                    Err(_) => {
+                        // syslog 守护进程可能已重启，重新连接后重试一次
+                        *socket = connect_unix(path)?;
+                        socket.send(buf)
+                    }
+                }
+            }
+            Self::Udp(socket) => socket.send(buf),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn connect_unix(path: &str) -> io::Result<UnixDatagram> {
+    let socket = UnixDatagram::unbound()?;
+    socket.connect(path)?;
+    Ok(socket)
+}
+
+/// RFC5424 Syslog 写入器工厂
+///
+/// 作为 `fmt::Layer` 的 writer 使用，每个日志事件发送一条 syslog 消息，
+/// 优先级根据事件级别计算。
+#[derive(Debug, Clone)]
+pub struct SyslogMakeWriter {
+    socket: Arc<SyslogSocket>,
+    facility: u8,
+    hostname: String,
+    app_name: String,
+    pid: u32,
+}
+
+impl SyslogMakeWriter {
+    /// 根据配置创建写入器工厂
+    pub fn new(config: &SyslogConfig) -> Result<Self> {
+        Ok(Self {
+            socket: Arc::new(SyslogSocket::connect(&config.address)?),
+            facility: parse_facility(&config.facility)?,
+            hostname: local_hostname(),
+            app_name: config.app_name.clone(),
+            pid: std::process::id(),
+        })
+    }
+
+    fn writer(&self, severity: u8) -> SyslogWriter<'_> {
+        SyslogWriter {
+            target: self,
+            severity,
+            buf: Vec::new(),
+        }
+    }
+}
+
+impl<'a> MakeWriter<'a> for SyslogMakeWriter {
+    type Writer = SyslogWriter<'a>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.writer(6)
+    }
+
+    fn make_writer_for(&'a self, meta: &Metadata<'_>) -> Self::Writer {
+        self.writer(severity(meta.level()))
+    }
+}
+
+/// 单条 syslog 消息写入器，在 drop 时发送消息
+pub struct SyslogWriter<'a> {
+    target: &'a SyslogMakeWriter,
+    severity: u8,
+    buf: Vec<u8>,
+}
+
+impl Write for SyslogWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buf.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for SyslogWriter<'_> {
+    fn drop(&mut self) {
+        let message = String::from_utf8_lossy(&self.buf);
+        let message = message.trim_end();
+        if message.is_empty() {
+            return;
+        }
+
+        let target = self.target;
+        let line = format!(
+            "<{}>1 {} {} {} {} - - {}",
+            target.facility * 8 + self.severity,
+            chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Micros, true),
+            target.hostname,
+            target.app_name,
+            target.pid,
+            message
+        );
+
+        // 日志发送失败时无处可报告，直接忽略
+        let _ = target.socket.send(line.as_bytes());
+    }
+}
+
+/// 将 tracing 级别映射为 syslog severity
+fn severity(level: &Level) -> u8 {
+    match *level {
+        Level::ERROR => 3,
+        Level::WARN => 4,
+        Level::INFO => 6,
+        _ => 7,
+    }
+}
+
+/// 解析 syslog facility 名称
+fn parse_facility(name: &str) -> Result<u8> {
+    let code = match name {
+        "kern" => 0,
+        "user" => 1,
+        "mail" => 2,
+        "daemon" => 3,
+        "auth" => 4,
+        "syslog" => 5,
+        "local0" => 16,
+        "local1" => 17,
+        "local2" => 18,
+        "local3" => 19,
+        "local4" => 20,
+        "local5" => 21,
+        "local6" => 22,
+        "local7" => 23,
+        _ => {
+            return Err(Error::Config(format!("无效的 syslog facility: {}", name)));
+        }
+    };
+    Ok(code)
+}
+
+/// 获取本机主机名，失败时使用 RFC5424 的空值 `-`
+fn local_hostname() -> String {
+    std::fs::read_to_string("/proc/sys/kernel/hostname")
+        .or_else(|_| std::fs::read_to_string("/etc/hostname"))
+        .map(|name| name.trim().to_string())
+        .ok()
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_syslog_udp_message_format() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let config = SyslogConfig {
+            address: format!("udp://{}", receiver.local_addr().unwrap()),
+            facility: "local0".to_string(),
+            app_name: "test-app".to_string(),
+        };
+
+        let make_writer = SyslogMakeWriter::new(&config).unwrap();
+        let mut writer = make_writer.writer(severity(&Level::WARN));
+        writer.write_all(b"hello syslog\n").unwrap();
+        drop(writer);
+
+        let mut buf = [0u8; 1024];
+        let len = receiver.recv(&mut buf).unwrap();
+        let message = String::from_utf8_lossy(&buf[..len]);
+
+        // local0 (16) * 8 + warning (4) = 132
+        assert!(message.starts_with("<132>1 "));
+        assert!(message.contains(" test-app "));
+        assert!(message.ends_with(" - - hello syslog"));
+    }
+
+    #[test]
+    fn test_syslog_udp_ipv6() {
+        let Ok(receiver) = UdpSocket::bind("[::1]:0") else {
+            // 环境不支持 IPv6 回环地址
+            return;
+        };
+        let config = SyslogConfig {
+            address: format!("udp://{}", receiver.local_addr().unwrap()),
+            ..Default::default()
+        };
+
+        let make_writer = SyslogMakeWriter::new(&config).unwrap();
+        let mut writer = make_writer.writer(severity(&Level::INFO));
+        writer.write_all(b"hello ipv6").unwrap();
+        drop(writer);
+
+        let mut buf = [0u8; 1024];
+        let len = receiver.recv(&mut buf).unwrap();
+        assert!(String::from_utf8_lossy(&buf[..len]).starts_with("<14>1 "));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_syslog_unix_reconnect() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("log.sock");
+        let receiver = UnixDatagram::bind(&path).unwrap();
+        let config = SyslogConfig {
+            address: path.to_string_lossy().to_string(),
+            ..Default::default()
+        };
+        let make_writer = SyslogMakeWriter::new(&config).unwrap();
+
+        // 模拟 syslog 守护进程重启：删除并重新绑定套接字
+        drop(receiver);
+        std::fs::remove_file(&path).unwrap();
+        let receiver = UnixDatagram::bind(&path).unwrap();
+
+        let mut writer = make_writer.writer(severity(&Level::ERROR));
+        writer.write_all(b"after restart").unwrap();
+        drop(writer);
+
+        let mut buf = [0u8; 1024];
+        let len = receiver.recv(&mut buf).unwrap();
+        assert!(String::from_utf8_lossy(&buf[..len]).ends_with("after restart"));
+    }
+
+    #[test]
+    fn test_invalid_facility() {
+        assert!(parse_facility("local9").is_err());
+        assert_eq!(parse_facility("daemon").unwrap(), 3);
+    }
+}
@@ -0,0 +1,300 @@
+//! 追踪上下文模块
+//!
+//! 按 W3C Trace Context 解析请求的 `traceparent` 和 `tracestate` 头，缺失或无效时生成新的 trace_id。
+//! 请求的追踪 span 和访问日志都会带上 `trace_id`/`span_id` 字段，
+//! 便于在 Grafana/Tempo 等系统中关联日志和追踪。
+//!
+//! 处理请求期间调用下游服务时，[`TraceContext::current`] 返回当前请求的上下文，
+//! 通过 [`TraceContext::inject`] 写入出站请求头即可让追踪跨服务延续：
+//!
+//! ```rust
+//! use axum::http::HeaderMap;
+//! use hwhkit::middleware::trace_context::TraceContext;
+//!
+//! let mut headers = HeaderMap::new();
+//! if let Some(context) = TraceContext::current() {
+//!     context.inject(&mut headers);
+//! }
+//! // reqwest::Client::new().get(url).headers(headers).send().await
+//! ```
+//!
+//! 内置的 HTTP 客户端（CDN 缓存清除、OAuth 登录、JWKS、反向代理和共享的 `HttpClient`）会自动写入这些头。
+
+use crate::error::{Error, Result};
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request},
+    http::{request::Parts, HeaderMap, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+
+/// W3C Trace Context 请求头
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+/// W3C Trace Context 中供应商自定义数据的请求头
+pub const TRACESTATE_HEADER: &str = "tracestate";
+
+/// `tracestate` 的最大长度，超出时丢弃
+const MAX_TRACESTATE_LEN: usize = 512;
+
+tokio::task_local! {
+    static CURRENT: TraceContext;
+}
+
+/// 请求的追踪上下文
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceContext {
+    /// 32 位十六进制 trace id
+    pub trace_id: String,
+    /// 本服务为该请求生成的 16 位十六进制 span id
+    pub span_id: String,
+    /// 上游传入的 span id
+    pub parent_span_id: Option<String>,
+    /// 上游是否采样（`trace-flags` 的最低位），没有上游时为 `true`
+    pub sampled: bool,
+    /// 上游传入的 `tracestate`，原样传给下游
+    pub tracestate: Option<String>,
+}
+
+impl TraceContext {
+    /// 从请求头创建追踪上下文，沿用上游的 trace id
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let parent = headers
+            .get(TRACEPARENT_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_traceparent);
+
+        // 没有有效的 traceparent 时 tracestate 也被忽略
+        match parent {
+            Some((trace_id, parent_span_id, sampled)) => Self {
+                trace_id,
+                span_id: random_hex(8),
+                parent_span_id: Some(parent_span_id),
+                sampled,
+                tracestate: parse_tracestate(headers),
+            },
+            None => Self::new(),
+        }
+    }
+
+    /// 生成新的追踪上下文
+    pub fn new() -> Self {
+        Self {
+            trace_id: random_hex(16),
+            span_id: random_hex(8),
+            parent_span_id: None,
+            sampled: true,
+            tracestate: None,
+        }
+    }
+
+    /// 当前请求的追踪上下文，不在追踪上下文中间件处理的请求中调用时返回 `None`
+    pub fn current() -> Option<Self> {
+        CURRENT.try_with(Clone::clone).ok()
+    }
+
+    /// 以 `traceparent` 格式输出，用于向下游传播
+    pub fn traceparent(&self) -> String {
+        format!("00-{}-{}-{:02x}", self.trace_id, self.span_id, self.sampled as u8)
+    }
+
+    /// 把 `traceparent` 和 `tracestate` 写入出站请求头，下游以本服务的 span 作为父 span
+    pub fn inject(&self, headers: &mut HeaderMap) {
+        if let Ok(value) = HeaderValue::from_str(&self.traceparent()) {
+            headers.insert(TRACEPARENT_HEADER, value);
+        }
+        if let Some(value) = self.tracestate.as_deref().and_then(|value| HeaderValue::from_str(value).ok()) {
+            headers.insert(TRACESTATE_HEADER, value);
+        }
+    }
+
+    /// 当前请求需要写入出站请求的追踪头，不在请求中时为空
+    #[cfg(any(feature = "cdn", feature = "oauth", feature = "jwks"))]
+    pub(crate) fn outgoing_headers() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(context) = Self::current() {
+            context.inject(&mut headers);
+        }
+        headers
+    }
+}
+
+impl Default for TraceContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for TraceContext {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+        parts
+            .extensions
+            .get::<TraceContext>()
+            .cloned()
+            .ok_or_else(|| Error::Internal("追踪上下文中间件未启用".to_string()))
+    }
+}
+
+/// 追踪上下文中间件，将 `TraceContext` 写入请求扩展，并在处理请求期间作为当前上下文
+pub async fn trace_context_middleware(mut request: Request, next: Next) -> Response {
+    let context = TraceContext::from_headers(request.headers());
+    request.extensions_mut().insert(context.clone());
+    CURRENT.scope(context, next.run(request)).await
+}
+
+/// 解析 `traceparent`，返回 (trace_id, parent_span_id, sampled)
+fn parse_traceparent(value: &str) -> Option<(String, String, bool)> {
+    let mut parts = value.trim().split('-');
+    let version = parts.next()?;
+    let trace_id = parts.next()?;
+    let span_id = parts.next()?;
+    let flags = parts.next()?;
+
+    let is_hex = |s: &str, len: usize| {
+        s.len() == len && s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+    };
+    if !is_hex(version, 2) || version == "ff" || !is_hex(flags, 2) {
+        return None;
+    }
+    // 全零的 id 无效
+    if !is_hex(trace_id, 32) || trace_id.bytes().all(|b| b == b'0') {
+        return None;
+    }
+    if !is_hex(span_id, 16) || span_id.bytes().all(|b| b == b'0') {
+        return None;
+    }
+
+    let flags = u8::from_str_radix(flags, 16).ok()?;
+    Some((trace_id.to_string(), span_id.to_string(), flags & 1 == 1))
+}
+
+/// 合并多个 `tracestate` 头，内容过长或包含非法字符时丢弃
+fn parse_tracestate(headers: &HeaderMap) -> Option<String> {
+    let members = headers
+        .get_all(TRACESTATE_HEADER)
+        .iter()
+        .map(|value| value.to_str().ok())
+        .collect::<Option<Vec<_>>>()?
+        .into_iter()
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|member| !member.is_empty())
+        .collect::<Vec<_>>();
+    let valid = members.iter().all(|member| {
+        member
+            .split_once('=')
+            .is_some_and(|(key, value)| !key.is_empty() && !value.is_empty())
+    });
+    let tracestate = members.join(",");
+    (valid && !tracestate.is_empty() && tracestate.len() <= MAX_TRACESTATE_LEN).then_some(tracestate)
+}
+
+fn random_hex(bytes: usize) -> String {
+    loop {
+        let id: String = (0..bytes).map(|_| format!("{:02x}", fastrand::u8(..))).collect();
+        if id.bytes().any(|b| b != b'0') {
+            return id;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_traceparent_propagation() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            TRACEPARENT_HEADER,
+            HeaderValue::from_static("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"),
+        );
+
+        let context = TraceContext::from_headers(&headers);
+        assert_eq!(context.trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(context.parent_span_id.as_deref(), Some("00f067aa0ba902b7"));
+        assert_ne!(context.span_id, "00f067aa0ba902b7");
+        assert_eq!(
+            context.traceparent(),
+            format!("00-4bf92f3577b34da6a3ce929d0e0e4736-{}-01", context.span_id)
+        );
+    }
+
+    #[test]
+    fn test_tracestate_and_flags() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            TRACEPARENT_HEADER,
+            HeaderValue::from_static("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00"),
+        );
+        headers.append(TRACESTATE_HEADER, HeaderValue::from_static("congo=t61rcWkgMzE"));
+        headers.append(TRACESTATE_HEADER, HeaderValue::from_static("rojo=00f067aa0ba902b7"));
+
+        let context = TraceContext::from_headers(&headers);
+        assert!(!context.sampled);
+        assert_eq!(context.tracestate.as_deref(), Some("congo=t61rcWkgMzE,rojo=00f067aa0ba902b7"));
+
+        // 向下游传播时保留采样标记和 tracestate，父 span 为本服务的 span
+        let mut outgoing = HeaderMap::new();
+        context.inject(&mut outgoing);
+        assert_eq!(
+            outgoing[TRACEPARENT_HEADER],
+            format!("00-4bf92f3577b34da6a3ce929d0e0e4736-{}-00", context.span_id).as_str()
+        );
+        assert_eq!(outgoing[TRACESTATE_HEADER], "congo=t61rcWkgMzE,rojo=00f067aa0ba902b7");
+
+        // 无效的 tracestate 被丢弃
+        headers.insert(TRACESTATE_HEADER, HeaderValue::from_static("invalid"));
+        assert!(TraceContext::from_headers(&headers).tracestate.is_none());
+        // 没有 traceparent 时忽略 tracestate
+        headers.remove(TRACEPARENT_HEADER);
+        assert!(TraceContext::from_headers(&headers).tracestate.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_current_context() {
+        use axum::{body::Body, routing::get, Router};
+        use tower::util::ServiceExt;
+
+        assert!(TraceContext::current().is_none());
+        let app = Router::new()
+            .route(
+                "/",
+                get(|context: TraceContext| async move {
+                    assert_eq!(TraceContext::current(), Some(context.clone()));
+                    context.traceparent()
+                }),
+            )
+            .layer(axum::middleware::from_fn(trace_context_middleware));
+        let request = Request::builder()
+            .uri("/")
+            .header(TRACEPARENT_HEADER, "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert!(body.starts_with(b"00-4bf92f3577b34da6a3ce929d0e0e4736-"));
+    }
+
+    #[test]
+    fn test_invalid_traceparent_generates_new_trace() {
+        for value in [
+            "garbage",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        ] {
+            let mut headers = HeaderMap::new();
+            headers.insert(TRACEPARENT_HEADER, HeaderValue::from_static(value));
+
+            let context = TraceContext::from_headers(&headers);
+            assert_eq!(context.trace_id.len(), 32);
+            assert_eq!(context.span_id.len(), 16);
+            assert!(context.parent_span_id.is_none(), "{}", value);
+        }
+    }
+}
@@ -0,0 +1,252 @@
+//! 配置迁移模块
+//!
+//! 加载配置文件时检查已更名、已删除和未知的配置项：更名的配置项自动迁移到新位置，
+//! 其余情况输出警告，避免升级后旧设置被静默忽略。配置文件顶层设置 `strict = true`
+//! 时，任何警告都会导致加载失败。
+
+/// 配置项变更记录
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigMigration {
+    /// 配置项更名，旧值会自动迁移
+    Renamed {
+        /// 旧路径，如 `middleware.logging.level`
+        from: &'static str,
+        /// 新路径
+        to: &'static str,
+        /// 变更的版本
+        since: &'static str,
+    },
+    /// 配置项已删除
+    Removed {
+        /// 路径
+        key: &'static str,
+        /// 变更的版本
+        since: &'static str,
+        /// 替代方案说明
+        hint: &'static str,
+    },
+}
+
+/// 已登记的配置项变更，重命名或删除配置项时需要在此登记
+pub const MIGRATIONS: &[ConfigMigration] = &[];
+
+/// 应用变更记录，返回需要提示的警告
+pub fn migrate(raw: &mut toml::Value, migrations: &[ConfigMigration]) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    for migration in migrations {
+        match *migration {
+            ConfigMigration::Renamed { from, to, since } => {
+                let Some(value) = remove_path(raw, from) else {
+                    continue;
+                };
+                if get_path(raw, to).is_some() {
+                    warnings.push(format!(
+                        "配置项 `{}` 已在 {} 中更名为 `{}`，两者同时存在，已忽略旧配置项",
+                        from, since, to
+                    ));
+                } else if insert_path(raw, to, value) {
+                    warnings.push(format!(
+                        "配置项 `{}` 已在 {} 中更名为 `{}`，已自动迁移，请更新配置文件",
+                        from, since, to
+                    ));
+                } else {
+                    warnings.push(format!(
+                        "配置项 `{}` 已在 {} 中更名为 `{}`，无法自动迁移，请手动修改",
+                        from, since, to
+                    ));
+                }
+            }
+            ConfigMigration::Removed { key, since, hint } => {
+                if remove_path(raw, key).is_some() {
+                    warnings.push(format!("配置项 `{}` 已在 {} 中删除: {}", key, since, hint));
+                }
+            }
+        }
+    }
+
+    warnings
+}
+
+/// 找出 `raw` 中存在但 `known`（由解析结果重新序列化得到）中不存在的配置项
+pub fn unknown_keys(raw: &toml::Value, known: &toml::Value) -> Vec<String> {
+    let mut unknown = Vec::new();
+    collect_unknown("", raw, known, &mut unknown);
+    unknown
+}
+
+fn collect_unknown(prefix: &str, raw: &toml::Value, known: &toml::Value, unknown: &mut Vec<String>) {
+    let (Some(raw), Some(known)) = (raw.as_table(), known.as_table()) else {
+        return;
+    };
+
+    for (key, value) in raw {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match known.get(key) {
+            Some(known) => collect_unknown(&path, value, known, unknown),
+            None => unknown.push(path),
+        }
+    }
+}
+
+/// 将 `overlay` 深度合并到 `base`：表逐键合并，其余值（包括数组）由 `overlay` 整体覆盖
+pub fn merge(base: &mut toml::Value, overlay: toml::Value) {
+    match (base, overlay) {
+        (toml::Value::Table(base), toml::Value::Table(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+fn get_path<'a>(value: &'a toml::Value, path: &str) -> Option<&'a toml::Value> {
+    path.split('.').try_fold(value, |value, key| value.get(key))
+}
+
+fn remove_path(value: &mut toml::Value, path: &str) -> Option<toml::Value> {
+    let (parent, key) = match path.rsplit_once('.') {
+        Some((parent, key)) => (get_path_mut(value, parent)?, key),
+        None => (value, path),
+    };
+    parent.as_table_mut()?.remove(key)
+}
+
+fn get_path_mut<'a>(value: &'a mut toml::Value, path: &str) -> Option<&'a mut toml::Value> {
+    path.split('.').try_fold(value, |value, key| value.get_mut(key))
+}
+
+/// 写入路径，必要时创建中间表；路径被非表值占用时返回 `false`
+fn insert_path(value: &mut toml::Value, path: &str, new_value: toml::Value) -> bool {
+    let mut current = value;
+    let mut keys = path.split('.').peekable();
+
+    while let Some(key) = keys.next() {
+        let Some(table) = current.as_table_mut() else {
+            return false;
+        };
+        if keys.peek().is_none() {
+            table.insert(key.to_string(), new_value);
+            return true;
+        }
+        current = table
+            .entry(key)
+            .or_insert_with(|| toml::Value::Table(Default::default()));
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_MIGRATIONS: &[ConfigMigration] = &[
+        ConfigMigration::Renamed {
+            from: "middleware.logging.log_level",
+            to: "middleware.logging.level",
+            since: "0.2.0",
+        },
+        ConfigMigration::Renamed {
+            from: "server.bind",
+            to: "server.listen.host",
+            since: "0.2.0",
+        },
+        ConfigMigration::Removed {
+            key: "middleware.legacy",
+            since: "0.2.0",
+            hint: "请改用 [middleware.cors]",
+        },
+    ];
+
+    fn parse(content: &str) -> toml::Value {
+        toml::from_str(content).unwrap()
+    }
+
+    #[test]
+    fn test_renamed_keys_are_migrated() {
+        let mut raw = parse(
+            r#"
+            [server]
+            bind = "127.0.0.1"
+
+            [middleware.logging]
+            log_level = "debug"
+
+            [middleware.legacy]
+            enabled = true
+            "#,
+        );
+
+        let warnings = migrate(&mut raw, TEST_MIGRATIONS);
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings[2].contains("请改用 [middleware.cors]"));
+
+        assert_eq!(get_path(&raw, "middleware.logging.level").unwrap().as_str(), Some("debug"));
+        assert_eq!(get_path(&raw, "server.listen.host").unwrap().as_str(), Some("127.0.0.1"));
+        assert!(get_path(&raw, "middleware.logging.log_level").is_none());
+        assert!(get_path(&raw, "middleware.legacy").is_none());
+    }
+
+    #[test]
+    fn test_new_key_takes_precedence() {
+        let mut raw = parse(
+            r#"
+            [middleware.logging]
+            log_level = "debug"
+            level = "warn"
+            "#,
+        );
+
+        let warnings = migrate(&mut raw, TEST_MIGRATIONS);
+        assert!(warnings[0].contains("已忽略旧配置项"));
+        assert_eq!(get_path(&raw, "middleware.logging.level").unwrap().as_str(), Some("warn"));
+    }
+
+    #[test]
+    fn test_unknown_keys() {
+        let raw = parse(
+            r#"
+            [server]
+            port = 3000
+            prot = 3001
+
+            [extra]
+            a = 1
+            "#,
+        );
+        let known = parse(
+            r#"
+            [server]
+            port = 3000
+            "#,
+        );
+
+        assert_eq!(unknown_keys(&raw, &known), ["extra", "server.prot"]);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut base = parse(
+            "[server]\nhost = \"0.0.0.0\"\nport = 3000\n\n[middleware.cors]\nenabled = true\norigins = [\"a\", \"b\"]\n",
+        );
+        merge(
+            &mut base,
+            parse("[server]\nport = 8080\n\n[middleware.cors]\norigins = [\"c\"]\n\n[mail]\nenabled = true\n"),
+        );
+
+        assert_eq!(base, parse(
+            "[server]\nhost = \"0.0.0.0\"\nport = 8080\n\n[middleware.cors]\nenabled = true\norigins = [\"c\"]\n\n[mail]\nenabled = true\n",
+        ));
+    }
+}
@@ -0,0 +1,255 @@
+//! 功能模块
+//!
+//! [`AppModule`] 把一组路由和它们需要的状态、配置节及初始化（如数据库迁移）打包在一起，
+//! 可以作为独立的 crate 发布，再通过 `WebServerBuilder::mount` 组合进同一个服务器：
+//!
+//! ```rust
+//! use hwhkit::{
+//!     get, module::{AppModule, ModuleContext}, async_trait, Deserialize, Json, Result, Router, State,
+//!     WebServerBuilder,
+//! };
+//!
+//! #[derive(Default, Deserialize)]
+//! #[serde(default)]
+//! struct UsersConfig {
+//!     page_size: usize,
+//! }
+//!
+//! #[derive(Clone)]
+//! struct UsersState {
+//!     page_size: usize,
+//! }
+//!
+//! struct UsersModule;
+//!
+//! #[async_trait]
+//! impl AppModule for UsersModule {
+//!     type Config = UsersConfig;
+//!     type State = UsersState;
+//!
+//!     fn name(&self) -> &str {
+//!         "users"
+//!     }
+//!
+//!     async fn state(&self, config: UsersConfig, _context: &ModuleContext<'_>) -> Result<UsersState> {
+//!         Ok(UsersState { page_size: config.page_size.max(1) })
+//!     }
+//!
+//!     fn routes(&self) -> Router<UsersState> {
+//!         Router::new().route(
+//!             "/users",
+//!             get(|State(state): State<UsersState>| async move { Json(state.page_size) }),
+//!         )
+//!     }
+//! }
+//!
+//! let builder = WebServerBuilder::new().mount(UsersModule);
+//! ```
+//!
+//! 模块配置从 `[modules.<name>]` 读取，未配置时使用默认值：
+//!
+//! ```toml
+//! [modules.users]
+//! page_size = 50
+//! ```
+//!
+//! 构建服务器时按挂载顺序依次读取配置、创建状态、执行 [`AppModule::migrate`]，
+//! 任一步骤失败时构建失败。模块路由与其他路由一样经过所有全局中间件。
+
+use crate::{
+    config::Config,
+    database::Databases,
+    error::{Error, Result},
+};
+use axum::{async_trait, Router};
+use serde::de::DeserializeOwned;
+use std::fmt;
+
+/// 可挂载的功能模块
+#[async_trait]
+pub trait AppModule: Send + Sync + 'static {
+    /// 模块配置，从 `[modules.<name>]` 读取
+    type Config: DeserializeOwned + Default + Send + 'static;
+    /// 模块状态，模块内的处理器通过 `State` 提取
+    type State: Clone + Send + Sync + 'static;
+
+    /// 模块名称，同时是配置节的名称，同一服务器中不能重复
+    fn name(&self) -> &str;
+
+    /// 根据配置创建模块状态
+    async fn state(&self, config: Self::Config, context: &ModuleContext<'_>) -> Result<Self::State>;
+
+    /// 执行数据库迁移等初始化，在创建状态后、挂载路由前调用
+    async fn migrate(&self, _state: &Self::State, _context: &ModuleContext<'_>) -> Result<()> {
+        Ok(())
+    }
+
+    /// 模块路由
+    fn routes(&self) -> Router<Self::State>;
+}
+
+/// 创建模块时可用的服务器资源
+#[derive(Debug)]
+pub struct ModuleContext<'a> {
+    config: &'a Config,
+    databases: &'a Databases,
+}
+
+impl<'a> ModuleContext<'a> {
+    pub(crate) fn new(config: &'a Config, databases: &'a Databases) -> Self {
+        Self { config, databases }
+    }
+
+    /// 服务器配置
+    pub fn config(&self) -> &Config {
+        self.config
+    }
+
+    /// 数据库连接池，可用于执行迁移
+    pub fn databases(&self) -> &Databases {
+        self.databases
+    }
+
+    /// 读取 `[modules.<name>]` 配置节，未配置时返回默认值
+    ///
+    /// # Errors
+    ///
+    /// 配置节无法反序列化为 `T` 时返回配置错误
+    pub fn section<T: DeserializeOwned + Default>(&self, name: &str) -> Result<T> {
+        match self.config.modules.get(name) {
+            Some(value) => serde_json::from_value(value.clone())
+                .map_err(|e| Error::Config(format!("[modules.{}] 配置无效: {}", name, e))),
+            None => Ok(T::default()),
+        }
+    }
+}
+
+/// 擦除了配置和状态类型的模块
+#[async_trait]
+trait ErasedModule: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn mount(self: Box<Self>, context: &ModuleContext<'_>) -> Result<Router>;
+}
+
+#[async_trait]
+impl<M: AppModule> ErasedModule for M {
+    fn name(&self) -> &str {
+        AppModule::name(self)
+    }
+
+    async fn mount(self: Box<Self>, context: &ModuleContext<'_>) -> Result<Router> {
+        let config = context.section::<M::Config>(AppModule::name(&*self))?;
+        let state = self.state(config, context).await?;
+        self.migrate(&state, context).await?;
+        tracing::info!("📦 挂载模块: {}", AppModule::name(&*self));
+        Ok(self.routes().with_state(state))
+    }
+}
+
+/// 通过 `WebServerBuilder::mount` 登记、构建时挂载的模块
+pub(crate) struct PendingModule(Box<dyn ErasedModule>);
+
+impl PendingModule {
+    pub(crate) fn new<M: AppModule>(module: M) -> Self {
+        Self(Box::new(module))
+    }
+
+    pub(crate) fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    /// 读取配置、创建状态并执行初始化，返回模块路由
+    pub(crate) async fn mount(self, context: &ModuleContext<'_>) -> Result<Router> {
+        self.0.mount(context).await
+    }
+}
+
+impl fmt::Debug for PendingModule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PendingModule").field(&self.name()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, extract::State, http::Request, routing::get};
+    use serde::Deserialize;
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+    use tower::ServiceExt;
+
+    #[derive(Default, Deserialize)]
+    #[serde(default)]
+    struct GreetConfig {
+        greeting: String,
+    }
+
+    struct GreetModule {
+        migrated: Arc<AtomicBool>,
+    }
+
+    #[async_trait]
+    impl AppModule for GreetModule {
+        type Config = GreetConfig;
+        type State = Arc<String>;
+
+        fn name(&self) -> &str {
+            "greet"
+        }
+
+        async fn state(&self, config: GreetConfig, _context: &ModuleContext<'_>) -> Result<Arc<String>> {
+            Ok(Arc::new(config.greeting))
+        }
+
+        async fn migrate(&self, _state: &Arc<String>, _context: &ModuleContext<'_>) -> Result<()> {
+            self.migrated.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn routes(&self) -> Router<Arc<String>> {
+            Router::new().route(
+                "/greet",
+                get(|State(greeting): State<Arc<String>>| async move { greeting.to_string() }),
+            )
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mount_module() {
+        let mut config = Config::default();
+        config
+            .modules
+            .insert("greet".to_string(), serde_json::json!({ "greeting": "hello" }));
+        let databases = Databases::default();
+        let context = ModuleContext::new(&config, &databases);
+
+        let migrated = Arc::new(AtomicBool::new(false));
+        let module = PendingModule::new(GreetModule {
+            migrated: migrated.clone(),
+        });
+        assert_eq!(module.name(), "greet");
+        let app = module.mount(&context).await.unwrap();
+        assert!(migrated.load(Ordering::SeqCst));
+
+        let request = Request::builder().uri("/greet").body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(body, "hello");
+    }
+
+    #[test]
+    fn test_invalid_section() {
+        let mut config = Config::default();
+        config
+            .modules
+            .insert("greet".to_string(), serde_json::json!({ "greeting": 42 }));
+        let databases = Databases::default();
+        let context = ModuleContext::new(&config, &databases);
+        assert!(matches!(context.section::<GreetConfig>("greet"), Err(Error::Config(_))));
+        assert!(context.section::<GreetConfig>("other").unwrap().greeting.is_empty());
+    }
+}
@@ -0,0 +1,272 @@
+//! 内容协商模块
+//!
+//! 处理器返回 [`Negotiate`]，按请求的 `Accept` 头选择序列化格式，同一个处理器可以同时服务
+//! 浏览器、移动端和内部服务。JSON 始终可用，其他格式需要启用对应 feature：
+//!
+//! | 格式 | Content-Type | feature |
+//! |------|--------------|---------|
+//! | JSON | `application/json` | - |
+//! | MessagePack | `application/msgpack` | `msgpack` |
+//! | CBOR | `application/cbor` | `cbor` |
+//! | XML | `application/xml` | `xml` |
+//!
+//! `Accept` 中没有支持的格式时返回 JSON。
+//!
+//! ```rust
+//! use hwhkit::negotiate::Negotiate;
+//! use serde::Serialize;
+//!
+//! #[derive(Serialize)]
+//! struct User {
+//!     id: u64,
+//! }
+//!
+//! async fn get_user() -> Negotiate<User> {
+//!     Negotiate(User { id: 1 })
+//! }
+//! ```
+
+use crate::error::Result;
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use std::sync::Arc;
+
+/// 响应的序列化格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// JSON
+    Json,
+    /// MessagePack
+    #[cfg(feature = "msgpack")]
+    MessagePack,
+    /// CBOR
+    #[cfg(feature = "cbor")]
+    Cbor,
+    /// XML
+    #[cfg(feature = "xml")]
+    Xml,
+}
+
+impl Format {
+    /// 对应的 Content-Type
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Format::Json => "application/json",
+            #[cfg(feature = "msgpack")]
+            Format::MessagePack => "application/msgpack",
+            #[cfg(feature = "cbor")]
+            Format::Cbor => "application/cbor",
+            #[cfg(feature = "xml")]
+            Format::Xml => "application/xml",
+        }
+    }
+
+    /// 根据媒体类型匹配格式，通配符匹配 JSON
+    pub fn from_media_type(media_type: &str) -> Option<Self> {
+        match media_type {
+            "application/json" | "application/*" | "*/*" => Some(Format::Json),
+            #[cfg(feature = "msgpack")]
+            "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack" => {
+                Some(Format::MessagePack)
+            }
+            #[cfg(feature = "cbor")]
+            "application/cbor" => Some(Format::Cbor),
+            #[cfg(feature = "xml")]
+            "application/xml" | "text/xml" => Some(Format::Xml),
+            _ => None,
+        }
+    }
+
+    /// 根据 `Accept` 头选择格式
+    ///
+    /// 选择 q 值最高的已支持格式，q 值相同时取先出现的，没有匹配时返回 JSON
+    pub fn from_accept(accept: Option<&str>) -> Self {
+        let mut best: Option<(f32, Format)> = None;
+        for item in accept.unwrap_or_default().split(',') {
+            let mut params = item.split(';');
+            let media_type = params.next().unwrap_or_default().trim().to_ascii_lowercase();
+            let quality = params
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .and_then(|q| q.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            let Some(format) = Format::from_media_type(&media_type) else {
+                continue;
+            };
+            if quality > 0.0 && best.is_none_or(|(best, _)| quality > best) {
+                best = Some((quality, format));
+            }
+        }
+        best.map_or(Format::Json, |(_, format)| format)
+    }
+
+    /// 序列化数据
+    pub fn serialize<T: Serialize + ?Sized>(self, value: &T) -> Result<Vec<u8>> {
+        match self {
+            Format::Json => Ok(serde_json::to_vec(value)?),
+            #[cfg(feature = "msgpack")]
+            Format::MessagePack => rmp_serde::to_vec_named(value)
+                .map_err(|e| crate::Error::Internal(format!("MessagePack 序列化失败: {}", e))),
+            #[cfg(feature = "cbor")]
+            Format::Cbor => {
+                let mut bytes = Vec::new();
+                ciborium::into_writer(value, &mut bytes)
+                    .map_err(|e| crate::Error::Internal(format!("CBOR 序列化失败: {}", e)))?;
+                Ok(bytes)
+            }
+            #[cfg(feature = "xml")]
+            Format::Xml => crate::xml::to_string(value).map(String::into_bytes),
+        }
+    }
+}
+
+type RenderFn = dyn Fn(Format) -> Result<Vec<u8>> + Send + Sync;
+
+/// 按协商结果重新序列化响应体，由 [`negotiate_middleware`] 使用
+#[derive(Clone)]
+struct Renderer(Arc<RenderFn>);
+
+/// 按 `Accept` 头选择格式的响应
+///
+/// 响应体先按 JSON 生成，由服务器的内容协商中间件按请求改写为其他格式
+#[derive(Debug, Clone)]
+pub struct Negotiate<T>(pub T);
+
+impl<T: Serialize + Send + Sync + 'static> IntoResponse for Negotiate<T> {
+    fn into_response(self) -> Response {
+        let body = match Format::Json.serialize(&self.0) {
+            Ok(body) => body,
+            Err(e) => return e.into_response(),
+        };
+        let value = self.0;
+        let mut response = (
+            [(header::CONTENT_TYPE, HeaderValue::from_static(Format::Json.content_type()))],
+            body,
+        )
+            .into_response();
+        response
+            .extensions_mut()
+            .insert(Renderer(Arc::new(move |format| format.serialize(&value))));
+        response
+    }
+}
+
+/// 内容协商中间件
+pub(crate) async fn negotiate_middleware(request: Request, next: Next) -> Response {
+    let format = Format::from_accept(
+        request
+            .headers()
+            .get(header::ACCEPT)
+            .and_then(|value| value.to_str().ok()),
+    );
+    let mut response = next.run(request).await;
+    let Some(Renderer(render)) = response.extensions_mut().remove::<Renderer>() else {
+        return response;
+    };
+
+    response
+        .headers_mut()
+        .append(header::VARY, HeaderValue::from_static("accept"));
+    if format == Format::Json {
+        return response;
+    }
+    match render(format) {
+        Ok(body) => {
+            let headers = response.headers_mut();
+            headers.remove(header::CONTENT_LENGTH);
+            headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(format.content_type()));
+            *response.body_mut() = Body::from(body);
+            response
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, routing::get, Router};
+    use serde::Deserialize;
+    use tower::util::ServiceExt;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct User {
+        id: u64,
+        name: String,
+    }
+
+    fn user() -> User {
+        User { id: 1, name: "张三".to_string() }
+    }
+
+    async fn call(accept: Option<&str>) -> (StatusCode, String, Vec<u8>) {
+        let app = Router::new()
+            .route("/user", get(|| async { (StatusCode::CREATED, Negotiate(user())) }))
+            .layer(axum::middleware::from_fn(negotiate_middleware));
+        let mut request = Request::get("/user");
+        if let Some(accept) = accept {
+            request = request.header(header::ACCEPT, accept);
+        }
+        let response = app.oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+        let status = response.status();
+        assert_eq!(response.headers()[header::VARY], "accept");
+        let content_type = response.headers()[header::CONTENT_TYPE].to_str().unwrap().to_string();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, content_type, body.to_vec())
+    }
+
+    #[test]
+    fn test_from_accept() {
+        assert_eq!(Format::from_accept(None), Format::Json);
+        assert_eq!(Format::from_accept(Some("text/html")), Format::Json);
+        assert_eq!(Format::from_accept(Some("text/html, */*;q=0.1")), Format::Json);
+        #[cfg(feature = "cbor")]
+        assert_eq!(
+            Format::from_accept(Some("application/json;q=0.5, application/cbor")),
+            Format::Cbor
+        );
+        #[cfg(feature = "msgpack")]
+        assert_eq!(
+            Format::from_accept(Some("application/msgpack;q=0, application/json;q=0.1")),
+            Format::Json
+        );
+    }
+
+    #[tokio::test]
+    async fn test_json_fallback() {
+        let (status, content_type, body) = call(Some("text/plain")).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(content_type, "application/json");
+        assert_eq!(serde_json::from_slice::<User>(&body).unwrap(), user());
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[tokio::test]
+    async fn test_msgpack() {
+        let (status, content_type, body) = call(Some("application/x-msgpack")).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(content_type, "application/msgpack");
+        assert_eq!(rmp_serde::from_slice::<User>(&body).unwrap(), user());
+    }
+
+    #[cfg(feature = "cbor")]
+    #[tokio::test]
+    async fn test_cbor() {
+        let (_, content_type, body) = call(Some("application/cbor")).await;
+        assert_eq!(content_type, "application/cbor");
+        assert_eq!(ciborium::from_reader::<User, _>(&body[..]).unwrap(), user());
+    }
+
+    #[cfg(feature = "xml")]
+    #[tokio::test]
+    async fn test_xml() {
+        let (_, content_type, body) = call(Some("application/json;q=0.9, text/xml")).await;
+        assert_eq!(content_type, "application/xml");
+        assert_eq!(String::from_utf8(body).unwrap(), "<User><id>1</id><name>张三</name></User>");
+    }
+}
@@ -0,0 +1,309 @@
+//! OpenAPI 文档模块（需要 `openapi` feature）
+//!
+//! 通过 utoipa 的 `#[utoipa::path]` 和 `#[derive(OpenApi)]` 收集接口和数据结构，再用
+//! `WebServerBuilder::openapi` 登记，构建时挂载到 `[openapi] path`（默认 `/api-docs/openapi.json`）。
+//!
+//! 文档在构建时生成：用 `route_doc` 登记、但没有注解的路由会补充到文档中，axum 风格的路径参数
+//! （`/users/:id`、`/files/*path`）转换为 OpenAPI 格式（`/users/{id}`、`/files/{path}`）。
+//!
+//! ```rust
+//! use hwhkit::WebServerBuilder;
+//! use utoipa::OpenApi;
+//!
+//! #[utoipa::path(get, path = "/health", responses((status = 200, description = "服务正常")))]
+//! async fn health() -> &'static str {
+//!     "ok"
+//! }
+//!
+//! #[derive(OpenApi)]
+//! #[openapi(paths(health))]
+//! struct ApiDoc;
+//!
+//! let builder = WebServerBuilder::new().openapi(ApiDoc::openapi());
+//! ```
+//!
+//! 配置 `[docs]` 后在 `path` 挂载交互式文档界面，所需的 Swagger UI / Redoc 资源已嵌入 crate。
+//! 设置 `username` 和 `password` 后，界面和文档 JSON 都需要 Basic Auth 认证：
+//!
+//! ```toml
+//! [docs]
+//! ui = "swagger"  # 或 "redoc"
+//! path = "/docs"
+//! username = "docs"
+//! password_file = "/run/secrets/docs_password"
+//! ```
+
+use crate::{
+    config::{DocsConfig, DocsUi},
+    error::Result,
+    middleware::constant_time_eq,
+    routing::{escape_html, RouteDoc},
+};
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::{self, Next},
+    response::{Html, IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::sync::Arc;
+use utoipa::openapi::{
+    path::{HttpMethod, OperationBuilder, Paths, PathsBuilder},
+    OpenApi, ResponsesBuilder,
+};
+
+/// 将 axum 路由路径转换为 OpenAPI 路径
+pub fn openapi_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| match segment.strip_prefix(':').or_else(|| segment.strip_prefix('*')) {
+            Some(name) => format!("{{{}}}", name),
+            None => segment.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn http_method(method: &str) -> Option<HttpMethod> {
+    Some(match method.to_ascii_uppercase().as_str() {
+        "GET" => HttpMethod::Get,
+        "POST" => HttpMethod::Post,
+        "PUT" => HttpMethod::Put,
+        "DELETE" => HttpMethod::Delete,
+        "PATCH" => HttpMethod::Patch,
+        "HEAD" => HttpMethod::Head,
+        "OPTIONS" => HttpMethod::Options,
+        "TRACE" => HttpMethod::Trace,
+        _ => return None,
+    })
+}
+
+/// 生成最终提供的文档
+///
+/// 登记的路由文档中没有注解的接口补充到文档中，已有注解的接口保持不变
+pub(crate) fn document(mut doc: OpenApi, route_docs: &[RouteDoc]) -> OpenApi {
+    let mut paths: Paths = PathsBuilder::new().build();
+    for route in route_docs {
+        let Some(method) = http_method(&route.method) else {
+            tracing::warn!("OpenAPI 文档忽略了不支持的方法 {} {}", route.method, route.path);
+            continue;
+        };
+        let operation = OperationBuilder::new()
+            .summary(Some(route.description.clone()))
+            .tags((!route.tags.is_empty()).then(|| route.tags.clone()))
+            .responses(ResponsesBuilder::new().build());
+        paths.add_path_operation(openapi_path(&route.path), vec![method], operation);
+    }
+
+    // 注解中的路径也可能使用 axum 格式
+    let documented = std::mem::replace(&mut doc.paths, PathsBuilder::new().build());
+    for (path, item) in documented.paths {
+        let mut single = PathsBuilder::new().build();
+        single.paths.insert(openapi_path(&path), item);
+        doc.paths.merge(single);
+    }
+    doc.paths.merge(paths);
+    doc
+}
+
+/// 提供文档 JSON 的路由
+pub(crate) fn router(path: &str, doc: &OpenApi) -> Result<Router> {
+    let json = doc.to_json()?;
+    Ok(Router::new().route(
+        path,
+        get(move || async move { ([(header::CONTENT_TYPE, "application/json")], json) }),
+    ))
+}
+
+const SWAGGER_UI_JS: &[u8] = include_bytes!("../res/docs/swagger-ui-bundle.js");
+const SWAGGER_UI_CSS: &[u8] = include_bytes!("../res/docs/swagger-ui.css");
+const REDOC_JS: &[u8] = include_bytes!("../res/docs/redoc.standalone.js");
+
+/// 嵌入的静态资源：文件名、内容类型、内容
+type Asset = (&'static str, &'static str, &'static [u8]);
+
+/// 文档界面路由，`spec_path` 为文档 JSON 的路径
+pub(crate) fn docs_router(config: &DocsConfig, spec_path: &str) -> Router {
+    let base = config.path.trim_end_matches('/');
+    let title = escape_html(&config.title);
+    let spec = escape_html(spec_path);
+    let (html, assets): (String, &[Asset]) = match config.ui {
+        DocsUi::Swagger => (
+            format!(
+                r##"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<link rel="stylesheet" href="{base}/swagger-ui.css">
+</head>
+<body>
+<div id="swagger-ui"></div>
+<script src="{base}/swagger-ui-bundle.js"></script>
+<script>window.onload = () => {{ window.ui = SwaggerUIBundle({{ url: "{spec}", dom_id: "#swagger-ui" }}); }};</script>
+</body>
+</html>
+"##
+            ),
+            &[
+                ("swagger-ui-bundle.js", "text/javascript", SWAGGER_UI_JS),
+                ("swagger-ui.css", "text/css", SWAGGER_UI_CSS),
+            ],
+        ),
+        DocsUi::Redoc => (
+            format!(
+                r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+</head>
+<body>
+<redoc spec-url="{spec}"></redoc>
+<script src="{base}/redoc.standalone.js"></script>
+</body>
+</html>
+"#
+            ),
+            &[("redoc.standalone.js", "text/javascript", REDOC_JS)],
+        ),
+        DocsUi::None => return Router::new(),
+    };
+
+    let page = if base.is_empty() { "/" } else { base };
+    let mut router = Router::new().route(page, get(move || async move { Html(html) }));
+    for &(name, content_type, bytes) in assets {
+        router = router.route(
+            &format!("{}/{}", base, name),
+            get(move || async move { ([(header::CONTENT_TYPE, content_type)], bytes) }),
+        );
+    }
+    router
+}
+
+/// 为文档路由添加 Basic Auth，未配置用户名时原样返回
+pub(crate) fn protect(router: Router, config: &DocsConfig) -> Router {
+    if config.username.is_empty() {
+        return router;
+    }
+    let expected = format!(
+        "Basic {}",
+        STANDARD.encode(format!("{}:{}", config.username, config.effective_password()))
+    );
+    router.route_layer(middleware::from_fn_with_state(Arc::new(expected), basic_auth))
+}
+
+/// Basic Auth 认证中间件
+async fn basic_auth(State(expected): State<Arc<String>>, request: Request, next: Next) -> Response {
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .is_some_and(|provided| constant_time_eq(provided.as_bytes(), expected.as_bytes()));
+    if !authorized {
+        return (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, HeaderValue::from_static(r#"Basic realm="API Docs""#))],
+        )
+            .into_response();
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use utoipa::openapi::{path::PathItem, OpenApiBuilder};
+
+    #[test]
+    fn test_openapi_path() {
+        assert_eq!(openapi_path("/users/:id"), "/users/{id}");
+        assert_eq!(openapi_path("/files/*path"), "/files/{path}");
+        assert_eq!(openapi_path("/health"), "/health");
+    }
+
+    #[test]
+    fn test_route_docs_fill_missing_operations() {
+        let annotated = OperationBuilder::new().summary(Some("注解"));
+        let doc = OpenApiBuilder::new()
+            .paths(PathsBuilder::new().path("/users/:id", PathItem::new(HttpMethod::Get, annotated)))
+            .build();
+        let route_docs = [
+            RouteDoc::new("GET", "/users/:id", "不覆盖注解"),
+            RouteDoc::new("delete", "/users/:id", "删除用户").tag("users"),
+            RouteDoc::new("GET", "/health", "健康检查"),
+        ];
+
+        let doc = document(doc, &route_docs);
+        let json: serde_json::Value = serde_json::from_str(&doc.to_json().unwrap()).unwrap();
+        let user = &json["paths"]["/users/{id}"];
+        assert_eq!(user["get"]["summary"], "注解");
+        assert_eq!(user["delete"]["summary"], "删除用户");
+        assert_eq!(user["delete"]["tags"][0], "users");
+        assert_eq!(json["paths"]["/health"]["get"]["summary"], "健康检查");
+        assert!(json["paths"].get("/users/:id").is_none());
+    }
+
+    async fn send(app: &Router, uri: &str, authorization: Option<&str>) -> Response {
+        use tower::util::ServiceExt;
+
+        let mut request = Request::builder().uri(uri);
+        if let Some(authorization) = authorization {
+            request = request.header(header::AUTHORIZATION, authorization);
+        }
+        app.clone()
+            .oneshot(request.body(axum::body::Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    async fn body(response: Response) -> String {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        String::from_utf8_lossy(&bytes).to_string()
+    }
+
+    #[tokio::test]
+    async fn test_swagger_ui() {
+        let config = DocsConfig {
+            ui: DocsUi::Swagger,
+            ..Default::default()
+        };
+        let app = docs_router(&config, "/api-docs/openapi.json");
+
+        let page = body(send(&app, "/docs", None).await).await;
+        assert!(page.contains(r#"url: "/api-docs/openapi.json""#));
+        assert!(page.contains("/docs/swagger-ui-bundle.js"));
+
+        let response = send(&app, "/docs/swagger-ui.css", None).await;
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "text/css");
+        assert_eq!(body(response).await.len(), SWAGGER_UI_CSS.len());
+        assert_eq!(send(&app, "/docs/redoc.standalone.js", None).await.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_redoc_with_basic_auth() {
+        let config = DocsConfig {
+            ui: DocsUi::Redoc,
+            path: "/reference/".to_string(),
+            username: "docs".to_string(),
+            password: "secret".to_string(),
+            ..Default::default()
+        };
+        let app = protect(docs_router(&config, "/openapi.json"), &config);
+
+        let response = send(&app, "/reference", None).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers()[header::WWW_AUTHENTICATE], r#"Basic realm="API Docs""#);
+
+        // docs:wrong
+        let response = send(&app, "/reference", Some("Basic ZG9jczp3cm9uZw==")).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // docs:secret
+        let response = send(&app, "/reference", Some("Basic ZG9jczpzZWNyZXQ=")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let page = body(response).await;
+        assert!(page.contains(r#"<redoc spec-url="/openapi.json">"#));
+        assert!(page.contains("/reference/redoc.standalone.js"));
+    }
+}
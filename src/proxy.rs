@@ -0,0 +1,488 @@
+//! 反向代理模块（需要 `proxy` feature）
+//!
+//! 把匹配的请求以流式方式转发到上游服务，适合逐步把旧服务的接口迁移到新服务：
+//! 已迁移的接口由本服务处理，其余路径原样转发给旧服务。
+//!
+//! ```rust
+//! use hwhkit::{proxy::ReverseProxy, WebServerBuilder};
+//! use std::time::Duration;
+//!
+//! let builder = WebServerBuilder::new()
+//!     .proxy("/legacy/*path", "http://old-service:8080")
+//!     .proxy_with(
+//!         "/billing/*path",
+//!         ReverseProxy::new("http://billing:9000/api")
+//!             .timeout(Duration::from_secs(5))
+//!             .request_header("x-api-key", "secret")
+//!             .remove_response_header("server"),
+//!     );
+//! ```
+//!
+//! 路径以 `*name` 通配段结尾时，只把通配段匹配的部分拼接到上游地址之后，上例中
+//! `/legacy/orders/1?page=2` 转发到 `http://old-service:8080/orders/1?page=2`；
+//! 没有通配段时拼接完整的请求路径。
+//!
+//! 转发时去除逐跳请求头，追加 `X-Forwarded-For`，缺少时补充 `X-Forwarded-Host` 和
+//! `X-Forwarded-Proto`，并写入当前请求的追踪上下文；`Host` 默认改写为上游地址。
+//! 上游无法连接时返回 502，等待响应头超时返回 504。超时、重试和熔断由 `[reverse_proxy]` 配置，
+//! 重试只用于 GET、HEAD 和 OPTIONS 请求，其余请求的请求体以流的形式转发，不会缓存。
+//! 不支持 WebSocket 等协议升级。
+
+use crate::{
+    config::ReverseProxyConfig,
+    error::{Error, Result},
+    middleware::trace_context::TraceContext,
+    resilience::CircuitBreaker,
+};
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, Request},
+    http::{header, request::Parts, HeaderMap, HeaderName, HeaderValue, Method, StatusCode, Uri},
+    response::Response,
+    routing::any,
+    Router,
+};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+/// 逐跳请求头，只对单个连接有效，不能转发
+const HOP_BY_HOP: [HeaderName; 8] = [
+    header::CONNECTION,
+    HeaderName::from_static("keep-alive"),
+    header::PROXY_AUTHENTICATE,
+    header::PROXY_AUTHORIZATION,
+    header::TE,
+    header::TRAILER,
+    header::TRANSFER_ENCODING,
+    header::UPGRADE,
+];
+
+const X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
+const X_FORWARDED_HOST: HeaderName = HeaderName::from_static("x-forwarded-host");
+const X_FORWARDED_PROTO: HeaderName = HeaderName::from_static("x-forwarded-proto");
+
+/// 代理路由，未设置的选项使用 `[reverse_proxy]` 中的配置
+#[derive(Debug, Clone)]
+pub struct ReverseProxy {
+    upstream: String,
+    timeout: Option<Duration>,
+    retries: Option<u32>,
+    preserve_host: Option<bool>,
+    request_headers: Vec<(String, Option<String>)>,
+    response_headers: Vec<(String, Option<String>)>,
+}
+
+impl ReverseProxy {
+    /// 创建转发到 `upstream` 的代理
+    ///
+    /// # Arguments
+    ///
+    /// * `upstream` - 上游地址，如 `http://old-service:8080`，可以包含路径前缀
+    pub fn new(upstream: &str) -> Self {
+        Self {
+            upstream: upstream.to_string(),
+            timeout: None,
+            retries: None,
+            preserve_host: None,
+            request_headers: Vec::new(),
+            response_headers: Vec::new(),
+        }
+    }
+
+    /// 设置等待上游响应头的超时时间
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// 设置 GET、HEAD、OPTIONS 请求的重试次数
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = Some(retries);
+        self
+    }
+
+    /// 是否保留原始的 `Host` 请求头
+    pub fn preserve_host(mut self, preserve: bool) -> Self {
+        self.preserve_host = Some(preserve);
+        self
+    }
+
+    /// 设置转发给上游的请求头，覆盖同名的原始请求头
+    pub fn request_header(mut self, name: &str, value: &str) -> Self {
+        self.request_headers.push((name.to_string(), Some(value.to_string())));
+        self
+    }
+
+    /// 去除转发给上游的请求头
+    pub fn remove_request_header(mut self, name: &str) -> Self {
+        self.request_headers.push((name.to_string(), None));
+        self
+    }
+
+    /// 设置返回给客户端的响应头，覆盖上游返回的同名响应头
+    pub fn response_header(mut self, name: &str, value: &str) -> Self {
+        self.response_headers.push((name.to_string(), Some(value.to_string())));
+        self
+    }
+
+    /// 去除上游返回的响应头
+    pub fn remove_response_header(mut self, name: &str) -> Self {
+        self.response_headers.push((name.to_string(), None));
+        self
+    }
+
+    /// 生成挂载在 `path` 上、接受所有方法的路由
+    pub(crate) fn into_router(
+        self,
+        path: &str,
+        config: &ReverseProxyConfig,
+        client: &reqwest::Client,
+    ) -> Result<Router> {
+        let url = reqwest::Url::parse(&self.upstream)
+            .map_err(|e| Error::Config(format!("无效的代理上游地址 '{}': {}", self.upstream, e)))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(Error::Config(format!(
+                "代理上游地址必须使用 http 或 https: {}",
+                self.upstream
+            )));
+        }
+
+        let upstream = Arc::new(Upstream {
+            client: client.clone(),
+            base: self.upstream.trim_end_matches('/').to_string(),
+            wildcard_depth: wildcard_depth(path),
+            timeout: self.timeout.unwrap_or(Duration::from_secs(config.timeout_secs)),
+            retries: self.retries.unwrap_or(config.retries),
+            retry_delay: Duration::from_millis(config.retry_delay_ms),
+            preserve_host: self.preserve_host.unwrap_or(config.preserve_host),
+            request_headers: parse_rewrites(&self.request_headers)?,
+            response_headers: parse_rewrites(&self.response_headers)?,
+            breaker: config
+                .circuit_breaker
+                .enabled
+                .then(|| CircuitBreaker::new(&format!("proxy:{}", path), &config.circuit_breaker)),
+        });
+
+        Ok(Router::new().route(
+            path,
+            any(move |request: Request| {
+                let upstream = upstream.clone();
+                async move { upstream.forward(request).await }
+            }),
+        ))
+    }
+}
+
+/// 创建所有代理路由共用的 HTTP 客户端，不跟随重定向
+pub(crate) fn client(config: &ReverseProxyConfig) -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(config.connect_timeout_secs))
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|e| Error::Config(format!("无法创建 HTTP 客户端: {}", e)))
+}
+
+/// 请求头或响应头的改写
+#[derive(Debug)]
+enum HeaderRewrite {
+    Set(HeaderName, HeaderValue),
+    Remove(HeaderName),
+}
+
+fn parse_rewrites(rewrites: &[(String, Option<String>)]) -> Result<Vec<HeaderRewrite>> {
+    rewrites
+        .iter()
+        .map(|(name, value)| {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| Error::Config(format!("无效的代理请求头名称 '{}': {}", name, e)))?;
+            Ok(match value {
+                Some(value) => HeaderRewrite::Set(
+                    name,
+                    HeaderValue::from_str(value)
+                        .map_err(|e| Error::Config(format!("无效的代理请求头值 '{}': {}", value, e)))?,
+                ),
+                None => HeaderRewrite::Remove(name),
+            })
+        })
+        .collect()
+}
+
+fn apply_rewrites(rewrites: &[HeaderRewrite], headers: &mut HeaderMap) {
+    for rewrite in rewrites {
+        match rewrite {
+            HeaderRewrite::Set(name, value) => {
+                headers.insert(name.clone(), value.clone());
+            }
+            HeaderRewrite::Remove(name) => {
+                headers.remove(name);
+            }
+        }
+    }
+}
+
+/// 去除逐跳请求头以及 `Connection` 中列出的请求头
+fn remove_hop_by_hop(headers: &mut HeaderMap) {
+    let listed: Vec<HeaderName> = headers
+        .get_all(header::CONNECTION)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|name| HeaderName::from_bytes(name.trim().as_bytes()).ok())
+        .collect();
+    for name in HOP_BY_HOP.iter().chain(&listed) {
+        headers.remove(name);
+    }
+}
+
+/// 通配段之前的路径段数，没有通配段时为 `None`
+fn wildcard_depth(path: &str) -> Option<usize> {
+    path.trim_start_matches('/')
+        .split('/')
+        .position(|segment| segment.starts_with('*'))
+}
+
+#[derive(Debug)]
+struct Upstream {
+    client: reqwest::Client,
+    base: String,
+    wildcard_depth: Option<usize>,
+    timeout: Duration,
+    retries: u32,
+    retry_delay: Duration,
+    preserve_host: bool,
+    request_headers: Vec<HeaderRewrite>,
+    response_headers: Vec<HeaderRewrite>,
+    breaker: Option<CircuitBreaker>,
+}
+
+impl Upstream {
+    async fn forward(&self, request: Request) -> Result<Response> {
+        let (parts, body) = request.into_parts();
+        let url = self.target_url(&parts.uri);
+        let headers = self.forward_headers(&parts);
+
+        let retryable = self.retries > 0 && matches!(parts.method, Method::GET | Method::HEAD | Method::OPTIONS);
+        if !retryable {
+            let body = reqwest::Body::wrap_stream(body.into_data_stream());
+            let response = self.send(&parts.method, &url, headers, body).await?;
+            return Ok(self.response(response));
+        }
+
+        // 重试时需要重新发送请求体
+        let body = axum::body::to_bytes(body, usize::MAX)
+            .await
+            .map_err(|e| Error::BadRequest(format!("无法读取请求体: {}", e)))?;
+        let mut delay = self.retry_delay;
+        let mut attempt = 0;
+        loop {
+            let result = self
+                .send(&parts.method, &url, headers.clone(), body.clone().into())
+                .await;
+            let retry = match &result {
+                Ok(response) => matches!(
+                    response.status(),
+                    StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT
+                ),
+                // 已熔断时重试没有意义
+                Err(Error::ServiceUnavailable(_)) => false,
+                Err(_) => true,
+            };
+            if !retry || attempt >= self.retries {
+                return result.map(|response| self.response(response));
+            }
+            attempt += 1;
+            tracing::warn!("代理请求 {} 失败，{} 毫秒后第 {} 次重试", url, delay.as_millis(), attempt);
+            tokio::time::sleep(delay).await;
+            delay *= 2;
+        }
+    }
+
+    fn target_url(&self, uri: &Uri) -> String {
+        let mut url = self.base.clone();
+        match self.wildcard_depth {
+            Some(depth) => {
+                let rest = uri
+                    .path()
+                    .trim_start_matches('/')
+                    .splitn(depth + 1, '/')
+                    .nth(depth)
+                    .unwrap_or_default();
+                url.push('/');
+                url.push_str(rest);
+            }
+            None => url.push_str(uri.path()),
+        }
+        if let Some(query) = uri.query() {
+            url.push('?');
+            url.push_str(query);
+        }
+        url
+    }
+
+    fn forward_headers(&self, parts: &Parts) -> HeaderMap {
+        let mut headers = parts.headers.clone();
+        remove_hop_by_hop(&mut headers);
+
+        let host = parts.headers.get(header::HOST).cloned().or_else(|| {
+            parts
+                .uri
+                .authority()
+                .and_then(|authority| HeaderValue::from_str(authority.as_str()).ok())
+        });
+        if !self.preserve_host {
+            headers.remove(header::HOST);
+        }
+
+        // 在已有的转发链之后追加直接连接的对端地址
+        if let Some(ConnectInfo(addr)) = parts.extensions.get::<ConnectInfo<SocketAddr>>() {
+            let mut chain: Vec<String> = parts
+                .headers
+                .get_all(&X_FORWARDED_FOR)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .map(str::to_string)
+                .collect();
+            chain.push(addr.ip().to_canonical().to_string());
+            if let Ok(value) = HeaderValue::from_str(&chain.join(", ")) {
+                headers.insert(X_FORWARDED_FOR, value);
+            }
+        }
+        if let Some(host) = host.filter(|_| !headers.contains_key(&X_FORWARDED_HOST)) {
+            headers.insert(X_FORWARDED_HOST, host);
+        }
+        if !headers.contains_key(&X_FORWARDED_PROTO) {
+            let proto = if parts.uri.scheme_str() == Some("https") { "https" } else { "http" };
+            headers.insert(X_FORWARDED_PROTO, HeaderValue::from_static(proto));
+        }
+
+        if let Some(context) = TraceContext::current() {
+            context.inject(&mut headers);
+        }
+        apply_rewrites(&self.request_headers, &mut headers);
+        headers
+    }
+
+    async fn send(
+        &self,
+        method: &Method,
+        url: &str,
+        headers: HeaderMap,
+        body: reqwest::Body,
+    ) -> Result<reqwest::Response> {
+        let request = self
+            .client
+            .request(method.clone(), url)
+            .headers(headers)
+            .body(body)
+            .send();
+        // 只限制等待响应头的时间，响应体按流转发
+        let send = async {
+            match tokio::time::timeout(self.timeout, request).await {
+                Ok(Ok(response)) => Ok(response),
+                Ok(Err(e)) => {
+                    tracing::warn!("代理请求 {} 失败: {}", url, e);
+                    Err(Error::BadGateway("上游服务不可用".to_string()))
+                }
+                Err(_) => {
+                    tracing::warn!("代理请求 {} 超时", url);
+                    Err(Error::GatewayTimeout("上游服务响应超时".to_string()))
+                }
+            }
+        };
+        match &self.breaker {
+            Some(breaker) => breaker.call(send).await,
+            None => send.await,
+        }
+    }
+
+    fn response(&self, upstream: reqwest::Response) -> Response {
+        let status = upstream.status();
+        let mut headers = upstream.headers().clone();
+        remove_hop_by_hop(&mut headers);
+        apply_rewrites(&self.response_headers, &mut headers);
+
+        let mut response = Response::new(Body::from_stream(upstream.bytes_stream()));
+        *response.status_mut() = status;
+        *response.headers_mut() = headers;
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn upstream(base: &str, path: &str) -> Upstream {
+        Upstream {
+            client: reqwest::Client::new(),
+            base: base.trim_end_matches('/').to_string(),
+            wildcard_depth: wildcard_depth(path),
+            timeout: Duration::from_secs(1),
+            retries: 0,
+            retry_delay: Duration::from_millis(1),
+            preserve_host: false,
+            request_headers: parse_rewrites(&[
+                ("x-api-key".to_string(), Some("secret".to_string())),
+                ("cookie".to_string(), None),
+            ])
+            .unwrap(),
+            response_headers: Vec::new(),
+            breaker: None,
+        }
+    }
+
+    #[test]
+    fn test_target_url() {
+        let legacy = upstream("http://old:8080/", "/legacy/*path");
+        let uri: Uri = "/legacy/orders/1?page=2".parse().unwrap();
+        assert_eq!(legacy.target_url(&uri), "http://old:8080/orders/1?page=2");
+
+        let nested = upstream("http://billing:9000/api", "/tenants/:id/billing/*rest");
+        let uri: Uri = "/tenants/7/billing/invoices".parse().unwrap();
+        assert_eq!(nested.target_url(&uri), "http://billing:9000/api/invoices");
+
+        let exact = upstream("http://old:8080", "/users/:id");
+        let uri: Uri = "/users/5".parse().unwrap();
+        assert_eq!(exact.target_url(&uri), "http://old:8080/users/5");
+    }
+
+    #[test]
+    fn test_forward_headers() {
+        let proxy = upstream("http://old:8080", "/*path");
+        let mut request = Request::builder()
+            .uri("/orders")
+            .header(header::HOST, "example.com")
+            .header(header::CONNECTION, "keep-alive, x-hop")
+            .header("x-hop", "1")
+            .header(header::COOKIE, "session=1")
+            .header("x-forwarded-for", "203.0.113.9")
+            .header(header::ACCEPT, "application/json")
+            .body(Body::empty())
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([10, 0, 0, 2], 4000))));
+        let (parts, _) = request.into_parts();
+
+        let headers = proxy.forward_headers(&parts);
+        assert!(headers.get(header::HOST).is_none());
+        assert!(headers.get(header::CONNECTION).is_none());
+        assert!(headers.get("x-hop").is_none());
+        assert!(headers.get(header::COOKIE).is_none());
+        assert_eq!(headers["x-forwarded-for"], "203.0.113.9, 10.0.0.2");
+        assert_eq!(headers["x-forwarded-host"], "example.com");
+        assert_eq!(headers["x-forwarded-proto"], "http");
+        assert_eq!(headers["x-api-key"], "secret");
+        assert_eq!(headers[header::ACCEPT], "application/json");
+    }
+
+    #[test]
+    fn test_invalid_upstream() {
+        let client = reqwest::Client::new();
+        let config = ReverseProxyConfig::default();
+        assert!(ReverseProxy::new("old-service:8080").into_router("/*path", &config, &client).is_err());
+        assert!(ReverseProxy::new("http://old")
+            .request_header("bad header", "1")
+            .into_router("/*path", &config, &client)
+            .is_err());
+    }
+}
@@ -0,0 +1,129 @@
+//! 就绪探针模块
+//!
+//! 启用 `[server.readiness]` 后在 `path`（默认 `/readyz`）提供就绪探针，依次执行登记的检查
+//! （如 MongoDB 连通性），全部通过时返回 200，否则返回 503。开始优雅关闭后同样返回 503，
+//! 负载均衡器据此停止转发新请求。
+//!
+//! ```json
+//! { "status": "unavailable", "checks": { "mongodb": "服务不可用: ..." } }
+//! ```
+
+use crate::{error::Result, shutdown::ShutdownSignal};
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use futures_util::future::BoxFuture;
+use serde_json::{json, Map, Value};
+use std::{fmt, sync::Arc};
+
+type CheckFn = Arc<dyn Fn() -> BoxFuture<'static, Result<()>> + Send + Sync>;
+
+/// 就绪检查集合
+#[derive(Clone, Default)]
+pub struct ReadinessChecks {
+    checks: Vec<(String, CheckFn)>,
+}
+
+impl fmt::Debug for ReadinessChecks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<_> = self.checks.iter().map(|(name, _)| name).collect();
+        f.debug_struct("ReadinessChecks").field("checks", &names).finish()
+    }
+}
+
+impl ReadinessChecks {
+    /// 登记检查，返回错误时探针报告未就绪
+    pub fn add<F>(&mut self, name: &str, check: F)
+    where
+        F: Fn() -> BoxFuture<'static, Result<()>> + Send + Sync + 'static,
+    {
+        self.checks.push((name.to_string(), Arc::new(check)));
+    }
+
+    /// 执行所有检查，返回是否全部通过及每项结果
+    pub async fn run(&self) -> (bool, Map<String, Value>) {
+        let mut ready = true;
+        let mut results = Map::new();
+        for (name, check) in &self.checks {
+            let result = match check().await {
+                Ok(()) => "ok".to_string(),
+                Err(e) => {
+                    ready = false;
+                    e.to_string()
+                }
+            };
+            results.insert(name.clone(), Value::String(result));
+        }
+        (ready, results)
+    }
+}
+
+#[derive(Clone)]
+struct ReadinessState {
+    checks: ReadinessChecks,
+    shutdown: ShutdownSignal,
+}
+
+/// 创建就绪探针路由
+pub fn readiness_router(path: &str, checks: ReadinessChecks, shutdown: ShutdownSignal) -> Router {
+    Router::new()
+        .route(path, get(readiness))
+        .with_state(ReadinessState { checks, shutdown })
+}
+
+async fn readiness(State(state): State<ReadinessState>) -> Response {
+    if state.shutdown.is_shutting_down() {
+        let body = json!({ "status": "shutting_down", "checks": {} });
+        return (StatusCode::SERVICE_UNAVAILABLE, Json(body)).into_response();
+    }
+
+    let (ready, checks) = state.checks.run().await;
+    let (status, label) = if ready {
+        (StatusCode::OK, "ready")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "unavailable")
+    };
+    (status, Json(json!({ "status": label, "checks": checks }))).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::ShutdownConfig, error::Error};
+    use axum::{body::Body, extract::Request};
+    use tower::util::ServiceExt;
+
+    async fn probe(app: &Router) -> (StatusCode, Value) {
+        let request = Request::builder().uri("/readyz").body(Body::empty()).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_readiness() {
+        let shutdown = ShutdownSignal::new(&ShutdownConfig::default());
+        let mut checks = ReadinessChecks::default();
+        checks.add("cache", || Box::pin(async { Ok(()) }));
+        let app = readiness_router("/readyz", checks.clone(), shutdown.clone());
+        let (status, body) = probe(&app).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["checks"]["cache"], "ok");
+
+        checks.add("db", || Box::pin(async { Err(Error::ServiceUnavailable("down".to_string())) }));
+        let app = readiness_router("/readyz", checks, shutdown.clone());
+        let (status, body) = probe(&app).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["status"], "unavailable");
+
+        shutdown.trigger();
+        let (status, body) = probe(&app).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["status"], "shutting_down");
+    }
+}
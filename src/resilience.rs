@@ -0,0 +1,320 @@
+//! 容错模块
+//!
+//! [`CircuitBreaker`] 包装对外部服务的调用：连续失败达到阈值后熔断，熔断期间的调用直接返回
+//! 503 而不再请求下游，熔断时间结束后放行少量探测调用，探测成功则恢复。
+//!
+//! ```rust,no_run
+//! use hwhkit::{config::CircuitBreakerConfig, resilience::CircuitBreaker, Result};
+//!
+//! # async fn example() -> Result<()> {
+//! let breaker = CircuitBreaker::new("payments", &CircuitBreakerConfig::default());
+//! let body = breaker
+//!     .call(async {
+//!         // 调用下游服务，返回 Err 计为一次失败
+//!         Ok("ok".to_string())
+//!     })
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! 内置的 CDN 缓存清除、JWKS 获取和反向代理通过各自的 `circuit_breaker` 配置启用熔断。
+//! 启用指标后，所有熔断器的状态以 `circuit_breaker_state` 和 `circuit_breaker_rejected_total`
+//! 导出，`name` 标签为创建时的名称。
+
+use crate::{
+    config::CircuitBreakerConfig,
+    error::{Error, Result},
+};
+use std::{
+    fmt,
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, OnceLock, Weak,
+    },
+    time::{Duration, Instant},
+};
+
+/// 熔断器状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// 正常放行
+    Closed,
+    /// 熔断中，调用直接失败
+    Open,
+    /// 熔断时间已过，放行探测调用
+    HalfOpen,
+}
+
+impl fmt::Display for CircuitState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Closed => "closed",
+            Self::Open => "open",
+            Self::HalfOpen => "half_open",
+        })
+    }
+}
+
+#[derive(Debug)]
+enum State {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    /// `probes` 为进行中的探测调用数
+    HalfOpen { probes: u32, successes: u32 },
+}
+
+#[derive(Debug)]
+struct Inner {
+    name: String,
+    failure_threshold: u32,
+    open_duration: Duration,
+    half_open_probes: u32,
+    state: Mutex<State>,
+    rejected: AtomicU64,
+}
+
+fn registry() -> &'static Mutex<Vec<Weak<Inner>>> {
+    static BREAKERS: OnceLock<Mutex<Vec<Weak<Inner>>>> = OnceLock::new();
+    BREAKERS.get_or_init(Default::default)
+}
+
+/// 熔断器
+///
+/// 克隆的熔断器共享同一状态
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    inner: Arc<Inner>,
+}
+
+impl CircuitBreaker {
+    /// 创建熔断器并注册到指标中
+    ///
+    /// 配置中的 `enabled` 由调用方判断，这里不做检查；为 0 的阈值和探测数按 1 处理
+    pub fn new(name: &str, config: &CircuitBreakerConfig) -> Self {
+        let inner = Arc::new(Inner {
+            name: name.to_string(),
+            failure_threshold: config.failure_threshold.max(1),
+            open_duration: Duration::from_secs(config.open_secs),
+            half_open_probes: config.half_open_probes.max(1),
+            state: Mutex::new(State::Closed { failures: 0 }),
+            rejected: AtomicU64::new(0),
+        });
+
+        if let Ok(mut breakers) = registry().lock() {
+            breakers.retain(|breaker| breaker.strong_count() > 0);
+            breakers.push(Arc::downgrade(&inner));
+        }
+        Self { inner }
+    }
+
+    /// 熔断器名称
+    pub fn name(&self) -> &str {
+        &self.inner.name
+    }
+
+    /// 当前状态，熔断时间已过但尚未探测时视为半开
+    pub fn state(&self) -> CircuitState {
+        match *self.lock() {
+            State::Closed { .. } => CircuitState::Closed,
+            State::Open { until } if Instant::now() < until => CircuitState::Open,
+            State::Open { .. } | State::HalfOpen { .. } => CircuitState::HalfOpen,
+        }
+    }
+
+    /// 因熔断被拒绝的调用数
+    pub fn rejected(&self) -> u64 {
+        self.inner.rejected.load(Ordering::Relaxed)
+    }
+
+    /// 通过熔断器执行调用
+    ///
+    /// `future` 返回 `Err` 计为一次失败。熔断中或半开状态下探测名额已满时不执行 `future`，
+    /// 直接返回 [`Error::ServiceUnavailable`]
+    pub async fn call<T, F>(&self, future: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        let mut permit = self.acquire()?;
+        let result = future.await;
+        permit.record(result.is_ok());
+        result
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.inner.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn acquire(&self) -> Result<Permit<'_>> {
+        let mut state = self.lock();
+        let probe = match &mut *state {
+            State::Closed { .. } => false,
+            State::Open { until } if Instant::now() >= *until => {
+                tracing::info!("熔断器 {} 进入半开状态", self.inner.name);
+                *state = State::HalfOpen { probes: 1, successes: 0 };
+                true
+            }
+            State::HalfOpen { probes, successes } if *probes + *successes < self.inner.half_open_probes => {
+                *probes += 1;
+                true
+            }
+            State::Open { .. } | State::HalfOpen { .. } => {
+                self.inner.rejected.fetch_add(1, Ordering::Relaxed);
+                return Err(Error::ServiceUnavailable(format!("{} 已熔断，请稍后重试", self.inner.name)));
+            }
+        };
+        Ok(Permit {
+            breaker: self,
+            probe,
+            done: false,
+        })
+    }
+
+    fn open(&self, state: &mut State) {
+        tracing::warn!(
+            "熔断器 {} 已熔断，{} 秒后重试",
+            self.inner.name,
+            self.inner.open_duration.as_secs()
+        );
+        *state = State::Open {
+            until: Instant::now() + self.inner.open_duration,
+        };
+    }
+}
+
+/// 单次调用的放行凭证，未记录结果就被丢弃（调用被取消）时释放探测名额
+struct Permit<'a> {
+    breaker: &'a CircuitBreaker,
+    probe: bool,
+    done: bool,
+}
+
+impl Permit<'_> {
+    fn record(&mut self, success: bool) {
+        self.done = true;
+        let breaker = self.breaker;
+        let mut state = breaker.lock();
+        match &mut *state {
+            State::Closed { failures } if success => *failures = 0,
+            State::Closed { failures } => {
+                *failures += 1;
+                if *failures >= breaker.inner.failure_threshold {
+                    breaker.open(&mut state);
+                }
+            }
+            // 只有探测调用的结果决定半开状态的去向
+            State::HalfOpen { probes, successes } if self.probe && success => {
+                *probes -= 1;
+                *successes += 1;
+                if *successes >= breaker.inner.half_open_probes {
+                    tracing::info!("熔断器 {} 已恢复", breaker.inner.name);
+                    *state = State::Closed { failures: 0 };
+                }
+            }
+            State::HalfOpen { .. } if self.probe => breaker.open(&mut state),
+            State::HalfOpen { .. } | State::Open { .. } => {}
+        }
+    }
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        if self.done || !self.probe {
+            return;
+        }
+        if let State::HalfOpen { probes, .. } = &mut *self.breaker.lock() {
+            *probes -= 1;
+        }
+    }
+}
+
+/// 所有仍在使用的熔断器，按名称排序
+pub(crate) fn registered() -> Vec<CircuitBreaker> {
+    let Ok(breakers) = registry().lock() else {
+        return Vec::new();
+    };
+    let mut breakers: Vec<_> = breakers
+        .iter()
+        .filter_map(Weak::upgrade)
+        .map(|inner| CircuitBreaker { inner })
+        .collect();
+    breakers.sort_by(|a, b| a.name().cmp(b.name()));
+    breakers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker(name: &str, open_secs: u64, half_open_probes: u32) -> CircuitBreaker {
+        CircuitBreaker::new(
+            name,
+            &CircuitBreakerConfig {
+                enabled: true,
+                failure_threshold: 2,
+                open_secs,
+                half_open_probes,
+            },
+        )
+    }
+
+    async fn fail(breaker: &CircuitBreaker) -> Result<()> {
+        breaker.call(async { Err::<(), _>(Error::Internal("down".to_string())) }).await
+    }
+
+    #[tokio::test]
+    async fn test_opens_after_threshold() {
+        let breaker = breaker("test-open", 60, 1);
+        assert!(fail(&breaker).await.is_err());
+        breaker.call(async { Ok(()) }).await.unwrap();
+        // 成功调用重置连续失败次数
+        assert!(fail(&breaker).await.is_err());
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(fail(&breaker).await.is_err());
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        let mut called = false;
+        let result = breaker
+            .call(async {
+                called = true;
+                Ok(())
+            })
+            .await;
+        assert!(matches!(result, Err(Error::ServiceUnavailable(_))));
+        assert!(!called);
+        assert_eq!(breaker.rejected(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_half_open_probes() {
+        let breaker = breaker("test-half-open", 0, 2);
+        fail(&breaker).await.unwrap_err();
+        fail(&breaker).await.unwrap_err();
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+
+        // 探测失败重新熔断
+        fail(&breaker).await.unwrap_err();
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+
+        // 探测名额用完后拒绝其他调用，全部探测成功后恢复
+        let first = breaker.acquire().unwrap();
+        let mut second = breaker.acquire().unwrap();
+        assert!(breaker.acquire().is_err());
+        // 取消的探测释放名额
+        drop(first);
+        let mut third = breaker.acquire().unwrap();
+        second.record(true);
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        third.record(true);
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_registry() {
+        let breaker = breaker("test-registry", 60, 1);
+        assert!(registered().iter().any(|b| b.name() == "test-registry"));
+        drop(breaker);
+        assert!(!registered().iter().any(|b| b.name() == "test-registry"));
+    }
+}
@@ -0,0 +1,285 @@
+//! 统一响应格式模块
+//!
+//! 处理器返回 [`Ok`]、[`Created`]、[`Paginated`] 或 [`ApiError`]，响应体统一包装为：
+//!
+//! ```json
+//! {"success": true, "data": {"id": 1}, "message": "操作成功"}
+//! {"success": true, "data": [], "pagination": {"page": 1, "per_page": 20, "total": 0, "total_pages": 0}, "message": "操作成功"}
+//! {"success": false, "error": "用户不存在", "code": 404}
+//! ```
+//!
+//! 设置 `[server.response] envelope = false` 后直接返回数据（分页响应为 `{"items", "pagination"}`，
+//! 错误与 [`Error`] 的响应格式相同）。
+//!
+//! ```rust
+//! use hwhkit::response::{ApiError, Created, Ok};
+//! use serde::Serialize;
+//!
+//! #[derive(Serialize)]
+//! struct User {
+//!     id: u64,
+//! }
+//!
+//! async fn get_user() -> Result<Ok<User>, ApiError> {
+//!     Err(ApiError::not_found("用户不存在"))
+//! }
+//!
+//! async fn create_user() -> Created<User> {
+//!     Created(User { id: 1 })
+//! }
+//! ```
+
+use crate::error::Error;
+use axum::{
+    body::{Body, Bytes},
+    extract::Request,
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use serde_json::json;
+
+/// 成功响应的默认提示
+const SUCCESS_MESSAGE: &str = "操作成功";
+
+/// 不包装时使用的响应体，由 [`unwrap_envelope`] 替换
+#[derive(Debug, Clone)]
+struct Bare(Bytes);
+
+/// 生成 JSON 响应，响应体为包装后的格式，并附带不包装时的响应体
+fn respond(status: StatusCode, enveloped: serde_json::Value, bare: serde_json::Value) -> Response {
+    let (Result::Ok(body), Result::Ok(bare)) = (serde_json::to_vec(&enveloped), serde_json::to_vec(&bare)) else {
+        return Error::Internal("响应序列化失败".to_string()).into_response();
+    };
+    let mut response = (
+        status,
+        [(header::CONTENT_TYPE, HeaderValue::from_static("application/json"))],
+        body,
+    )
+        .into_response();
+    response.extensions_mut().insert(Bare(bare.into()));
+    response
+}
+
+fn success<T: Serialize>(status: StatusCode, data: T) -> Response {
+    match serde_json::to_value(data) {
+        Result::Ok(data) => respond(
+            status,
+            json!({ "success": true, "data": data, "message": SUCCESS_MESSAGE }),
+            data,
+        ),
+        Err(e) => Error::Internal(format!("响应序列化失败: {}", e)).into_response(),
+    }
+}
+
+/// 200 成功响应
+#[derive(Debug, Clone)]
+pub struct Ok<T>(pub T);
+
+impl<T: Serialize> IntoResponse for Ok<T> {
+    fn into_response(self) -> Response {
+        success(StatusCode::OK, self.0)
+    }
+}
+
+/// 201 创建成功响应
+#[derive(Debug, Clone)]
+pub struct Created<T>(pub T);
+
+impl<T: Serialize> IntoResponse for Created<T> {
+    fn into_response(self) -> Response {
+        success(StatusCode::CREATED, self.0)
+    }
+}
+
+/// 分页响应
+#[derive(Debug, Clone)]
+pub struct Paginated<T> {
+    /// 当前页的数据
+    pub items: Vec<T>,
+    /// 页码，从 1 开始
+    pub page: u64,
+    /// 每页数量
+    pub per_page: u64,
+    /// 总数
+    pub total: u64,
+}
+
+impl<T> Paginated<T> {
+    /// 创建分页响应
+    pub fn new(items: Vec<T>, page: u64, per_page: u64, total: u64) -> Self {
+        Self { items, page, per_page, total }
+    }
+
+    /// 总页数
+    pub fn total_pages(&self) -> u64 {
+        if self.per_page == 0 {
+            0
+        } else {
+            self.total.div_ceil(self.per_page)
+        }
+    }
+}
+
+impl<T: Serialize> IntoResponse for Paginated<T> {
+    fn into_response(self) -> Response {
+        let pagination = json!({
+            "page": self.page,
+            "per_page": self.per_page,
+            "total": self.total,
+            "total_pages": self.total_pages(),
+        });
+        match serde_json::to_value(self.items) {
+            Result::Ok(items) => respond(
+                StatusCode::OK,
+                json!({
+                    "success": true,
+                    "data": items,
+                    "pagination": pagination,
+                    "message": SUCCESS_MESSAGE,
+                }),
+                json!({ "items": items, "pagination": pagination }),
+            ),
+            Err(e) => Error::Internal(format!("响应序列化失败: {}", e)).into_response(),
+        }
+    }
+}
+
+/// 错误响应
+#[derive(Debug, Clone)]
+pub struct ApiError {
+    /// 状态码
+    pub status: StatusCode,
+    /// 错误信息
+    pub message: String,
+}
+
+impl ApiError {
+    /// 创建错误响应
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self { status, message: message.into() }
+    }
+
+    /// 400 请求错误
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, message)
+    }
+
+    /// 401 未认证
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, message)
+    }
+
+    /// 403 无权限
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::FORBIDDEN, message)
+    }
+
+    /// 404 资源不存在
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, message)
+    }
+
+    /// 500 内部错误
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, message)
+    }
+}
+
+impl From<Error> for ApiError {
+    fn from(error: Error) -> Self {
+        let (status, message) = error.status_and_message();
+        Self { status, message }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        // 信息是翻译键时使用当前请求语言的翻译
+        #[cfg(feature = "i18n")]
+        let message = crate::i18n::lookup(&self.message).unwrap_or(self.message);
+        #[cfg(not(feature = "i18n"))]
+        let message = self.message;
+        let code = self.status.as_u16();
+        respond(
+            self.status,
+            json!({ "success": false, "error": message, "code": code }),
+            json!({ "error": message, "status": code }),
+        )
+    }
+}
+
+/// 关闭包装时替换响应体的中间件
+pub(crate) async fn unwrap_envelope(request: Request, next: Next) -> Response {
+    let mut response = next.run(request).await;
+    if let Some(Bare(bare)) = response.extensions_mut().remove::<Bare>() {
+        response.headers_mut().remove(header::CONTENT_LENGTH);
+        *response.body_mut() = Body::from(bare);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::get, Router};
+    use serde_json::Value;
+    use tower::util::ServiceExt;
+
+    fn app() -> Router {
+        Router::new()
+            .route("/ok", get(|| async { Ok(json!({ "id": 1 })) }))
+            .route("/created", get(|| async { Created("new") }))
+            .route("/page", get(|| async { Paginated::new(vec![1, 2], 2, 2, 5) }))
+            .route(
+                "/error",
+                get(|| async { ApiError::from(Error::Forbidden("没有权限".to_string())) }),
+            )
+    }
+
+    async fn call(app: Router, uri: &str) -> (StatusCode, Value) {
+        let response = app
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_envelope() {
+        let (status, body) = call(app(), "/ok").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, json!({ "success": true, "data": { "id": 1 }, "message": "操作成功" }));
+
+        let (status, body) = call(app(), "/created").await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body["data"], "new");
+
+        let (_, body) = call(app(), "/page").await;
+        assert_eq!(body["data"], json!([1, 2]));
+        assert_eq!(body["pagination"], json!({ "page": 2, "per_page": 2, "total": 5, "total_pages": 3 }));
+
+        let (status, body) = call(app(), "/error").await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body, json!({ "success": false, "error": "禁止访问: 没有权限", "code": 403 }));
+    }
+
+    #[tokio::test]
+    async fn test_without_envelope() {
+        let app = app().layer(axum::middleware::from_fn(unwrap_envelope));
+
+        let (_, body) = call(app.clone(), "/ok").await;
+        assert_eq!(body, json!({ "id": 1 }));
+
+        let (_, body) = call(app.clone(), "/page").await;
+        assert_eq!(body["items"], json!([1, 2]));
+        assert_eq!(body["pagination"]["total_pages"], 3);
+
+        let (status, body) = call(app, "/error").await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body, json!({ "error": "禁止访问: 没有权限", "status": 403 }));
+    }
+}
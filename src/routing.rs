@@ -0,0 +1,656 @@
+//! 命名路由模块
+//!
+//! 通过 `WebServerBuilder::route_named` 注册的路由可以按名称反向生成 URL，
+//! 修改路径时不需要逐个修改处理器和模板中的链接。
+//!
+//! ```rust
+//! use hwhkit::{get, WebServerBuilder};
+//!
+//! let builder = WebServerBuilder::new()
+//!     .route_named("user_detail", "/users/:id", get(|| async { "user" }));
+//!
+//! // 构建后即可使用：url_for!("user_detail", id = 5) == "/users/5"
+//! ```
+//!
+//! 模板中使用 `{{ url_for(name="user_detail", id=5) }}`。
+//!
+//! 路由还可以通过 `WebServerBuilder::route_doc` 附加说明和标签，启用 `[server.index_page]`
+//! 后在首页生成可读的接口列表（含示例 curl 命令），适合在开发环境中使用。
+//!
+//! 构建后通过 `WebServer::routes` 查看服务器注册的路由，启动时也会以表格形式输出到日志；
+//! 启用 `[server.routes_endpoint]` 后可以通过 `/_routes` 获取 JSON 格式的路由列表。
+//! 通过 `WebServerBuilder::routes` 添加的路由器无法获取其中的路由，用 `route_doc` 登记后会出现在列表中。
+//!
+//! 共享路径前缀、访问控制和中间件的路由可以通过 `WebServerBuilder::group` 声明为 [`RouteGroup`]：
+//!
+//! ```rust
+//! use hwhkit::{auth::rbac::require_role, get, put, WebServerBuilder};
+//!
+//! let builder = WebServerBuilder::new().group("/admin", |group| {
+//!     group
+//!         .guard(require_role("admin"))
+//!         .route("/users", get(|| async { "users" }))
+//!         .route_named("admin_user", "/users/:id", put(|| async { "updated" }))
+//! });
+//! ```
+//!
+//! 未匹配任何路由的请求默认返回统一格式的 JSON 404（`[server.fallback]`），
+//! 也可以通过 `WebServerBuilder::fallback` 指定自己的处理函数。
+
+use crate::{
+    error::{Error, Result},
+    middleware::matcher::matched_route,
+    response::ApiError,
+};
+use axum::{
+    body::HttpBody,
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::{FromFnLayer, Next},
+    response::{IntoResponse, Response},
+    routing::{get, MethodRouter, Route},
+    Json, Router,
+};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    fmt::{self, Display},
+    sync::{Arc, OnceLock, RwLock},
+};
+use tower::{Layer, Service};
+
+fn registry() -> &'static RwLock<HashMap<String, String>> {
+    static ROUTES: OnceLock<RwLock<HashMap<String, String>>> = OnceLock::new();
+    ROUTES.get_or_init(Default::default)
+}
+
+/// 注册命名路由
+///
+/// 同名路由指向不同路径时返回错误
+pub fn register(name: &str, path: &str) -> Result<()> {
+    let mut routes = registry()
+        .write()
+        .map_err(|_| Error::Internal("命名路由表不可用".to_string()))?;
+
+    match routes.get(name) {
+        Some(existing) if existing != path => Err(Error::Config(format!(
+            "路由名称 '{}' 已被 {} 使用，无法再指向 {}",
+            name, existing, path
+        ))),
+        _ => {
+            routes.insert(name.to_string(), path.to_string());
+            Ok(())
+        }
+    }
+}
+
+/// 按名称生成 URL
+///
+/// 路径参数（`:id`、`*rest`）使用同名参数填充，其余参数作为查询字符串附加。
+///
+/// # Errors
+///
+/// 路由名称不存在或缺少路径参数时返回错误
+pub fn url_for(name: &str, params: &[(&str, &dyn Display)]) -> Result<String> {
+    let pattern = registry()
+        .read()
+        .map_err(|_| Error::Internal("命名路由表不可用".to_string()))?
+        .get(name)
+        .cloned()
+        .ok_or_else(|| Error::Internal(format!("未知的路由名称: {}", name)))?;
+
+    let params: Vec<(&str, String)> = params.iter().map(|(k, v)| (*k, v.to_string())).collect();
+    build_url(name, &pattern, &params)
+}
+
+fn build_url(name: &str, pattern: &str, params: &[(&str, String)]) -> Result<String> {
+    let mut used = vec![false; params.len()];
+    let mut lookup = |key: &str| {
+        params.iter().position(|(k, _)| *k == key).map(|i| {
+            used[i] = true;
+            params[i].1.as_str()
+        })
+    };
+
+    let mut segments = Vec::new();
+    for segment in pattern.split('/') {
+        let (key, wildcard) = match (segment.strip_prefix(':'), segment.strip_prefix('*')) {
+            (Some(key), _) => (key, false),
+            (_, Some(key)) => (key, true),
+            _ => {
+                segments.push(segment.to_string());
+                continue;
+            }
+        };
+
+        let value = lookup(key).ok_or_else(|| {
+            Error::Internal(format!("路由 '{}' 缺少参数: {}", name, key))
+        })?;
+        segments.push(encode_path(value, wildcard));
+    }
+
+    let mut url = segments.join("/");
+    let query: Vec<(&str, &str)> = params
+        .iter()
+        .zip(&used)
+        .filter(|(_, used)| !**used)
+        .map(|((k, v), _)| (*k, v.as_str()))
+        .collect();
+    if !query.is_empty() {
+        let query = serde_urlencoded::to_string(query)
+            .map_err(|e| Error::Internal(format!("无法编码查询参数: {}", e)))?;
+        url.push('?');
+        url.push_str(&query);
+    }
+
+    Ok(url)
+}
+
+/// 编码路径参数，通配参数保留 `/`
+fn encode_path(value: &str, keep_slash: bool) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            b'/' if keep_slash => "/".to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// 按名称生成 URL
+///
+/// ```rust
+/// hwhkit::routing::register("user_detail", "/users/:id").unwrap();
+///
+/// assert_eq!(hwhkit::url_for!("user_detail", id = 5).unwrap(), "/users/5");
+/// ```
+#[macro_export]
+macro_rules! url_for {
+    ($name:expr $(, $key:ident = $value:expr)* $(,)?) => {
+        $crate::routing::url_for(
+            $name,
+            &[$((stringify!($key), &$value as &dyn ::std::fmt::Display)),*],
+        )
+    };
+}
+
+/// 路由文档
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteDoc {
+    /// HTTP 方法
+    pub method: String,
+    /// 路由路径
+    pub path: String,
+    /// 说明
+    pub description: String,
+    /// 标签，首页按标签分组
+    pub tags: Vec<String>,
+    /// 示例请求体（JSON）
+    pub example_body: Option<serde_json::Value>,
+}
+
+impl RouteDoc {
+    /// 创建路由文档
+    pub fn new(method: &str, path: &str, description: &str) -> Self {
+        Self {
+            method: method.to_uppercase(),
+            path: path.to_string(),
+            description: description.to_string(),
+            tags: Vec::new(),
+            example_body: None,
+        }
+    }
+
+    /// 添加标签
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.tags.push(tag.to_string());
+        self
+    }
+
+    /// 设置示例请求体
+    pub fn example_body(mut self, body: serde_json::Value) -> Self {
+        self.example_body = Some(body);
+        self
+    }
+
+    /// 示例 curl 命令
+    pub fn curl(&self, base_url: &str) -> String {
+        let mut command = format!("curl -X {} {}{}", self.method, base_url, self.path);
+        if let Some(body) = &self.example_body {
+            command.push_str(&format!(
+                " -H 'content-type: application/json' -d '{}'",
+                body.to_string().replace('\'', "'\\''")
+            ));
+        }
+        command
+    }
+}
+
+/// 服务器注册的路由
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RouteInfo {
+    /// HTTP 方法，`*` 表示任意方法
+    pub method: String,
+    /// 路由路径
+    pub path: String,
+    /// 通过 `route_named` 注册时的名称
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// 构建时收集的路由表
+#[derive(Debug, Default)]
+pub(crate) struct RouteTable(Vec<RouteInfo>);
+
+impl RouteTable {
+    /// 记录路由，重复的方法和路径只保留一条
+    pub(crate) fn add(&mut self, method: &str, path: &str) {
+        let method = method.to_uppercase();
+        if !self.0.iter().any(|route| route.method == method && route.path == path) {
+            self.0.push(RouteInfo {
+                method,
+                path: path.to_string(),
+                name: None,
+            });
+        }
+    }
+
+    /// 记录方法未知的路由，路由文档已登记该路径时沿用文档中的方法
+    pub(crate) fn add_route(&mut self, path: &str, name: Option<&str>) {
+        let name = name.map(str::to_string);
+        let mut documented = false;
+        for route in self.0.iter_mut().filter(|route| route.path == path) {
+            if name.is_some() {
+                route.name = name.clone();
+            }
+            documented = true;
+        }
+        if !documented {
+            self.0.push(RouteInfo {
+                method: "*".to_string(),
+                path: path.to_string(),
+                name,
+            });
+        }
+    }
+
+    /// 按路径和方法排序后的路由列表
+    pub(crate) fn into_sorted(mut self) -> Vec<RouteInfo> {
+        self.0
+            .sort_by(|a, b| (&a.path, &a.method).cmp(&(&b.path, &b.method)));
+        self.0
+    }
+}
+
+/// 将路由列表格式化为对齐的表格行
+pub(crate) fn route_table_lines(routes: &[RouteInfo]) -> Vec<String> {
+    let width = routes.iter().map(|route| route.path.len()).max().unwrap_or(0);
+    routes
+        .iter()
+        .map(|route| {
+            let line = format!("{:<7} {:<width$}", route.method, route.path, width = width);
+            match &route.name {
+                Some(name) => format!("{}  {}", line, name),
+                None => line.trim_end().to_string(),
+            }
+        })
+        .collect()
+}
+
+/// 以 JSON 返回路由列表的调试接口
+pub(crate) fn routes_router(path: &str, routes: Arc<[RouteInfo]>) -> Router {
+    Router::new().route(path, get(move || async move { Json(routes.to_vec()) }))
+}
+
+type GroupLayer = Box<dyn FnOnce(Router) -> Router + Send + Sync>;
+
+/// 路由组
+///
+/// 组内路由共享路径前缀，通过 [`guard`](Self::guard)、[`layer`](Self::layer) 和
+/// [`middleware`](Self::middleware) 添加的层只在请求匹配到组内路由（包括嵌套的子组）时执行，
+/// 与声明顺序无关；多个层按添加顺序由内向外包裹。
+pub struct RouteGroup {
+    prefix: String,
+    router: Router,
+    /// 组内路由的完整路径和名称
+    routes: Vec<(String, Option<String>)>,
+    layers: Vec<GroupLayer>,
+}
+
+impl fmt::Debug for RouteGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RouteGroup")
+            .field("prefix", &self.prefix)
+            .field("routes", &self.routes)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RouteGroup {
+    /// 创建路由组
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.trim_end_matches('/').to_string(),
+            router: Router::new(),
+            routes: Vec::new(),
+            layers: Vec::new(),
+        }
+    }
+
+    /// 添加路由，`path` 相对于组的前缀，`/` 表示前缀本身
+    pub fn route(mut self, path: &str, method_router: MethodRouter) -> Self {
+        let path = self.full_path(path);
+        self.router = self.router.route(&path, method_router);
+        self.routes.push((path, None));
+        self
+    }
+
+    /// 添加命名路由，名称对应完整路径
+    pub fn route_named(mut self, name: &str, path: &str, method_router: MethodRouter) -> Self {
+        let path = self.full_path(path);
+        self.router = self.router.route(&path, method_router);
+        self.routes.push((path, Some(name.to_string())));
+        self
+    }
+
+    /// 添加嵌套的子组，子组的前缀相对于当前组
+    pub fn group<F>(mut self, prefix: &str, group: F) -> Self
+    where
+        F: FnOnce(RouteGroup) -> RouteGroup,
+    {
+        let group = group(RouteGroup::new(&self.full_path(prefix)));
+        self.routes.extend(group.routes.iter().cloned());
+        self.router = self.router.merge(group.into_router());
+        self
+    }
+
+    /// 添加访问控制层，如 [`require_role`](crate::auth::rbac::require_role)
+    pub fn guard<L>(mut self, guard: L) -> Self
+    where
+        L: Layer<Route> + Clone + Send + Sync + 'static,
+        L::Service: Service<Request> + Clone + Send + 'static,
+        <L::Service as Service<Request>>::Response: IntoResponse + 'static,
+        <L::Service as Service<Request>>::Error: Into<Infallible> + 'static,
+        <L::Service as Service<Request>>::Future: Send + 'static,
+    {
+        self.layers.push(Box::new(move |router| router.route_layer(guard)));
+        self
+    }
+
+    /// 添加中间件层
+    pub fn layer<L>(mut self, layer: L) -> Self
+    where
+        L: Layer<Route> + Clone + Send + Sync + 'static,
+        L::Service: Service<Request> + Clone + Send + 'static,
+        <L::Service as Service<Request>>::Response: IntoResponse + 'static,
+        <L::Service as Service<Request>>::Error: Into<Infallible> + 'static,
+        <L::Service as Service<Request>>::Future: Send + 'static,
+    {
+        self.layers.push(Box::new(move |router| router.route_layer(layer)));
+        self
+    }
+
+    /// 添加函数中间件，与 `axum::middleware::from_fn` 的用法相同
+    pub fn middleware<F, T>(self, middleware: F) -> Self
+    where
+        FromFnLayer<F, (), T>: Layer<Route> + Clone + Send + Sync + 'static,
+        <FromFnLayer<F, (), T> as Layer<Route>>::Service: Service<Request> + Clone + Send + 'static,
+        <<FromFnLayer<F, (), T> as Layer<Route>>::Service as Service<Request>>::Response:
+            IntoResponse + 'static,
+        <<FromFnLayer<F, (), T> as Layer<Route>>::Service as Service<Request>>::Error:
+            Into<Infallible> + 'static,
+        <<FromFnLayer<F, (), T> as Layer<Route>>::Service as Service<Request>>::Future: Send + 'static,
+    {
+        self.layer(axum::middleware::from_fn(middleware))
+    }
+
+    /// 组内路由的完整路径和名称
+    pub(crate) fn routes(&self) -> &[(String, Option<String>)] {
+        &self.routes
+    }
+
+    /// 应用组内的层，返回包含所有路由的路由器
+    pub(crate) fn into_router(self) -> Router {
+        self.layers
+            .into_iter()
+            .fold(self.router, |router, layer| layer(router))
+    }
+
+    fn full_path(&self, path: &str) -> String {
+        match path.trim_start_matches('/') {
+            "" if self.prefix.is_empty() => "/".to_string(),
+            "" => self.prefix.clone(),
+            path => format!("{}/{}", self.prefix, path),
+        }
+    }
+}
+
+/// 生成接口列表页面
+///
+/// 按第一个标签分组，没有标签的路由归入“其他”
+pub fn render_index_page(title: &str, base_url: &str, docs: &[RouteDoc]) -> String {
+    let mut groups: Vec<(&str, Vec<&RouteDoc>)> = Vec::new();
+    for doc in docs {
+        let tag = doc.tags.first().map(String::as_str).unwrap_or("其他");
+        match groups.iter_mut().find(|(name, _)| *name == tag) {
+            Some((_, routes)) => routes.push(doc),
+            None => groups.push((tag, vec![doc])),
+        }
+    }
+
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>{0}</title></head>\n<body>\n<h1>{0}</h1>\n",
+        escape_html(title)
+    );
+    for (tag, routes) in groups {
+        html.push_str(&format!("<h2>{}</h2>\n<ul>\n", escape_html(tag)));
+        for doc in routes {
+            html.push_str(&format!(
+                "<li><code>{} {}</code> - {}<pre>{}</pre></li>\n",
+                escape_html(&doc.method),
+                escape_html(&doc.path),
+                escape_html(&doc.description),
+                escape_html(&doc.curl(base_url))
+            ));
+        }
+        html.push_str("</ul>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// 未匹配路由时返回 JSON 404 的中间件
+///
+/// 只替换 axum 默认 fallback 生成的空 404，自定义 fallback（如静态文件、SPA）返回的内容保持不变
+pub(crate) async fn not_found_middleware(
+    State(message): State<Arc<str>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let matched = matched_route(request.extensions()).is_some();
+    let response = next.run(request).await;
+    if matched
+        || response.status() != StatusCode::NOT_FOUND
+        || response.headers().contains_key(header::CONTENT_TYPE)
+        || !response.body().is_end_stream()
+    {
+        return response;
+    }
+    ApiError::not_found(message.as_ref()).into_response()
+}
+
+pub(crate) fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// 模板函数 `url_for(name="...", ...)`，`name` 以外的参数用于填充路由
+#[cfg(feature = "templates")]
+pub(crate) fn template_url_for<'a>(
+    args: impl IntoIterator<Item = (&'a str, &'a serde_json::Value)>,
+) -> Result<String> {
+    let mut name = None;
+    let mut params: Vec<(&str, String)> = Vec::new();
+    for (key, value) in args {
+        match (key, value) {
+            ("name", serde_json::Value::String(value)) => name = Some(value.as_str()),
+            (key, serde_json::Value::String(value)) => params.push((key, value.clone())),
+            (key, value) => params.push((key, value.to_string())),
+        }
+    }
+    let name = name.ok_or_else(|| Error::BadRequest("url_for 需要 name 参数".to_string()))?;
+    let params: Vec<(&str, &dyn Display)> =
+        params.iter().map(|(k, v)| (*k, v as &dyn Display)).collect();
+    url_for(name, &params)
+}
+
+/// Tera 模板函数 `url_for`
+#[cfg(feature = "templates")]
+pub(crate) fn tera_url_for(args: &HashMap<String, tera::Value>) -> tera::Result<tera::Value> {
+    template_url_for(args.iter().map(|(key, value)| (key.as_str(), value)))
+        .map(tera::Value::String)
+        .map_err(|e| tera::Error::msg(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_for() {
+        register("test_user_detail", "/users/:id").unwrap();
+        register("test_files", "/files/*path").unwrap();
+
+        assert_eq!(crate::url_for!("test_user_detail", id = 5).unwrap(), "/users/5");
+        assert_eq!(
+            crate::url_for!("test_user_detail", id = "a b", tab = "posts").unwrap(),
+            "/users/a%20b?tab=posts"
+        );
+        assert_eq!(
+            crate::url_for!("test_files", path = "docs/read me.md").unwrap(),
+            "/files/docs/read%20me.md"
+        );
+
+        assert!(crate::url_for!("test_user_detail").is_err());
+        assert!(crate::url_for!("test_unknown").is_err());
+    }
+
+    #[test]
+    fn test_route_table() {
+        let mut table = RouteTable::default();
+        table.add("post", "/users");
+        table.add("GET", "/users");
+        table.add("GET", "/users");
+        table.add_route("/users", Some("user_list"));
+        table.add_route("/users/:id", Some("user_detail"));
+        table.add_route("/health", None);
+        table.add_route("/metrics", None);
+        table.add("GET", "/health");
+
+        let routes = table.into_sorted();
+        let summary: Vec<_> = routes
+            .iter()
+            .map(|r| (r.method.as_str(), r.path.as_str(), r.name.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("*", "/health", None),
+                ("GET", "/health", None),
+                ("*", "/metrics", None),
+                ("GET", "/users", Some("user_list")),
+                ("POST", "/users", Some("user_list")),
+                ("*", "/users/:id", Some("user_detail")),
+            ]
+        );
+
+        let lines = route_table_lines(&routes);
+        assert_eq!(lines[1], "GET     /health");
+        assert_eq!(lines[5], "*       /users/:id  user_detail");
+    }
+
+    #[tokio::test]
+    async fn test_route_group() {
+        use crate::auth::rbac::require_role;
+        use axum::{body::Body, http::HeaderValue};
+        use tower::ServiceExt;
+
+        async fn tag(request: Request, next: Next) -> Response {
+            let mut response = next.run(request).await;
+            response.headers_mut().insert("x-group", HeaderValue::from_static("admin"));
+            response
+        }
+
+        let group = RouteGroup::new("/admin/")
+            .route("/", get(|| async { "index" }))
+            .middleware(tag)
+            .group("reports", |group| {
+                group
+                    .guard(require_role("auditor"))
+                    .route_named("test_report", "/:id", get(|| async { "report" }))
+            });
+        assert_eq!(
+            group.routes(),
+            [
+                ("/admin".to_string(), None),
+                ("/admin/reports/:id".to_string(), Some("test_report".to_string())),
+            ]
+        );
+
+        let app = Router::new()
+            .route("/public", get(|| async { "public" }))
+            .merge(group.into_router());
+        let get = |uri: &str| Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+        let response = app.clone().oneshot(get("/admin")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["x-group"], "admin");
+
+        // 子组继承外层中间件，并在匹配的路由上执行访问控制
+        let response = app.clone().oneshot(get("/admin/reports/1")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers()["x-group"], "admin");
+
+        let response = app.clone().oneshot(get("/public")).await.unwrap();
+        assert!(!response.headers().contains_key("x-group"));
+        let response = app.oneshot(get("/admin/missing")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert!(!response.headers().contains_key("x-group"));
+    }
+
+    #[test]
+    fn test_render_index_page() {
+        let docs = vec![
+            RouteDoc::new("get", "/users", "列出用户").tag("users"),
+            RouteDoc::new("POST", "/users", "创建<用户>")
+                .tag("users")
+                .example_body(serde_json::json!({ "name": "O'Neil" })),
+            RouteDoc::new("GET", "/health", "健康检查"),
+        ];
+
+        let html = render_index_page("API", "http://localhost:3000", &docs);
+        assert_eq!(html.matches("<h2>").count(), 2);
+        assert!(html.contains("<code>GET /users</code> - 列出用户"));
+        assert!(html.contains("创建&lt;用户&gt;"));
+        assert!(html.contains("curl -X GET http://localhost:3000/health"));
+        assert!(html.contains("<h2>其他</h2>"));
+
+        assert_eq!(
+            docs[1].curl("http://localhost:3000"),
+            r#"curl -X POST http://localhost:3000/users -H 'content-type: application/json' -d '{"name":"O'\''Neil"}'"#
+        );
+    }
+
+    #[test]
+    fn test_register_conflict() {
+        register("test_conflict", "/a").unwrap();
+        register("test_conflict", "/a").unwrap();
+        assert!(register("test_conflict", "/b").is_err());
+    }
+}
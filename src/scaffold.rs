@@ -0,0 +1,119 @@
+//! 示例项目生成模块
+//!
+//! 将 `examples/full-server.rs` 使用的模板、静态资源和配置写入指定目录，
+//! 无需克隆仓库即可试用 Full 架构：
+//!
+//! ```rust,no_run
+//! let written = hwhkit::scaffold::full_demo("my-app").unwrap();
+//! println!("已生成 {} 个文件", written.len());
+//! // 在 my-app 目录中使用 WebServerBuilder::new().config_from_file("config.toml") 启动
+//! ```
+//!
+//! 文件内容在编译时从仓库的 `examples` 目录嵌入，与示例保持一致。
+
+use crate::error::{Error, Result};
+use std::path::{Path, PathBuf};
+
+/// Full 架构示例的文件，路径相对于目标目录
+const FULL_DEMO_FILES: &[(&str, &str)] = &[
+    ("templates/base.html", include_str!("../examples/templates/base.html")),
+    ("templates/index.html", include_str!("../examples/templates/index.html")),
+    ("templates/users.html", include_str!("../examples/templates/users.html")),
+    ("templates/about.html", include_str!("../examples/templates/about.html")),
+    ("templates/partials/nav.html", include_str!("../examples/templates/partials/nav.html")),
+    ("templates/partials/footer.html", include_str!("../examples/templates/partials/footer.html")),
+    ("templates/errors/404.html", include_str!("../examples/templates/errors/404.html")),
+    ("static/style.css", include_str!("../examples/static/style.css")),
+    ("static/script.js", include_str!("../examples/static/script.js")),
+];
+
+const FULL_DEMO_CONFIG: &str = include_str!("../examples/full-config.toml");
+
+/// 在 `target` 目录中生成 Full 架构示例
+///
+/// 生成 `config.toml`、`templates/` 和 `static/`，配置中的目录指向生成的文件。
+/// 已存在的文件不会被覆盖，返回实际写入的文件路径。
+///
+/// # Errors
+///
+/// 无法创建目录或写入文件时返回错误
+pub fn full_demo<P: AsRef<Path>>(target: P) -> Result<Vec<PathBuf>> {
+    let target = target.as_ref();
+    let config = FULL_DEMO_CONFIG
+        .replace("\"examples/static\"", "\"static\"")
+        .replace("\"examples/templates\"", "\"templates\"");
+
+    let files = FULL_DEMO_FILES
+        .iter()
+        .copied()
+        .chain(std::iter::once(("config.toml", config.as_str())));
+
+    let mut written = Vec::new();
+    for (relative, content) in files {
+        let path = target.join(relative);
+        if path.exists() {
+            tracing::warn!("文件已存在，跳过: {}", path.display());
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, content)
+            .map_err(|e| Error::Config(format!("无法写入 {}: {}", path.display(), e)))?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_full_demo() {
+        let temp_dir = TempDir::new().unwrap();
+        let written = full_demo(temp_dir.path()).unwrap();
+        assert_eq!(written.len(), FULL_DEMO_FILES.len() + 1);
+
+        let config = crate::Config::from_file(temp_dir.path().join("config.toml")).unwrap();
+        assert_eq!(config.middleware.templates.dir, "templates");
+        assert_eq!(config.middleware.static_files.dir, "static");
+
+        // 再次生成时不覆盖已有文件
+        assert!(full_demo(temp_dir.path()).unwrap().is_empty());
+    }
+
+    #[cfg(feature = "templates")]
+    #[test]
+    fn test_full_demo_templates_render() {
+        use crate::{config::TemplateConfig, templates::TemplateEngine};
+
+        let temp_dir = TempDir::new().unwrap();
+        full_demo(temp_dir.path()).unwrap();
+
+        let engine = TemplateEngine::new(&TemplateConfig {
+            enabled: true,
+            dir: temp_dir.path().join("templates").to_string_lossy().to_string(),
+            extension: "html".to_string(),
+            layout: Some("base.html".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        let context = serde_json::json!({
+            "title": "关于",
+            "app_name": "演示应用",
+            "users": [{ "id": 1, "name": "张三", "email": "zhangsan@example.com" }],
+            "features": ["高性能"],
+        });
+        for template in ["index.html", "users.html", "about.html"] {
+            let html = engine.render_in_layout(template, &context).unwrap();
+            assert!(html.contains("演示应用"), "{} 应该在 base.html 布局中渲染", template);
+        }
+
+        let context = serde_json::json!({ "status": 404, "title": "404 Not Found", "message": null, "path": "/missing" });
+        let html = engine.render_in_layout("errors/404.html", &context).unwrap();
+        assert!(html.contains("404 - 页面不存在"));
+    }
+}
@@ -1,9 +1,39 @@
 //! Web 服务器模块
 
-use crate::{config::Config, error::{Error, Result}};
+use crate::{
+    assets::AssetPipeline,
+    config::Config,
+    database::Databases,
+    error::{Error, Result},
+    inject::Services,
+    routing::RouteInfo,
+    shutdown::{self, InFlight, ShutdownSignal},
+    watchdog::Watchdog,
+};
 use axum::Router;
-use std::net::SocketAddr;
-use tokio::net::TcpListener;
+use futures_util::future::BoxFuture;
+use std::{fmt, future::IntoFuture, net::SocketAddr, sync::Arc, time::Duration};
+use tokio::{net::TcpListener, sync::Notify};
+use crate::middleware::logging::LogGuards;
+
+/// 启动或关闭回调
+pub(crate) type LifecycleHook = Box<dyn FnOnce() -> BoxFuture<'static, Result<()>> + Send>;
+
+/// 通过 `WebServerBuilder::on_startup` 和 `on_shutdown` 注册的回调
+#[derive(Default)]
+pub(crate) struct LifecycleHooks {
+    pub(crate) startup: Vec<LifecycleHook>,
+    pub(crate) shutdown: Vec<LifecycleHook>,
+}
+
+impl fmt::Debug for LifecycleHooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LifecycleHooks")
+            .field("startup", &self.startup.len())
+            .field("shutdown", &self.shutdown.len())
+            .finish()
+    }
+}
 
 /// Web 服务器
 /// 
@@ -12,6 +42,18 @@ use tokio::net::TcpListener;
 pub struct WebServer {
     app: Router,
     config: Config,
+    /// 日志写入线程和追踪导出器的 guard，随服务器一起释放
+    log_guards: LogGuards,
+    /// 优雅关闭信号
+    shutdown: ShutdownSignal,
+    /// 数据库连接池，服务器停止后关闭
+    databases: Databases,
+    /// 通过 `WebServerBuilder::provide` 登记的服务
+    services: Services,
+    /// 启动和关闭回调
+    lifecycle: LifecycleHooks,
+    /// 构建时记录的路由
+    routes: Arc<[RouteInfo]>,
 }
 
 impl WebServer {
@@ -22,7 +64,71 @@ impl WebServer {
     /// * `app` - 配置好的 Axum 应用
     /// * `config` - 服务器配置
     pub fn new(app: Router, config: Config) -> Self {
-        Self { app, config }
+        let shutdown = ShutdownSignal::new(&config.server.shutdown);
+        Self {
+            app,
+            config,
+            log_guards: LogGuards::default(),
+            shutdown,
+            databases: Databases::default(),
+            services: Services::default(),
+            lifecycle: LifecycleHooks::default(),
+            routes: Arc::new([]),
+        }
+    }
+
+    /// 保存日志写入线程和追踪导出器的 guard
+    pub(crate) fn with_log_guards(mut self, guards: LogGuards) -> Self {
+        self.log_guards = guards;
+        self
+    }
+
+    /// 使用处理器可见的优雅关闭信号
+    pub(crate) fn with_shutdown_signal(mut self, shutdown: ShutdownSignal) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// 保存数据库连接池，服务器停止后关闭
+    pub(crate) fn with_databases(mut self, databases: Databases) -> Self {
+        self.databases = databases;
+        self
+    }
+
+    /// 保存已登记的服务
+    pub(crate) fn with_services(mut self, services: Services) -> Self {
+        self.services = services;
+        self
+    }
+
+    /// 保存构建时记录的路由
+    pub(crate) fn with_routes(mut self, routes: Arc<[RouteInfo]>) -> Self {
+        self.routes = routes;
+        self
+    }
+
+    /// 保存启动和关闭回调
+    pub(crate) fn with_lifecycle_hooks(mut self, hooks: LifecycleHooks) -> Self {
+        self.lifecycle = hooks;
+        self
+    }
+
+    /// 服务器注册的路由，按路径排序
+    ///
+    /// 包含 HwhKit 挂载的路由、命名路由和通过 `route_doc` 登记的路由，
+    /// 通过 `routes()` 添加的路由器中未登记文档的路由不在其中
+    pub fn routes(&self) -> &[RouteInfo] {
+        &self.routes
+    }
+
+    /// 优雅关闭信号，调用 `trigger` 后服务器停止接受新连接并开始关闭
+    pub fn shutdown_signal(&self) -> ShutdownSignal {
+        self.shutdown.clone()
+    }
+
+    /// 按类型获取通过 `WebServerBuilder::provide` 登记的服务
+    pub fn service<T: Clone + Send + Sync + 'static>(&self) -> Option<&T> {
+        self.services.get::<T>()
     }
 
     /// 运行服务器
@@ -40,6 +146,7 @@ impl WebServer {
         
         // 打印中间件信息
         self.log_middleware_status();
+        self.log_routes();
 
         // 解析地址
         let socket_addr: SocketAddr = bind_addr.parse().map_err(|e| {
@@ -51,13 +158,89 @@ impl WebServer {
             Error::ServerStart(format!("无法绑定到地址 '{}': {}", bind_addr, e))
         })?;
 
+        // 执行启动回调，失败时不开始接受连接
+        let hooks = self.lifecycle;
+        for hook in hooks.startup {
+            hook().await?;
+        }
+
+        // 启动资源监控，超过阈值时可触发优雅重启
+        let restart = Arc::new(Notify::new());
+        let watchdog = self
+            .config
+            .telemetry
+            .watchdog
+            .enabled
+            .then(|| Watchdog::new(&self.config.telemetry.watchdog).spawn(restart.clone()));
+
+        // 开发模式下监视资源文件变化
+        let static_files = &self.config.middleware.static_files;
+        let asset_watcher = (static_files.enabled
+            && static_files.pipeline.enabled
+            && static_files.pipeline.watch)
+            .then(|| AssetPipeline::new(static_files).watch());
+
         tracing::info!("✅ 服务器启动成功，等待连接...");
 
+        // 收到信号后通知长连接处理器，经过宽限期后停止接受新连接
+        let shutdown_config = &self.config.server.shutdown;
+        let signals = shutdown_config.signals.clone();
+        let grace_period = Duration::from_secs(shutdown_config.grace_period_secs);
+        let signal = self.shutdown.clone();
+        let begin_shutdown = async move {
+            tokio::select! {
+                _ = restart.notified() => tracing::info!("⏳ 资源监控触发重启，开始优雅关闭"),
+                received = shutdown::os_signal(&signals) => tracing::info!("⏳ 收到 {:?} 信号，开始优雅关闭", received),
+                _ = signal.wait() => tracing::info!("⏳ 开始优雅关闭"),
+            }
+            signal.trigger();
+            tokio::time::sleep(grace_period).await;
+        };
+
+        let drain_timeout = Duration::from_secs(shutdown_config.drain_timeout_secs);
+        let signal = self.shutdown.clone();
+        let drain_deadline = async move {
+            signal.wait().await;
+            tokio::time::sleep(grace_period + drain_timeout).await;
+        };
+
         // 启动服务器
-        axum::serve(listener, self.app).await.map_err(|e| {
-            Error::ServerStart(format!("服务器运行时错误: {}", e))
-        })?;
+        let in_flight = InFlight::default();
+        let app = self.app.layer(axum::middleware::from_fn_with_state(
+            in_flight.clone(),
+            shutdown::track_in_flight,
+        ));
+        let serve = axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>())
+            .with_graceful_shutdown(begin_shutdown)
+            .into_future();
+        let result = tokio::select! {
+            result = serve => result.map_err(|e| Error::ServerStart(format!("服务器运行时错误: {}", e))),
+            _ = drain_deadline => {
+                tracing::warn!(
+                    "等待连接关闭超过 {} 秒，强制断开（{} 个请求未完成）",
+                    drain_timeout.as_secs(),
+                    in_flight.count()
+                );
+                Ok(())
+            }
+        };
+
+        if let Some(watchdog) = watchdog {
+            watchdog.abort();
+        }
+        if let Some(asset_watcher) = asset_watcher {
+            asset_watcher.abort();
+        }
+        // 连接关闭后执行关闭回调，单个回调失败不影响后续回调
+        for hook in hooks.shutdown {
+            if let Err(e) = hook().await {
+                tracing::warn!("关闭回调执行失败: {}", e);
+            }
+        }
+        self.databases.close().await;
+        result?;
 
+        tracing::info!("👋 服务器已停止");
         Ok(())
     }
 
@@ -76,6 +259,17 @@ impl WebServer {
         &self.app
     }
 
+    /// 打印路由表
+    fn log_routes(&self) {
+        if self.routes.is_empty() {
+            return;
+        }
+        tracing::info!("🧭 路由:");
+        for line in crate::routing::route_table_lines(&self.routes) {
+            tracing::info!("  {}", line);
+        }
+    }
+
     /// 打印中间件状态信息
     fn log_middleware_status(&self) {
         tracing::info!("🔧 中间件状态:");
@@ -91,13 +285,23 @@ impl WebServer {
             tracing::info!("  ✅ 静态文件: 已启用");
             tracing::info!("    📁 目录: {}", self.config.middleware.static_files.dir);
             tracing::info!("    🔗 前缀: {}", self.config.middleware.static_files.prefix);
+            let pipeline = &self.config.middleware.static_files.pipeline;
+            if pipeline.enabled {
+                tracing::info!(
+                    "    🎨 资源处理: {} 条规则{}",
+                    pipeline.rules.len(),
+                    if pipeline.watch { "（监视变化）" } else { "" }
+                );
+            }
         } else {
             tracing::info!("  ❌ 静态文件: 已禁用");
         }
 
         if self.config.middleware.templates.enabled {
-            tracing::info!("  ✅ 模板引擎: 已启用");
-            tracing::info!("    📁 目录: {}", self.config.middleware.templates.dir);
+            tracing::info!("  ✅ 模板引擎: 已启用（{:?}）", self.config.middleware.templates.engine);
+            if self.config.middleware.templates.engine.is_runtime() {
+                tracing::info!("    📁 目录: {}", self.config.middleware.templates.dir);
+            }
         } else {
             tracing::info!("  ❌ 模板引擎: 已禁用");
         }
@@ -115,6 +319,47 @@ impl WebServer {
         } else {
             tracing::info!("  ❌ 请求日志: 已禁用");
         }
+
+        let access_log = &self.config.middleware.logging.access_log;
+        if access_log.enabled {
+            tracing::info!("  ✅ 访问日志: 已启用");
+            tracing::info!("    📤 输出: {:?} ({:?})", access_log.target, access_log.format);
+        } else {
+            tracing::info!("  ❌ 访问日志: 已禁用");
+        }
+
+        let limits = &self.config.server.limits;
+        if limits.max_concurrent_requests > 0 {
+            tracing::info!(
+                "  ✅ 并发限制: {}{}",
+                limits.max_concurrent_requests,
+                if limits.load_shed { "（超出时返回 503）" } else { "（超出时排队）" }
+            );
+        }
+
+        if self.config.server.batch.enabled {
+            tracing::info!("  ✅ 批量请求: {}", self.config.server.batch.path);
+        }
+
+        if self.config.server.index_page.enabled {
+            tracing::info!("  ✅ 接口列表首页: {}", self.config.server.index_page.path);
+        }
+
+        if self.config.admin.enabled {
+            tracing::info!("  ✅ 管理接口: {}", self.config.admin.path);
+        }
+
+        let shutdown = &self.config.server.shutdown;
+        tracing::info!(
+            "  ⏳ 优雅关闭: 信号 {:?}，宽限 {} 秒，最多等待 {} 秒",
+            shutdown.signals,
+            shutdown.grace_period_secs,
+            shutdown.drain_timeout_secs
+        );
+
+        if self.config.telemetry.watchdog.enabled {
+            tracing::info!("  ✅ 资源监控: 每 {} 秒采样", self.config.telemetry.watchdog.interval_secs);
+        }
     }
 }
 
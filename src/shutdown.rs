@@ -0,0 +1,317 @@
+//! 优雅关闭模块
+//!
+//! 服务器收到 `signals` 中的系统信号（默认 Ctrl+C / SIGTERM，或资源监控触发重启）后，通过
+//! `ShutdownSignal` 通知长连接处理器：SSE 流发送 `shutdown` 事件后结束，WebSocket
+//! 发送 1001（Going Away）关闭帧。再经过 `grace_period_secs` 后停止接收新连接，
+//! 超过 `drain_timeout_secs` 仍未关闭的连接会被直接断开，并记录未完成的请求数。
+//!
+//! ```rust
+//! use axum::response::sse::{Event, Sse};
+//! use futures_util::stream::{self, Stream};
+//! use hwhkit::shutdown::ShutdownSignal;
+//! use std::convert::Infallible;
+//!
+//! async fn events(shutdown: ShutdownSignal) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+//!     let updates = stream::pending();
+//!     Sse::new(shutdown.sse_stream(updates))
+//! }
+//! ```
+
+use crate::{
+    config::{OsSignal, ShutdownConfig},
+    error::{Error, Result},
+};
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request, State},
+    http::request::Parts,
+    middleware::Next,
+    response::{sse::Event, Response},
+};
+use futures_util::{
+    future,
+    stream::{self, Stream, StreamExt},
+};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+use tokio::sync::watch;
+
+/// 优雅关闭信号
+///
+/// 可作为提取器在处理器中使用
+#[derive(Debug, Clone)]
+pub struct ShutdownSignal {
+    sender: Arc<watch::Sender<bool>>,
+    reason: Arc<str>,
+}
+
+impl ShutdownSignal {
+    /// 根据配置创建关闭信号
+    pub fn new(config: &ShutdownConfig) -> Self {
+        let (sender, _) = watch::channel(false);
+        Self {
+            sender: Arc::new(sender),
+            reason: config.reason.as_str().into(),
+        }
+    }
+
+    /// 开始关闭，通知所有等待者
+    pub fn trigger(&self) {
+        self.sender.send_replace(true);
+    }
+
+    /// 是否已经开始关闭
+    pub fn is_shutting_down(&self) -> bool {
+        *self.sender.borrow()
+    }
+
+    /// 等待关闭开始
+    pub async fn wait(&self) {
+        let mut receiver = self.sender.subscribe();
+        // 发送端与信号同生命周期，不会提前关闭
+        let _ = receiver.wait_for(|shutting_down| *shutting_down).await;
+    }
+
+    /// 发送给客户端的关闭原因
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+
+    /// 包装 SSE 事件流
+    ///
+    /// 关闭开始时停止转发原有事件，发送一条 `shutdown` 事件（内容为关闭原因）后结束
+    pub fn sse_stream<S, E>(&self, events: S) -> impl Stream<Item = std::result::Result<Event, E>> + Send
+    where
+        S: Stream<Item = std::result::Result<Event, E>> + Send,
+        E: Send,
+    {
+        let signal = self.clone();
+        let farewell = self.clone();
+        events
+            .take_until(async move { signal.wait().await })
+            .chain(
+                stream::once(async move {
+                    farewell
+                        .is_shutting_down()
+                        .then(|| Ok(Event::default().event("shutdown").data(farewell.reason())))
+                })
+                .filter_map(future::ready),
+            )
+    }
+
+    /// WebSocket 关闭消息（1001 Going Away，附带关闭原因）
+    #[cfg(feature = "ws")]
+    pub fn close_message(&self) -> axum::extract::ws::Message {
+        use axum::extract::ws::{close_code, CloseFrame, Message};
+
+        Message::Close(Some(CloseFrame {
+            code: close_code::AWAY,
+            reason: self.reason.to_string().into(),
+        }))
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ShutdownSignal {
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+        parts
+            .extensions
+            .get::<ShutdownSignal>()
+            .cloned()
+            .ok_or_else(|| Error::Internal("优雅关闭信号不可用".to_string()))
+    }
+}
+
+/// 等待任一配置的系统信号，返回收到的信号
+///
+/// 未配置信号时一直等待（只能通过 `ShutdownSignal::trigger` 关闭）
+pub(crate) async fn os_signal(signals: &[OsSignal]) -> OsSignal {
+    if signals.is_empty() {
+        return future::pending().await;
+    }
+    let waits = signals.iter().map(|&kind| Box::pin(wait_for(kind)));
+    future::select_all(waits).await.0
+}
+
+async fn wait_for(kind: OsSignal) -> OsSignal {
+    if kind == OsSignal::Interrupt {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::warn!("无法监听 Ctrl+C: {}", e);
+            future::pending::<()>().await;
+        }
+        return kind;
+    }
+
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        let unix_kind = match kind {
+            OsSignal::Terminate => SignalKind::terminate(),
+            OsSignal::Hangup => SignalKind::hangup(),
+            OsSignal::Quit => SignalKind::quit(),
+            OsSignal::Interrupt => SignalKind::interrupt(),
+        };
+        match signal(unix_kind) {
+            Ok(mut receiver) => {
+                receiver.recv().await;
+            }
+            Err(e) => {
+                tracing::warn!("无法监听 {:?}: {}", kind, e);
+                future::pending::<()>().await;
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        tracing::warn!("当前平台不支持监听 {:?}", kind);
+        future::pending::<()>().await;
+    }
+
+    kind
+}
+
+/// 处理中的请求数，强制断开时用于记录
+#[derive(Debug, Clone, Default)]
+pub(crate) struct InFlight(Arc<AtomicUsize>);
+
+impl InFlight {
+    pub(crate) fn count(&self) -> usize {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// 请求结束（包括被取消）时计数减一
+struct InFlightGuard(InFlight);
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        (self.0).0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// 统计处理中的请求
+pub(crate) async fn track_in_flight(State(in_flight): State<InFlight>, request: Request, next: Next) -> Response {
+    in_flight.0.fetch_add(1, Ordering::SeqCst);
+    let _guard = InFlightGuard(in_flight);
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{convert::Infallible, time::Duration};
+
+    fn signal() -> ShutdownSignal {
+        ShutdownSignal::new(&ShutdownConfig {
+            reason: "maintenance".to_string(),
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_sse_stream_ends_with_shutdown_event() {
+        let signal = signal();
+        let events = stream::iter([Ok::<_, Infallible>(Event::default().data("tick"))])
+            .chain(stream::pending());
+        let mut wrapped = Box::pin(signal.sse_stream(events));
+
+        assert!(wrapped.next().await.is_some());
+
+        signal.trigger();
+        let last = tokio::time::timeout(Duration::from_secs(1), wrapped.next())
+            .await
+            .expect("stream should react to shutdown")
+            .unwrap()
+            .unwrap();
+        let rendered = format!("{:?}", last);
+        assert!(rendered.contains("shutdown") && rendered.contains("maintenance"));
+        assert!(wrapped.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_sse_stream_without_shutdown() {
+        let events = stream::iter([Ok::<_, Infallible>(Event::default().data("only"))]);
+        let collected: Vec<_> = signal().sse_stream(events).collect().await;
+        assert_eq!(collected.len(), 1, "正常结束的流不应追加 shutdown 事件");
+    }
+
+    #[tokio::test]
+    async fn test_os_signal_without_signals() {
+        let result = tokio::time::timeout(Duration::from_millis(50), os_signal(&[])).await;
+        assert!(result.is_err(), "未配置信号时不应返回");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_os_signal_hangup() {
+        let waiting = tokio::spawn(async { os_signal(&[OsSignal::Hangup]).await });
+        // 等待信号处理器注册
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        std::process::Command::new("kill")
+            .args(["-HUP", &std::process::id().to_string()])
+            .status()
+            .unwrap();
+        let received = tokio::time::timeout(Duration::from_secs(1), waiting).await.unwrap().unwrap();
+        assert_eq!(received, OsSignal::Hangup);
+    }
+
+    #[tokio::test]
+    async fn test_track_in_flight() {
+        use axum::{body::Body, routing::get, Router};
+        use tower::util::ServiceExt;
+
+        let in_flight = InFlight::default();
+        let (entered, on_entered) = tokio::sync::oneshot::channel::<()>();
+        let entered = Arc::new(std::sync::Mutex::new(Some(entered)));
+        let app = Router::new()
+            .route(
+                "/slow",
+                get(move || async move {
+                    if let Some(entered) = entered.lock().unwrap().take() {
+                        let _ = entered.send(());
+                    }
+                    future::pending::<()>().await;
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(in_flight.clone(), track_in_flight));
+
+        let request = Request::builder().uri("/slow").body(Body::empty()).unwrap();
+        let pending = tokio::spawn(app.oneshot(request));
+        on_entered.await.unwrap();
+        assert_eq!(in_flight.count(), 1);
+
+        // 请求被取消后计数恢复
+        pending.abort();
+        let _ = pending.await;
+        assert_eq!(in_flight.count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_wait_after_trigger() {
+        let signal = signal();
+        assert!(!signal.is_shutting_down());
+        signal.trigger();
+        tokio::time::timeout(Duration::from_secs(1), signal.wait())
+            .await
+            .expect("已触发的信号应立即返回");
+    }
+
+    #[cfg(feature = "ws")]
+    #[test]
+    fn test_close_message() {
+        use axum::extract::ws::{close_code, Message};
+
+        match signal().close_message() {
+            Message::Close(Some(frame)) => {
+                assert_eq!(frame.code, close_code::AWAY);
+                assert_eq!(frame.reason, "maintenance");
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+}
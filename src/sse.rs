@@ -0,0 +1,185 @@
+//! Server-Sent Events 模块
+//!
+//! [`SseBroadcaster`] 可以放进应用状态或通过 `provide` 登记，在任意位置调用
+//! [`send`](SseBroadcaster::send) 推送事件，所有已连接的客户端都会收到。订阅的连接定期发送
+//! `: keep-alive` 注释防止代理断开空闲连接，服务器关闭时发送 `shutdown` 事件后结束。
+//!
+//! ```rust
+//! use axum::response::sse::Event;
+//! use hwhkit::{sse::SseBroadcaster, Router};
+//!
+//! let broadcaster = SseBroadcaster::new(64);
+//! let app: Router = Router::new().route("/events", broadcaster.handler());
+//!
+//! // 在其他任务中推送
+//! broadcaster.send(Event::default().event("tick").data("1"));
+//! ```
+
+use crate::{error::Result, shutdown::ShutdownSignal};
+use axum::{
+    extract::State,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    routing::{get, MethodRouter},
+};
+use futures_util::stream::{self, Stream};
+use serde::Serialize;
+use std::{convert::Infallible, time::Duration};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+/// 默认的 keep-alive 注释间隔
+const DEFAULT_KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// SSE 事件广播器
+///
+/// 克隆后共享同一个通道。客户端处理速度跟不上时丢弃积压超过 `capacity` 的旧事件
+#[derive(Debug, Clone)]
+pub struct SseBroadcaster {
+    sender: broadcast::Sender<Event>,
+    keep_alive: Duration,
+}
+
+impl SseBroadcaster {
+    /// 创建广播器
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - 每个客户端最多积压的事件数
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        Self {
+            sender,
+            keep_alive: DEFAULT_KEEP_ALIVE,
+        }
+    }
+
+    /// 设置 keep-alive 注释间隔（默认 15 秒）
+    pub fn keep_alive(mut self, interval: Duration) -> Self {
+        self.keep_alive = interval;
+        self
+    }
+
+    /// 推送事件，返回收到事件的客户端数量
+    pub fn send(&self, event: Event) -> usize {
+        self.sender.send(event).unwrap_or(0)
+    }
+
+    /// 以 JSON 数据推送命名事件
+    pub fn send_json<T: Serialize>(&self, name: &str, data: &T) -> Result<usize> {
+        Ok(self.send(Event::default().event(name).data(serde_json::to_string(data)?)))
+    }
+
+    /// 当前连接的客户端数量
+    pub fn client_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// 订阅事件流
+    ///
+    /// 客户端落后时跳过丢失的事件并记录警告
+    pub fn subscribe(&self) -> impl Stream<Item = std::result::Result<Event, Infallible>> + Send + 'static {
+        stream::unfold(self.sender.subscribe(), |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => return Some((Ok(event), receiver)),
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!("SSE 客户端处理过慢，丢弃了 {} 个事件", skipped);
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+
+    /// 订阅事件并生成 SSE 响应
+    ///
+    /// 传入 `shutdown` 时，服务器关闭会发送 `shutdown` 事件后结束连接
+    pub fn sse(&self, shutdown: Option<&ShutdownSignal>) -> Response {
+        let keep_alive = KeepAlive::new().interval(self.keep_alive).text("keep-alive");
+        match shutdown {
+            Some(shutdown) => Sse::new(shutdown.sse_stream(self.subscribe()))
+                .keep_alive(keep_alive)
+                .into_response(),
+            None => Sse::new(self.subscribe()).keep_alive(keep_alive).into_response(),
+        }
+    }
+
+    /// 订阅此广播器的处理器，可直接挂载到路由
+    pub fn handler<S: Clone + Send + Sync + 'static>(&self) -> MethodRouter<S> {
+        let broadcaster = self.clone();
+        get(move |shutdown: Option<ShutdownSignal>| async move { broadcaster.sse(shutdown.as_ref()) })
+    }
+}
+
+/// 从应用状态取得广播器并订阅的处理器
+///
+/// 应用状态需要实现 `FromRef`，或直接以 `SseBroadcaster` 作为状态
+pub async fn handler(
+    State(broadcaster): State<SseBroadcaster>,
+    shutdown: Option<ShutdownSignal>,
+) -> Response {
+    broadcaster.sse(shutdown.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ShutdownConfig;
+    use axum::{
+        body::{Body, BodyDataStream},
+        http::Request,
+        Extension, Router,
+    };
+    use futures_util::StreamExt;
+    use tower::util::ServiceExt;
+
+    async fn next_frame(body: &mut BodyDataStream) -> String {
+        let frame = tokio::time::timeout(Duration::from_secs(2), body.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        String::from_utf8(frame.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_to_subscribers() {
+        let broadcaster = SseBroadcaster::new(8);
+        let shutdown = ShutdownSignal::new(&ShutdownConfig::default());
+        let app = Router::new()
+            .route("/events", get(handler))
+            .with_state(broadcaster.clone())
+            .layer(Extension(shutdown.clone()));
+
+        assert_eq!(broadcaster.send(Event::default().data("nobody")), 0);
+
+        let response = app
+            .oneshot(Request::get("/events").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.headers()["content-type"], "text/event-stream");
+        let mut body = response.into_body().into_data_stream();
+        assert_eq!(broadcaster.client_count(), 1);
+
+        broadcaster.send_json("tick", &serde_json::json!({ "n": 1 })).unwrap();
+        assert_eq!(next_frame(&mut body).await, "event: tick\ndata: {\"n\":1}\n\n");
+
+        shutdown.trigger();
+        assert!(next_frame(&mut body).await.starts_with("event: shutdown\n"));
+    }
+
+    #[tokio::test]
+    async fn test_keep_alive_comments() {
+        let broadcaster = SseBroadcaster::new(8).keep_alive(Duration::from_millis(50));
+        let app: Router = Router::new().route("/events", broadcaster.handler());
+
+        let response = app
+            .oneshot(Request::get("/events").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let mut body = response.into_body().into_data_stream();
+        assert_eq!(next_frame(&mut body).await, ": keep-alive\n\n");
+    }
+}
@@ -1,7 +1,22 @@
 //! 模板渲染模块
+//!
+//! 通过 `[middleware.templates] engine` 选择模板引擎：
+//!
+//! - `tera`（默认）
+//! - `minijinja`（需要 `minijinja` feature）
+//! - `handlebars`（需要 `handlebars` feature）
+//!
+//! 各引擎都提供 `csrf_token`、`url_for` 和 `request_value` 三个内置函数，启用 `i18n` 特性时
+//! 还提供翻译函数 `t`（见 [`crate::i18n`]）。
+//! 模板名称为相对于模板目录的路径，如 `users/list.html`。
+//!
+//! 局部模板目录（默认 `partials/`）中的模板额外按相对于该目录的名称注册：
+//! Tera / MiniJinja 中使用 `{% include "nav.html" %}`，Handlebars 中使用 `{{> nav}}`。
+//! 配置 `layout` 后，[`TemplateEngine::render_in_layout`] 先渲染页面，再把结果作为 `content`
+//! 交给布局模板（Tera / MiniJinja 中写 `{{ content | safe }}`，Handlebars 中写 `{{{content}}}`）。
 
 #[cfg(feature = "templates")]
-use crate::{config::TemplateConfig, error::{Error, Result}};
+use crate::{config::{TemplateConfig, TemplateEngineKind}, error::{Error, Result}};
 
 #[cfg(feature = "templates")]
 use axum::{
@@ -13,22 +28,90 @@ use axum::{
 use serde::Serialize;
 
 #[cfg(feature = "templates")]
-use std::{collections::HashMap, path::Path, sync::Arc};
+use std::{collections::HashMap, fmt, path::Path, sync::Arc};
 
 #[cfg(feature = "templates")]
 use tera::{Context, Tera};
 
+#[cfg(feature = "templates")]
+use crate::admin::FeatureFlags;
+
+#[cfg(feature = "templates")]
+pub use tera;
+
+#[cfg(feature = "minijinja")]
+mod minijinja_backend;
+
+#[cfg(feature = "handlebars")]
+mod handlebars_backend;
+
+/// 模板后端
+///
+/// 内置 Tera、MiniJinja 和 Handlebars 的实现，也可以实现此 trait 接入其他模板引擎，
+/// 通过 [`TemplateEngine::from_backend`] 使用
+#[cfg(feature = "templates")]
+pub trait TemplateBackend: Send + Sync + 'static {
+    /// 使用 JSON 上下文渲染模板
+    fn render(&self, name: &str, context: &serde_json::Value) -> Result<String>;
+
+    /// 所有模板名称
+    fn template_names(&self) -> Vec<String>;
+}
+
+#[cfg(feature = "templates")]
+impl TemplateBackend for Tera {
+    fn render(&self, name: &str, context: &serde_json::Value) -> Result<String> {
+        let mut tera_context = Context::new();
+        if let serde_json::Value::Object(map) = context {
+            for (key, value) in map {
+                tera_context.insert(key, value);
+            }
+        }
+        Tera::render(self, name, &tera_context).map_err(Error::Template)
+    }
+
+    fn template_names(&self) -> Vec<String> {
+        self.get_template_names().map(|s| s.to_string()).collect()
+    }
+}
+
 /// 模板引擎包装器
 #[cfg(feature = "templates")]
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct TemplateEngine {
-    tera: Arc<Tera>,
+    backend: Backend,
+    layout: Option<String>,
+    globals: serde_json::Map<String, serde_json::Value>,
+    feature_flags: Option<FeatureFlags>,
+}
+
+#[cfg(feature = "templates")]
+#[derive(Clone)]
+enum Backend {
+    /// 保留具体类型以便注册过滤器等自定义扩展
+    Tera(Arc<Tera>),
+    Other(Arc<dyn TemplateBackend>),
+}
+
+#[cfg(feature = "templates")]
+impl fmt::Debug for TemplateEngine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TemplateEngine")
+            .field("templates", &self.get_template_names())
+            .field("layout", &self.layout)
+            .field("globals", &self.globals)
+            .finish()
+    }
 }
 
 #[cfg(feature = "templates")]
 impl TemplateEngine {
-    /// 创建新的模板引擎
+    /// 按配置创建模板引擎
     pub fn new(config: &TemplateConfig) -> Result<Self> {
+        if !config.engine.is_runtime() {
+            return Err(Error::Config("Askama 模板在编译期生成，不需要创建 TemplateEngine".to_string()));
+        }
+
         let template_dir = Path::new(&config.dir);
         
         if !template_dir.exists() {
@@ -38,33 +121,100 @@ impl TemplateEngine {
             )));
         }
 
-        let glob_pattern = format!("{}/**/*.{}", config.dir, config.extension);
-        let tera = Tera::new(&glob_pattern).map_err(Error::Template)?;
+        if config.strict {
+            check_templates(config)?;
+        }
 
-        tracing::info!("✅ 模板引擎初始化成功");
+        let backend = match config.engine {
+            TemplateEngineKind::Tera => Backend::Tera(Arc::new(tera_backend(config)?)),
+            #[cfg(feature = "minijinja")]
+            TemplateEngineKind::MiniJinja => Backend::Other(Arc::new(minijinja_backend::new(config)?)),
+            #[cfg(not(feature = "minijinja"))]
+            TemplateEngineKind::MiniJinja => {
+                return Err(Error::Config("MiniJinja 模板引擎需要启用 `minijinja` feature".to_string()))
+            }
+            #[cfg(feature = "handlebars")]
+            TemplateEngineKind::Handlebars => Backend::Other(Arc::new(handlebars_backend::new(config)?)),
+            #[cfg(not(feature = "handlebars"))]
+            TemplateEngineKind::Handlebars => {
+                return Err(Error::Config("Handlebars 模板引擎需要启用 `handlebars` feature".to_string()))
+            }
+            TemplateEngineKind::Askama => unreachable!("编译期模板已在上面返回"),
+        };
+
+        let engine = Self {
+            backend,
+            layout: config.layout.clone(),
+            globals: config.globals.clone().into_iter().collect(),
+            feature_flags: None,
+        };
+        if let Some(layout) = &config.layout {
+            if !engine.get_template_names().contains(layout) {
+                return Err(Error::Config(format!("布局模板不存在: {}", layout)));
+            }
+        }
+
+        tracing::info!("✅ 模板引擎初始化成功: {:?}", config.engine);
         tracing::info!("📁 模板目录: {}", config.dir);
         tracing::info!("🔗 文件扩展名: .{}", config.extension);
 
-        Ok(Self {
-            tera: Arc::new(tera),
-        })
+        Ok(engine)
+    }
+
+    /// 使用自定义的模板后端
+    pub fn from_backend(backend: impl TemplateBackend) -> Self {
+        Self {
+            backend: Backend::Other(Arc::new(backend)),
+            layout: None,
+            globals: serde_json::Map::new(),
+            feature_flags: None,
+        }
+    }
+
+    /// 设置 `render_in_layout` 使用的布局模板
+    pub fn with_layout(mut self, layout: impl Into<String>) -> Self {
+        self.layout = Some(layout.into());
+        self
+    }
+
+    /// 添加全局变量，合并到每次渲染的上下文中，处理函数传入的同名变量优先
+    pub fn add_global(&mut self, key: impl Into<String>, value: impl Serialize) -> Result<()> {
+        let value = serde_json::to_value(value).map_err(Error::Serialization)?;
+        self.globals.insert(key.into(), value);
+        Ok(())
+    }
+
+    /// 渲染时以 `feature_flags` 变量提供功能开关的当前值，如 `{% if feature_flags.new_ui %}`
+    pub fn set_feature_flags(&mut self, feature_flags: FeatureFlags) {
+        self.feature_flags = Some(feature_flags);
+    }
+
+    /// 合并全局变量和渲染上下文
+    fn context(&self, context: serde_json::Value) -> serde_json::Value {
+        if self.globals.is_empty() && self.feature_flags.is_none() {
+            return context;
+        }
+        let mut merged = self.globals.clone();
+        if let Some(flags) = &self.feature_flags {
+            merged.insert("feature_flags".to_string(), serde_json::json!(flags.snapshot()));
+        }
+        if let serde_json::Value::Object(map) = context {
+            merged.extend(map);
+        }
+        serde_json::Value::Object(merged)
+    }
+
+    fn backend(&self) -> &dyn TemplateBackend {
+        match &self.backend {
+            Backend::Tera(tera) => tera.as_ref(),
+            Backend::Other(backend) => backend.as_ref(),
+        }
     }
 
     /// 渲染模板
     pub fn render<T: Serialize>(&self, template_name: &str, context: &T) -> Result<String> {
-        let mut tera_context = Context::new();
-        
-        // 将传入的上下文序列化为 serde_json::Value 然后添加到 Tera Context
         let value = serde_json::to_value(context).map_err(Error::Serialization)?;
-        if let serde_json::Value::Object(map) = value {
-            for (key, val) in map {
-                tera_context.insert(&key, &val);
-            }
-        }
-
-        self.tera
-            .render(template_name, &tera_context)
-            .map_err(Error::Template)
+        self.backend().render(template_name, &self.context(value))
     }
 
     /// 渲染模板（使用 HashMap 上下文）
@@ -73,19 +223,280 @@ impl TemplateEngine {
         template_name: &str, 
         context: HashMap<String, serde_json::Value>
     ) -> Result<String> {
-        let mut tera_context = Context::new();
-        for (key, value) in context {
-            tera_context.insert(&key, &value);
+        let context = serde_json::Value::Object(context.into_iter().collect());
+        self.backend().render(template_name, &self.context(context))
+    }
+
+    /// 在布局中渲染页面
+    ///
+    /// 先用 `context` 渲染 `template_name`，再把结果作为 `content` 连同 `context` 中的其他值
+    /// 一起渲染布局模板，页面不需要各自重复导航栏和页脚
+    pub fn render_in_layout<T: Serialize>(&self, template_name: &str, context: &T) -> Result<String> {
+        let layout = self
+            .layout
+            .as_deref()
+            .ok_or_else(|| Error::Config("未配置布局模板 [middleware.templates] layout".to_string()))?;
+
+        let mut value = self.context(serde_json::to_value(context).map_err(Error::Serialization)?);
+        let content = self.backend().render(template_name, &value)?;
+        match &mut value {
+            serde_json::Value::Object(map) => {
+                map.insert("content".to_string(), content.into());
+            }
+            _ => value = serde_json::json!({ "content": content }),
         }
+        self.backend().render(layout, &value)
+    }
 
-        self.tera
-            .render(template_name, &tera_context)
-            .map_err(Error::Template)
+    /// 渲染完整页面，配置了布局时在布局中渲染
+    pub(crate) fn render_page<T: Serialize>(&self, template_name: &str, context: &T) -> Result<String> {
+        if self.layout.is_some() {
+            self.render_in_layout(template_name, context)
+        } else {
+            self.render(template_name, context)
+        }
     }
 
     /// 获取所有可用的模板名称
     pub fn get_template_names(&self) -> Vec<String> {
-        self.tera.get_template_names().map(|s| s.to_string()).collect()
+        self.backend().template_names()
+    }
+
+    /// 注册过滤器，如 `{{ price | currency }}`（仅 Tera）
+    ///
+    /// 只影响当前实例，之前克隆出的引擎不会看到新注册的过滤器
+    pub fn register_filter<F: tera::Filter + 'static>(&mut self, name: &str, filter: F) -> Result<()> {
+        self.customize(|tera| tera.register_filter(name, filter))
+    }
+
+    /// 注册函数，如 `{{ asset_url(path="app.css") | safe }}`（仅 Tera）
+    pub fn register_function<F: tera::Function + 'static>(&mut self, name: &str, function: F) -> Result<()> {
+        self.customize(|tera| tera.register_function(name, function))
+    }
+
+    /// 注册测试器，如 `{% if user is admin %}`（仅 Tera）
+    pub fn register_tester<T: tera::Test + 'static>(&mut self, name: &str, tester: T) -> Result<()> {
+        self.customize(|tera| tera.register_tester(name, tester))
+    }
+
+    /// 直接修改底层的 Tera 实例，其他模板引擎返回错误
+    pub fn customize(&mut self, customize: impl FnOnce(&mut Tera)) -> Result<()> {
+        match &mut self.backend {
+            Backend::Tera(tera) => {
+                customize(Arc::make_mut(tera));
+                Ok(())
+            }
+            Backend::Other(_) => Err(Error::Config("只有 Tera 模板引擎支持自定义扩展".to_string())),
+        }
+    }
+}
+
+/// 加载模板目录并注册内置函数
+#[cfg(feature = "templates")]
+fn tera_backend(config: &TemplateConfig) -> Result<Tera> {
+    let glob_pattern = format!("{}/**/*.{}", config.dir, config.extension);
+    let mut tera = Tera::new(&glob_pattern).map_err(Error::Template)?;
+    let partials = read_partials(config)?;
+    for (name, _) in &partials {
+        if tera.get_template(name).is_ok() {
+            return Err(Error::Config(format!("局部模板与已有模板重名: {}", name)));
+        }
+    }
+    tera.add_raw_templates(partials).map_err(Error::Template)?;
+
+    // {{ csrf_token() }}，未启用 CSRF 防护时为空字符串
+    tera.register_function("csrf_token", |_: &HashMap<String, tera::Value>| {
+        Ok(tera::Value::String(
+            crate::middleware::csrf::current_token().unwrap_or_default(),
+        ))
+    });
+    // {{ url_for(name="user_detail", id=5) }}
+    tera.register_function("url_for", crate::routing::tera_url_for);
+    // {{ request_value(name="current_user") }}，读取 RequestScope::expose 提供的值
+    tera.register_function("request_value", |args: &HashMap<String, tera::Value>| {
+        let name = args
+            .get("name")
+            .and_then(tera::Value::as_str)
+            .ok_or_else(|| tera::Error::msg("request_value 需要 name 参数"))?;
+        Ok(request_value(name))
+    });
+    // {{ t(key="welcome", name=user.name) }}，使用当前请求的语言翻译
+    #[cfg(feature = "i18n")]
+    tera.register_function("t", |args: &HashMap<String, tera::Value>| {
+        let key = args
+            .get("key")
+            .and_then(tera::Value::as_str)
+            .ok_or_else(|| tera::Error::msg("t 需要 key 参数"))?;
+        let params: serde_json::Map<_, _> = args
+            .iter()
+            .filter(|(name, _)| *name != "key")
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        Ok(crate::i18n::t(key, &params.into()).into())
+    });
+    Ok(tera)
+}
+
+/// 逐个解析模板目录中的模板，有错误时列出所有出错的模板
+#[cfg(feature = "templates")]
+fn check_templates(config: &TemplateConfig) -> Result<()> {
+    let mut broken = Vec::new();
+    for (name, source) in read_dir(Path::new(&config.dir), &config.extension)? {
+        let result = match config.engine {
+            TemplateEngineKind::Tera => tera::Template::new(&name, None, &source)
+                .map(|_| ())
+                .map_err(|e| error_chain(&e)),
+            #[cfg(feature = "minijinja")]
+            TemplateEngineKind::MiniJinja => minijinja_backend::parse(&name, &source),
+            #[cfg(feature = "handlebars")]
+            TemplateEngineKind::Handlebars => handlebars_backend::parse(&source),
+            _ => Ok(()),
+        };
+        if let Err(e) = result {
+            tracing::error!("模板 {} 有错误: {}", name, e);
+            broken.push(format!("  {}: {}", name, e));
+        }
+    }
+
+    if broken.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::Config(format!("{} 个模板有错误:\n{}", broken.len(), broken.join("\n"))))
+    }
+}
+
+/// 拼接错误及其来源，Tera 的语法错误详情在来源中
+#[cfg(feature = "templates")]
+fn error_chain(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(e) = source {
+        message = format!("{}: {}", message, e);
+        source = e.source();
+    }
+    message
+}
+
+/// `request_value` 内置函数，读取 `RequestScope::expose` 提供的值
+#[cfg(feature = "templates")]
+fn request_value(name: &str) -> serde_json::Value {
+    crate::middleware::request_scope::current_scope()
+        .and_then(|scope| scope.global(name))
+        .unwrap_or(serde_json::Value::Null)
+}
+
+/// 读取模板目录中的所有模板
+#[cfg(any(feature = "minijinja", feature = "handlebars"))]
+fn read_templates(config: &TemplateConfig) -> Result<Vec<(String, String)>> {
+    read_dir(Path::new(&config.dir), &config.extension)
+}
+
+/// 读取局部模板目录，名称相对于局部模板目录；目录不存在时为空
+#[cfg(feature = "templates")]
+fn read_partials(config: &TemplateConfig) -> Result<Vec<(String, String)>> {
+    let root = Path::new(&config.dir).join(&config.partials_dir);
+    if !root.is_dir() {
+        return Ok(Vec::new());
+    }
+    read_dir(&root, &config.extension)
+}
+
+/// 递归读取目录中指定扩展名的文件，返回按名称排序的 (模板名称, 内容)
+#[cfg(feature = "templates")]
+fn read_dir(root: &Path, extension: &str) -> Result<Vec<(String, String)>> {
+    fn visit(dir: &Path, root: &Path, extension: &str, templates: &mut Vec<(String, String)>) -> Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                visit(&path, root, extension, templates)?;
+            } else if path.extension().is_some_and(|ext| ext == extension) {
+                let name = path
+                    .strip_prefix(root)
+                    .unwrap_or(&path)
+                    .components()
+                    .map(|part| part.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                templates.push((name, std::fs::read_to_string(&path)?));
+            }
+        }
+        Ok(())
+    }
+
+    let mut templates = Vec::new();
+    visit(root, root, extension, &mut templates)?;
+    templates.sort();
+    Ok(templates)
+}
+
+/// 渲染模板的响应
+///
+/// 处理函数不需要自己获取 [`TemplateEngine`]，由 [`WebServerBuilder`](crate::WebServerBuilder)
+/// 安装的中间件使用服务器的模板引擎渲染；配置了 `layout` 时在布局中渲染：
+///
+/// ```rust
+/// use hwhkit::templates::Render;
+/// use serde_json::json;
+///
+/// async fn index() -> Render<serde_json::Value> {
+///     Render("index.html", json!({ "title": "首页" }))
+/// }
+/// ```
+///
+/// 可以和状态码、响应头组合，如 `(StatusCode::NOT_FOUND, Render("404.html", ctx))`。
+#[cfg(feature = "templates")]
+#[derive(Debug, Clone)]
+pub struct Render<T>(pub &'static str, pub T);
+
+/// 待渲染的模板，由 [`render_middleware`] 渲染
+#[cfg(feature = "templates")]
+#[derive(Clone)]
+struct PendingRender {
+    name: &'static str,
+    context: serde_json::Value,
+}
+
+#[cfg(feature = "templates")]
+impl<T: Serialize> IntoResponse for Render<T> {
+    fn into_response(self) -> axum::response::Response {
+        let context = match serde_json::to_value(&self.1) {
+            Ok(context) => context,
+            Err(e) => return Error::Serialization(e).into_response(),
+        };
+        let mut response = Html(()).into_response();
+        response.extensions_mut().insert(PendingRender {
+            name: self.0,
+            context,
+        });
+        response
+    }
+}
+
+/// 渲染 [`Render`] 响应的中间件，未启用模板时返回 500
+#[cfg(feature = "templates")]
+pub(crate) async fn render_middleware(
+    State(engine): State<Option<Arc<TemplateEngine>>>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    let mut response = next.run(request).await;
+    let Some(PendingRender { name, context }) = response.extensions_mut().remove::<PendingRender>() else {
+        return response;
+    };
+    let Some(engine) = engine else {
+        return Error::Internal("模板引擎未启用，请配置 [middleware.templates]".to_string()).into_response();
+    };
+
+    match engine.render_page(name, &context) {
+        Ok(html) => {
+            response.headers_mut().remove(axum::http::header::CONTENT_LENGTH);
+            *response.body_mut() = html.into();
+            response
+        }
+        Err(e) => {
+            tracing::error!("模板 {} 渲染失败: {}", name, e);
+            e.into_response()
+        }
     }
 }
 
@@ -144,12 +555,506 @@ mod tests {
             enabled: true,
             dir: "/nonexistent/directory".to_string(),
             extension: "html".to_string(),
+            ..Default::default()
         };
 
         let result = TemplateEngine::new(&config);
         assert!(result.is_err());
     }
 
+    #[cfg(feature = "templates")]
+    #[tokio::test]
+    async fn test_csrf_token_function() {
+        use crate::{
+            config::{CsrfConfig, TemplateConfig},
+            middleware::csrf::{csrf_middleware, CsrfProtection},
+        };
+        use axum::{body::Body, http::Request, routing::get, Router};
+        use tower::util::ServiceExt;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("form.html"),
+            r#"<input name="_csrf" value="{{ csrf_token() }}">"#,
+        )
+        .unwrap();
+        let engine = Arc::new(
+            TemplateEngine::new(&TemplateConfig {
+                enabled: true,
+                dir: temp_dir.path().to_string_lossy().to_string(),
+                extension: "html".to_string(),
+                ..Default::default()
+            })
+            .unwrap(),
+        );
+
+        let app = Router::new()
+            .route(
+                "/",
+                get(move || async move { engine.render("form.html", &serde_json::json!({})).unwrap() }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                Arc::new(CsrfProtection::new(&CsrfConfig::default(), &Default::default()).unwrap()),
+                csrf_middleware,
+            ));
+
+        let response = app
+            .oneshot(Request::builder().uri("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let cookie = response.headers()["set-cookie"].to_str().unwrap().to_string();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let html = String::from_utf8(body.to_vec()).unwrap();
+
+        let token = cookie.split(';').next().unwrap().trim_start_matches("csrf_token=");
+        assert_eq!(html, format!(r#"<input name="_csrf" value="{}">"#, token));
+    }
+
+    #[cfg(feature = "templates")]
+    #[test]
+    fn test_url_for_function() {
+        use crate::config::TemplateConfig;
+
+        crate::routing::register("template_post_detail", "/posts/:slug").unwrap();
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("link.html"),
+            r#"<a href="{{ url_for(name="template_post_detail", slug="hello") | safe }}">"#,
+        )
+        .unwrap();
+        let engine = TemplateEngine::new(&TemplateConfig {
+            enabled: true,
+            dir: temp_dir.path().to_string_lossy().to_string(),
+            extension: "html".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let html = engine.render("link.html", &serde_json::json!({})).unwrap();
+        assert_eq!(html, r#"<a href="/posts/hello">"#);
+    }
+
+    #[cfg(feature = "templates")]
+    #[tokio::test]
+    async fn test_request_value_function() {
+        use crate::{
+            config::TemplateConfig,
+            middleware::request_scope::{request_scope_middleware, RequestScope},
+        };
+        use axum::{body::Body, http::Request, routing::get, Router};
+        use tower::util::ServiceExt;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("nav.html"),
+            r#"{% set user = request_value(name="user") %}{{ user.name }}|{{ request_value(name="missing") }}"#,
+        )
+        .unwrap();
+        let engine = Arc::new(
+            TemplateEngine::new(&TemplateConfig {
+                enabled: true,
+                dir: temp_dir.path().to_string_lossy().to_string(),
+                extension: "html".to_string(),
+                ..Default::default()
+            })
+            .unwrap(),
+        );
+
+        let app = Router::new()
+            .route(
+                "/",
+                get(move |scope: RequestScope| async move {
+                    scope.expose("user", &serde_json::json!({ "name": "alice" })).unwrap();
+                    engine.render("nav.html", &serde_json::json!({})).unwrap()
+                }),
+            )
+            .layer(axum::middleware::from_fn(request_scope_middleware));
+
+        let response = app
+            .oneshot(Request::builder().uri("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"alice|");
+    }
+
+    #[cfg(feature = "templates")]
+    #[test]
+    fn test_register_helpers() {
+        use crate::config::TemplateConfig;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("price.html"),
+            r#"{{ price | currency }}|{{ asset_url(path="app.css") | safe }}|{% if n is even_number %}even{% endif %}|{{ "x" | shout }}"#,
+        )
+        .unwrap();
+        let mut engine = TemplateEngine::new(&TemplateConfig {
+            enabled: true,
+            dir: temp_dir.path().to_string_lossy().to_string(),
+            extension: "html".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        let before = engine.clone();
+
+        engine.register_filter("currency", |value: &tera::Value, _: &HashMap<String, tera::Value>| {
+            Ok(tera::Value::String(format!("¥{:.2}", value.as_f64().unwrap_or_default())))
+        })
+        .unwrap();
+        engine.register_function("asset_url", |args: &HashMap<String, tera::Value>| {
+            let path = args.get("path").and_then(tera::Value::as_str).unwrap_or_default();
+            Ok(tera::Value::String(format!("/static/{}", path)))
+        })
+        .unwrap();
+        engine.register_tester("even_number", |value: Option<&tera::Value>, _: &[tera::Value]| {
+            Ok(value.and_then(tera::Value::as_u64).is_some_and(|n| n % 2 == 0))
+        })
+        .unwrap();
+        engine.customize(|tera| {
+            tera.register_filter("shout", |value: &tera::Value, _: &HashMap<String, tera::Value>| {
+                Ok(tera::Value::String(value.as_str().unwrap_or_default().to_uppercase()))
+            });
+        })
+        .unwrap();
+
+        let html = engine.render("price.html", &serde_json::json!({ "price": 12.5, "n": 4 })).unwrap();
+        assert_eq!(html, "¥12.50|/static/app.css|even|X");
+        // 注册前克隆的引擎不受影响
+        assert!(before.render("price.html", &serde_json::json!({ "price": 12.5, "n": 4 })).is_err());
+    }
+
+    /// 在临时目录中写入模板，使用指定引擎创建模板引擎
+    #[cfg(feature = "templates")]
+    fn engine_with(
+        engine: crate::config::TemplateEngineKind,
+        templates: &[(&str, &str)],
+    ) -> (tempfile::TempDir, Result<TemplateEngine>) {
+        let config = TemplateConfig {
+            engine,
+            ..Default::default()
+        };
+        engine_with_config(config, templates)
+    }
+
+    #[cfg(feature = "templates")]
+    fn engine_with_config(
+        mut config: TemplateConfig,
+        templates: &[(&str, &str)],
+    ) -> (tempfile::TempDir, Result<TemplateEngine>) {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        for (name, source) in templates {
+            let path = temp_dir.path().join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, source).unwrap();
+        }
+        config.enabled = true;
+        config.dir = temp_dir.path().to_string_lossy().to_string();
+        (temp_dir, TemplateEngine::new(&config))
+    }
+
+    #[cfg(feature = "templates")]
+    #[test]
+    fn test_render_in_layout() {
+        use crate::config::TemplateEngineKind;
+
+        let jinja = ("{% include \"nav.html\" %}<main>{{ content | safe }}</main>", "<nav>{{ title }}</nav>");
+        #[allow(unused_mut)]
+        let mut engines = vec![(TemplateEngineKind::Tera, jinja)];
+        #[cfg(feature = "minijinja")]
+        engines.push((TemplateEngineKind::MiniJinja, jinja));
+        #[cfg(feature = "handlebars")]
+        engines.push((TemplateEngineKind::Handlebars, ("{{> nav}}<main>{{{content}}}</main>", "<nav>{{title}}</nav>")));
+
+        for (kind, (layout, nav)) in engines {
+            let config = TemplateConfig {
+                engine: kind,
+                layout: Some("layout.html".to_string()),
+                ..Default::default()
+            };
+            let (_dir, engine) = engine_with_config(
+                config,
+                &[("layout.html", layout), ("partials/nav.html", nav), ("index.html", "<p>{{ name }}</p>")],
+            );
+            let engine = engine.unwrap();
+
+            let html = engine
+                .render_in_layout("index.html", &serde_json::json!({ "title": "首页", "name": "a&b" }))
+                .unwrap();
+            assert_eq!(html, "<nav>首页</nav><main><p>a&amp;b</p></main>", "{:?}", kind);
+            // 不经过布局直接渲染
+            assert_eq!(engine.render("index.html", &serde_json::json!({ "name": "x" })).unwrap(), "<p>x</p>");
+        }
+    }
+
+    #[cfg(feature = "i18n")]
+    #[tokio::test]
+    async fn test_translate_function() {
+        use crate::{config::{I18nConfig, TemplateEngineKind}, i18n::Translations};
+
+        let locales = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(locales.path().join("en")).unwrap();
+        std::fs::write(locales.path().join("en/main.ftl"), "welcome = Welcome, { $name }!").unwrap();
+        let translations = std::sync::Arc::new(
+            Translations::load(&I18nConfig {
+                dir: locales.path().to_string_lossy().to_string(),
+                default_locale: "en".to_string(),
+                ..Default::default()
+            })
+            .unwrap(),
+        );
+
+        #[allow(unused_mut)]
+        let mut engines = vec![(TemplateEngineKind::Tera, r#"{{ t(key="welcome", name=name) }}"#)];
+        #[cfg(feature = "minijinja")]
+        engines.push((TemplateEngineKind::MiniJinja, r#"{{ t("welcome", name=name) }}"#));
+        #[cfg(feature = "handlebars")]
+        engines.push((TemplateEngineKind::Handlebars, r#"{{t "welcome" name=name}}"#));
+
+        for (kind, source) in engines {
+            let (_dir, engine) = engine_with(kind, &[("index.html", source)]);
+            let engine = engine.unwrap();
+            let html = crate::i18n::scope(translations.clone(), "en".to_string(), async {
+                engine.render("index.html", &serde_json::json!({ "name": "Bob" })).unwrap()
+            })
+            .await;
+            assert_eq!(html, "Welcome, Bob!", "{:?}", kind);
+        }
+    }
+
+    #[cfg(feature = "templates")]
+    #[tokio::test]
+    async fn test_render_responder() {
+        use axum::{body::Body, http::{Request, StatusCode}, routing::get, Router};
+        use tower::ServiceExt;
+
+        let config = TemplateConfig {
+            layout: Some("layout.html".to_string()),
+            ..Default::default()
+        };
+        let (_dir, engine) = engine_with_config(
+            config,
+            &[("layout.html", "<main>{{ content | safe }}</main>"), ("index.html", "{{ title }}")],
+        );
+        let make_app = |engine: Option<Arc<TemplateEngine>>| {
+            Router::new()
+                .route("/", get(|| async { Render("index.html", serde_json::json!({ "title": "首页" })) }))
+                .route(
+                    "/missing",
+                    get(|| async { (StatusCode::NOT_FOUND, Render("index.html", serde_json::json!({ "title": "404" }))) }),
+                )
+                .route("/error", get(|| async { Render("unknown.html", ()) }))
+                .layer(axum::middleware::from_fn_with_state(engine, render_middleware))
+        };
+        let call = |app: Router, uri: &'static str| async move {
+            let response = app.oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap()).await.unwrap();
+            let status = response.status();
+            let content_type = response.headers()[axum::http::header::CONTENT_TYPE].to_str().unwrap().to_string();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            (status, content_type, String::from_utf8(body.to_vec()).unwrap())
+        };
+
+        let app = make_app(Some(Arc::new(engine.unwrap())));
+        let (status, content_type, body) = call(app.clone(), "/").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(content_type, "text/html; charset=utf-8");
+        assert_eq!(body, "<main>首页</main>");
+
+        let (status, _, body) = call(app.clone(), "/missing").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body, "<main>404</main>");
+
+        let (status, _, _) = call(app, "/error").await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+
+        // 未启用模板引擎
+        let (status, _, _) = call(make_app(None), "/").await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[cfg(feature = "templates")]
+    #[test]
+    fn test_strict_check() {
+        use crate::config::TemplateEngineKind;
+
+        #[allow(unused_mut)]
+        let mut engines = vec![(TemplateEngineKind::Tera, "{% if %}", "{{ name }")];
+        #[cfg(feature = "minijinja")]
+        engines.push((TemplateEngineKind::MiniJinja, "{% if %}", "{{ name }"));
+        #[cfg(feature = "handlebars")]
+        engines.push((TemplateEngineKind::Handlebars, "{{#if}}", "{{name}"));
+
+        for (kind, broken_a, broken_b) in engines {
+            let config = TemplateConfig {
+                engine: kind,
+                strict: true,
+                ..Default::default()
+            };
+            let templates = [("ok.html", "{{ name }}"), ("a.html", broken_a), ("users/b.html", broken_b)];
+            let (_dir, result) = engine_with_config(config, &templates);
+            let Err(Error::Config(message)) = result else {
+                panic!("{:?} 应该检查出模板错误", kind);
+            };
+            assert!(message.starts_with("2 个模板有错误"), "{}", message);
+            assert!(message.contains("a.html") && message.contains("users/b.html"), "{}", message);
+            assert!(!message.contains("ok.html"), "{}", message);
+        }
+    }
+
+    #[cfg(any(feature = "minijinja", feature = "handlebars"))]
+    #[test]
+    fn test_strict_variables() {
+        use crate::config::TemplateEngineKind;
+
+        let mut engines = Vec::new();
+        #[cfg(feature = "minijinja")]
+        engines.push(TemplateEngineKind::MiniJinja);
+        #[cfg(feature = "handlebars")]
+        engines.push(TemplateEngineKind::Handlebars);
+
+        for kind in engines {
+            for strict_variables in [false, true] {
+                let config = TemplateConfig {
+                    engine: kind,
+                    strict_variables,
+                    ..Default::default()
+                };
+                let (_dir, engine) = engine_with_config(config, &[("index.html", "[{{ missing }}]")]);
+                let result = engine.unwrap().render("index.html", &serde_json::json!({}));
+                if strict_variables {
+                    assert!(matches!(result, Err(Error::TemplateRender(_))), "{:?}", kind);
+                } else {
+                    assert_eq!(result.unwrap(), "[]", "{:?}", kind);
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "templates")]
+    #[test]
+    fn test_globals() {
+        let config = TemplateConfig {
+            globals: HashMap::from([
+                ("app_name".to_string(), serde_json::json!("HwhKit")),
+                ("version".to_string(), serde_json::json!("1.0")),
+            ]),
+            ..Default::default()
+        };
+        let (_dir, engine) = engine_with_config(
+            config,
+            &[("index.html", "{{ app_name }} {{ version }} {{ title }}{% if feature_flags.new_ui %} new{% endif %}")],
+        );
+        let mut engine = engine.unwrap();
+        engine.add_global("version", "2.0").unwrap();
+        let flags = FeatureFlags::new(HashMap::from([("new_ui".to_string(), false)]));
+        engine.set_feature_flags(flags.clone());
+
+        assert_eq!(engine.render("index.html", &serde_json::json!({ "title": "首页" })).unwrap(), "HwhKit 2.0 首页");
+        // 处理函数传入的变量优先，功能开关使用当前值
+        flags.set("new_ui", true);
+        assert_eq!(
+            engine.render("index.html", &serde_json::json!({ "title": "首页", "app_name": "Demo" })).unwrap(),
+            "Demo 2.0 首页 new"
+        );
+    }
+
+    #[cfg(feature = "templates")]
+    #[test]
+    fn test_layout_errors() {
+        let config = TemplateConfig {
+            layout: Some("missing.html".to_string()),
+            ..Default::default()
+        };
+        let (_dir, result) = engine_with_config(config, &[("index.html", "")]);
+        assert!(matches!(result, Err(Error::Config(_))), "布局模板必须存在");
+
+        let (_dir, engine) = engine_with(Default::default(), &[("index.html", "")]);
+        assert!(matches!(engine.unwrap().render_in_layout("index.html", &()), Err(Error::Config(_))));
+
+        // 局部模板名称不能和模板目录顶层的模板重复
+        let (_dir, result) = engine_with(Default::default(), &[("nav.html", ""), ("partials/nav.html", "")]);
+        assert!(matches!(result, Err(Error::Config(_))));
+    }
+
+    #[cfg(feature = "minijinja")]
+    #[test]
+    fn test_minijinja_backend() {
+        use crate::config::TemplateEngineKind;
+
+        crate::routing::register("minijinja_user_detail", "/users/:id").unwrap();
+        let (_dir, engine) = engine_with(
+            TemplateEngineKind::MiniJinja,
+            &[
+                ("base.html", "<main>{% block content %}{% endblock %}</main>"),
+                (
+                    "users/show.html",
+                    r#"{% extends "base.html" %}{% block content %}{{ name }}|{{ url_for(name="minijinja_user_detail", id=7) }}|{{ csrf_token() }}|{{ request_value(name="missing") }}{% endblock %}"#,
+                ),
+            ],
+        );
+        let mut engine = engine.unwrap();
+
+        assert_eq!(engine.get_template_names(), vec!["base.html", "users/show.html"]);
+        let html = engine
+            .render("users/show.html", &serde_json::json!({ "name": "<b>alice</b>" }))
+            .unwrap();
+        assert_eq!(html, "<main>&lt;b&gt;alice&lt;&#x2f;b&gt;|&#x2f;users&#x2f;7||None</main>");
+
+        assert!(matches!(engine.render("missing.html", &()), Err(Error::TemplateRender(_))));
+        assert!(engine.customize(|_| {}).is_err(), "只有 Tera 支持自定义扩展");
+    }
+
+    #[cfg(feature = "handlebars")]
+    #[test]
+    fn test_handlebars_backend() {
+        use crate::config::TemplateEngineKind;
+
+        crate::routing::register("handlebars_user_detail", "/users/:id").unwrap();
+        let (_dir, engine) = engine_with(
+            TemplateEngineKind::Handlebars,
+            &[(
+                "users/show.html",
+                r#"{{name}}|{{url_for name="handlebars_user_detail" id=7}}|{{csrf_token}}|{{#if (request_value name="missing")}}x{{else}}none{{/if}}"#,
+            )],
+        );
+        let engine = engine.unwrap();
+
+        assert_eq!(engine.get_template_names(), vec!["users/show.html"]);
+        let html = engine
+            .render("users/show.html", &serde_json::json!({ "name": "<b>alice</b>" }))
+            .unwrap();
+        assert_eq!(html, "&lt;b&gt;alice&lt;/b&gt;|/users/7||none");
+        assert!(matches!(engine.render("missing.html", &()), Err(Error::TemplateRender(_))));
+    }
+
+    #[cfg(all(feature = "templates", not(feature = "minijinja")))]
+    #[test]
+    fn test_minijinja_requires_feature() {
+        let (_dir, result) = engine_with(crate::config::TemplateEngineKind::MiniJinja, &[]);
+        assert!(matches!(result, Err(Error::Config(_))));
+    }
+
+    #[cfg(feature = "templates")]
+    #[test]
+    fn test_custom_backend() {
+        struct Echo;
+
+        impl TemplateBackend for Echo {
+            fn render(&self, name: &str, context: &serde_json::Value) -> Result<String> {
+                Ok(format!("{}:{}", name, context))
+            }
+
+            fn template_names(&self) -> Vec<String> {
+                vec!["any".to_string()]
+            }
+        }
+
+        let engine = TemplateEngine::from_backend(Echo);
+        assert_eq!(engine.render("any", &serde_json::json!({ "a": 1 })).unwrap(), r#"any:{"a":1}"#);
+        assert_eq!(engine.get_template_names(), vec!["any"]);
+    }
+
     #[cfg(not(feature = "templates"))]
     #[test]
     fn test_template_engine_disabled() {
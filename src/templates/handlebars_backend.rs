@@ -0,0 +1,110 @@
+//! Handlebars 模板后端
+
+use super::{read_partials, read_templates, request_value, TemplateBackend};
+use crate::{
+    config::TemplateConfig,
+    error::{Error, Result},
+};
+use handlebars::{
+    Context, Handlebars, Helper, HelperDef, RenderContext, RenderError, RenderErrorReason, ScopedJson,
+};
+
+impl TemplateBackend for Handlebars<'static> {
+    fn render(&self, name: &str, context: &serde_json::Value) -> Result<String> {
+        Handlebars::render(self, name, context).map_err(|e| Error::TemplateRender(e.to_string()))
+    }
+
+    fn template_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.get_templates().keys().cloned().collect();
+        names.sort();
+        names
+    }
+}
+
+type HelperFn = dyn Fn(&Helper<'_>) -> std::result::Result<serde_json::Value, String> + Send + Sync;
+
+/// 以 JSON 值作为结果的辅助函数，可以直接输出，也可以作为子表达式使用
+struct JsonHelper(Box<HelperFn>);
+
+impl HelperDef for JsonHelper {
+    fn call_inner<'reg: 'rc, 'rc>(
+        &self,
+        helper: &Helper<'rc>,
+        _: &'reg Handlebars<'reg>,
+        _: &'rc Context,
+        _: &mut RenderContext<'reg, 'rc>,
+    ) -> std::result::Result<ScopedJson<'rc>, RenderError> {
+        (self.0)(helper)
+            .map(ScopedJson::Derived)
+            .map_err(|e| RenderErrorReason::Other(e).into())
+    }
+}
+
+/// 只检查模板语法
+pub(super) fn parse(source: &str) -> std::result::Result<(), String> {
+    handlebars::Template::compile(source).map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// 加载模板目录并注册内置辅助函数
+pub(super) fn new(config: &TemplateConfig) -> Result<Handlebars<'static>> {
+    let mut handlebars = Handlebars::new();
+    handlebars.set_strict_mode(config.strict_variables);
+    for (name, source) in read_templates(config)? {
+        handlebars
+            .register_template_string(&name, source)
+            .map_err(|e| Error::TemplateRender(e.to_string()))?;
+    }
+    // {{> nav}}，名称不带扩展名
+    for (name, source) in read_partials(config)? {
+        let name = name.strip_suffix(&format!(".{}", config.extension)).unwrap_or(&name);
+        handlebars
+            .register_partial(name, source)
+            .map_err(|e| Error::TemplateRender(e.to_string()))?;
+    }
+
+    // {{csrf_token}}，未启用 CSRF 防护时为空字符串
+    handlebars.register_helper(
+        "csrf_token",
+        Box::new(JsonHelper(Box::new(|_| {
+            Ok(crate::middleware::csrf::current_token().unwrap_or_default().into())
+        }))),
+    );
+    // {{url_for name="user_detail" id=5}}
+    handlebars.register_helper(
+        "url_for",
+        Box::new(JsonHelper(Box::new(|helper| {
+            crate::routing::template_url_for(helper.hash().iter().map(|(key, value)| (*key, value.value())))
+                .map(Into::into)
+                .map_err(|e| e.to_string())
+        }))),
+    );
+    // {{#with (request_value name="current_user")}}{{name}}{{/with}}
+    handlebars.register_helper(
+        "request_value",
+        Box::new(JsonHelper(Box::new(|helper| {
+            let name = helper
+                .hash_get("name")
+                .and_then(|value| value.value().as_str())
+                .ok_or_else(|| "request_value 需要 name 参数".to_string())?;
+            Ok(request_value(name))
+        }))),
+    );
+    // {{t "welcome" name=user.name}}
+    #[cfg(feature = "i18n")]
+    handlebars.register_helper(
+        "t",
+        Box::new(JsonHelper(Box::new(|helper| {
+            let key = helper
+                .param(0)
+                .and_then(|value| value.value().as_str())
+                .ok_or_else(|| "t 需要翻译键参数".to_string())?;
+            let args: serde_json::Map<_, _> = helper
+                .hash()
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.value().clone()))
+                .collect();
+            Ok(crate::i18n::t(key, &args.into()).into())
+        }))),
+    );
+    Ok(handlebars)
+}
@@ -0,0 +1,78 @@
+//! MiniJinja 模板后端
+
+use super::{read_partials, read_templates, request_value, TemplateBackend};
+use crate::{
+    config::TemplateConfig,
+    error::{Error, Result},
+};
+use minijinja::{value::Kwargs, Environment, ErrorKind, UndefinedBehavior, Value};
+
+impl TemplateBackend for Environment<'static> {
+    fn render(&self, name: &str, context: &serde_json::Value) -> Result<String> {
+        self.get_template(name)
+            .and_then(|template| template.render(context))
+            .map_err(|e| Error::TemplateRender(format!("{:#}", e)))
+    }
+
+    fn template_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.templates().map(|(name, _)| name.to_string()).collect();
+        names.sort();
+        names
+    }
+}
+
+/// 只检查模板语法
+pub(super) fn parse(name: &str, source: &str) -> std::result::Result<(), String> {
+    Environment::new()
+        .template_from_named_str(name, source)
+        .map(|_| ())
+        .map_err(|e| format!("{:#}", e))
+}
+
+/// 加载模板目录并注册内置函数
+pub(super) fn new(config: &TemplateConfig) -> Result<Environment<'static>> {
+    let mut env = Environment::new();
+    if config.strict_variables {
+        env.set_undefined_behavior(UndefinedBehavior::Strict);
+    }
+    for (name, source) in read_templates(config)? {
+        env.add_template_owned(name.clone(), source)
+            .map_err(|e| Error::TemplateRender(format!("{}: {:#}", name, e)))?;
+    }
+    for (name, source) in read_partials(config)? {
+        if env.get_template(&name).is_ok() {
+            return Err(Error::Config(format!("局部模板与已有模板重名: {}", name)));
+        }
+        env.add_template_owned(name.clone(), source)
+            .map_err(|e| Error::TemplateRender(format!("{}: {:#}", name, e)))?;
+    }
+
+    // {{ csrf_token() }}，未启用 CSRF 防护时为空字符串
+    env.add_function("csrf_token", || {
+        crate::middleware::csrf::current_token().unwrap_or_default()
+    });
+    // {{ url_for(name="user_detail", id=5) }}
+    env.add_function("url_for", |kwargs: Kwargs| -> std::result::Result<String, minijinja::Error> {
+        let args = kwargs
+            .args()
+            .map(|key| Ok((key.to_string(), serde_json::to_value(kwargs.get::<Value>(key)?).unwrap_or_default())))
+            .collect::<std::result::Result<Vec<_>, minijinja::Error>>()?;
+        crate::routing::template_url_for(args.iter().map(|(key, value)| (key.as_str(), value)))
+            .map_err(|e| minijinja::Error::new(ErrorKind::InvalidOperation, e.to_string()))
+    });
+    // {{ request_value(name="current_user") }}
+    env.add_function("request_value", |kwargs: Kwargs| -> std::result::Result<Value, minijinja::Error> {
+        let name: String = kwargs.get("name")?;
+        Ok(Value::from_serialize(request_value(&name)))
+    });
+    // {{ t("welcome", name=user.name) }}
+    #[cfg(feature = "i18n")]
+    env.add_function("t", |key: String, kwargs: Kwargs| -> std::result::Result<String, minijinja::Error> {
+        let args = kwargs
+            .args()
+            .map(|name| Ok((name.to_string(), serde_json::to_value(kwargs.get::<Value>(name)?).unwrap_or_default())))
+            .collect::<std::result::Result<serde_json::Map<_, _>, minijinja::Error>>()?;
+        Ok(crate::i18n::t(&key, &args.into()))
+    });
+    Ok(env)
+}
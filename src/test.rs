@@ -0,0 +1,273 @@
+//! 测试工具模块（需要 `testing` feature）
+//!
+//! [`TestServer`] 在进程内运行完整的路由和中间件栈，请求直接交给路由器处理，不需要监听端口：
+//!
+//! ```rust,no_run
+//! use hwhkit::{test::TestServer, Router, WebServerBuilder};
+//!
+//! # async fn example() -> hwhkit::Result<()> {
+//! let builder = WebServerBuilder::new()
+//!     .config_from_file("config.toml")
+//!     .routes(Router::new().route("/users", hwhkit::post(|| async { "created" })));
+//! let server = TestServer::from_builder(builder).await?;
+//!
+//! let response = server
+//!     .post("/users")
+//!     .header("authorization", "Bearer token")
+//!     .json(&serde_json::json!({ "name": "alice" }))
+//!     .send()
+//!     .await;
+//! assert_eq!(response.status(), 200);
+//! assert_eq!(response.text(), "created");
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! 请求默认来自 `127.0.0.1`，可以通过 [`TestRequest::remote_addr`] 修改，用于测试
+//! [`ClientIp`](crate::middleware::client_ip::ClientIp) 等依赖连接地址的功能。
+
+use crate::{builder::WebServerBuilder, error::Result};
+use axum::{
+    body::{Body, Bytes},
+    extract::ConnectInfo,
+    http::{header, HeaderMap, HeaderName, HeaderValue, Method, Request, StatusCode},
+    Router,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    future::{Future, IntoFuture},
+    net::SocketAddr,
+    pin::Pin,
+};
+use tower::ServiceExt;
+
+/// 进程内测试服务器
+#[derive(Debug, Clone)]
+pub struct TestServer {
+    app: Router,
+}
+
+impl TestServer {
+    /// 构建服务器并应用配置中的所有中间件
+    ///
+    /// # Errors
+    ///
+    /// 构建失败时返回与 [`WebServerBuilder::build`] 相同的错误
+    pub async fn from_builder(builder: WebServerBuilder) -> Result<Self> {
+        let server = builder.build().await?;
+        Ok(Self::new(server.app().clone()))
+    }
+
+    /// 使用已经构建好的路由器
+    pub fn new(app: Router) -> Self {
+        Self { app }
+    }
+
+    /// 创建请求
+    pub fn request(&self, method: Method, path: &str) -> TestRequest {
+        TestRequest {
+            app: self.app.clone(),
+            method,
+            path: path.to_string(),
+            headers: HeaderMap::new(),
+            body: Body::empty(),
+            remote_addr: SocketAddr::from(([127, 0, 0, 1], 0)),
+        }
+    }
+
+    /// 创建 GET 请求
+    pub fn get(&self, path: &str) -> TestRequest {
+        self.request(Method::GET, path)
+    }
+
+    /// 创建 POST 请求
+    pub fn post(&self, path: &str) -> TestRequest {
+        self.request(Method::POST, path)
+    }
+
+    /// 创建 PUT 请求
+    pub fn put(&self, path: &str) -> TestRequest {
+        self.request(Method::PUT, path)
+    }
+
+    /// 创建 PATCH 请求
+    pub fn patch(&self, path: &str) -> TestRequest {
+        self.request(Method::PATCH, path)
+    }
+
+    /// 创建 DELETE 请求
+    pub fn delete(&self, path: &str) -> TestRequest {
+        self.request(Method::DELETE, path)
+    }
+}
+
+/// 待发送的测试请求，`.await` 或调用 [`TestRequest::send`] 发送
+#[derive(Debug)]
+pub struct TestRequest {
+    app: Router,
+    method: Method,
+    path: String,
+    headers: HeaderMap,
+    body: Body,
+    remote_addr: SocketAddr,
+}
+
+impl TestRequest {
+    /// 添加请求头
+    ///
+    /// # Panics
+    ///
+    /// 头名称或值无效时 panic
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        let name = HeaderName::try_from(name).unwrap_or_else(|e| panic!("无效的请求头名称 {}: {}", name, e));
+        let value = HeaderValue::try_from(value).unwrap_or_else(|e| panic!("无效的请求头值 {}: {}", value, e));
+        self.headers.append(name, value);
+        self
+    }
+
+    /// 以 JSON 作为请求体，并设置 `Content-Type`
+    ///
+    /// # Panics
+    ///
+    /// 序列化失败时 panic
+    pub fn json<T: Serialize + ?Sized>(mut self, value: &T) -> Self {
+        let body = serde_json::to_vec(value).unwrap_or_else(|e| panic!("无法序列化请求体: {}", e));
+        self.headers
+            .insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        self.body = Body::from(body);
+        self
+    }
+
+    /// 设置请求体
+    pub fn body(mut self, body: impl Into<Body>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// 设置连接的对端地址
+    pub fn remote_addr(mut self, addr: SocketAddr) -> Self {
+        self.remote_addr = addr;
+        self
+    }
+
+    /// 发送请求并读取完整的响应
+    ///
+    /// # Panics
+    ///
+    /// 请求路径无效或读取响应体失败时 panic
+    pub async fn send(self) -> TestResponse {
+        let mut request = Request::builder()
+            .method(self.method)
+            .uri(&self.path)
+            .body(self.body)
+            .unwrap_or_else(|e| panic!("无效的请求 {}: {}", self.path, e));
+        *request.headers_mut() = self.headers;
+        request.extensions_mut().insert(ConnectInfo(self.remote_addr));
+
+        let response = match self.app.oneshot(request).await {
+            Ok(response) => response,
+            Err(infallible) => match infallible {},
+        };
+        let (parts, body) = response.into_parts();
+        let body = axum::body::to_bytes(body, usize::MAX)
+            .await
+            .unwrap_or_else(|e| panic!("无法读取响应体: {}", e));
+
+        TestResponse {
+            status: parts.status,
+            headers: parts.headers,
+            body,
+        }
+    }
+}
+
+impl IntoFuture for TestRequest {
+    type Output = TestResponse;
+    type IntoFuture = Pin<Box<dyn Future<Output = TestResponse> + Send>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(self.send())
+    }
+}
+
+/// 测试响应，响应体已完整读取
+#[derive(Debug, Clone)]
+pub struct TestResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl TestResponse {
+    /// 响应状态码
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// 响应头
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// 读取响应头，不存在或不是有效字符串时返回 `None`
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|value| value.to_str().ok())
+    }
+
+    /// 响应体字节
+    pub fn bytes(&self) -> &Bytes {
+        &self.body
+    }
+
+    /// 以 UTF-8 文本读取响应体，无效字节会被替换
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    /// 将响应体反序列化为 JSON
+    ///
+    /// # Panics
+    ///
+    /// 响应体不是有效的 `T` 时 panic，消息中包含响应体内容
+    pub fn json<T: DeserializeOwned>(&self) -> T {
+        serde_json::from_slice(&self.body)
+            .unwrap_or_else(|e| panic!("无法解析响应 JSON ({}): {}", e, self.text()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::client_ip::ClientIp;
+    use axum::{routing::get, Json};
+
+    fn server() -> TestServer {
+        TestServer::new(
+            Router::new()
+                .route("/echo", axum::routing::post(|Json(value): Json<serde_json::Value>| async { Json(value) }))
+                .route(
+                    "/header",
+                    get(|headers: HeaderMap| async move { headers["x-name"].to_str().unwrap().to_string() }),
+                )
+                .route("/ip", get(|ClientIp(ip): ClientIp| async move { ip.to_string() })),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_json_round_trip() {
+        let response = server().post("/echo").json(&serde_json::json!({ "id": 7 })).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.header("content-type"), Some("application/json"));
+        assert_eq!(response.json::<serde_json::Value>()["id"], 7);
+    }
+
+    #[tokio::test]
+    async fn test_headers_and_remote_addr() {
+        let server = server();
+        assert_eq!(server.get("/header").header("x-name", "alice").await.text(), "alice");
+        assert_eq!(server.get("/ip").await.text(), "127.0.0.1");
+        let addr = SocketAddr::from(([203, 0, 113, 9], 4000));
+        assert_eq!(server.get("/ip").remote_addr(addr).await.text(), "203.0.113.9");
+        assert_eq!(server.get("/missing").await.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,269 @@
+//! 文件上传模块（需要 `uploads` feature）
+//!
+//! [`MultipartUpload`] 解析 `multipart/form-data` 请求，文件字段直接写入临时目录而不是缓存在内存中，
+//! 文本字段按名称保存。单个字段和整个请求体的大小受 `[middleware.uploads]` 限制，超出时返回 413。
+//!
+//! 临时文件在 [`UploadedFile`] 被丢弃时删除，需要保留时调用 [`UploadedFile::persist`]。
+//!
+//! ```toml
+//! [middleware.uploads]
+//! max_field_size = 10485760   # 10 MiB
+//! max_total_size = 52428800   # 50 MiB
+//! temp_dir = "/var/tmp/uploads"
+//! ```
+//!
+//! ```rust
+//! use hwhkit::uploads::MultipartUpload;
+//!
+//! async fn upload(upload: MultipartUpload) -> hwhkit::Result<String> {
+//!     let title = upload.field("title").unwrap_or("untitled").to_string();
+//!     for file in upload.files {
+//!         let name = file.filename.clone().unwrap_or_default();
+//!         file.persist(format!("/srv/files/{}", name)).await?;
+//!     }
+//!     Ok(title)
+//! }
+//! ```
+
+use crate::{
+    config::UploadConfig,
+    error::{Error, Result},
+};
+use axum::{
+    async_trait,
+    extract::{FromRequest, Request},
+    http::header,
+};
+use multer::{Constraints, Multipart, SizeLimit};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tempfile::NamedTempFile;
+use tokio::io::AsyncWriteExt;
+
+/// 上传到临时目录的文件
+#[derive(Debug)]
+pub struct UploadedFile {
+    /// 表单字段名
+    pub field_name: String,
+    /// 客户端提供的文件名
+    pub filename: Option<String>,
+    /// 客户端声明的 MIME 类型，未声明时为 `application/octet-stream`
+    pub content_type: String,
+    /// 文件大小（字节）
+    pub size: u64,
+    file: NamedTempFile,
+}
+
+impl UploadedFile {
+    /// 临时文件路径
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+
+    /// 将临时文件移动到 `path`，跨文件系统时复制后删除临时文件
+    pub async fn persist(self, path: impl AsRef<Path>) -> Result<PathBuf> {
+        let path = path.as_ref().to_path_buf();
+        if let Err(e) = self.file.persist(&path) {
+            tokio::fs::copy(e.file.path(), &path).await?;
+        }
+        Ok(path)
+    }
+}
+
+/// `multipart/form-data` 上传提取器
+#[derive(Debug, Default)]
+pub struct MultipartUpload {
+    /// 文本字段，同名字段保留最后一个值
+    pub fields: HashMap<String, String>,
+    /// 文件字段，按上传顺序排列
+    pub files: Vec<UploadedFile>,
+}
+
+impl MultipartUpload {
+    /// 按名称获取文本字段
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields.get(name).map(String::as_str)
+    }
+
+    /// 按字段名获取第一个文件
+    pub fn file(&self, field_name: &str) -> Option<&UploadedFile> {
+        self.files.iter().find(|file| file.field_name == field_name)
+    }
+}
+
+fn multer_error(error: multer::Error) -> Error {
+    match error {
+        multer::Error::FieldSizeExceeded { field_name, .. } => Error::PayloadTooLarge(format!(
+            "字段 {} 超过大小限制",
+            field_name.unwrap_or_default()
+        )),
+        multer::Error::StreamSizeExceeded { .. } => {
+            Error::PayloadTooLarge("上传内容超过大小限制".to_string())
+        }
+        e => Error::BadRequest(format!("无效的 multipart 请求: {}", e)),
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequest<S> for MultipartUpload {
+    type Rejection = Error;
+
+    async fn from_request(request: Request, _state: &S) -> Result<Self> {
+        let config = request
+            .extensions()
+            .get::<Arc<UploadConfig>>()
+            .cloned()
+            .unwrap_or_default();
+        let boundary = request
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| multer::parse_boundary(value).ok())
+            .ok_or_else(|| Error::BadRequest("请求体需要 multipart/form-data 类型".to_string()))?;
+
+        let constraints = Constraints::new().size_limit(
+            SizeLimit::new()
+                .per_field(config.max_field_size)
+                .whole_stream(config.max_total_size),
+        );
+        let stream = request.into_body().into_data_stream();
+        let mut multipart = Multipart::with_constraints(stream, boundary, constraints);
+
+        let mut upload = MultipartUpload::default();
+        while let Some(mut field) = multipart.next_field().await.map_err(multer_error)? {
+            let field_name = field.name().unwrap_or_default().to_string();
+            let Some(filename) = field.file_name().map(str::to_string) else {
+                let text = field.text().await.map_err(multer_error)?;
+                upload.fields.insert(field_name, text);
+                continue;
+            };
+
+            let file = match &config.temp_dir {
+                Some(dir) => NamedTempFile::new_in(dir)?,
+                None => NamedTempFile::new()?,
+            };
+            let mut writer = tokio::fs::File::from_std(file.reopen()?);
+            let mut size = 0;
+            while let Some(chunk) = field.chunk().await.map_err(multer_error)? {
+                size += chunk.len() as u64;
+                writer.write_all(&chunk).await?;
+            }
+            writer.flush().await?;
+
+            upload.files.push(UploadedFile {
+                field_name,
+                filename: Some(filename).filter(|name| !name.is_empty()),
+                content_type: field
+                    .content_type()
+                    .map(|mime| mime.to_string())
+                    .unwrap_or_else(|| "application/octet-stream".to_string()),
+                size,
+                file,
+            });
+        }
+        Ok(upload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::StatusCode, routing::post, Extension, Router};
+    use tower::util::ServiceExt;
+
+    const BOUNDARY: &str = "hwhkit-boundary";
+
+    fn multipart_body(file_content: &str) -> String {
+        format!(
+            "--{b}\r\nContent-Disposition: form-data; name=\"title\"\r\n\r\n季度报告\r\n\
+             --{b}\r\nContent-Disposition: form-data; name=\"report\"; filename=\"report.csv\"\r\n\
+             Content-Type: text/csv\r\n\r\n{content}\r\n--{b}--\r\n",
+            b = BOUNDARY,
+            content = file_content
+        )
+    }
+
+    async fn upload(config: UploadConfig, body: String) -> (StatusCode, String) {
+        let app = Router::new()
+            .route(
+                "/upload",
+                post(|upload: MultipartUpload| async move {
+                    let file = upload.file("report").unwrap();
+                    let content = std::fs::read_to_string(file.path()).unwrap();
+                    format!(
+                        "{}|{}|{}|{}|{}",
+                        upload.field("title").unwrap(),
+                        file.filename.as_deref().unwrap(),
+                        file.content_type,
+                        file.size,
+                        content
+                    )
+                }),
+            )
+            .layer(Extension(Arc::new(config)));
+        let request = Request::post("/upload")
+            .header(header::CONTENT_TYPE, format!("multipart/form-data; boundary={}", BOUNDARY))
+            .body(Body::from(body))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_upload_to_temp_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = UploadConfig {
+            temp_dir: Some(dir.path().to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        let (status, body) = upload(config, multipart_body("a,b\n1,2")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "季度报告|report.csv|text/csv|7|a,b\n1,2");
+        // 处理器返回后临时文件已删除
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_size_limits() {
+        let config = UploadConfig {
+            max_field_size: 16,
+            max_total_size: 1024,
+            temp_dir: None,
+        };
+        let (status, body) = upload(config, multipart_body(&"x".repeat(64))).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert!(body.contains("report"));
+
+        let config = UploadConfig {
+            max_field_size: 64,
+            max_total_size: 128,
+            temp_dir: None,
+        };
+        let (status, _) = upload(config, multipart_body(&"x".repeat(64))).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn test_persist() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("kept.csv");
+        let file = NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), "kept").unwrap();
+        let uploaded = UploadedFile {
+            field_name: "report".to_string(),
+            filename: None,
+            content_type: "text/csv".to_string(),
+            size: 4,
+            file,
+        };
+        let temp_path = uploaded.path().to_path_buf();
+        uploaded.persist(&target).await.unwrap();
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "kept");
+        assert!(!temp_path.exists());
+    }
+}
@@ -0,0 +1,167 @@
+//! 请求参数校验模块（需要 `validation` feature）
+//!
+//! [`ValidatedJson`] 和 [`ValidatedForm`] 解析请求体后调用 `T::validate()`，校验失败时返回 422，
+//! 响应体的 `fields` 按字段列出错误：
+//!
+//! ```json
+//! {
+//!   "error": "参数校验失败",
+//!   "status": 422,
+//!   "fields": {"email": [{"code": "email", "message": null, "params": {"value": "not-an-email"}}]}
+//! }
+//! ```
+//!
+//! ```rust
+//! use hwhkit::validation::ValidatedJson;
+//! use serde::Deserialize;
+//! use validator::Validate;
+//!
+//! #[derive(Deserialize, Validate)]
+//! struct SignUp {
+//!     #[validate(email)]
+//!     email: String,
+//!     #[validate(length(min = 8, message = "密码至少 8 位"))]
+//!     password: String,
+//! }
+//!
+//! async fn sign_up(ValidatedJson(form): ValidatedJson<SignUp>) -> String {
+//!     form.email
+//! }
+//! ```
+
+use crate::error::{Error, Result};
+use axum::{
+    async_trait,
+    extract::{FromRequest, Request},
+    Form, Json,
+};
+use serde::de::DeserializeOwned;
+use std::ops::Deref;
+
+pub use validator::Validate;
+
+/// 解析并校验 JSON 请求体
+///
+/// 请求体无法解析时返回 400，校验失败时返回 422
+#[derive(Debug, Clone)]
+pub struct ValidatedJson<T>(pub T);
+
+/// 解析并校验表单请求体（`GET` 请求读取查询参数）
+///
+/// 请求体无法解析时返回 400，校验失败时返回 422
+#[derive(Debug, Clone)]
+pub struct ValidatedForm<T>(pub T);
+
+impl<T> Deref for ValidatedJson<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> Deref for ValidatedForm<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[async_trait]
+impl<S, T> FromRequest<S> for ValidatedJson<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned + Validate,
+{
+    type Rejection = Error;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self> {
+        let Json(value) = Json::<T>::from_request(request, state)
+            .await
+            .map_err(|e| Error::BadRequest(e.body_text()))?;
+        value.validate()?;
+        Ok(Self(value))
+    }
+}
+
+#[async_trait]
+impl<S, T> FromRequest<S> for ValidatedForm<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned + Validate,
+{
+    type Rejection = Error;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self> {
+        let Form(value) = Form::<T>::from_request(request, state)
+            .await
+            .map_err(|e| Error::BadRequest(e.body_text()))?;
+        value.validate()?;
+        Ok(Self(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::StatusCode, routing::post, Router};
+    use serde::Deserialize;
+    use serde_json::{json, Value};
+    use tower::util::ServiceExt;
+
+    #[derive(Deserialize, Validate)]
+    struct SignUp {
+        #[validate(email)]
+        email: String,
+        #[validate(length(min = 8, message = "密码至少 8 位"))]
+        password: String,
+    }
+
+    fn app() -> Router {
+        Router::new()
+            .route("/json", post(|ValidatedJson(form): ValidatedJson<SignUp>| async move { form.email }))
+            .route("/form", post(|ValidatedForm(form): ValidatedForm<SignUp>| async move { form.email }))
+    }
+
+    async fn call(content_type: &str, uri: &str, body: String) -> (StatusCode, String) {
+        let request = Request::post(uri)
+            .header("content-type", content_type)
+            .body(Body::from(body))
+            .unwrap();
+        let response = app().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_validated_json() {
+        let valid = json!({ "email": "a@example.com", "password": "12345678" }).to_string();
+        assert_eq!(call("application/json", "/json", valid).await, (StatusCode::OK, "a@example.com".to_string()));
+
+        let invalid = json!({ "email": "not-an-email", "password": "short" }).to_string();
+        let (status, body) = call("application/json", "/json", invalid).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let body: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["status"], 422);
+        assert_eq!(body["fields"]["email"][0]["code"], "email");
+        assert_eq!(body["fields"]["password"][0]["message"], "密码至少 8 位");
+
+        let (status, _) = call("application/json", "/json", "{".to_string()).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_validated_form() {
+        let form = "application/x-www-form-urlencoded";
+        let (status, body) = call(form, "/form", "email=a%40example.com&password=12345678".to_string()).await;
+        assert_eq!((status, body.as_str()), (StatusCode::OK, "a@example.com"));
+
+        let (status, body) = call(form, "/form", "email=a%40example.com&password=short".to_string()).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let body: Value = serde_json::from_str(&body).unwrap();
+        assert!(body["fields"].get("email").is_none());
+        assert_eq!(body["fields"]["password"][0]["code"], "length");
+    }
+}
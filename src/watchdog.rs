@@ -0,0 +1,178 @@
+//! 进程资源监控模块
+//!
+//! 定期采样进程的常驻内存（RSS）和打开的文件描述符数量，超过阈值时输出警告，
+//! 并可选择触发优雅重启（停止接收新连接并退出，由 systemd、Kubernetes 等负责拉起）。
+//!
+//! 目前仅支持 Linux（读取 `/proc/self`），其他平台上采样结果为空。
+
+use crate::config::WatchdogConfig;
+use std::{sync::Arc, time::Duration};
+use tokio::{sync::Notify, task::JoinHandle};
+
+/// 进程资源使用情况
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProcessStats {
+    /// 常驻内存（字节）
+    pub rss_bytes: Option<u64>,
+    /// 打开的文件描述符数量
+    pub open_fds: Option<u64>,
+}
+
+impl ProcessStats {
+    /// 采样当前进程的资源使用情况
+    pub fn sample() -> Self {
+        Self {
+            rss_bytes: read_rss_bytes(),
+            open_fds: count_open_fds(),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn read_rss_bytes() -> Option<u64> {
+    // VmRSS 以 kB 为单位，如 "VmRSS:	   12345 kB"
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(target_os = "linux")]
+fn count_open_fds() -> Option<u64> {
+    let entries = std::fs::read_dir("/proc/self/fd").ok()?;
+    // read_dir 自身会占用一个描述符
+    Some((entries.count() as u64).saturating_sub(1))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn read_rss_bytes() -> Option<u64> {
+    None
+}
+
+#[cfg(not(target_os = "linux"))]
+fn count_open_fds() -> Option<u64> {
+    None
+}
+
+/// 进程资源监控
+#[derive(Debug, Clone)]
+pub struct Watchdog {
+    config: WatchdogConfig,
+}
+
+impl Watchdog {
+    /// 根据配置创建监控
+    pub fn new(config: &WatchdogConfig) -> Self {
+        Self {
+            config: config.clone(),
+        }
+    }
+
+    /// 检查采样结果，返回超出阈值的描述
+    pub fn check(&self, stats: &ProcessStats) -> Vec<String> {
+        let mut exceeded = Vec::new();
+
+        if let (Some(rss), Some(limit)) = (stats.rss_bytes, self.config.max_rss_mb) {
+            if rss > limit * 1024 * 1024 {
+                exceeded.push(format!("常驻内存 {} MiB 超过阈值 {} MiB", rss / 1024 / 1024, limit));
+            }
+        }
+        if let (Some(fds), Some(limit)) = (stats.open_fds, self.config.max_open_fds) {
+            if fds > limit {
+                exceeded.push(format!("文件描述符 {} 超过阈值 {}", fds, limit));
+            }
+        }
+
+        exceeded
+    }
+
+    /// 在后台定期采样
+    ///
+    /// 启用 `restart_on_exceed` 时，超过阈值会通知 `shutdown` 触发优雅重启
+    pub fn spawn(self, shutdown: Arc<Notify>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(self.config.interval_secs.max(1)));
+
+            loop {
+                interval.tick().await;
+
+                let stats = ProcessStats::sample();
+                tracing::debug!(rss_bytes = ?stats.rss_bytes, open_fds = ?stats.open_fds, "进程资源采样");
+
+                let exceeded = self.check(&stats);
+                if exceeded.is_empty() {
+                    continue;
+                }
+                for message in &exceeded {
+                    tracing::warn!("⚠️  {}", message);
+                }
+
+                if self.config.restart_on_exceed {
+                    tracing::warn!("资源使用超过阈值，开始优雅重启");
+                    shutdown.notify_one();
+                    return;
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_sample_process_stats() {
+        let stats = ProcessStats::sample();
+        assert!(stats.rss_bytes.unwrap() > 0);
+        assert!(stats.open_fds.unwrap() >= 3);
+    }
+
+    #[test]
+    fn test_threshold_check() {
+        let watchdog = Watchdog::new(&WatchdogConfig {
+            enabled: true,
+            max_rss_mb: Some(100),
+            max_open_fds: Some(1000),
+            ..Default::default()
+        });
+
+        let ok = ProcessStats {
+            rss_bytes: Some(50 * 1024 * 1024),
+            open_fds: Some(10),
+        };
+        assert!(watchdog.check(&ok).is_empty());
+
+        let exceeded = ProcessStats {
+            rss_bytes: Some(200 * 1024 * 1024),
+            open_fds: Some(2000),
+        };
+        assert_eq!(watchdog.check(&exceeded).len(), 2);
+
+        // 无法采样时不触发
+        assert!(watchdog.check(&ProcessStats::default()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_restart_on_exceed() {
+        let watchdog = Watchdog::new(&WatchdogConfig {
+            enabled: true,
+            interval_secs: 1,
+            max_open_fds: Some(0),
+            restart_on_exceed: true,
+            ..Default::default()
+        });
+
+        let shutdown = Arc::new(Notify::new());
+        let handle = watchdog.spawn(shutdown.clone());
+
+        if cfg!(target_os = "linux") {
+            tokio::time::timeout(Duration::from_secs(5), shutdown.notified())
+                .await
+                .expect("watchdog should request shutdown");
+        }
+        handle.abort();
+    }
+}
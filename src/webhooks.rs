@@ -0,0 +1,391 @@
+//! Webhook 签名校验模块（需要 `webhooks` feature）
+//!
+//! 在 `[webhooks.<provider>]` 中配置签名密钥，处理器使用 [`Webhook<P>`] 提取器接收请求：
+//! 提取器读取完整的原始请求体，按配置校验 HMAC-SHA256 签名，校验失败时返回 401，
+//! 处理器不会被调用。
+//!
+//! ```toml
+//! [webhooks.github]
+//! secret_file = "/run/secrets/github_webhook"
+//!
+//! [webhooks.stripe]
+//! format = "stripe"
+//! secret = "whsec_..."
+//! tolerance_secs = 300
+//! ```
+//!
+//! ```rust
+//! use hwhkit::{webhooks::{GitHub, Webhook}, Deserialize, Result};
+//!
+//! #[derive(Deserialize)]
+//! struct PushEvent {
+//!     r#ref: String,
+//! }
+//!
+//! async fn github(webhook: Webhook<GitHub>) -> Result<String> {
+//!     let event: PushEvent = webhook.json()?;
+//!     Ok(event.r#ref)
+//! }
+//! ```
+//!
+//! 其他服务商实现 [`WebhookProvider`] 指定配置节名称即可。签名是对原始字节计算的，
+//! 需要解析请求体时使用 [`Webhook::json`] 或 [`Webhook::body`]，不要再使用 `Json` 等提取器。
+
+use crate::{
+    config::{WebhookConfig, WebhookFormat},
+    error::{Error, Result},
+    inject::Services,
+};
+use axum::{
+    async_trait,
+    body::Bytes,
+    extract::{FromRequest, Request},
+    http::{HeaderMap, HeaderName},
+};
+use hmac::{Hmac, Mac};
+use serde::de::DeserializeOwned;
+use sha2::Sha256;
+use std::{
+    collections::HashMap,
+    fmt,
+    marker::PhantomData,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Webhook 发送方，`NAME` 是 `[webhooks.<name>]` 配置节的名称
+pub trait WebhookProvider: Send + Sync + 'static {
+    /// 配置节名称
+    const NAME: &'static str;
+}
+
+/// GitHub，读取 `[webhooks.github]`
+#[derive(Debug, Clone, Copy)]
+pub struct GitHub;
+
+impl WebhookProvider for GitHub {
+    const NAME: &'static str = "github";
+}
+
+/// Stripe，读取 `[webhooks.stripe]`
+#[derive(Debug, Clone, Copy)]
+pub struct Stripe;
+
+impl WebhookProvider for Stripe {
+    const NAME: &'static str = "stripe";
+}
+
+/// 单个发送方的签名校验器
+#[derive(Clone)]
+pub struct WebhookVerifier {
+    format: WebhookFormat,
+    header: HeaderName,
+    secret: Vec<u8>,
+    tolerance_secs: u64,
+}
+
+impl WebhookVerifier {
+    /// 根据配置创建
+    ///
+    /// # Errors
+    ///
+    /// 未配置密钥或请求头名称无效时返回配置错误
+    pub fn new(config: &WebhookConfig) -> Result<Self> {
+        let secret = config.effective_secret();
+        if secret.is_empty() {
+            return Err(Error::Config("Webhook 签名密钥不能为空".to_string()));
+        }
+        let header = HeaderName::from_bytes(config.effective_header().as_bytes()).map_err(|e| {
+            Error::Config(format!("无效的 Webhook 签名请求头 '{}': {}", config.effective_header(), e))
+        })?;
+        Ok(Self {
+            format: config.format,
+            header,
+            secret: secret.as_bytes().to_vec(),
+            tolerance_secs: config.tolerance_secs,
+        })
+    }
+
+    /// 校验请求头中的签名，返回签名中的时间戳（仅带时间戳的格式）
+    ///
+    /// # Errors
+    ///
+    /// 缺少签名、签名无效或时间戳超出允许偏差时返回 [`Error::Unauthorized`]
+    pub fn verify(&self, headers: &HeaderMap, body: &[u8]) -> Result<Option<i64>> {
+        self.verify_at(headers, body, unix_now())
+    }
+
+    /// 按配置的格式生成签名请求头的值，可用于测试或向其他服务发送 Webhook
+    ///
+    /// `timestamp` 只用于带时间戳的格式
+    pub fn sign(&self, body: &[u8], timestamp: i64) -> String {
+        match self.format {
+            WebhookFormat::Github => format!("sha256={}", to_hex(&self.mac(&[body]))),
+            WebhookFormat::Stripe => {
+                let prefix = format!("{}.", timestamp);
+                format!("t={},v1={}", timestamp, to_hex(&self.mac(&[prefix.as_bytes(), body])))
+            }
+            WebhookFormat::Hex => to_hex(&self.mac(&[body])),
+        }
+    }
+
+    fn verify_at(&self, headers: &HeaderMap, body: &[u8], now: i64) -> Result<Option<i64>> {
+        let value = headers
+            .get(&self.header)
+            .and_then(|value| value.to_str().ok())
+            .ok_or_else(|| Error::Unauthorized("缺少 Webhook 签名".to_string()))?;
+        let invalid = || Error::Unauthorized("Webhook 签名无效".to_string());
+
+        match self.format {
+            WebhookFormat::Github => {
+                let signature = value.trim().strip_prefix("sha256=").ok_or_else(invalid)?;
+                self.check(&[body], signature).then_some(None).ok_or_else(invalid)
+            }
+            WebhookFormat::Hex => self.check(&[body], value.trim()).then_some(None).ok_or_else(invalid),
+            WebhookFormat::Stripe => {
+                let mut timestamp = None;
+                let mut signatures = Vec::new();
+                for item in value.split(',') {
+                    match item.trim().split_once('=') {
+                        Some(("t", t)) => timestamp = t.parse::<i64>().ok(),
+                        Some(("v1", signature)) => signatures.push(signature),
+                        _ => {}
+                    }
+                }
+                let timestamp = timestamp.ok_or_else(invalid)?;
+                let prefix = format!("{}.", timestamp);
+                if !signatures
+                    .iter()
+                    .any(|signature| self.check(&[prefix.as_bytes(), body], signature))
+                {
+                    return Err(invalid());
+                }
+                // 先校验签名，避免伪造的时间戳影响判断
+                if self.tolerance_secs > 0 && now.abs_diff(timestamp) > self.tolerance_secs {
+                    return Err(Error::Unauthorized("Webhook 签名已过期".to_string()));
+                }
+                Ok(Some(timestamp))
+            }
+        }
+    }
+
+    fn mac(&self, parts: &[&[u8]]) -> Vec<u8> {
+        let mut mac = self.hmac();
+        for part in parts {
+            mac.update(part);
+        }
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    /// 以常量时间比较签名
+    fn check(&self, parts: &[&[u8]], signature: &str) -> bool {
+        let Some(signature) = from_hex(signature) else {
+            return false;
+        };
+        let mut mac = self.hmac();
+        for part in parts {
+            mac.update(part);
+        }
+        mac.verify_slice(&signature).is_ok()
+    }
+
+    fn hmac(&self) -> HmacSha256 {
+        // HMAC 接受任意长度的密钥
+        HmacSha256::new_from_slice(&self.secret).expect("HMAC 密钥长度无效")
+    }
+}
+
+impl fmt::Debug for WebhookVerifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebhookVerifier")
+            .field("format", &self.format)
+            .field("header", &self.header)
+            .field("tolerance_secs", &self.tolerance_secs)
+            .finish_non_exhaustive()
+    }
+}
+
+/// 所有配置了的发送方，构建服务器时登记为服务
+#[derive(Debug, Clone, Default)]
+pub(crate) struct WebhookVerifiers(Arc<HashMap<String, WebhookVerifier>>);
+
+impl WebhookVerifiers {
+    pub(crate) fn new(config: &HashMap<String, WebhookConfig>) -> Result<Self> {
+        let verifiers = config
+            .iter()
+            .map(|(name, config)| {
+                WebhookVerifier::new(config)
+                    .map(|verifier| (name.clone(), verifier))
+                    .map_err(|e| match e {
+                        Error::Config(message) => Error::Config(format!("[webhooks.{}] {}", name, message)),
+                        e => e,
+                    })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self(Arc::new(verifiers)))
+    }
+}
+
+/// 签名校验通过的 Webhook 请求，保留原始请求体
+pub struct Webhook<P> {
+    body: Bytes,
+    timestamp: Option<i64>,
+    provider: PhantomData<fn() -> P>,
+}
+
+impl<P> Webhook<P> {
+    /// 原始请求体
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    /// 取出原始请求体
+    pub fn into_body(self) -> Bytes {
+        self.body
+    }
+
+    /// 签名中的时间戳（Unix 秒），仅带时间戳的格式有值
+    pub fn timestamp(&self) -> Option<i64> {
+        self.timestamp
+    }
+
+    /// 将请求体解析为 JSON
+    ///
+    /// # Errors
+    ///
+    /// 请求体不是有效的 `T` 时返回 [`Error::BadRequest`]
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_slice(&self.body)
+            .map_err(|e| Error::BadRequest(format!("Webhook 请求体无效: {}", e)))
+    }
+}
+
+impl<P> fmt::Debug for Webhook<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Webhook")
+            .field("body", &self.body)
+            .field("timestamp", &self.timestamp)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<S, P> FromRequest<S> for Webhook<P>
+where
+    S: Send + Sync,
+    P: WebhookProvider,
+{
+    type Rejection = Error;
+
+    async fn from_request(request: Request, _state: &S) -> Result<Self> {
+        let (parts, body) = request.into_parts();
+        let verifier = parts
+            .extensions
+            .get::<Services>()
+            .and_then(Services::get::<WebhookVerifiers>)
+            .and_then(|verifiers| verifiers.0.get(P::NAME))
+            .ok_or_else(|| Error::Internal(format!("未配置 [webhooks.{}]", P::NAME)))?;
+        let body = axum::body::to_bytes(body, usize::MAX)
+            .await
+            .map_err(|e| Error::BadRequest(format!("无法读取请求体: {}", e)))?;
+
+        let timestamp = verifier.verify(&parts.headers, &body).inspect_err(|e| {
+            tracing::warn!("Webhook {} 签名校验失败: {}", P::NAME, e);
+        })?;
+        Ok(Self {
+            body,
+            timestamp,
+            provider: PhantomData,
+        })
+    }
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn from_hex(value: &str) -> Option<Vec<u8>> {
+    if !value.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(value.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn verifier(format: WebhookFormat) -> WebhookVerifier {
+        WebhookVerifier::new(&WebhookConfig {
+            format,
+            secret: "It's a Secret to Everybody".to_string(),
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    fn headers(name: &str, value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(HeaderName::from_bytes(name.as_bytes()).unwrap(), HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_github_signature() {
+        // GitHub 文档中的示例
+        let verifier = verifier(WebhookFormat::Github);
+        let signature = "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17";
+        assert_eq!(verifier.sign(b"Hello, World!", 0), signature);
+
+        let valid = headers("x-hub-signature-256", signature);
+        assert_eq!(verifier.verify(&valid, b"Hello, World!").unwrap(), None);
+        assert!(matches!(verifier.verify(&valid, b"Hello, World?"), Err(Error::Unauthorized(_))));
+        assert!(verifier.verify(&HeaderMap::new(), b"Hello, World!").is_err());
+        assert!(verifier.verify(&headers("x-hub-signature-256", "sha256=zz"), b"").is_err());
+    }
+
+    #[test]
+    fn test_stripe_signature() {
+        let verifier = verifier(WebhookFormat::Stripe);
+        let body = br#"{"type":"charge.succeeded"}"#;
+        let signature = verifier.sign(body, 1_700_000_000);
+        // 轮换密钥期间可能带有多个签名
+        let header = headers("stripe-signature", &format!("{},v1=deadbeef,v0=00", signature));
+
+        assert_eq!(verifier.verify_at(&header, body, 1_700_000_100).unwrap(), Some(1_700_000_000));
+        assert!(verifier.verify_at(&header, body, 1_700_000_301).is_err());
+        assert!(verifier.verify_at(&header, b"{}", 1_700_000_000).is_err());
+
+        // 篡改时间戳后签名不再有效
+        let tampered = signature.replace("t=1700000000", "t=1700000200");
+        assert!(verifier.verify_at(&headers("stripe-signature", &tampered), body, 1_700_000_200).is_err());
+    }
+
+    #[test]
+    fn test_custom_header() {
+        let verifier = WebhookVerifier::new(&WebhookConfig {
+            format: WebhookFormat::Hex,
+            header: "x-webhook-signature".to_string(),
+            secret: "secret".to_string(),
+            ..Default::default()
+        })
+        .unwrap();
+        let signature = verifier.sign(b"payload", 0);
+        assert!(verifier.verify(&headers("x-webhook-signature", &signature), b"payload").is_ok());
+        assert!(verifier.verify(&headers("x-signature", &signature), b"payload").is_err());
+        assert!(WebhookVerifier::new(&WebhookConfig::default()).is_err());
+    }
+}
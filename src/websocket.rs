@@ -0,0 +1,252 @@
+//! WebSocket 模块
+//!
+//! 通过 `WebServerBuilder::websocket` 注册 WebSocket 路由，不需要直接处理升级请求。
+//! 连接按 `[middleware.websocket]` 限制帧和消息大小，并定期发送 Ping 保持连接；
+//! 服务器开始优雅关闭时发送 1001 关闭帧，[`Socket::recv`] 返回 `None`。
+//!
+//! ```toml
+//! [middleware.websocket]
+//! max_frame_size = 16777216
+//! max_message_size = 67108864
+//! ping_interval_secs = 30
+//! ```
+//!
+//! ```rust
+//! use hwhkit::{websocket::Socket, WebServerBuilder};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Deserialize, Serialize)]
+//! struct Chat {
+//!     text: String,
+//! }
+//!
+//! async fn echo(mut socket: Socket) {
+//!     while let Some(Ok(message)) = socket.recv_json::<Chat>().await {
+//!         if socket.send_json(&message).await.is_err() {
+//!             break;
+//!         }
+//!     }
+//! }
+//!
+//! let builder = WebServerBuilder::new().websocket("/ws", echo);
+//! ```
+
+use crate::{
+    config::WebSocketConfig,
+    error::{Error, Result},
+    shutdown::ShutdownSignal,
+};
+use axum::{
+    extract::ws::{Message, WebSocket, WebSocketUpgrade},
+    routing::{get, MethodRouter},
+};
+use futures_util::future;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{future::Future, time::Duration};
+use tokio::time::{interval_at, Instant, Interval};
+
+/// WebSocket 连接
+///
+/// 在 [`recv`](Self::recv) 等待消息期间定期发送 Ping，并在服务器开始关闭时发送关闭帧
+#[derive(Debug)]
+pub struct Socket {
+    inner: WebSocket,
+    ping: Option<Interval>,
+    shutdown: Option<ShutdownSignal>,
+}
+
+/// `recv` 等待期间发生的事件
+enum Event {
+    Message(Option<std::result::Result<Message, axum::Error>>),
+    Ping,
+    Shutdown,
+}
+
+impl Socket {
+    /// 包装已升级的连接
+    pub fn new(inner: WebSocket, config: &WebSocketConfig, shutdown: Option<ShutdownSignal>) -> Self {
+        let ping = (config.ping_interval_secs > 0).then(|| {
+            let period = Duration::from_secs(config.ping_interval_secs);
+            interval_at(Instant::now() + period, period)
+        });
+        Self { inner, ping, shutdown }
+    }
+
+    /// 接收下一条消息
+    ///
+    /// 连接关闭或服务器开始关闭时返回 `None`
+    pub async fn recv(&mut self) -> Option<Result<Message>> {
+        loop {
+            let Self { inner, ping, shutdown } = self;
+            let tick = async {
+                match ping {
+                    Some(ping) => ping.tick().await,
+                    None => future::pending().await,
+                }
+            };
+            let closing = async {
+                match shutdown {
+                    Some(shutdown) => shutdown.wait().await,
+                    None => future::pending().await,
+                }
+            };
+            let event = tokio::select! {
+                message = inner.recv() => Event::Message(message),
+                _ = tick => Event::Ping,
+                _ = closing => Event::Shutdown,
+            };
+
+            match event {
+                Event::Message(message) => return message.map(|message| message.map_err(ws_error)),
+                Event::Ping => {
+                    if let Err(e) = self.send(Message::Ping(Vec::new())).await {
+                        return Some(Err(e));
+                    }
+                }
+                Event::Shutdown => {
+                    if let Some(shutdown) = &self.shutdown {
+                        let _ = self.inner.send(shutdown.close_message()).await;
+                    }
+                    return None;
+                }
+            }
+        }
+    }
+
+    /// 接收下一条 JSON 消息，跳过 Ping/Pong
+    ///
+    /// 文本和二进制消息都按 JSON 解析，解析失败时返回 400 错误（连接保持打开）
+    pub async fn recv_json<T: DeserializeOwned>(&mut self) -> Option<Result<T>> {
+        loop {
+            let parsed = match self.recv().await? {
+                Ok(Message::Text(text)) => serde_json::from_str(&text),
+                Ok(Message::Binary(bytes)) => serde_json::from_slice(&bytes),
+                Ok(Message::Close(_)) => return None,
+                Ok(Message::Ping(_) | Message::Pong(_)) => continue,
+                Err(e) => return Some(Err(e)),
+            };
+            return Some(parsed.map_err(|e| Error::BadRequest(format!("无效的 JSON 消息: {}", e))));
+        }
+    }
+
+    /// 发送消息
+    pub async fn send(&mut self, message: Message) -> Result<()> {
+        self.inner.send(message).await.map_err(ws_error)
+    }
+
+    /// 以文本消息发送 JSON
+    pub async fn send_json<T: Serialize>(&mut self, value: &T) -> Result<()> {
+        self.send(Message::Text(serde_json::to_string(value)?)).await
+    }
+
+    /// 取出底层连接
+    pub fn into_inner(self) -> WebSocket {
+        self.inner
+    }
+}
+
+fn ws_error(e: axum::Error) -> Error {
+    Error::Internal(format!("WebSocket 错误: {}", e))
+}
+
+/// 创建 WebSocket 路由
+///
+/// 升级请求按配置限制帧和消息大小，升级后以 [`Socket`] 调用 `handler`
+pub fn route<F, Fut>(config: &WebSocketConfig, handler: F) -> MethodRouter
+where
+    F: Fn(Socket) -> Fut + Clone + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let config = config.clone();
+    get(move |upgrade: WebSocketUpgrade, shutdown: Option<ShutdownSignal>| async move {
+        upgrade
+            .max_frame_size(config.max_frame_size)
+            .max_message_size(config.max_message_size)
+            .on_upgrade(move |socket| handler(Socket::new(socket, &config, shutdown)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ShutdownConfig;
+    use axum::{Extension, Router};
+    use futures_util::{SinkExt, StreamExt};
+    use serde_json::{json, Value};
+    use tokio_tungstenite::tungstenite::{protocol::frame::coding::CloseCode, Message as ClientMessage};
+
+    async fn echo(mut socket: Socket) {
+        while let Some(message) = socket.recv_json::<Value>().await {
+            let reply = match message {
+                Ok(value) => json!({ "echo": value }),
+                Err(e) => json!({ "error": e.to_string() }),
+            };
+            if socket.send_json(&reply).await.is_err() {
+                break;
+            }
+        }
+    }
+
+    async fn serve(config: WebSocketConfig, shutdown: ShutdownSignal) -> String {
+        let app = Router::new()
+            .route("/ws", route(&config, echo))
+            .layer(Extension(shutdown));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/ws", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_json_messages_and_shutdown() {
+        let shutdown = ShutdownSignal::new(&ShutdownConfig::default());
+        let url = serve(WebSocketConfig::default(), shutdown.clone()).await;
+        let (mut client, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+
+        client.send(ClientMessage::text(r#"{"text":"hi"}"#)).await.unwrap();
+        let reply = client.next().await.unwrap().unwrap();
+        let reply: Value = serde_json::from_str(reply.to_text().unwrap()).unwrap();
+        assert_eq!(reply["echo"]["text"], "hi");
+
+        client.send(ClientMessage::text("not json")).await.unwrap();
+        let reply = client.next().await.unwrap().unwrap();
+        assert!(reply.to_text().unwrap().contains("error"));
+
+        shutdown.trigger();
+        match client.next().await.unwrap().unwrap() {
+            ClientMessage::Close(Some(frame)) => assert_eq!(frame.code, CloseCode::Away),
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ping_and_frame_limit() {
+        let config = WebSocketConfig {
+            max_frame_size: 64,
+            max_message_size: 64,
+            ping_interval_secs: 1,
+        };
+        let url = serve(config, ShutdownSignal::new(&ShutdownConfig::default())).await;
+        let (mut client, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+
+        let message = tokio::time::timeout(Duration::from_secs(3), client.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert!(message.is_ping());
+
+        // 超过大小限制的消息导致连接关闭
+        client.send(ClientMessage::text("x".repeat(128))).await.unwrap();
+        let closed = tokio::time::timeout(Duration::from_secs(3), async {
+            loop {
+                match client.next().await {
+                    Some(Ok(ClientMessage::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => continue,
+                }
+            }
+        })
+        .await;
+        assert!(closed.is_ok());
+    }
+}
@@ -0,0 +1,185 @@
+//! XML 序列化模块（需要 `xml` feature）
+//!
+//! 基于 quick-xml 的 serde 支持。结构体以类型名作为根元素，序列等没有名称的值以 `<response>`
+//! 作为根元素。
+//!
+//! [`Xml`] 既是提取器也是响应，用于对接要求 `application/xml` 请求体的企业系统：
+//!
+//! ```rust
+//! use hwhkit::xml::Xml;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Deserialize)]
+//! struct OrderRequest {
+//!     id: u64,
+//! }
+//!
+//! #[derive(Serialize)]
+//! struct OrderResult {
+//!     id: u64,
+//!     accepted: bool,
+//! }
+//!
+//! async fn submit(Xml(order): Xml<OrderRequest>) -> Xml<OrderResult> {
+//!     Xml(OrderResult { id: order.id, accepted: true })
+//! }
+//! ```
+
+use crate::error::{Error, Result};
+use axum::{
+    async_trait,
+    body::Bytes,
+    extract::{FromRequest, Request},
+    http::{header, HeaderValue},
+    response::{IntoResponse, Response},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::ops::Deref;
+
+/// 没有类型名时使用的根元素
+const DEFAULT_ROOT: &str = "response";
+
+/// 序列化为 XML 字符串
+pub fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String> {
+    quick_xml::se::to_string(value)
+        .or_else(|_| quick_xml::se::to_string_with_root(DEFAULT_ROOT, value))
+        .map_err(|e| Error::Internal(format!("XML 序列化失败: {}", e)))
+}
+
+/// 从 XML 字符串反序列化
+pub fn from_str<T: DeserializeOwned>(xml: &str) -> Result<T> {
+    quick_xml::de::from_str(xml).map_err(|e| Error::BadRequest(format!("无效的 XML: {}", e)))
+}
+
+/// XML 请求体提取器和响应
+///
+/// 提取时要求 `Content-Type` 为 `application/xml`、`text/xml` 或 `+xml` 结尾的类型，
+/// 否则或无法解析时返回 400
+#[derive(Debug, Clone)]
+pub struct Xml<T>(pub T);
+
+impl<T> Deref for Xml<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+fn is_xml_content_type(request: &Request) -> bool {
+    request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .map(|media_type| media_type.trim().to_ascii_lowercase())
+        .is_some_and(|media_type| {
+            media_type == "application/xml" || media_type == "text/xml" || media_type.ends_with("+xml")
+        })
+}
+
+#[async_trait]
+impl<S, T> FromRequest<S> for Xml<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned,
+{
+    type Rejection = Error;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self> {
+        if !is_xml_content_type(&request) {
+            return Err(Error::BadRequest("请求体需要 application/xml 类型".to_string()));
+        }
+        let bytes = Bytes::from_request(request, state)
+            .await
+            .map_err(|e| Error::BadRequest(format!("无法读取请求体: {}", e)))?;
+        let xml = std::str::from_utf8(&bytes)
+            .map_err(|e| Error::BadRequest(format!("XML 请求体不是有效的 UTF-8: {}", e)))?;
+        from_str(xml).map(Xml)
+    }
+}
+
+impl<T: Serialize> IntoResponse for Xml<T> {
+    fn into_response(self) -> Response {
+        match to_string(&self.0) {
+            Ok(body) => (
+                [(header::CONTENT_TYPE, HeaderValue::from_static("application/xml"))],
+                body,
+            )
+                .into_response(),
+            Err(e) => e.into_response(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::StatusCode, routing::post, Router};
+    use serde::Deserialize;
+    use tower::util::ServiceExt;
+
+    #[derive(Serialize)]
+    struct Order {
+        id: u64,
+        items: Vec<String>,
+    }
+
+    #[test]
+    fn test_to_string() {
+        let order = Order { id: 7, items: vec!["a".to_string(), "b".to_string()] };
+        assert_eq!(
+            to_string(&order).unwrap(),
+            "<Order><id>7</id><items>a</items><items>b</items></Order>"
+        );
+        assert_eq!(to_string(&42).unwrap(), "<response>42</response>");
+    }
+
+    #[derive(Deserialize)]
+    struct OrderRequest {
+        id: u64,
+        items: Vec<String>,
+    }
+
+    async fn submit(content_type: &str, body: &str) -> (StatusCode, String) {
+        let app = Router::new().route(
+            "/orders",
+            post(|Xml(request): Xml<OrderRequest>| async move {
+                Xml(Order { id: request.id * 10, items: request.items })
+            }),
+        );
+        let request = Request::post("/orders")
+            .header(header::CONTENT_TYPE, content_type)
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let status = response.status();
+        if status == StatusCode::OK {
+            assert_eq!(response.headers()[header::CONTENT_TYPE], "application/xml");
+        }
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_xml_extractor_and_responder() {
+        let xml = "<OrderRequest><id>7</id><items>a</items><items>b</items></OrderRequest>";
+        let (status, body) = submit("text/xml; charset=utf-8", xml).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "<Order><id>70</id><items>a</items><items>b</items></Order>");
+
+        let (status, _) = submit("application/soap+xml", xml).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_xml_rejections() {
+        let (status, body) = submit("application/json", "{}").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body.contains("application/xml"));
+
+        let (status, body) = submit("application/xml", "<OrderRequest><id>x</id></OrderRequest>").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body.contains("无效的 XML"));
+    }
+}
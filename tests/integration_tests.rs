@@ -91,7 +91,6 @@ async fn test_builder_with_jwt() {
 #[tokio::test]
 async fn test_config_validation() {
     use hwhkit::Config;
-    use hwhkit::config::{ServerConfig, MiddlewareConfig, ArchitectureType};
 
     let mut config = Config::default();
     
@@ -107,6 +106,447 @@ async fn test_config_validation() {
     assert!(config.validate().is_ok(), "恢复后的配置应该是有效的");
 }
 
+#[test]
+fn test_syslog_app_name_validation() {
+    use hwhkit::Config;
+
+    let mut config = Config::default();
+    for invalid in ["", "my app", "a".repeat(49).as_str()] {
+        config.middleware.logging.syslog.app_name = invalid.to_string();
+        assert!(config.validate().is_err(), "app_name {:?} 应该是无效的", invalid);
+    }
+
+    config.middleware.logging.syslog.app_name = "my-service".to_string();
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn test_sampling_ratio_validation() {
+    use hwhkit::config::RouteSamplingConfig;
+    use hwhkit::Config;
+
+    let mut config = Config::default();
+    config.telemetry.sampling.routes.push(RouteSamplingConfig {
+        path: "/checkout/*".to_string(),
+        ratio: 1.5,
+    });
+    assert!(config.validate().is_err(), "采样率超过 1.0 应该是无效的");
+
+    config.telemetry.sampling.routes[0].ratio = 1.0;
+    config.telemetry.sampling.ratio = -0.1;
+    assert!(config.validate().is_err(), "采样率小于 0.0 应该是无效的");
+}
+
+#[test]
+fn test_session_secret_validation() {
+    use hwhkit::{config::SameSite, Config};
+
+    let mut config = Config::default();
+    config.middleware.session.enabled = true;
+    config.middleware.session.secret = "too-short".to_string();
+    assert!(config.validate().is_err(), "少于 32 字节的会话密钥应该是无效的");
+
+    config.middleware.session.secret = "0123456789abcdef0123456789abcdef".to_string();
+    assert!(config.validate().is_ok());
+
+    config.middleware.session.same_site = SameSite::None;
+    assert!(config.validate().is_err(), "SameSite=None 必须配合 secure");
+}
+
+#[test]
+fn test_redis_session_store_requires_url() {
+    use hwhkit::{config::SessionStoreKind, Config};
+
+    let mut config = Config::default();
+    config.middleware.session.enabled = true;
+    config.middleware.session.secret = "0123456789abcdef0123456789abcdef".to_string();
+    config.middleware.session.store = SessionStoreKind::Redis;
+    assert!(config.validate().is_err(), "Redis 会话存储缺少 url 应该是无效的");
+
+    config.middleware.session.url = Some("redis://127.0.0.1/".to_string());
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn test_websocket_size_validation() {
+    use hwhkit::Config;
+
+    let mut config = Config::default();
+    config.middleware.websocket.max_frame_size = 0;
+    assert!(config.validate().is_err(), "max_frame_size 为 0 应该是无效的");
+
+    config.middleware.websocket.max_frame_size = 1024;
+    config.middleware.websocket.max_message_size = 512;
+    assert!(config.validate().is_err(), "max_message_size 小于 max_frame_size 应该是无效的");
+
+    config.middleware.websocket.max_message_size = 1024;
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn test_upload_size_validation() {
+    use hwhkit::Config;
+
+    let mut config = Config::default();
+    config.middleware.uploads.max_field_size = 0;
+    assert!(config.validate().is_err(), "max_field_size 为 0 应该是无效的");
+
+    config.middleware.uploads.max_field_size = 1024;
+    config.middleware.uploads.max_total_size = 512;
+    assert!(config.validate().is_err(), "max_total_size 小于 max_field_size 应该是无效的");
+
+    config.middleware.uploads.max_total_size = 1024;
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn test_spa_fallback_requires_index() {
+    use hwhkit::Config;
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut config = Config::default();
+    config.middleware.static_files.enabled = true;
+    config.middleware.static_files.dir = dir.path().to_string_lossy().to_string();
+    config.middleware.static_files.spa_fallback = true;
+    assert!(config.validate().is_err(), "缺少 index.html 时启用 spa_fallback 应该是无效的");
+
+    std::fs::write(dir.path().join("index.html"), "<div id=app></div>").unwrap();
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn test_static_cache_max_age_validation() {
+    use hwhkit::Config;
+
+    let mut config = Config::default();
+    config.middleware.static_files.cache.default.max_age = Some("1 fortnight".to_string());
+    assert!(config.validate().is_err(), "无法解析的 max_age 应该是无效的");
+
+    config.middleware.static_files.cache.default.max_age = Some("1y".to_string());
+    assert!(config.validate().is_ok());
+    assert_eq!(
+        config.middleware.static_files.cache.default.header_value().unwrap().as_deref(),
+        Some("public, max-age=31536000")
+    );
+}
+
+#[test]
+fn test_asymmetric_jwt_requires_public_key() {
+    use hwhkit::{config::JwtAlgorithm, Config};
+
+    let mut config = Config::default();
+    config.middleware.jwt.enabled = true;
+    config.middleware.jwt.algorithm = JwtAlgorithm::Rs256;
+    assert!(config.validate().is_err(), "RS256 缺少公钥应该是无效的");
+
+    config.middleware.jwt.public_key_path = Some("tests/fixtures/jwt/rsa_public.pem".to_string());
+    assert!(config.validate().is_ok());
+    config.resolve_secret_files().unwrap();
+    assert!(config.middleware.jwt.resolved_public_key.unwrap().starts_with("-----BEGIN PUBLIC KEY-----"));
+}
+
+#[test]
+fn test_cdn_config_requires_provider_ids() {
+    use hwhkit::{config::CdnProvider, Config};
+
+    let mut config = Config::default();
+    config.cdn.provider = CdnProvider::Fastly;
+    assert!(config.validate().is_err(), "Fastly 缺少 service_id 应该是无效的");
+    config.cdn.service_id = "svc".to_string();
+    assert!(config.validate().is_ok());
+
+    config.cdn.provider = CdnProvider::Cloudflare;
+    assert!(config.validate().is_err(), "Cloudflare 缺少 zone_id 应该是无效的");
+    config.cdn.zone_id = "zone".to_string();
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn test_csrf_requires_full_architecture() {
+    use hwhkit::{config::ArchitectureType, Config};
+
+    let mut config = Config::default();
+    config.middleware.csrf.enabled = true;
+    assert!(config.validate().is_err(), "API 架构下启用 CSRF 应该是无效的");
+
+    config.server.architecture = ArchitectureType::Full;
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn test_metrics_bucket_validation() {
+    use hwhkit::Config;
+
+    let mut config = Config::default();
+    config.telemetry.metrics.enabled = true;
+    assert!(config.validate().is_ok(), "默认桶边界应该是有效的");
+
+    config.telemetry.metrics.buckets = vec![0.1, 0.05];
+    assert!(config.validate().is_err(), "非递增的桶边界应该是无效的");
+
+    config.telemetry.metrics.buckets = vec![];
+    assert!(config.validate().is_err(), "空的桶边界应该是无效的");
+
+    config.telemetry.metrics.buckets = vec![0.1];
+    config.telemetry.metrics.max_paths = 0;
+    assert!(config.validate().is_err(), "max_paths 为 0 应该是无效的");
+}
+
+#[tokio::test]
+async fn test_routes_with_state() {
+    use hwhkit::State;
+    use tower::util::ServiceExt;
+
+    #[derive(Clone)]
+    struct AppState {
+        greeting: String,
+    }
+
+    let server = WebServerBuilder::new()
+        .routes(Router::new().route("/health", get(|| async { "ok" })))
+        .state(AppState { greeting: "hello".to_string() })
+        .routes_with_state(|_state: &AppState| {
+            Router::new().route("/greet", get(|State(state): State<AppState>| async move { state.greeting }))
+        })
+        .build()
+        .await
+        .unwrap();
+
+    let request = axum::http::Request::builder().uri("/greet").body(axum::body::Body::empty()).unwrap();
+    let response = server.app().clone().oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(&body[..], b"hello");
+
+    // 未设置状态时构建失败
+    let result = WebServerBuilder::new()
+        .routes_with_state(|_state: &AppState| Router::new())
+        .build()
+        .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_provide_and_inject() {
+    use hwhkit::inject::Inject;
+    use tower::util::ServiceExt;
+
+    #[derive(Clone)]
+    struct Mailer {
+        from: String,
+    }
+
+    let server = WebServerBuilder::new()
+        .provide(Mailer { from: "noreply@example.com".to_string() })
+        .routes(Router::new().route("/from", get(|Inject(mailer): Inject<Mailer>| async move { mailer.from })))
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(server.service::<Mailer>().unwrap().from, "noreply@example.com");
+
+    let request = axum::http::Request::builder().uri("/from").body(axum::body::Body::empty()).unwrap();
+    let response = server.app().clone().oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(&body[..], b"noreply@example.com");
+}
+
+#[cfg(feature = "openapi")]
+#[tokio::test]
+async fn test_openapi_document() {
+    use hwhkit::routing::RouteDoc;
+    use tower::util::ServiceExt;
+    use utoipa::OpenApi;
+
+    #[utoipa::path(get, path = "/users/:id", responses((status = 200, description = "用户详情")))]
+    #[allow(dead_code)]
+    async fn get_user() {}
+
+    #[derive(OpenApi)]
+    #[openapi(paths(get_user))]
+    struct ApiDoc;
+
+    let server = WebServerBuilder::new()
+        .openapi(ApiDoc::openapi())
+        .route_doc(RouteDoc::new("POST", "/users", "创建用户"))
+        .build()
+        .await
+        .unwrap();
+
+    let request = axum::http::Request::builder()
+        .uri("/api-docs/openapi.json")
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let response = server.app().clone().oneshot(request).await.unwrap();
+    assert_eq!(response.headers()["content-type"], "application/json");
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let doc: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert!(doc["paths"]["/users/{id}"]["get"].is_object());
+    assert_eq!(doc["paths"]["/users"]["post"]["summary"], "创建用户");
+
+    // 配置文档界面和 Basic Auth 后，界面和文档 JSON 都需要认证
+    let mut config = hwhkit::Config::default();
+    config.docs.ui = hwhkit::config::DocsUi::Swagger;
+    config.docs.username = "docs".to_string();
+    config.docs.password = "secret".to_string();
+    let server = WebServerBuilder::new()
+        .config(config)
+        .openapi(ApiDoc::openapi())
+        .build()
+        .await
+        .unwrap();
+    for uri in ["/docs", "/api-docs/openapi.json"] {
+        let request = axum::http::Request::builder().uri(uri).body(axum::body::Body::empty()).unwrap();
+        let response = server.app().clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), 401, "{} 应该需要认证", uri);
+    }
+    let request = axum::http::Request::builder()
+        .uri("/docs")
+        .header("authorization", "Basic ZG9jczpzZWNyZXQ=")
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let response = server.app().clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), 200);
+}
+
+#[tokio::test]
+async fn test_docs_ui_requires_openapi_document() {
+    use hwhkit::{config::DocsUi, Config};
+
+    let mut config = Config::default();
+    config.docs.ui = DocsUi::Redoc;
+    assert!(config.validate().is_ok());
+    let result = WebServerBuilder::new().config(config.clone()).build().await;
+    assert!(result.is_err(), "未登记 OpenAPI 文档时不能挂载文档界面");
+
+    config.docs.username = "docs".to_string();
+    assert!(config.validate().is_err(), "只设置 username 应该是无效的");
+    config.docs.password = "secret".to_string();
+    assert!(config.validate().is_ok());
+
+    config.docs.path = config.openapi.path.clone();
+    assert!(config.validate().is_err(), "界面路径不能与文档 JSON 路径相同");
+}
+
+#[tokio::test]
+async fn test_response_envelope_toggle() {
+    use hwhkit::{response, Config};
+    use tower::util::ServiceExt;
+
+    for (envelope, expected) in [
+        (true, json!({ "success": true, "data": [1, 2], "message": "操作成功" })),
+        (false, json!([1, 2])),
+    ] {
+        let mut config = Config::default();
+        config.server.response.envelope = envelope;
+        let server = WebServerBuilder::new()
+            .config(config)
+            .routes(Router::new().route("/items", get(|| async { response::Ok(vec![1, 2]) })))
+            .build()
+            .await
+            .unwrap();
+
+        let request = axum::http::Request::builder().uri("/items").body(axum::body::Body::empty()).unwrap();
+        let response = server.app().clone().oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body, expected, "envelope = {}", envelope);
+    }
+}
+
+#[cfg(feature = "msgpack")]
+#[tokio::test]
+async fn test_negotiate_accept_header() {
+    use hwhkit::negotiate::Negotiate;
+    use tower::util::ServiceExt;
+
+    let server = WebServerBuilder::new()
+        .routes(Router::new().route("/items", get(|| async { Negotiate(vec![1, 2]) })))
+        .build()
+        .await
+        .unwrap();
+
+    for (accept, content_type) in [
+        ("application/msgpack", "application/msgpack"),
+        ("text/html", "application/json"),
+    ] {
+        let request = axum::http::Request::builder()
+            .uri("/items")
+            .header("accept", accept)
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = server.app().clone().oneshot(request).await.unwrap();
+        assert_eq!(response.headers()["content-type"], content_type);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let items: Vec<u8> = match content_type {
+            "application/msgpack" => rmp_serde::from_slice(&body).unwrap(),
+            _ => serde_json::from_slice(&body).unwrap(),
+        };
+        assert_eq!(items, vec![1, 2]);
+    }
+}
+
+#[tokio::test]
+async fn test_lifecycle_hooks() {
+    use std::sync::{Arc, Mutex};
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let (started, on_started) = tokio::sync::oneshot::channel();
+    let startup_events = events.clone();
+    let shutdown_events = events.clone();
+
+    let server = WebServerBuilder::new()
+        .on_startup(move || async move {
+            startup_events.lock().unwrap().push("startup");
+            let _ = started.send(());
+            Ok(())
+        })
+        .on_shutdown(move || async move {
+            shutdown_events.lock().unwrap().push("shutdown");
+            Ok(())
+        })
+        .build()
+        .await
+        .unwrap();
+    let signal = server.shutdown_signal();
+    let running = tokio::spawn(async move { server.run(Some("127.0.0.1:0")).await });
+
+    on_started.await.unwrap();
+    assert_eq!(*events.lock().unwrap(), ["startup"]);
+    signal.trigger();
+    tokio::time::timeout(Duration::from_secs(5), running)
+        .await
+        .expect("服务器应在触发关闭后停止")
+        .unwrap()
+        .unwrap();
+    assert_eq!(*events.lock().unwrap(), ["startup", "shutdown"]);
+
+    // 启动回调失败时服务器不会启动
+    let server = WebServerBuilder::new()
+        .on_startup(|| async { Err(hwhkit::Error::Internal("warmup failed".to_string())) })
+        .build()
+        .await
+        .unwrap();
+    assert!(server.run(Some("127.0.0.1:0")).await.is_err());
+}
+
+#[test]
+fn test_database_validation() {
+    use hwhkit::Config;
+
+    let mut config = Config::default();
+    config.database.enabled = true;
+    assert!(config.validate().is_err(), "启用数据库但没有 url 应该是无效的");
+
+    config.database.url = "postgres://localhost/app".to_string();
+    assert!(config.validate().is_ok());
+
+    config.database.min_connections = 20;
+    assert!(config.validate().is_err(), "min_connections 大于 max_connections 应该是无效的");
+
+    let mut config = Config::default();
+    config.mongodb.enabled = true;
+    assert!(config.validate().is_err(), "启用 MongoDB 但没有 database 应该是无效的");
+}
+
 #[tokio::test]
 async fn test_server_address_formatting() {
     use hwhkit::Config;
@@ -129,6 +569,7 @@ async fn test_cors_middleware() {
         origins: vec!["http://localhost:3000".to_string()],
         methods: vec!["GET".to_string(), "POST".to_string()],
         headers: vec!["Content-Type".to_string()],
+        ..Default::default()
     };
 
     let cors_layer_result = create_cors_layer(&cors_config);
@@ -144,6 +585,7 @@ async fn test_jwt_auth_creation() {
         enabled: true,
         secret: "test-secret-key".to_string(),
         expires_in: 3600,
+        ..Default::default()
     };
 
     let jwt_auth = JwtAuth::new(&jwt_config);
@@ -264,6 +706,124 @@ requests = true
     }
 }
 
+#[test]
+fn test_config_unknown_keys() {
+    use hwhkit::Config;
+    use std::fs;
+    use tempfile::NamedTempFile;
+
+    // 示例配置不应产生警告
+    for path in ["examples/api-config.toml", "examples/full-config.toml"] {
+        let content = fs::read_to_string(path).unwrap();
+        let (_, warnings) = Config::from_str_with_warnings(&content).unwrap();
+        assert!(warnings.is_empty(), "{} 产生了警告: {:?}", path, warnings);
+    }
+
+    let temp_file = NamedTempFile::new().unwrap();
+    Config::default().save_to_file(temp_file.path()).unwrap();
+    let content = fs::read_to_string(temp_file.path())
+        .unwrap()
+        .replace("[server]\n", "[server]\nprot = 3001\n");
+
+    let (config, warnings) = Config::from_str_with_warnings(&content).unwrap();
+    assert_eq!(config.server.port, 3000);
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].contains("server.prot"));
+
+    // 严格模式下加载失败
+    fs::write(temp_file.path(), content.replace("strict = false", "strict = true")).unwrap();
+    let err = Config::from_file(temp_file.path()).unwrap_err();
+    assert!(err.to_string().contains("server.prot"));
+}
+
+#[test]
+fn test_jwt_secret_from_file() {
+    use hwhkit::Config;
+    use std::fs;
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().expect("创建临时目录失败");
+    let secret_path = temp_dir.path().join("jwt");
+
+    let mut config = Config::default();
+    config.middleware.jwt.enabled = true;
+    config.middleware.jwt.secret = String::new();
+    config.middleware.jwt.secret_file = Some(secret_path.to_string_lossy().to_string());
+
+    // 去除末尾的换行符（包括 Windows 风格的 \r\n）
+    fs::write(&secret_path, "mounted-secret\r\n").expect("写入密钥文件失败");
+    config.resolve_secret_files().expect("读取密钥文件失败");
+    assert_eq!(config.middleware.jwt.effective_secret(), "mounted-secret");
+    assert!(config.validate().is_ok());
+
+    // 保存配置时不能写出从文件读取的密钥
+    let saved_path = temp_dir.path().join("saved.toml");
+    config.save_to_file(&saved_path).expect("保存配置失败");
+    let saved = fs::read_to_string(&saved_path).expect("读取保存的配置失败");
+    assert!(!saved.contains("mounted-secret"));
+    assert!(saved.contains("secret_file"));
+
+    // 文件内容为空时，启用 JWT 的配置无效
+    fs::write(&secret_path, "\n").expect("写入密钥文件失败");
+    config.resolve_secret_files().expect("读取密钥文件失败");
+    assert!(config.validate().is_err());
+
+    // 密钥文件不存在时应报错
+    config.middleware.jwt.secret_file = Some("/nonexistent/secret".to_string());
+    assert!(config.resolve_secret_files().is_err());
+}
+
+#[tokio::test]
+async fn test_builder_fails_on_missing_secret_file() {
+    use hwhkit::Config;
+
+    let mut config = Config::default();
+    config.middleware.jwt.enabled = true;
+    config.middleware.jwt.secret_file = Some("/nonexistent/secret".to_string());
+
+    let server_result = WebServerBuilder::new()
+        .config(config)
+        .routes(Router::new().route("/", get(test_handler)))
+        .build()
+        .await;
+
+    assert!(server_result.is_err(), "密钥文件缺失时服务器不应启动");
+}
+
+#[cfg(feature = "schema")]
+#[test]
+fn test_config_json_schema() {
+    use hwhkit::Config;
+
+    let schema = Config::json_schema();
+    let defs = &schema["$defs"];
+
+    fn enum_values(def: &serde_json::Value) -> Vec<&str> {
+        def["oneOf"]
+            .as_array()
+            .expect("枚举应生成 oneOf")
+            .iter()
+            .map(|variant| variant["const"].as_str().unwrap())
+            .collect()
+    }
+
+    // 中间件各部分都应生成定义
+    for name in ["CorsConfig", "JwtConfig", "StaticConfig", "TemplateConfig", "LogConfig", "AccessLogConfig", "SyslogConfig"] {
+        assert!(defs[name].is_object(), "Schema 缺少 {} 定义", name);
+    }
+
+    // 枚举值与 TOML 中的写法一致，非法值（如 architecture = "foo"）无法通过校验
+    assert_eq!(enum_values(&defs["ArchitectureType"]), ["api", "full"]);
+    assert_eq!(enum_values(&defs["LogBackend"]), ["console", "syslog", "journald"]);
+
+    // 带默认值的字段不是必填项，运行时字段不出现在 Schema 中
+    let jwt_required = defs["JwtConfig"]["required"].as_array().unwrap();
+    assert!(!jwt_required.iter().any(|field| field == "secret" || field == "secret_file"));
+    assert!(defs["JwtConfig"]["properties"]["resolved_secret"].is_null());
+    let log_required = defs["LogConfig"]["required"].as_array().unwrap();
+    assert!(!log_required.iter().any(|field| field == "access_log" || field == "backend"));
+}
+
 #[cfg(feature = "templates")]
 #[tokio::test]
 async fn test_template_engine() {
@@ -289,6 +849,7 @@ async fn test_template_engine() {
         enabled: true,
         dir: temp_dir.path().to_string_lossy().to_string(),
         extension: "html".to_string(),
+        ..Default::default()
     };
 
     let engine_result = TemplateEngine::new(&template_config);
@@ -308,4 +869,1248 @@ async fn test_template_engine() {
             assert!(html.contains("Hello, World!"));
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(feature = "templates")]
+#[tokio::test]
+async fn test_templates_customize() {
+    use axum::Extension;
+    use hwhkit::{config::ArchitectureType, templates::{tera, TemplateEngine}, Config};
+    use std::{collections::HashMap, sync::Arc};
+    use tower::util::ServiceExt;
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    std::fs::write(temp_dir.path().join("price.html"), "{{ price | currency }}").unwrap();
+    let mut config = Config::default();
+    config.server.architecture = ArchitectureType::Full;
+    config.middleware.templates.enabled = true;
+    config.middleware.templates.dir = temp_dir.path().to_string_lossy().to_string();
+
+    let server = WebServerBuilder::new()
+        .config(config)
+        .templates_customize(|tera| {
+            tera.register_filter("currency", |value: &tera::Value, _: &HashMap<String, tera::Value>| {
+                Ok(format!("¥{:.2}", value.as_f64().unwrap_or_default()).into())
+            });
+        })
+        .routes(Router::new().route(
+            "/price",
+            get(|Extension(templates): Extension<Arc<TemplateEngine>>| async move {
+                templates.render("price.html", &json!({ "price": 3 })).unwrap()
+            }),
+        ))
+        .build()
+        .await
+        .unwrap();
+
+    let request = axum::http::Request::builder().uri("/price").body(axum::body::Body::empty()).unwrap();
+    let response = server.app().clone().oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(&body[..], "¥3.00".as_bytes());
+
+    // 未启用模板时自定义函数无处执行
+    let result = WebServerBuilder::new().templates_customize(|_| {}).build().await;
+    assert!(result.is_err());
+}
+
+#[cfg(feature = "templates")]
+#[tokio::test]
+async fn test_templates_render_responder() {
+    use axum::http::StatusCode;
+    use hwhkit::templates::Render;
+    use tower::util::ServiceExt;
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    std::fs::write(temp_dir.path().join("hello.html"), "Hello {{ name }}").unwrap();
+    let routes = || Router::new().route("/", get(|| async { Render("hello.html", json!({ "name": "hwhkit" })) }));
+
+    let server = WebServerBuilder::new()
+        .architecture(ArchitectureType::Full)
+        .templates(temp_dir.path(), "html")
+        .routes(routes())
+        .build()
+        .await
+        .unwrap();
+    let request = axum::http::Request::builder().uri("/").body(axum::body::Body::empty()).unwrap();
+    let response = server.app().clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(&body[..], b"Hello hwhkit");
+
+    // 未启用模板时返回 500
+    let server = WebServerBuilder::new().routes(routes()).build().await.unwrap();
+    let request = axum::http::Request::builder().uri("/").body(axum::body::Body::empty()).unwrap();
+    let response = server.app().clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[cfg(feature = "askama")]
+#[tokio::test]
+async fn test_askama_templates() {
+    use hwhkit::{askama::{Askama, Template}, config::{ArchitectureType, TemplateEngineKind}, Config};
+    use tower::util::ServiceExt;
+
+    #[derive(Template)]
+    #[template(source = "<h1>{{ title }}</h1>", ext = "html")]
+    struct Page {
+        title: &'static str,
+    }
+
+    // 编译期模板不需要模板目录
+    let mut config = Config::default();
+    config.server.architecture = ArchitectureType::Full;
+    config.middleware.templates.enabled = true;
+    config.middleware.templates.dir = "does-not-exist".to_string();
+    config.middleware.templates.engine = TemplateEngineKind::Askama;
+    assert!(config.validate().is_ok());
+
+    let server = WebServerBuilder::new()
+        .architecture(ArchitectureType::Full)
+        .askama_templates()
+        .routes(Router::new().route("/", get(|| async { Askama(Page { title: "首页" }) })))
+        .build()
+        .await
+        .unwrap();
+
+    let request = axum::http::Request::builder().uri("/").body(axum::body::Body::empty()).unwrap();
+    let response = server.app().clone().oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(&body[..], "<h1>首页</h1>".as_bytes());
+}
+
+#[cfg(feature = "i18n")]
+#[tokio::test]
+async fn test_i18n() {
+    use hwhkit::{i18n::{self, Locale}, Config};
+    use tower::util::ServiceExt;
+
+    let mut config = Config::default();
+    config.middleware.i18n.enabled = true;
+    config.middleware.i18n.dir = "does-not-exist".to_string();
+    assert!(config.validate().is_err(), "翻译目录必须存在");
+
+    let locales = tempfile::TempDir::new().unwrap();
+    for (locale, source) in [("zh-CN", "hello = 你好，{ $name }"), ("en", "hello = Hello, { $name }")] {
+        std::fs::create_dir(locales.path().join(locale)).unwrap();
+        std::fs::write(locales.path().join(locale).join("main.ftl"), source).unwrap();
+    }
+    config.middleware.i18n.dir = locales.path().to_string_lossy().to_string();
+
+    let server = WebServerBuilder::new()
+        .config(config)
+        .routes(Router::new().route(
+            "/",
+            get(|Locale(locale): Locale| async move { format!("{}|{}", locale, i18n::t("hello", &json!({ "name": "A" }))) }),
+        ))
+        .build()
+        .await
+        .unwrap();
+
+    for (cookie, accept, expected) in [
+        (None, None, "zh-CN|你好，A"),
+        (None, Some("en-US,en;q=0.8"), "en|Hello, A"),
+        (Some("lang=zh-CN"), Some("en"), "zh-CN|你好，A"),
+    ] {
+        let mut request = axum::http::Request::builder().uri("/");
+        if let Some(cookie) = cookie {
+            request = request.header("cookie", cookie);
+        }
+        if let Some(accept) = accept {
+            request = request.header("accept-language", accept);
+        }
+        let response = server.app().clone().oneshot(request.body(axum::body::Body::empty()).unwrap()).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], expected.as_bytes());
+    }
+}
+
+#[cfg(feature = "templates")]
+#[tokio::test]
+async fn test_template_globals() {
+    use hwhkit::{templates::Render, Config};
+    use tower::util::ServiceExt;
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    std::fs::write(temp_dir.path().join("index.html"), "{{ app_name }}|{{ version }}|{{ build_sha }}").unwrap();
+    let mut config = Config::default();
+    config.server.architecture = hwhkit::config::ArchitectureType::Full;
+    config.middleware.templates = toml::from_str(&format!(
+        "enabled = true\ndir = {:?}\n\n[globals]\napp_name = \"Demo\"\nversion = \"1.0\"\n",
+        temp_dir.path().to_string_lossy()
+    ))
+    .unwrap();
+
+    let server = WebServerBuilder::new()
+        .config(config)
+        .template_global("version", "2.0")
+        .template_global("build_sha", "abc123")
+        .routes(Router::new().route("/", get(|| async { Render("index.html", ()) })))
+        .build()
+        .await
+        .unwrap();
+    let request = axum::http::Request::builder().uri("/").body(axum::body::Body::empty()).unwrap();
+    let response = server.app().clone().oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(&body[..], b"Demo|2.0|abc123");
+}
+
+#[cfg(feature = "templates")]
+#[tokio::test]
+async fn test_error_pages() {
+    use axum::http::{header, StatusCode};
+    use hwhkit::{config::ArchitectureType, Config};
+    use tower::util::ServiceExt;
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    std::fs::write(temp_dir.path().join("404.html"), "<h1>{{ title }}</h1>").unwrap();
+    let mut config = Config::default();
+    config.server.architecture = ArchitectureType::Full;
+    config.errors.pages.insert("404".to_string(), "404.html".to_string());
+
+    let server = WebServerBuilder::new()
+        .config(config.clone())
+        .templates(temp_dir.path(), "html")
+        .build()
+        .await
+        .unwrap();
+    let get = |accept: &'static str| {
+        axum::http::Request::builder()
+            .uri("/missing")
+            .header(header::ACCEPT, accept)
+            .body(axum::body::Body::empty())
+            .unwrap()
+    };
+
+    let response = server.app().clone().oneshot(get("text/html")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(&body[..], b"<h1>404 Not Found</h1>");
+
+    let response = server.app().clone().oneshot(get("application/json")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+
+    // 错误页面需要模板引擎
+    assert!(WebServerBuilder::new().config(config).build().await.is_err());
+}
+
+#[tokio::test]
+async fn test_on_error() {
+    use axum::{http::StatusCode, response::IntoResponse};
+    use hwhkit::{response::ApiError, Error};
+    use tower::util::ServiceExt;
+
+    let server = WebServerBuilder::new()
+        .on_error(|err, parts| {
+            let status = match err {
+                Error::NotFound(_) => StatusCode::GONE,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (status, Json(json!({ "code": status.as_u16(), "msg": err.to_string(), "path": parts.uri.path() })))
+                .into_response()
+        })
+        .routes(
+            Router::new()
+                .route("/users/:id", get(|| async { Err::<(), _>(Error::NotFound("用户不存在".to_string())) }))
+                .route("/api", get(|| async { ApiError::not_found("用户不存在") })),
+        )
+        .build()
+        .await
+        .unwrap();
+    let get = |uri: &str| axum::http::Request::builder().uri(uri).body(axum::body::Body::empty()).unwrap();
+
+    let response = server.app().clone().oneshot(get("/users/1")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::GONE);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body, json!({ "code": 410, "msg": "资源不存在: 用户不存在", "path": "/users/1" }));
+
+    // ApiError 不经过错误处理函数
+    let response = server.app().clone().oneshot(get("/api")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_on_panic() {
+    use axum::http::StatusCode;
+    use std::sync::{Arc, Mutex};
+    use tower::util::ServiceExt;
+
+    let panics = Arc::new(Mutex::new(Vec::new()));
+    let recorded = panics.clone();
+    let server = WebServerBuilder::new()
+        .on_panic(move |report| recorded.lock().unwrap().push(report.message.clone()))
+        .routes(Router::new().route(
+            "/panic",
+            get(|| async {
+                if true {
+                    panic!("boom");
+                }
+                "unreachable"
+            }),
+        ))
+        .build()
+        .await
+        .unwrap();
+
+    let request = axum::http::Request::builder().uri("/panic").body(axum::body::Body::empty()).unwrap();
+    let response = server.app().clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let request_id = response.headers()["x-request-id"].to_str().unwrap().to_string();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["request_id"], request_id.as_str());
+    assert_eq!(*panics.lock().unwrap(), vec!["boom".to_string()]);
+}
+
+#[tokio::test]
+async fn test_fallback() {
+    use axum::http::{StatusCode, Uri};
+    use tower::util::ServiceExt;
+
+    let request = || axum::http::Request::builder().uri("/missing").body(axum::body::Body::empty()).unwrap();
+    let body = |response: axum::response::Response| async move {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+    };
+
+    // 默认返回统一格式的 JSON 404，处理函数自己返回的 404 不受影响
+    let server = WebServerBuilder::new()
+        .routes(Router::new().route("/gone", get(|| async { StatusCode::NOT_FOUND })))
+        .build()
+        .await
+        .unwrap();
+    let response = server.app().clone().oneshot(request()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(body(response).await, json!({ "success": false, "error": "请求的资源不存在", "code": 404 }));
+    let request_gone = axum::http::Request::builder().uri("/gone").body(axum::body::Body::empty()).unwrap();
+    let response = server.app().clone().oneshot(request_gone).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(response.headers().get("content-type").is_none());
+
+    // 自定义处理函数
+    let server = WebServerBuilder::new()
+        .fallback(|uri: Uri| async move { (StatusCode::NOT_FOUND, Json(json!({ "missing": uri.path() }))) })
+        .build()
+        .await
+        .unwrap();
+    let response = server.app().clone().oneshot(request()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(body(response).await, json!({ "missing": "/missing" }));
+}
+
+#[cfg(feature = "mail")]
+#[tokio::test]
+async fn test_mail() {
+    use hwhkit::{
+        config::MailTls,
+        mail::{Email, Mailer},
+        Config,
+    };
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tower::util::ServiceExt;
+
+    // 只实现发送所需命令的 SMTP 服务，收到的邮件内容通过通道返回
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (sent, mut inbox) = tokio::sync::mpsc::unbounded_channel::<String>();
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        writer.write_all(b"220 localhost ESMTP\r\n").await.unwrap();
+        let mut data: Option<String> = None;
+        while let Some(line) = lines.next_line().await.unwrap() {
+            if let Some(body) = data.as_mut() {
+                if line == "." {
+                    sent.send(data.take().unwrap()).unwrap();
+                    writer.write_all(b"250 queued\r\n").await.unwrap();
+                } else {
+                    body.push_str(&line);
+                    body.push('\n');
+                }
+                continue;
+            }
+            let reply: &[u8] = match line.split(' ').next().unwrap().to_ascii_uppercase().as_str() {
+                "EHLO" | "HELO" | "MAIL" | "RCPT" => b"250 OK\r\n",
+                "DATA" => {
+                    data = Some(String::new());
+                    b"354 go ahead\r\n"
+                }
+                "QUIT" => b"221 bye\r\n",
+                _ => b"502 not implemented\r\n",
+            };
+            writer.write_all(reply).await.unwrap();
+        }
+    });
+
+    let mut config = Config::default();
+    config.mail.enabled = true;
+    config.mail.host = "127.0.0.1".to_string();
+    config.mail.port = port;
+    config.mail.tls = MailTls::None;
+    config.mail.from = "App <noreply@example.com>".to_string();
+    let server = WebServerBuilder::new()
+        .config(config)
+        .routes(Router::new().route(
+            "/invite",
+            hwhkit::post(|mailer: Mailer| async move {
+                let email = Email::new().to("alice@example.com").subject("Invitation").text("Join us");
+                mailer.send(email).await.map(|_| "sent")
+            }),
+        ))
+        .build()
+        .await
+        .unwrap();
+
+    let request = axum::http::Request::post("/invite").body(axum::body::Body::empty()).unwrap();
+    let response = server.app().clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+
+    let message = tokio::time::timeout(Duration::from_secs(5), inbox.recv()).await.unwrap().unwrap();
+    assert!(message.contains("From: App <noreply@example.com>"));
+    assert!(message.contains("To: alice@example.com"));
+    assert!(message.contains("Subject: Invitation"));
+    assert!(message.contains("Join us"));
+
+    // 未启用时提取器返回 500
+    let server = WebServerBuilder::new()
+        .routes(Router::new().route("/invite", hwhkit::post(|_: Mailer| async { "sent" })))
+        .build()
+        .await
+        .unwrap();
+    let request = axum::http::Request::post("/invite").body(axum::body::Body::empty()).unwrap();
+    let response = server.app().clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[tokio::test]
+async fn test_cache_control_config() {
+    use axum::http::{header, StatusCode};
+    use hwhkit::{config::RouteCacheControlConfig, Config};
+    use tower::util::ServiceExt;
+
+    let mut config = Config::default();
+    config.middleware.cache_control = vec![RouteCacheControlConfig {
+        path: "/api/v1/catalog/*".to_string(),
+        policy: "public, max-age=300".to_string(),
+    }];
+    config.middleware.cache_validation.enabled = true;
+    config.middleware.cache_validation.routes = vec!["/api/*".to_string()];
+    let server = WebServerBuilder::new()
+        .config(config)
+        .routes(Router::new().route("/api/v1/catalog/items", get(|| async { Json(json!([1, 2])) })))
+        .build()
+        .await
+        .unwrap();
+
+    let request = axum::http::Request::get("/api/v1/catalog/items").body(axum::body::Body::empty()).unwrap();
+    let response = server.app().clone().oneshot(request).await.unwrap();
+    assert_eq!(response.headers()[header::CACHE_CONTROL], "public, max-age=300");
+    let etag = response.headers()[header::ETAG].clone();
+
+    // 304 响应同样带有缓存策略
+    let request = axum::http::Request::get("/api/v1/catalog/items")
+        .header(header::IF_NONE_MATCH, etag)
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let response = server.app().clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(response.headers()[header::CACHE_CONTROL], "public, max-age=300");
+}
+
+#[tokio::test]
+async fn test_client_ip() {
+    use hwhkit::{middleware::client_ip::ClientIp, Config};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let mut config = Config::default();
+    config.server.proxy.trusted_proxies = vec!["127.0.0.1".to_string()];
+    let server = WebServerBuilder::new()
+        .config(config)
+        .routes(Router::new().route("/ip", get(|ClientIp(ip): ClientIp| async move { ip.to_string() })))
+        .build()
+        .await
+        .unwrap();
+    let shutdown = server.shutdown_signal();
+    let address = format!("127.0.0.1:{}", port);
+    let running = tokio::spawn(async move { server.run(Some(&address)).await });
+
+    // 通过真实连接验证服务器提供了对端地址，且受信任的代理可以转发客户端地址
+    let get_ip = |forwarded: Option<&str>| {
+        let request = format!(
+            "GET /ip HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n{}\r\n",
+            forwarded.map(|ip| format!("X-Forwarded-For: {}\r\n", ip)).unwrap_or_default()
+        );
+        async move {
+            let mut stream = loop {
+                match tokio::net::TcpStream::connect(("127.0.0.1", port)).await {
+                    Ok(stream) => break stream,
+                    Err(_) => tokio::time::sleep(Duration::from_millis(20)).await,
+                }
+            };
+            stream.write_all(request.as_bytes()).await.unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await.unwrap();
+            response
+        }
+    };
+    assert!(get_ip(None).await.ends_with("\r\n\r\n127.0.0.1"));
+    assert!(get_ip(Some("198.51.100.4")).await.ends_with("\r\n\r\n198.51.100.4"));
+
+    shutdown.trigger();
+    running.await.unwrap().unwrap();
+}
+
+#[cfg(feature = "cdn")]
+#[tokio::test]
+async fn test_trace_context_propagation() {
+    use axum::http::HeaderMap;
+    use hwhkit::{cache::PurgeClient, config::CdnProvider, Config};
+    use std::sync::{Arc, Mutex};
+    use tower::util::ServiceExt;
+
+    // 模拟 CDN 接口，记录收到的追踪头
+    let received = Arc::new(Mutex::new(None));
+    let recorded = received.clone();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let mock = Router::new().route(
+        "/service/:id/purge",
+        hwhkit::post(move |headers: HeaderMap| async move {
+            *recorded.lock().unwrap() = Some((headers["traceparent"].clone(), headers["tracestate"].clone()));
+            "{}"
+        }),
+    );
+    tokio::spawn(async move { axum::serve(listener, mock).await.unwrap() });
+
+    let mut config = Config::default();
+    config.cdn.provider = CdnProvider::Fastly;
+    config.cdn.service_id = "svc".to_string();
+    config.cdn.api_base = Some(base);
+    let server = WebServerBuilder::new()
+        .config(config)
+        .routes(Router::new().route(
+            "/purge",
+            hwhkit::post(|client: PurgeClient| async move { client.purge_keys(&["articles"]).await.map(|_| "ok") }),
+        ))
+        .build()
+        .await
+        .unwrap();
+
+    let request = axum::http::Request::post("/purge")
+        .header("traceparent", "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01")
+        .header("tracestate", "congo=t61rcWkgMzE")
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let response = server.app().clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+
+    // 下游收到同一个 trace id，父 span 为本服务的 span
+    let (traceparent, tracestate) = received.lock().unwrap().clone().unwrap();
+    let traceparent = traceparent.to_str().unwrap();
+    assert!(traceparent.starts_with("00-4bf92f3577b34da6a3ce929d0e0e4736-"), "{}", traceparent);
+    assert!(!traceparent.contains("00f067aa0ba902b7"));
+    assert!(traceparent.ends_with("-01"));
+    assert_eq!(tracestate, "congo=t61rcWkgMzE");
+}
+
+#[cfg(feature = "cdn")]
+#[tokio::test]
+async fn test_cdn_circuit_breaker() {
+    use axum::http::StatusCode;
+    use hwhkit::{cache::PurgeClient, config::CdnProvider, Config};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+    use tower::util::ServiceExt;
+
+    // 模拟持续失败的 CDN 接口
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counted = calls.clone();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let mock = Router::new().route(
+        "/service/:id/purge",
+        hwhkit::post(move || async move {
+            counted.fetch_add(1, Ordering::SeqCst);
+            StatusCode::BAD_GATEWAY
+        }),
+    );
+    tokio::spawn(async move { axum::serve(listener, mock).await.unwrap() });
+
+    let mut config = Config::default();
+    config.cdn.provider = CdnProvider::Fastly;
+    config.cdn.service_id = "svc".to_string();
+    config.cdn.api_base = Some(base);
+    config.cdn.circuit_breaker.enabled = true;
+    config.cdn.circuit_breaker.failure_threshold = 2;
+    config.telemetry.metrics.enabled = true;
+    let server = WebServerBuilder::new()
+        .config(config)
+        .routes(Router::new().route(
+            "/purge",
+            hwhkit::post(|client: PurgeClient| async move { client.purge_keys(&["articles"]).await.map(|_| "ok") }),
+        ))
+        .build()
+        .await
+        .unwrap();
+
+    let mut statuses = Vec::new();
+    for _ in 0..3 {
+        let request = axum::http::Request::post("/purge").body(axum::body::Body::empty()).unwrap();
+        statuses.push(server.app().clone().oneshot(request).await.unwrap().status());
+    }
+    assert_eq!(
+        statuses,
+        [
+            StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::SERVICE_UNAVAILABLE
+        ]
+    );
+    // 熔断后不再请求 CDN
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+    let request = axum::http::Request::get("/metrics").body(axum::body::Body::empty()).unwrap();
+    let response = server.app().clone().oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body = String::from_utf8(body.to_vec()).unwrap();
+    assert!(body.contains("circuit_breaker_state{name=\"cdn\"} 1"), "{}", body);
+}
+
+#[tokio::test]
+async fn test_concurrency_limit() {
+    use axum::http::StatusCode;
+    use hwhkit::Config;
+    use std::sync::Arc;
+    use tokio::sync::Notify;
+    use tower::util::ServiceExt;
+
+    let release = Arc::new(Notify::new());
+    let waiting = release.clone();
+    let mut config = Config::default();
+    config.server.limits.max_concurrent_requests = 1;
+    config.server.readiness.enabled = true;
+    let server = WebServerBuilder::new()
+        .config(config)
+        .routes(Router::new().route(
+            "/slow",
+            get(move || {
+                let waiting = waiting.clone();
+                async move {
+                    waiting.notified().await;
+                    "done"
+                }
+            }),
+        ))
+        .build()
+        .await
+        .unwrap();
+    let request = |uri: &str| axum::http::Request::get(uri).body(axum::body::Body::empty()).unwrap();
+
+    let slow = tokio::spawn(server.app().clone().oneshot(request("/slow")));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let response = server.app().clone().oneshot(request("/slow")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(response.headers()["retry-after"], "1");
+    assert!(response.headers().contains_key("x-request-id"));
+    // 就绪探针不受限制
+    let response = server.app().clone().oneshot(request("/readyz")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    release.notify_one();
+    assert_eq!(slow.await.unwrap().unwrap().status(), StatusCode::OK);
+}
+
+#[cfg(feature = "testing")]
+#[tokio::test]
+async fn test_test_server() {
+    use axum::http::StatusCode;
+    use hwhkit::{test::TestServer, Config, Json};
+
+    let mut config = Config::default();
+    config.middleware.request_id.enabled = true;
+    let builder = WebServerBuilder::new().config(config).routes(Router::new().route(
+        "/users",
+        hwhkit::post(|Json(user): Json<serde_json::Value>| async move {
+            (StatusCode::CREATED, Json(serde_json::json!({ "id": 1, "name": user["name"] })))
+        }),
+    ));
+    let server = TestServer::from_builder(builder).await.unwrap();
+
+    let response = server
+        .post("/users")
+        .header("x-request-id", "req-42")
+        .json(&serde_json::json!({ "name": "alice" }))
+        .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    // 响应经过配置中的中间件
+    assert_eq!(response.header("x-request-id"), Some("req-42"));
+    assert_eq!(response.json::<serde_json::Value>()["name"], "alice");
+
+    // 请求体无效时返回框架的错误响应
+    let response = server.post("/users").body("not json").await;
+    assert!(response.status().is_client_error());
+}
+
+#[tokio::test]
+async fn test_route_listing() {
+    use hwhkit::{routing::RouteDoc, Config};
+    use tower::util::ServiceExt;
+
+    let mut config = Config::default();
+    config.server.routes_endpoint.enabled = true;
+    config.server.readiness.enabled = true;
+    let server = WebServerBuilder::new()
+        .config(config)
+        .routes(Router::new().route("/users", hwhkit::get(|| async { "users" })))
+        .route_doc(RouteDoc::new("GET", "/users", "列出用户"))
+        .route_named("user_detail", "/users/:id", hwhkit::get(|| async { "user" }))
+        .build()
+        .await
+        .unwrap();
+
+    let routes: Vec<_> = server
+        .routes()
+        .iter()
+        .map(|route| (route.method.as_str(), route.path.as_str(), route.name.as_deref()))
+        .collect();
+    assert!(routes.contains(&("GET", "/users", None)), "{:?}", routes);
+    assert!(routes.contains(&("*", "/users/:id", Some("user_detail"))));
+    assert!(routes.contains(&("GET", "/_routes", None)));
+    assert!(routes.contains(&("GET", &server.config().server.readiness.path, None)));
+
+    let request = axum::http::Request::get("/_routes").body(axum::body::Body::empty()).unwrap();
+    let response = server.app().clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), axum::http::StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let listed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(listed.as_array().unwrap().len(), routes.len());
+    assert!(listed
+        .as_array()
+        .unwrap()
+        .contains(&serde_json::json!({ "method": "*", "path": "/users/:id", "name": "user_detail" })));
+}
+
+#[tokio::test]
+async fn test_route_group() {
+    use axum::http::StatusCode;
+    use hwhkit::auth::rbac::require_role;
+    use tower::util::ServiceExt;
+
+    let server = WebServerBuilder::new()
+        .routes(Router::new().route("/", hwhkit::get(|| async { "home" })))
+        .group("/admin", |group| {
+            group
+                .guard(require_role("admin"))
+                .route("/stats", hwhkit::get(|| async { "stats" }))
+                .route_named("admin_user", "/users/:id", hwhkit::get(|| async { "user" }))
+        })
+        .build()
+        .await
+        .unwrap();
+
+    assert_eq!(hwhkit::url_for!("admin_user", id = 3).unwrap(), "/admin/users/3");
+    assert!(server
+        .routes()
+        .iter()
+        .any(|route| route.path == "/admin/stats" && route.method == "*"));
+
+    let status = |uri: &'static str| {
+        let app = server.app().clone();
+        async move {
+            let request = axum::http::Request::get(uri).body(axum::body::Body::empty()).unwrap();
+            app.oneshot(request).await.unwrap().status()
+        }
+    };
+    assert_eq!(status("/").await, StatusCode::OK);
+    assert_eq!(status("/admin/stats").await, StatusCode::UNAUTHORIZED);
+    assert_eq!(status("/admin/users/3").await, StatusCode::UNAUTHORIZED);
+    assert_eq!(status("/admin/missing").await, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_mount_module() {
+    use hwhkit::{
+        async_trait,
+        module::{AppModule, ModuleContext},
+        Config, Deserialize, Result, State,
+    };
+    use tower::util::ServiceExt;
+
+    #[derive(Default, Deserialize)]
+    #[serde(default)]
+    struct CounterConfig {
+        start: u64,
+    }
+
+    struct CounterModule;
+
+    #[async_trait]
+    impl AppModule for CounterModule {
+        type Config = CounterConfig;
+        type State = u64;
+
+        fn name(&self) -> &str {
+            "counter"
+        }
+
+        async fn state(&self, config: CounterConfig, context: &ModuleContext<'_>) -> Result<u64> {
+            assert_eq!(context.config().server.port, 3000);
+            Ok(config.start)
+        }
+
+        fn routes(&self) -> Router<u64> {
+            Router::new().route(
+                "/counter",
+                hwhkit::get(|State(start): State<u64>| async move { start.to_string() }),
+            )
+        }
+    }
+
+    let content = toml::to_string(&Config::default()).unwrap() + "\n[modules.counter]\nstart = 7\n";
+    let (config, warnings) = Config::from_str_with_warnings(&content).unwrap();
+    assert!(warnings.is_empty(), "{:?}", warnings);
+    let server = WebServerBuilder::new().config(config).mount(CounterModule).build().await.unwrap();
+
+    let request = axum::http::Request::get("/counter").body(axum::body::Body::empty()).unwrap();
+    let response = server.app().clone().oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(body, "7");
+
+    // 同一模块不能重复挂载
+    let result = WebServerBuilder::new().mount(CounterModule).mount(CounterModule).build().await;
+    assert!(matches!(result, Err(hwhkit::Error::Config(_))));
+}
+
+#[tokio::test]
+async fn test_config_section() {
+    use hwhkit::{inject::AppConfig, Config, Deserialize};
+    use tower::util::ServiceExt;
+
+    #[derive(Deserialize)]
+    struct PaymentsConfig {
+        provider: String,
+        #[serde(default)]
+        sandbox: bool,
+    }
+
+    let content = toml::to_string(&Config::default()).unwrap()
+        + "\n[extensions.payments]\nprovider = \"stripe\"\nsandbox = true\n";
+    let (config, warnings) = Config::from_str_with_warnings(&content).unwrap();
+    assert!(warnings.is_empty(), "{:?}", warnings);
+    let server = WebServerBuilder::new()
+        .config(config.clone())
+        .config_section::<PaymentsConfig>("payments")
+        .routes(Router::new().route(
+            "/provider",
+            hwhkit::get(|AppConfig(payments): AppConfig<PaymentsConfig>| async move {
+                format!("{}:{}", payments.provider, payments.sandbox)
+            }),
+        ))
+        .build()
+        .await
+        .unwrap();
+
+    let request = axum::http::Request::get("/provider").body(axum::body::Body::empty()).unwrap();
+    let response = server.app().clone().oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(body, "stripe:true");
+
+    // 缺少必填字段时构建失败
+    let result = WebServerBuilder::new()
+        .config(config)
+        .config_section::<PaymentsConfig>("billing")
+        .build()
+        .await;
+    assert!(matches!(result, Err(hwhkit::Error::Config(_))));
+}
+
+#[tokio::test]
+async fn test_config_from_files() {
+    use hwhkit::Config;
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let base = temp_dir.path().join("base.toml");
+    let overrides = temp_dir.path().join("overrides.toml");
+    let mut config = Config::default();
+    config.server.host = "0.0.0.0".to_string();
+    config.middleware.cors.origins = vec!["https://a.example.com".to_string(), "https://b.example.com".to_string()];
+    config.save_to_file(&base).unwrap();
+    std::fs::write(
+        &overrides,
+        "[server]\nport = 8080\n\n[middleware.cors]\norigins = [\"https://c.example.com\"]\n",
+    )
+    .unwrap();
+
+    let config = Config::from_files([&base, &overrides]).unwrap();
+    assert_eq!(config.server.host, "0.0.0.0");
+    assert_eq!(config.server.port, 8080);
+    assert_eq!(config.middleware.cors.origins, vec!["https://c.example.com".to_string()]);
+
+    // 只有覆盖文件本身不是完整配置
+    assert!(Config::from_files([&overrides]).is_err());
+    assert!(Config::from_files(Vec::<&str>::new()).is_err());
+
+    let server = WebServerBuilder::new()
+        .config_from_files([&base, &overrides])
+        .build()
+        .await
+        .unwrap();
+    assert_eq!(server.config().server.port, 8080);
+}
+
+#[test]
+fn test_local_config_override() {
+    use hwhkit::Config;
+    use tempfile::TempDir;
+
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("config.toml");
+    Config::default().save_to_file(&path).unwrap();
+    assert_eq!(Config::from_file(&path).unwrap().server.port, 3000);
+
+    std::fs::write(
+        temp_dir.path().join("config.local.toml"),
+        "[server]\nport = 4000\n\n[middleware.jwt]\nsecret = \"local-secret\"\n",
+    )
+    .unwrap();
+    let config = Config::from_file(&path).unwrap();
+    assert_eq!(config.server.port, 4000);
+    assert_eq!(config.server.host, Config::default().server.host);
+    assert_eq!(config.middleware.jwt.secret, "local-secret");
+}
+
+#[cfg(feature = "proxy")]
+#[tokio::test]
+async fn test_reverse_proxy() {
+    use axum::http::{HeaderMap, StatusCode};
+    use hwhkit::{proxy::ReverseProxy, Config};
+    use tower::util::ServiceExt;
+
+    // 模拟旧服务，返回收到的路径、查询参数和转发头
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let upstream = format!("http://{}", listener.local_addr().unwrap());
+    let legacy = Router::new()
+        .route(
+            "/orders/:id",
+            hwhkit::get(|uri: axum::http::Uri, headers: HeaderMap| async move {
+                let header = |name: &str| headers.get(name).map(|v| v.to_str().unwrap().to_string()).unwrap_or_default();
+                (
+                    [("server", "legacy"), ("x-legacy", "1")],
+                    format!("{}|{}|{}|{}", uri, header("x-forwarded-for"), header("x-forwarded-host"), header("x-api-key")),
+                )
+            }),
+        )
+        .route("/echo", hwhkit::post(|body: String| async move { (StatusCode::CREATED, body) }));
+    tokio::spawn(async move { axum::serve(listener, legacy).await.unwrap() });
+
+    let mut config = Config::default();
+    config.reverse_proxy.routes.push(hwhkit::config::ProxyRouteConfig {
+        path: "/api/*path".to_string(),
+        upstream: upstream.clone(),
+    });
+    let server = WebServerBuilder::new()
+        .config(config)
+        .routes(Router::new().route("/orders/new", hwhkit::get(|| async { "migrated" })))
+        .proxy_with(
+            "/legacy/*path",
+            ReverseProxy::new(&upstream)
+                .request_header("x-api-key", "secret")
+                .remove_response_header("server"),
+        )
+        .proxy("/down/*path", "http://127.0.0.1:1")
+        .build()
+        .await
+        .unwrap();
+    assert!(server.routes().iter().any(|route| route.method == "*" && route.path == "/legacy/*path"));
+
+    let send = |request: axum::http::Request<axum::body::Body>| {
+        let app = server.app().clone();
+        async move {
+            let mut request = request;
+            request
+                .extensions_mut()
+                .insert(axum::extract::ConnectInfo(std::net::SocketAddr::from(([10, 0, 0, 2], 4000))));
+            let response = app.oneshot(request).await.unwrap();
+            let status = response.status();
+            let headers = response.headers().clone();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            (status, headers, String::from_utf8(body.to_vec()).unwrap())
+        }
+    };
+
+    let (status, headers, body) = send(
+        axum::http::Request::get("/legacy/orders/7?page=2")
+            .header("host", "example.com")
+            .body(axum::body::Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "/orders/7?page=2|10.0.0.2|example.com|secret");
+    assert!(headers.get("server").is_none());
+    assert_eq!(headers["x-legacy"], "1");
+
+    // 配置文件中的代理路由，请求体按流转发
+    let (status, _, body) = send(
+        axum::http::Request::post("/api/echo")
+            .body(axum::body::Body::from("payload"))
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body, "payload");
+
+    // 已迁移的接口由本服务处理，上游无法连接时返回 502
+    let (_, _, body) = send(axum::http::Request::get("/orders/new").body(axum::body::Body::empty()).unwrap()).await;
+    assert_eq!(body, "migrated");
+    let (status, _, _) = send(axum::http::Request::get("/down/x").body(axum::body::Body::empty()).unwrap()).await;
+    assert_eq!(status, StatusCode::BAD_GATEWAY);
+}
+
+#[cfg(feature = "http_client")]
+#[tokio::test]
+async fn test_http_client() {
+    use axum::http::HeaderMap;
+    use hwhkit::{http_client::HttpClient, Config};
+    use tower::util::ServiceExt;
+
+    // 模拟外部服务，返回收到的 User-Agent 和查询参数
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let external = Router::new().route(
+        "/weather",
+        hwhkit::get(|uri: axum::http::Uri, headers: HeaderMap| async move {
+            format!("{}|{}", headers["user-agent"].to_str().unwrap(), uri.query().unwrap_or_default())
+        }),
+    );
+    tokio::spawn(async move { axum::serve(listener, external).await.unwrap() });
+
+    let mut config = Config::default();
+    config.http_client.user_agent = "my-app/1.0".to_string();
+    let server = WebServerBuilder::new()
+        .config(config)
+        .routes(Router::new().route(
+            "/weather",
+            hwhkit::get(move |client: HttpClient| async move {
+                let response = client
+                    .get(&format!("{}/weather", base))
+                    .query(&[("city", "beijing")])
+                    .send()
+                    .await?;
+                Ok::<_, hwhkit::Error>(response.text().await.unwrap())
+            }),
+        ))
+        .build()
+        .await
+        .unwrap();
+    assert!(server.service::<HttpClient>().is_some());
+
+    let request = axum::http::Request::get("/weather").body(axum::body::Body::empty()).unwrap();
+    let response = server.app().clone().oneshot(request).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(body, "my-app/1.0|city=beijing");
+
+    // 代理地址无效时构建失败
+    let mut config = Config::default();
+    config.http_client.proxy = Some("not a url".to_string());
+    let result = WebServerBuilder::new().config(config).build().await;
+    assert!(matches!(result, Err(hwhkit::Error::Config(_))));
+}
+
+#[cfg(feature = "webhooks")]
+#[tokio::test]
+async fn test_webhook_signature() {
+    use axum::http::StatusCode;
+    use hwhkit::{
+        config::{WebhookConfig, WebhookFormat},
+        webhooks::{Stripe, Webhook, WebhookVerifier},
+        Config,
+    };
+    use tower::util::ServiceExt;
+
+    let stripe = WebhookConfig {
+        format: WebhookFormat::Stripe,
+        secret: "whsec_test".to_string(),
+        ..Default::default()
+    };
+    let mut config = Config::default();
+    config.webhooks.insert("stripe".to_string(), stripe.clone());
+    let server = WebServerBuilder::new()
+        .config(config)
+        .routes(Router::new().route(
+            "/webhooks/stripe",
+            hwhkit::post(|webhook: Webhook<Stripe>| async move {
+                let event: serde_json::Value = webhook.json()?;
+                Ok::<_, hwhkit::Error>(event["type"].as_str().unwrap_or_default().to_string())
+            }),
+        ))
+        .build()
+        .await
+        .unwrap();
+
+    let body = r#"{"type":"charge.succeeded"}"#;
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    let verifier = WebhookVerifier::new(&stripe).unwrap();
+    let send = |signature: String, body: &'static str| {
+        let app = server.app().clone();
+        async move {
+            let request = axum::http::Request::post("/webhooks/stripe")
+                .header("stripe-signature", signature)
+                .body(axum::body::Body::from(body))
+                .unwrap();
+            let response = app.oneshot(request).await.unwrap();
+            let status = response.status();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            (status, body)
+        }
+    };
+
+    let (status, response) = send(verifier.sign(body.as_bytes(), now), body).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(response, "charge.succeeded");
+
+    // 请求体被篡改或签名过期
+    let (status, _) = send(verifier.sign(body.as_bytes(), now), r#"{"type":"charge.refunded"}"#).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = send(verifier.sign(body.as_bytes(), now - 3600), body).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // 未配置密钥时构建失败
+    let mut config = Config::default();
+    config.webhooks.insert("github".to_string(), WebhookConfig::default());
+    let result = WebServerBuilder::new().config(config).build().await;
+    assert!(matches!(result, Err(hwhkit::Error::Config(_))));
+}
+
+#[cfg(feature = "cookies")]
+#[tokio::test]
+async fn test_cookie_jar() {
+    use hwhkit::{
+        middleware::cookies::{CookieJar, RawCookie},
+        Config,
+    };
+    use tower::util::ServiceExt;
+
+    let mut config = Config::default();
+    config.cookies.keys = vec!["0123456789abcdef0123456789abcdef".to_string()];
+    let server = WebServerBuilder::new()
+        .config(config)
+        .routes(Router::new().route(
+            "/visits",
+            hwhkit::get(|mut jar: CookieJar| async move {
+                let visits = jar
+                    .signed()
+                    .get("visits")
+                    .and_then(|cookie| cookie.value().parse::<u32>().ok())
+                    .unwrap_or(0)
+                    + 1;
+                jar.signed().add(RawCookie::build(("visits", visits.to_string())).path("/"));
+                (jar, visits.to_string())
+            }),
+        ))
+        .build()
+        .await
+        .unwrap();
+
+    let visit = |cookie: Option<String>| {
+        let app = server.app().clone();
+        async move {
+            let mut request = axum::http::Request::get("/visits");
+            if let Some(cookie) = cookie {
+                request = request.header("cookie", cookie);
+            }
+            let response = app.oneshot(request.body(axum::body::Body::empty()).unwrap()).await.unwrap();
+            let set_cookie = response.headers()["set-cookie"].to_str().unwrap().to_string();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            (set_cookie.split(';').next().unwrap().to_string(), String::from_utf8(body.to_vec()).unwrap())
+        }
+    };
+
+    let (cookie, body) = visit(None).await;
+    assert_eq!(body, "1");
+    let (_, body) = visit(Some(cookie.clone())).await;
+    assert_eq!(body, "2");
+    // 篡改后的 Cookie 签名无效，计数重新开始
+    let tampered = format!("{}5", cookie.strip_suffix('1').unwrap());
+    let (_, body) = visit(Some(tampered)).await;
+    assert_eq!(body, "1");
+}
+
+#[cfg(feature = "cookies")]
+#[tokio::test]
+async fn test_typed_cookie() {
+    use hwhkit::{
+        config::SameSite,
+        middleware::cookies::{Cookie, CookieData},
+        Config, Deserialize,
+    };
+    use tower::util::ServiceExt;
+
+    #[derive(Clone, Default, Serialize, Deserialize)]
+    struct Preferences {
+        theme: String,
+    }
+
+    impl CookieData for Preferences {
+        const NAME: &'static str = "prefs";
+    }
+
+    let mut config = Config::default();
+    config.cookies.keys = vec!["0123456789abcdef0123456789abcdef".to_string()];
+    config.cookies.same_site = SameSite::Strict;
+    config.cookies.secure = true;
+    let server = WebServerBuilder::new()
+        .config(config)
+        .routes(
+            Router::new()
+                .route(
+                    "/theme/:theme",
+                    hwhkit::post(
+                        |hwhkit::Path(theme): hwhkit::Path<String>, mut prefs: Cookie<Preferences>| async move {
+                            prefs.set(Preferences { theme })?;
+                            Ok::<_, hwhkit::Error>((prefs, "ok"))
+                        },
+                    ),
+                )
+                .route(
+                    "/theme",
+                    hwhkit::get(|prefs: Cookie<Preferences>| async move {
+                        prefs.into_inner().map(|prefs| prefs.theme).unwrap_or_else(|| "light".to_string())
+                    }),
+                ),
+        )
+        .build()
+        .await
+        .unwrap();
+
+    let request = axum::http::Request::post("/theme/dark").body(axum::body::Body::empty()).unwrap();
+    let response = server.app().clone().oneshot(request).await.unwrap();
+    let set_cookie = response.headers()["set-cookie"].to_str().unwrap().to_string();
+    assert!(set_cookie.contains("SameSite=Strict"));
+    assert!(set_cookie.contains("Secure"));
+    assert!(set_cookie.contains("HttpOnly"));
+
+    let theme = |cookie: Option<&str>| {
+        let mut request = axum::http::Request::get("/theme");
+        if let Some(cookie) = cookie {
+            request = request.header("cookie", cookie);
+        }
+        server.app().clone().oneshot(request.body(axum::body::Body::empty()).unwrap())
+    };
+    let response = theme(Some(set_cookie.split(';').next().unwrap())).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(body, "dark");
+
+    let response = theme(None).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(body, "light");
+}